@@ -16,7 +16,7 @@ const SPLUNK_HEC_TOKEN: &str = "splunk_hec_token";
 
 /// The top-level metadata structure contained by both `struct Metric`
 /// and `struct LogEvent` types.
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct EventMetadata {
     /// Arbitrary data stored with an event
     #[serde(default = "default_metadata_value", skip)]
@@ -35,6 +35,21 @@ pub struct EventMetadata {
     /// TODO(Jean): must not skip serialization to track schemas across restarts.
     #[serde(default = "default_schema_definition", skip)]
     schema_definition: Arc<schema::Definition>,
+
+    /// The ID of the component output the event was last sent from, such as `my_source` or
+    /// `my_route.my_output`.
+    #[serde(default, skip)]
+    upstream_id: Option<Arc<str>>,
+}
+
+// The upstream ID is not compared, as it only records the path taken by the event.
+impl PartialEq for EventMetadata {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+            && self.secrets == other.secrets
+            && self.finalizers == other.finalizers
+            && self.schema_definition == other.schema_definition
+    }
 }
 
 fn default_metadata_value() -> Value {
@@ -98,6 +113,7 @@ impl Default for EventMetadata {
             secrets: Secrets::new(),
             finalizers: Default::default(),
             schema_definition: default_schema_definition(),
+            upstream_id: None,
         }
     }
 }
@@ -198,6 +214,17 @@ impl EventMetadata {
         self.schema_definition.as_ref()
     }
 
+    /// Returns the ID of the component output the event was last sent from, if it was sent
+    /// through the topology.
+    pub fn upstream_id(&self) -> Option<&str> {
+        self.upstream_id.as_deref()
+    }
+
+    /// Sets the ID of the component output the event is sent from.
+    pub fn set_upstream_id(&mut self, upstream_id: Arc<str>) {
+        self.upstream_id = Some(upstream_id);
+    }
+
     /// Set the schema definition.
     pub fn set_schema_definition(&mut self, definition: &Arc<schema::Definition>) {
         self.schema_definition = Arc::clone(definition);
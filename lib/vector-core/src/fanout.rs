@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fmt, task::Poll};
+use std::{collections::HashMap, fmt, sync::Arc, task::Poll};
 
 use futures::{Stream, StreamExt};
 use futures_util::{pending, poll};
@@ -41,6 +41,8 @@ pub type ControlChannel = mpsc::UnboundedSender<ControlMessage>;
 pub struct Fanout {
     senders: IndexMap<ComponentKey, Option<Sender>>,
     control_channel: mpsc::UnboundedReceiver<ControlMessage>,
+    /// The ID of the component output this fanout sends from, set on the events it sends.
+    upstream_id: Option<Arc<str>>,
 }
 
 impl Fanout {
@@ -50,11 +52,18 @@ impl Fanout {
         let fanout = Self {
             senders: Default::default(),
             control_channel: control_rx,
+            upstream_id: None,
         };
 
         (fanout, control_tx)
     }
 
+    /// Sets the ID of the component output this fanout sends from, so that the components
+    /// receiving the events can tell which of their inputs they come from.
+    pub fn set_upstream_id(&mut self, upstream_id: impl Into<Arc<str>>) {
+        self.upstream_id = Some(upstream_id.into());
+    }
+
     /// Add a new sink as an output.
     ///
     /// # Panics
@@ -171,7 +180,7 @@ impl Fanout {
     ///
     /// If an error occurs while sending events to any of the connected sinks, an error variant will be
     /// returned detailing the cause.
-    pub async fn send(&mut self, events: EventArray) -> crate::Result<()> {
+    pub async fn send(&mut self, mut events: EventArray) -> crate::Result<()> {
         // First, process any available control messages in a non-blocking fashion.  If any of our
         // senders were replaced, we additionally wait until they're replaced.
         while let Ok(message) = self.control_channel.try_recv() {
@@ -186,6 +195,14 @@ impl Fanout {
             return Ok(());
         }
 
+        if let Some(upstream_id) = &self.upstream_id {
+            for mut event in events.iter_events_mut() {
+                event
+                    .metadata_mut()
+                    .set_upstream_id(Arc::clone(upstream_id));
+            }
+        }
+
         // Keep track of whether the control channel has returned `Ready(None)`, and stop polling
         // it once it has. If we don't do this check, it will continue to return `Ready(None)` any
         // time it is polled, which can lead to a busy loop below.
@@ -577,6 +594,23 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn fanout_sets_upstream_id() {
+        let (mut fanout, _, receivers) = fanout_from_senders(&[2]).await;
+        fanout.set_upstream_id("my_route.my_output");
+
+        fanout
+            .send(make_event_array(2))
+            .await
+            .expect("should not fail");
+
+        let events = collect_ready_events(receivers.into_iter().next().unwrap().into_stream());
+        assert_eq!(events.len(), 2);
+        for event in events {
+            assert_eq!(event.metadata().upstream_id(), Some("my_route.my_output"));
+        }
+    }
+
     #[tokio::test]
     async fn fanout_notready() {
         let (mut fanout, _, mut receivers) = fanout_from_senders(&[2, 1, 2]).await;
@@ -8,7 +8,7 @@ use vector_common::{
 };
 
 use crate::{
-    config::Output,
+    config::{ComponentKey, Output},
     event::{into_event_stream, Event, EventArray, EventContainer, EventRef},
     fanout::{self, Fanout},
     ByteSizeOf,
@@ -222,14 +222,22 @@ pub struct TransformOutputs {
 }
 
 impl TransformOutputs {
-    pub fn new(outputs_in: Vec<Output>) -> (Self, HashMap<Option<String>, fanout::ControlChannel>) {
+    /// Creates the outputs of the transform `component`, and their control channels.
+    pub fn new(
+        outputs_in: Vec<Output>,
+        component: &ComponentKey,
+    ) -> (Self, HashMap<Option<String>, fanout::ControlChannel>) {
         let outputs_spec = outputs_in.clone();
         let mut primary_output = None;
         let mut named_outputs = HashMap::new();
         let mut controls = HashMap::new();
 
         for output in outputs_in {
-            let (fanout, control) = Fanout::new();
+            let (mut fanout, control) = Fanout::new();
+            fanout.set_upstream_id(match &output.port {
+                None => component.to_string(),
+                Some(port) => format!("{}.{}", component, port),
+            });
             match output.port {
                 None => {
                     primary_output = Some(fanout);
@@ -8,21 +8,31 @@ use std::{
 };
 
 use ::value::Value;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use lookup::{owned_value_path, OwnedTargetPath};
 use value::Secrets;
 use vector_common::TimeZone;
 use vrl::state::TypeState;
-use vrl::{diagnostic::Formatter, state, Program, Runtime, Target, VrlRuntime};
-use vrl::{CompilationResult, CompileConfig};
+use vrl::{
+    diagnostic::Formatter, state, Profile, Program, Runtime, Target, Tracer, VrlRuntime,
+    WriteTracer,
+};
+use vrl::{CompilationResult, CompileConfig, StateStore};
 
 #[cfg(feature = "repl")]
 use super::repl;
-use super::Error;
+use super::{fmt, Error};
 
 #[derive(Parser, Debug)]
-#[command(name = "VRL", about = "Vector Remap Language CLI")]
+#[command(
+    name = "VRL",
+    about = "Vector Remap Language CLI",
+    args_conflicts_with_subcommands = true
+)]
 pub struct Opts {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// The VRL program to execute. The program ".foo = true", for example, sets the event object's
     /// `foo` field to `true`.
     #[arg(id = "PROGRAM")]
@@ -52,6 +62,23 @@ pub struct Opts {
     // Should the CLI emit warnings
     #[arg(long = "print-warnings")]
     print_warnings: bool,
+
+    /// Print each evaluated expression to stderr, with its location in the program, the
+    /// assignments it makes and its result.
+    #[arg(long)]
+    trace: bool,
+
+    /// Measure the number of evaluations and the time spent in each expression and function call
+    /// of the program, and print a report of the most expensive ones to stderr once all the
+    /// objects are processed.
+    #[arg(long)]
+    profile: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Format VRL programs in a canonical style.
+    Fmt(fmt::Opts),
 }
 
 impl Opts {
@@ -96,6 +123,10 @@ impl Opts {
 
 #[must_use]
 pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    if let Some(Command::Fmt(opts)) = &opts.command {
+        return fmt::cmd(opts);
+    }
+
     match run(opts) {
         Ok(_) => exitcode::OK,
         Err(err) => {
@@ -128,6 +159,9 @@ fn run(opts: &Opts) -> Result<(), Error> {
         // The CLI should be moved out of the "vrl" module, and then it can use the `vector-core::compile_vrl` function which includes this automatically
         let mut config = CompileConfig::default();
         config.set_read_only_path(OwnedTargetPath::metadata(owned_value_path!("vector")), true);
+        if let Some(path) = opts.program_file.as_ref().and_then(|path| path.parent()) {
+            config.set_import_paths(vec![path.to_path_buf()]);
+        }
 
         let state = TypeState::default();
 
@@ -135,10 +169,9 @@ fn run(opts: &Opts) -> Result<(), Error> {
             program,
             warnings,
             config: _,
-        } = vrl::compile_with_state(&source, &stdlib::all(), &state, CompileConfig::default())
-            .map_err(|diagnostics| {
-                Error::Parse(Formatter::new(&source, diagnostics).colored().to_string())
-            })?;
+        } = vrl::compile_with_state(&source, &stdlib::all(), &state, config).map_err(
+            |diagnostics| Error::Parse(Formatter::new(&source, diagnostics).colored().to_string()),
+        )?;
 
         #[allow(clippy::print_stderr)]
         if opts.print_warnings {
@@ -146,6 +179,11 @@ fn run(opts: &Opts) -> Result<(), Error> {
             eprintln!("{warnings}")
         }
 
+        // State is kept between the objects, as it is between the events processed by a program.
+        let state_store = StateStore::default();
+        let mut tracer = opts.trace.then(|| WriteTracer::new(io::stderr(), &source));
+        let mut profile = opts.profile.then(Profile::default);
+
         for mut object in objects {
             let mut metadata = Value::Object(BTreeMap::new());
             let mut secrets = Secrets::new();
@@ -155,9 +193,19 @@ fn run(opts: &Opts) -> Result<(), Error> {
                 secrets: &mut secrets,
             };
             let state = state::Runtime::default();
-            let runtime = Runtime::new(state);
-
-            let result = execute(&mut target, &program, tz, runtime, opts.runtime).map(|v| {
+            let runtime = Runtime::new(state).with_state_store(state_store.clone());
+
+            let tracer = tracer.as_mut().map(|tracer| tracer as &mut dyn Tracer);
+            let result = execute(
+                &mut target,
+                &program,
+                tz,
+                runtime,
+                opts.runtime,
+                tracer,
+                profile.as_mut(),
+            )
+            .map(|v| {
                 if opts.print_object {
                     object.to_string()
                 } else {
@@ -173,6 +221,11 @@ fn run(opts: &Opts) -> Result<(), Error> {
             }
         }
 
+        #[allow(clippy::print_stderr)]
+        if let Some(profile) = profile {
+            eprint!("{}", profile.report(&source));
+        }
+
         Ok(())
     }
 }
@@ -206,10 +259,12 @@ fn execute(
     timezone: TimeZone,
     mut runtime: Runtime,
     vrl_runtime: VrlRuntime,
+    tracer: Option<&mut dyn Tracer>,
+    profile: Option<&mut Profile>,
 ) -> Result<Value, Error> {
     match vrl_runtime {
         VrlRuntime::Ast => runtime
-            .resolve(object, program, &timezone)
+            .resolve_with(object, program, &timezone, tracer, profile)
             .map_err(Error::Runtime),
     }
 }
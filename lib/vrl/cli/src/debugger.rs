@@ -0,0 +1,198 @@
+use std::{
+    collections::BTreeSet,
+    fmt,
+    io::{self, BufRead, Write},
+};
+
+use ::value::Value;
+use indoc::indoc;
+use lookup::OwnedTargetPath;
+use vrl::{
+    diagnostic::Span,
+    prelude::expression::{Expr, Resolved},
+    SourceMap, Target, Tracer,
+};
+
+/// The debugging settings of the REPL, changed by its commands.
+#[derive(Debug, Default)]
+pub(crate) struct Debugger {
+    trace: bool,
+    step: bool,
+    breakpoints: BTreeSet<usize>,
+}
+
+impl Debugger {
+    /// Handles a debugger command of the REPL, returning the message to print,
+    /// or `None` if `line` isn't a debugger command.
+    pub(crate) fn command(&mut self, line: &str) -> Option<String> {
+        let mut words = line.split_whitespace();
+        let message = match (words.next()?, words.next(), words.next()) {
+            ("trace", Some("on"), None) => {
+                self.trace = true;
+                "tracing enabled".to_owned()
+            }
+            ("trace", Some("off"), None) => {
+                self.trace = false;
+                "tracing disabled".to_owned()
+            }
+            ("step", Some("on"), None) => {
+                self.step = true;
+                "stepping enabled, programs pause before each expression".to_owned()
+            }
+            ("step", Some("off"), None) => {
+                self.step = false;
+                "stepping disabled".to_owned()
+            }
+            ("break", Some(line), None) => match line.parse::<usize>() {
+                Ok(line) if line > 0 => {
+                    self.breakpoints.insert(line);
+                    format!("breakpoint set on line {}", line)
+                }
+                _ => format!("invalid line number: {}", line),
+            },
+            ("clear", None, None) => {
+                self.breakpoints.clear();
+                "all breakpoints cleared".to_owned()
+            }
+            ("clear", Some(line), None) => match line.parse::<usize>() {
+                Ok(line) if self.breakpoints.remove(&line) => {
+                    format!("breakpoint cleared on line {}", line)
+                }
+                _ => format!("no breakpoint on line {}", line),
+            },
+            ("breakpoints", None, None) if self.breakpoints.is_empty() => {
+                "no breakpoints".to_owned()
+            }
+            ("breakpoints", None, None) => {
+                let lines = self
+                    .breakpoints
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                format!("breakpoints on lines {}", lines.join(", "))
+            }
+            _ => return None,
+        };
+
+        Some(message)
+    }
+
+    /// Returns a [`Tracer`] for a program compiled from `source`, if any
+    /// debugging is enabled.
+    pub(crate) fn session(&self, source: &str) -> Option<Session<'_>> {
+        (self.trace || self.step || !self.breakpoints.is_empty()).then(|| Session {
+            debugger: self,
+            source_map: SourceMap::new(source),
+            stepping: self.step,
+            depth: 0,
+        })
+    }
+}
+
+/// Debugs a single execution of a program, pausing on breakpoints and, when
+/// stepping, before every expression.
+pub(crate) struct Session<'a> {
+    debugger: &'a Debugger,
+    source_map: SourceMap,
+    stepping: bool,
+    depth: usize,
+}
+
+impl Session<'_> {
+    fn verbose(&self) -> bool {
+        self.debugger.trace || self.stepping
+    }
+
+    fn print(&self, line: fmt::Arguments<'_>) {
+        #[allow(clippy::print_stdout)]
+        {
+            println!("{:indent$}{}", "", line, indent = self.depth * 2);
+        }
+    }
+
+    /// Reads debugger commands from stdin until execution is resumed.
+    #[allow(clippy::print_stdout)]
+    fn pause(&mut self, target: &dyn Target) {
+        let stdin = io::stdin();
+        let mut input = String::new();
+
+        loop {
+            print!("(debug) ");
+            let _ = io::stdout().flush();
+
+            input.clear();
+            match stdin.lock().read_line(&mut input) {
+                // Resume on end of input, to never block the program.
+                Ok(0) | Err(_) => {
+                    self.stepping = false;
+                    return;
+                }
+                Ok(_) => {}
+            }
+
+            match input.trim() {
+                "" | "s" | "step" => {
+                    self.stepping = true;
+                    return;
+                }
+                "c" | "continue" => {
+                    self.stepping = false;
+                    return;
+                }
+                "p" | "print" => match target.target_get(&OwnedTargetPath::event_root()) {
+                    Ok(Some(value)) => println!("{}", value),
+                    Ok(None) => println!("null"),
+                    Err(err) => println!("error querying target object: {}", err),
+                },
+                "h" | "help" => println!("{}", PAUSE_HELP_TEXT),
+                command => println!("unknown command: {}, try `help`", command),
+            }
+        }
+    }
+}
+
+impl Tracer for Session<'_> {
+    fn before_expression(&mut self, span: Span, expr: &Expr, target: &dyn Target) {
+        let location = self.source_map.format_span(span);
+        let breakpoint = self
+            .source_map
+            .line_column(span.start())
+            .map_or(false, |(line, _)| self.debugger.breakpoints.contains(&line));
+
+        if breakpoint && !self.stepping {
+            self.print(format_args!("breakpoint at {}", location));
+        }
+        if breakpoint || self.verbose() {
+            self.print(format_args!("{}: {}", location, expr));
+        }
+        if breakpoint || self.stepping {
+            self.pause(target);
+        }
+
+        self.depth += 1;
+    }
+
+    fn after_expression(&mut self, _span: Span, _expr: &Expr, result: &Resolved) {
+        if self.verbose() {
+            match result {
+                Ok(value) => self.print(format_args!("=> {}", value)),
+                Err(error) => self.print(format_args!("=> error: {}", error)),
+            }
+        }
+
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    fn on_assignment(&mut self, target: &dyn fmt::Display, value: &Value) {
+        if self.verbose() {
+            self.print(format_args!("{} = {}", target, value));
+        }
+    }
+}
+
+const PAUSE_HELP_TEXT: &str = indoc! {r#"
+    Debugger commands:
+      step      Evaluate the next expression and pause again (aliases: ["s", ""])
+      continue  Resume until the next breakpoint (alias: "c")
+      print     Print the event object (alias: "p")
+"#};
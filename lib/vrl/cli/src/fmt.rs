@@ -0,0 +1,92 @@
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::PathBuf,
+};
+
+use clap::Args;
+use vrl::diagnostic::{DiagnosticMessage, Formatter};
+
+use super::Error;
+
+#[derive(Args, Debug)]
+pub struct Opts {
+    /// The files containing the VRL programs to format in place. If no file is given, the program
+    /// is read from stdin and the formatted program is written to stdout.
+    #[arg(id = "FILE")]
+    files: Vec<PathBuf>,
+
+    /// Check that the programs are formatted, without changing them. Exits with an error if any of
+    /// them isn't formatted.
+    #[arg(long)]
+    check: bool,
+}
+
+/// Formats a VRL program in the canonical style, or returns the rendered diagnostic of the parse
+/// error preventing it.
+pub fn format_program(source: &str) -> Result<String, String> {
+    parser::format(source).map_err(|error| {
+        let diagnostics = vec![Box::new(error) as Box<dyn DiagnosticMessage>];
+        Formatter::new(source, diagnostics).colored().to_string()
+    })
+}
+
+#[must_use]
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    match run(opts) {
+        Ok(true) => exitcode::OK,
+        Ok(false) => exitcode::DATAERR,
+        Err(err) => {
+            #[allow(clippy::print_stderr)]
+            {
+                eprintln!("{}", err);
+            }
+            exitcode::SOFTWARE
+        }
+    }
+}
+
+/// Formats the programs, returning whether they were all formatted already
+/// when checking them.
+fn run(opts: &Opts) -> Result<bool, Error> {
+    if opts.files.is_empty() {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source)?;
+        let formatted = format_program(&source).map_err(Error::Parse)?;
+
+        if opts.check {
+            return Ok(report("<stdin>", &source, &formatted));
+        }
+
+        io::stdout().write_all(formatted.as_bytes())?;
+        return Ok(true);
+    }
+
+    let mut formatted_already = true;
+    for path in &opts.files {
+        let source = fs::read_to_string(path)?;
+        let formatted = format_program(&source)
+            .map_err(|diagnostics| Error::Parse(format!("{}:{}", path.display(), diagnostics)))?;
+
+        if opts.check {
+            formatted_already &= report(&path.display().to_string(), &source, &formatted);
+        } else if formatted != source {
+            fs::write(path, formatted)?;
+        }
+    }
+
+    Ok(formatted_already)
+}
+
+/// Reports a program that isn't formatted, returning whether it is.
+fn report(name: &str, source: &str, formatted: &str) -> bool {
+    if source == formatted {
+        return true;
+    }
+
+    #[allow(clippy::print_stderr)]
+    {
+        eprintln!("{} is not formatted", name);
+    }
+    false
+}
@@ -14,6 +14,9 @@
 
 pub mod cmd;
 #[cfg(feature = "repl")]
+mod debugger;
+pub mod fmt;
+#[cfg(feature = "repl")]
 mod repl;
 
 pub use cmd::{cmd, Opts};
@@ -20,9 +20,12 @@ use vector_common::TimeZone;
 use vector_vrl_functions::vrl_functions;
 use vrl::state::TypeState;
 use vrl::{
-    diagnostic::Formatter, prelude::BTreeMap, state, CompileConfig, Runtime, Target, VrlRuntime,
+    diagnostic::Formatter, prelude::BTreeMap, state, CompileConfig, Runtime, StateStore, Target,
+    Tracer, VrlRuntime,
 };
 
+use crate::debugger::Debugger;
+
 // Create a list of all possible error values for potential docs lookup
 static ERRORS: Lazy<Vec<String>> = Lazy::new(|| {
     [
@@ -47,6 +50,13 @@ const RESERVED_TERMS: &[&str] = &[
     "help funcs",
     "help fs",
     "help docs",
+    "trace on",
+    "trace off",
+    "step on",
+    "step off",
+    "break",
+    "clear",
+    "breakpoints",
 ];
 
 pub(crate) fn run(
@@ -59,8 +69,9 @@ pub(crate) fn run(
     let error_docs_regex = Regex::new(r"^help\serror\s(\w{1,})$").unwrap();
 
     let mut state = TypeState::default();
+    let mut debugger = Debugger::default();
 
-    let mut rt = Runtime::new(state::Runtime::default());
+    let mut rt = Runtime::new(state::Runtime::default()).with_state_store(StateStore::default());
     let mut rl = Editor::<Repl>::new()?;
     rl.set_helper(Some(Repl::new()));
 
@@ -83,6 +94,16 @@ pub(crate) fn run(
             // Capture "help docs <func_name>"
             Ok(line) if func_docs_regex.is_match(line) => show_func_docs(line, &func_docs_regex),
             Ok(line) => {
+                if let Some(message) = debugger.command(line) {
+                    rl.add_history_entry(line);
+
+                    #[allow(clippy::print_stdout)]
+                    {
+                        println!("{}\n", message);
+                    }
+                    continue;
+                }
+
                 rl.add_history_entry(line);
 
                 let command = match line {
@@ -126,6 +147,7 @@ pub(crate) fn run(
                     &mut state,
                     timezone,
                     vrl_runtime,
+                    Some(&debugger),
                 );
 
                 let string = match result {
@@ -158,6 +180,7 @@ fn resolve(
     state: &mut TypeState,
     timezone: TimeZone,
     vrl_runtime: VrlRuntime,
+    debugger: Option<&Debugger>,
 ) -> Result<Value, String> {
     let mut functions = stdlib::all();
     functions.extend(vector_vrl_functions::vrl_functions());
@@ -166,15 +189,19 @@ fn resolve(
     // The CLI should be moved out of the "vrl" module, and then it can use the `vector-core::compile_vrl` function which includes this automatically
     config.set_read_only_path(OwnedTargetPath::metadata(owned_value_path!("vector")), true);
 
-    let program = match vrl::compile_with_state(program, &functions, state, config) {
+    let source = program;
+    let program = match vrl::compile_with_state(source, &functions, state, config) {
         Ok(result) => result.program,
         Err(diagnostics) => {
-            return Err(Formatter::new(program, diagnostics).colored().to_string());
+            return Err(Formatter::new(source, diagnostics).colored().to_string());
         }
     };
 
     *state = program.final_type_state();
-    execute(runtime, &program, target, timezone, vrl_runtime)
+    let mut session = debugger.and_then(|debugger| debugger.session(source));
+    let tracer = session.as_mut().map(|session| session as &mut dyn Tracer);
+
+    execute(runtime, &program, target, timezone, vrl_runtime, tracer)
 }
 
 fn execute(
@@ -183,11 +210,14 @@ fn execute(
     object: &mut dyn Target,
     timezone: TimeZone,
     vrl_runtime: VrlRuntime,
+    tracer: Option<&mut dyn Tracer>,
 ) -> Result<Value, String> {
     match vrl_runtime {
-        VrlRuntime::Ast => runtime
-            .resolve(object, program, &timezone)
-            .map_err(|err| err.to_string()),
+        VrlRuntime::Ast => match tracer {
+            Some(tracer) => runtime.resolve_with_tracer(object, program, &timezone, tracer),
+            None => runtime.resolve(object, program, &timezone),
+        }
+        .map_err(|err| err.to_string()),
     }
 }
 
@@ -304,6 +334,7 @@ impl Validator for Repl {
             &mut state,
             timezone,
             VrlRuntime::Ast,
+            None,
         );
 
         let result = match result {
@@ -416,7 +447,15 @@ const HELP_TEXT: &str = indoc! {r#"
       help error <code>  Navigate to the docs for a specific error code
       next               Load the next object or create a new one
       prev               Load the previous object
+      trace on|off       Print each evaluated expression, its assignments and its result
+      step on|off        Pause before each evaluated expression
+      break <line>       Pause before the expressions starting on a line of the program
+      clear [<line>]     Remove the breakpoint on a line, or all breakpoints
+      breakpoints        List the breakpoints
       exit               Terminate the program
+
+    While paused, enter `step` to evaluate the next expression, `continue` to resume until the
+    next breakpoint, or `print` to print the event object.
 "#};
 
 const BANNER_TEXT: &str = indoc! {r#"
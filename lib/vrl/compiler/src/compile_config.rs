@@ -1,11 +1,12 @@
 use anymap::AnyMap;
 use lookup::OwnedTargetPath;
-use std::collections::BTreeSet;
+use std::{collections::BTreeSet, path::PathBuf};
 
 pub struct CompileConfig {
     /// Custom context injected by the external environment
     custom: AnyMap,
     read_only_paths: BTreeSet<ReadOnlyPath>,
+    import_paths: Vec<PathBuf>,
 }
 
 impl CompileConfig {
@@ -61,6 +62,21 @@ impl CompileConfig {
         self.read_only_paths
             .insert(ReadOnlyPath { path, recursive });
     }
+
+    /// The directories that relative `import` paths are resolved against, in order.
+    #[must_use]
+    pub fn import_paths(&self) -> &[PathBuf] {
+        &self.import_paths
+    }
+
+    /// Sets the directories that relative `import` paths are resolved against.
+    ///
+    /// Files imported by an imported file are first resolved against the directory of that file.
+    /// If none of the directories contain the file, the path is resolved against the current
+    /// working directory.
+    pub fn set_import_paths(&mut self, paths: Vec<PathBuf>) {
+        self.import_paths = paths;
+    }
 }
 
 impl Default for CompileConfig {
@@ -68,6 +84,7 @@ impl Default for CompileConfig {
         Self {
             custom: AnyMap::new(),
             read_only_paths: BTreeSet::new(),
+            import_paths: Vec::new(),
         }
     }
 }
@@ -1,3 +1,7 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+
 use core::Value;
 use diagnostic::{DiagnosticList, DiagnosticMessage, Note, Severity, Span};
 use lookup::{OwnedTargetPath, OwnedValuePath, PathPrefix};
@@ -10,15 +14,26 @@ use crate::{
     expression::{
         assignment, function_call, literal, predicate, query, Abort, Array, Assignment, Block,
         Container, Error, Expr, Expression, FunctionArgument, FunctionCall, Group, IfStatement,
-        Literal, Noop, Not, Object, Op, Predicate, Query, Target, Unary, Variable,
+        Literal, Noop, Not, Object, Op, Predicate, Query, Target, Unary, UserFunctionCall,
+        Variable,
     },
-    parser::ast::RootExpr,
+    import::{self, ImportedSource},
+    parser::ast::{Ident, RootExpr},
     program::ProgramInfo,
     CompileConfig, DeprecationWarning, Function, Program, TypeDef,
 };
 
 pub(crate) type Diagnostics = Vec<Box<dyn DiagnosticMessage>>;
 
+/// A function defined by the program, or by one of the files it imports.
+struct UserFunction {
+    parameters: Vec<Node<Ident>>,
+    block: Node<ast::Block>,
+
+    /// The file the function is defined in, if it was imported.
+    source: Option<Arc<ImportedSource>>,
+}
+
 pub struct CompilationResult {
     pub program: Program,
     pub warnings: DiagnosticList,
@@ -51,6 +66,16 @@ pub struct Compiler<'a> {
     /// nullifies the fallibility of that expression.
     fallible_expression_error: Option<Box<dyn DiagnosticMessage>>,
 
+    /// The functions defined by the program and its imports.
+    user_functions: HashMap<Ident, Arc<UserFunction>>,
+
+    /// The user-defined functions whose body is currently being compiled,
+    /// used to reject recursive calls.
+    user_function_stack: Vec<Ident>,
+
+    /// The canonical paths of the files that have already been imported.
+    imports: HashSet<PathBuf>,
+
     config: CompileConfig,
 }
 
@@ -73,9 +98,12 @@ impl<'a> Compiler<'a> {
             external_assignments: vec![],
             skip_missing_query_target: vec![],
             fallible_expression_error: None,
+            user_functions: HashMap::new(),
+            user_function_stack: vec![],
+            imports: HashSet::new(),
             config,
         };
-        let expressions = compiler.compile_root_exprs(ast, &mut state);
+        let (expressions, spans) = compiler.compile_root_exprs(ast, &mut state);
 
         let (errors, warnings): (Vec<_>, Vec<_>) =
             compiler.diagnostics.into_iter().partition(|diagnostic| {
@@ -88,7 +116,7 @@ impl<'a> Compiler<'a> {
 
         let result = CompilationResult {
             program: Program {
-                expressions: Block::new_inline(expressions),
+                expressions: Block::new_inline(expressions).with_spans(spans),
                 info: ProgramInfo {
                     fallible: compiler.fallible,
                     abortable: compiler.abortable,
@@ -132,6 +160,9 @@ impl<'a> Compiler<'a> {
             Op(node) => self.compile_op(node, state).map(Into::into),
             Assignment(node) => self.compile_assignment(node, state).map(Into::into),
             Query(node) => self.compile_query(node, state).map(Into::into),
+            FunctionCall(node) if self.user_functions.contains_key(node.ident.inner()) => {
+                self.compile_user_function_call(node, state).map(Into::into)
+            }
             FunctionCall(node) => self.compile_function_call(node, state).map(Into::into),
             Variable(node) => self.compile_variable(node, state).map(Into::into),
             Unary(node) => self.compile_unary(node, state).map(Into::into),
@@ -224,20 +255,42 @@ impl<'a> Compiler<'a> {
         &mut self,
         nodes: impl IntoIterator<Item = Node<ast::RootExpr>>,
         state: &mut TypeState,
-    ) -> Vec<Expr> {
+    ) -> (Vec<Expr>, Vec<Span>) {
         let mut node_exprs = vec![];
+        let mut spans = vec![];
+
+        // Function definitions and imports are registered before compiling any
+        // expression, so that a function can be called before its definition.
+        let nodes = nodes
+            .into_iter()
+            .filter_map(|root_expr| match root_expr.into_inner() {
+                RootExpr::FunctionDefinition(node) => {
+                    self.define_function(node, None);
+                    None
+                }
+                RootExpr::Import(node) => {
+                    self.import(node, None);
+                    None
+                }
+                root_expr => Some(root_expr),
+            })
+            .collect::<Vec<_>>();
 
         for root_expr in nodes {
-            match root_expr.into_inner() {
+            match root_expr {
+                // Already registered above.
+                RootExpr::FunctionDefinition(_) | RootExpr::Import(_) => {}
                 RootExpr::Expr(node_expr) => {
                     self.fallible_expression_error = None;
 
+                    let span = node_expr.span();
                     if let Some(expr) = self.compile_expr(node_expr, state) {
                         if let Some(error) = self.fallible_expression_error.take() {
                             self.diagnostics.push(error);
                         }
 
                         node_exprs.push(expr);
+                        spans.push(span);
                     }
                 }
                 RootExpr::Error(err) => self.handle_parser_error(err),
@@ -246,8 +299,9 @@ impl<'a> Compiler<'a> {
 
         if node_exprs.is_empty() {
             node_exprs.push(Expr::Noop(Noop));
+            spans.push(Span::default());
         }
-        node_exprs
+        (node_exprs, spans)
     }
 
     fn compile_block(&mut self, node: Node<ast::Block>, state: &mut TypeState) -> Option<Block> {
@@ -261,8 +315,10 @@ impl<'a> Compiler<'a> {
         state: &mut TypeState,
     ) -> Option<(Block, TypeDef)> {
         let original_state = state.clone();
-        let exprs = self.compile_exprs(node.into_inner().into_iter(), state)?;
-        let block = Block::new_scoped(exprs);
+        let nodes = node.into_inner().into_inner();
+        let spans = nodes.iter().map(Node::span).collect();
+        let exprs = self.compile_exprs(nodes, state)?;
+        let block = Block::new_scoped(exprs).with_spans(spans);
 
         // The type information from `compile_exprs` doesn't applying the "scoping" from the block.
         // This is recalculated using the block.
@@ -813,6 +869,301 @@ impl<'a> Compiler<'a> {
         Some(FunctionArgument::new(ident, node))
     }
 
+    /// Registers a function definition, reporting any conflict with the name
+    /// of another function.
+    fn define_function(
+        &mut self,
+        node: Node<ast::FunctionDefinition>,
+        source: Option<&Arc<ImportedSource>>,
+    ) {
+        use crate::expression::user_function_call::Error;
+
+        let ast::FunctionDefinition {
+            ident,
+            parameters,
+            block,
+        } = node.into_inner();
+        let (ident_span, ident) = ident.take();
+
+        let builtin = self
+            .fns
+            .iter()
+            .any(|function| function.identifier() == &*ident);
+        if builtin || self.user_functions.contains_key(&ident) {
+            self.diagnostics.push(Box::new(Error::AlreadyDefined {
+                ident_span,
+                builtin,
+            }));
+            return;
+        }
+
+        for (index, parameter) in parameters.iter().enumerate() {
+            if parameters[..index]
+                .iter()
+                .any(|previous| previous.inner() == parameter.inner())
+            {
+                self.diagnostics.push(Box::new(Error::DuplicateParameter {
+                    parameter_span: parameter.span(),
+                }));
+                return;
+            }
+        }
+
+        let function = UserFunction {
+            parameters,
+            block,
+            source: source.cloned(),
+        };
+        self.user_functions.insert(ident, Arc::new(function));
+    }
+
+    /// Registers the function definitions of an imported file.
+    ///
+    /// Each file is only imported once, which also breaks import cycles.
+    fn import(&mut self, node: Node<ast::Import>, importer: Option<&Arc<ImportedSource>>) {
+        let (span, ast::Import { path }) = node.take();
+
+        let path = import::resolve(
+            path.inner(),
+            importer.map(|source| source.path.as_path()),
+            self.config.import_paths(),
+        );
+        if !self.imports.insert(path.clone()) {
+            return;
+        }
+
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => Arc::new(ImportedSource { path, source }),
+            Err(error) => {
+                self.diagnostics
+                    .push(Box::new(import::Error::Unreadable { span, path, error }));
+                return;
+            }
+        };
+
+        self.with_imported_diagnostics(&source, span, |compiler| {
+            let program = match parser::parse(&source.source) {
+                Ok(program) => program,
+                Err(error) => return compiler.handle_parser_error(error),
+            };
+
+            for root_expr in program {
+                match root_expr.into_inner() {
+                    RootExpr::FunctionDefinition(node) => {
+                        compiler.define_function(node, Some(&source))
+                    }
+                    RootExpr::Import(node) => compiler.import(node, Some(&source)),
+                    RootExpr::Expr(node) => {
+                        compiler
+                            .diagnostics
+                            .push(Box::new(import::Error::UnexpectedExpression {
+                                span: node.span(),
+                            }))
+                    }
+                    RootExpr::Error(error) => compiler.handle_parser_error(error),
+                }
+            }
+        });
+    }
+
+    /// Runs `f`, reporting the diagnostics it produces against the source of
+    /// the imported file, attached to `span` in the importing program.
+    fn with_imported_diagnostics<T>(
+        &mut self,
+        source: &ImportedSource,
+        span: Span,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let diagnostics = std::mem::take(&mut self.diagnostics);
+        let result = f(self);
+        let imported = std::mem::replace(&mut self.diagnostics, diagnostics);
+
+        if !imported.is_empty() {
+            self.diagnostics
+                .push(Box::new(import::Error::diagnostics(span, source, imported)));
+        }
+
+        result
+    }
+
+    #[cfg(feature = "expr-function_call")]
+    fn compile_user_function_call(
+        &mut self,
+        node: Node<ast::FunctionCall>,
+        state: &mut TypeState,
+    ) -> Option<UserFunctionCall> {
+        use crate::expression::user_function_call::{bind_parameters, Error};
+
+        let call_span = node.span();
+        let ast::FunctionCall {
+            ident,
+            abort_on_error,
+            arguments,
+            closure,
+        } = node.into_inner();
+        let (ident_span, ident) = ident.take();
+        let function = Arc::clone(self.user_functions.get(&ident)?);
+        let parameters = &function.parameters;
+
+        if let Some(closure) = closure {
+            self.diagnostics.push(Box::new(Error::UnexpectedClosure {
+                call_span,
+                closure_span: closure.span(),
+            }));
+            return None;
+        }
+
+        if self.user_function_stack.contains(&ident) {
+            self.diagnostics
+                .push(Box::new(Error::Recursive { call_span, ident }));
+            return None;
+        }
+
+        // Bind keyword arguments to the parameter of the same name, and then
+        // positional arguments to the remaining parameters, in order.
+        let mut positions = vec![None; arguments.len()];
+        let mut bound = vec![false; parameters.len()];
+
+        for (position, argument) in positions.iter_mut().zip(&arguments) {
+            if let Some(keyword) = &argument.ident {
+                match parameters
+                    .iter()
+                    .position(|parameter| parameter.inner() == keyword.inner())
+                {
+                    Some(index) if bound[index] => {
+                        self.diagnostics.push(Box::new(Error::DuplicateArgument {
+                            keyword_span: keyword.span(),
+                        }));
+                        return None;
+                    }
+                    Some(index) => {
+                        bound[index] = true;
+                        *position = Some(index);
+                    }
+                    None => {
+                        self.diagnostics.push(Box::new(Error::UnknownKeyword {
+                            keyword_span: keyword.span(),
+                            ident_span,
+                            keywords: parameters
+                                .iter()
+                                .map(|parameter| parameter.inner().clone())
+                                .collect(),
+                        }));
+                        return None;
+                    }
+                }
+            }
+        }
+
+        let mut unbound = (0..parameters.len())
+            .filter(|index| !bound[*index])
+            .collect::<Vec<_>>()
+            .into_iter();
+        for (position, argument) in positions.iter_mut().zip(&arguments) {
+            if argument.ident.is_none() {
+                match unbound.next() {
+                    Some(index) => {
+                        bound[index] = true;
+                        *position = Some(index);
+                    }
+                    None => {
+                        self.diagnostics.push(Box::new(Error::WrongNumberOfArgs {
+                            arguments_span: argument.span(),
+                            max: parameters.len(),
+                        }));
+                        return None;
+                    }
+                }
+            }
+        }
+
+        if let Some(position) = bound.iter().position(|bound| !bound) {
+            self.diagnostics.push(Box::new(Error::MissingArgument {
+                call_span,
+                keyword: parameters[position].inner().clone(),
+                position,
+            }));
+            return None;
+        }
+
+        let original_state = state.clone();
+
+        let mut compiled_arguments = Vec::with_capacity(arguments.len());
+        for (position, argument) in positions.into_iter().zip(arguments) {
+            let index = position.expect("every argument is bound to a parameter");
+            let expr = argument.into_inner().expr;
+            let expr_span = expr.span();
+
+            let before_argument = state.clone();
+            let expr = self.compile_expr(expr, state)?;
+            if expr.type_info(&before_argument).result.is_fallible() {
+                self.diagnostics
+                    .push(Box::new(Error::FallibleArgument { expr_span }));
+                return None;
+            }
+
+            compiled_arguments.push((parameters[index].inner().clone(), expr));
+        }
+
+        // The body is compiled for each call, with the types of the arguments
+        // of that call as its only variables.
+        let mut body_state = original_state.clone();
+        body_state.local = bind_parameters(
+            compiled_arguments
+                .iter()
+                .map(|(parameter, argument)| (parameter, argument)),
+            &mut body_state,
+        );
+
+        self.user_function_stack.push(ident.clone());
+        let fallible_expression_error = self.fallible_expression_error.take();
+
+        let block = function.block.clone();
+        let body = match &function.source {
+            Some(source) => self.with_imported_diagnostics(source, call_span, |compiler| {
+                compiler.compile_block_with_type(block, &mut body_state)
+            }),
+            None => self.compile_block_with_type(block, &mut body_state),
+        };
+
+        // An unhandled error in the body makes the call itself fallible, which
+        // is reported by the caller.
+        self.fallible_expression_error = fallible_expression_error;
+        self.user_function_stack.pop();
+
+        let (body, body_type) = body?;
+
+        if abort_on_error {
+            self.fallible = true;
+
+            if !body_type.is_fallible() {
+                self.diagnostics.push(Box::new(Error::AbortInfallible {
+                    ident_span,
+                    abort_span: Span::new(ident_span.end(), ident_span.end() + 1),
+                }));
+                return None;
+            }
+        }
+
+        let call =
+            UserFunctionCall::new(ident, abort_on_error, compiled_arguments, body, call_span);
+
+        // Update the final state using the call expression, which discards the
+        // variables of the body.
+        *state = call.type_info(&original_state).state;
+        Some(call)
+    }
+
+    #[cfg(not(feature = "expr-function_call"))]
+    fn compile_user_function_call(
+        &mut self,
+        node: Node<ast::FunctionCall>,
+        _: &mut ExternalEnv,
+    ) -> Option<Noop> {
+        self.handle_missing_feature_error(node.span(), "expr-function_call");
+        None
+    }
+
     #[cfg(not(feature = "expr-function_call"))]
     fn compile_function_call(
         &mut self,
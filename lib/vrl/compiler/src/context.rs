@@ -1,11 +1,21 @@
+use std::{fmt, time::Duration};
+
+use value::Value;
 use vector_common::TimeZone;
 
-use crate::{state::Runtime, Target};
+use crate::{
+    expression::{Expr, Resolved},
+    state::Runtime,
+    Profile, Span, StateStore, Target, Tracer,
+};
 
 pub struct Context<'a> {
     target: &'a mut dyn Target,
     state: &'a mut Runtime,
     timezone: &'a TimeZone,
+    state_store: Option<&'a StateStore>,
+    tracer: Option<&'a mut dyn Tracer>,
+    profile: Option<&'a mut Profile>,
 }
 
 impl<'a> Context<'a> {
@@ -15,6 +25,73 @@ impl<'a> Context<'a> {
             target,
             state,
             timezone,
+            state_store: None,
+            tracer: None,
+            profile: None,
+        }
+    }
+
+    /// Sets the [`StateStore`] kept between executions of the program.
+    #[must_use]
+    pub fn with_state_store(mut self, state_store: &'a StateStore) -> Self {
+        self.state_store = Some(state_store);
+        self
+    }
+
+    /// Sets the [`Tracer`] observing the evaluation of the program.
+    #[must_use]
+    pub fn with_tracer(mut self, tracer: &'a mut dyn Tracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    /// Returns whether the evaluation of the program is traced.
+    #[must_use]
+    pub fn is_tracing(&self) -> bool {
+        self.tracer.is_some()
+    }
+
+    pub(crate) fn trace_before_expression(&mut self, span: Span, expr: &Expr) {
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.before_expression(span, expr, &*self.target);
+        }
+    }
+
+    pub(crate) fn trace_after_expression(&mut self, span: Span, expr: &Expr, result: &Resolved) {
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.after_expression(span, expr, result);
+        }
+    }
+
+    pub(crate) fn trace_assignment(&mut self, target: &dyn fmt::Display, value: &Value) {
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.on_assignment(target, value);
+        }
+    }
+
+    /// Sets the [`Profile`] in which the cost of evaluating the program is
+    /// collected.
+    #[must_use]
+    pub fn with_profile(mut self, profile: &'a mut Profile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Returns whether the cost of evaluating the program is collected.
+    #[must_use]
+    pub fn is_profiling(&self) -> bool {
+        self.profile.is_some()
+    }
+
+    pub(crate) fn profile_expression(&mut self, span: Span, duration: Duration) {
+        if let Some(profile) = self.profile.as_mut() {
+            profile.record_expression(span, duration);
+        }
+    }
+
+    pub(crate) fn profile_function_call(&mut self, span: Span, function: &str, duration: Duration) {
+        if let Some(profile) = self.profile.as_mut() {
+            profile.record_function_call(span, function, duration);
         }
     }
 
@@ -40,6 +117,13 @@ impl<'a> Context<'a> {
         self.state
     }
 
+    /// Get a reference to the [`StateStore`], if the program keeps state
+    /// between executions.
+    #[must_use]
+    pub fn state_store(&self) -> Option<&StateStore> {
+        self.state_store
+    }
+
     /// Get a reference to the [`TimeZone`]
     #[must_use]
     pub fn timezone(&self) -> &TimeZone {
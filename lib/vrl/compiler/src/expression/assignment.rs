@@ -367,6 +367,10 @@ impl Target {
     fn insert(&self, value: Value, ctx: &mut Context) {
         use Target::{External, Internal, Noop};
 
+        if ctx.is_tracing() && !matches!(self, Noop) {
+            ctx.trace_assignment(self, &value);
+        }
+
         match self {
             Noop => {}
             Internal(ident, path) => {
@@ -1,15 +1,19 @@
-use std::fmt;
+use std::{fmt, time::Instant};
 
 use crate::state::{TypeInfo, TypeState};
 use crate::{
     expression::{Expr, Resolved},
-    Context, Expression, TypeDef,
+    Context, Expression, Span, TypeDef,
 };
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct Block {
     inner: Vec<Expr>,
 
+    // The spans of the expressions, only used to trace or profile the
+    // evaluation of the block. Empty if the expressions don't originate from the source.
+    spans: Vec<Span>,
+
     // false - This is just an inline block of code
     // true - This is a block of code nested in a child scope
     new_scope: bool,
@@ -18,7 +22,11 @@ pub struct Block {
 impl Block {
     #[must_use]
     fn new(inner: Vec<Expr>, new_scope: bool) -> Self {
-        Self { inner, new_scope }
+        Self {
+            inner,
+            spans: vec![],
+            new_scope,
+        }
     }
 
     #[must_use]
@@ -31,6 +39,15 @@ impl Block {
         Self::new(inner, false)
     }
 
+    /// Sets the spans of the expressions of the block, to trace or profile
+    /// them.
+    #[must_use]
+    pub fn with_spans(mut self, spans: Vec<Span>) -> Self {
+        debug_assert_eq!(spans.len(), self.inner.len());
+        self.spans = spans;
+        self
+    }
+
     #[must_use]
     pub fn into_inner(self) -> Vec<Expr> {
         self.inner
@@ -42,17 +59,51 @@ impl Block {
     }
 }
 
+impl Block {
+    fn resolve_traced(&self, index: usize, expr: &Expr, ctx: &mut Context) -> Resolved {
+        let span = match self.spans.get(index) {
+            Some(span) => *span,
+            None => return expr.resolve(ctx),
+        };
+
+        ctx.trace_before_expression(span, expr);
+        let start = ctx.is_profiling().then(Instant::now);
+        let result = expr.resolve(ctx);
+        if let Some(start) = start {
+            ctx.profile_expression(span, start.elapsed());
+        }
+        ctx.trace_after_expression(span, expr, &result);
+        result
+    }
+}
+
+impl PartialEq for Block {
+    fn eq(&self, other: &Self) -> bool {
+        // The spans don't change the meaning of the block.
+        self.inner == other.inner && self.new_scope == other.new_scope
+    }
+}
+
 impl Expression for Block {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         // Variables are checked at compile-time to ensure only variables
         // in scope can be accessed here, so it doesn't need to be checked at runtime.
         let (last, other) = self.inner.split_last().expect("at least one expression");
 
+        if !ctx.is_tracing() && !ctx.is_profiling() {
+            other
+                .iter()
+                .try_for_each(|expr| expr.resolve(ctx).map(|_| ()))?;
+
+            return last.resolve(ctx);
+        }
+
         other
             .iter()
-            .try_for_each(|expr| expr.resolve(ctx).map(|_| ()))?;
+            .enumerate()
+            .try_for_each(|(index, expr)| self.resolve_traced(index, expr, ctx).map(|_| ()))?;
 
-        last.resolve(ctx)
+        self.resolve_traced(other.len(), last, ctx)
     }
 
     fn type_info(&self, state: &TypeState) -> TypeInfo {
@@ -1,5 +1,5 @@
 use diagnostic::{DiagnosticMessage, Label, Note, Urls};
-use std::{fmt, sync::Arc};
+use std::{fmt, sync::Arc, time::Instant};
 
 use super::Block;
 use crate::state::{TypeInfo, TypeState};
@@ -626,7 +626,13 @@ impl FunctionCall {
 
 impl Expression for FunctionCall {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
-        self.expr.resolve(ctx).map_err(|err| match err {
+        let start = ctx.is_profiling().then(Instant::now);
+        let result = self.expr.resolve(ctx);
+        if let Some(start) = start {
+            ctx.profile_function_call(self.span, self.ident, start.elapsed());
+        }
+
+        result.map_err(|err| match err {
             #[cfg(feature = "expr-abort")]
             ExpressionError::Abort { .. } => {
                 panic!("abort errors must only be defined by `abort` statement")
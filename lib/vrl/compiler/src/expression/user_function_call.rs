@@ -0,0 +1,354 @@
+use diagnostic::{DiagnosticMessage, Label, Note, Urls};
+use std::{collections::HashMap, fmt, time::Instant};
+
+use super::{Block, Expr};
+use crate::state::{TypeInfo, TypeState};
+use crate::{
+    expression::ExpressionError, parser::Ident, state::LocalEnv, type_def::Details, Context,
+    Expression, Resolved, Span,
+};
+
+/// A call to a function defined by the program, or by one of the files it
+/// imports.
+///
+/// The body of the function is compiled for every call, using the types of the
+/// arguments of that call, and is evaluated with the arguments as its only
+/// variables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserFunctionCall {
+    ident: Ident,
+    abort_on_error: bool,
+
+    /// The arguments, in the order they are evaluated, with the parameter each
+    /// one is bound to.
+    arguments: Vec<(Ident, Expr)>,
+    body: Block,
+    span: Span,
+}
+
+impl UserFunctionCall {
+    pub(crate) fn new(
+        ident: Ident,
+        abort_on_error: bool,
+        arguments: Vec<(Ident, Expr)>,
+        body: Block,
+        span: Span,
+    ) -> Self {
+        Self {
+            ident,
+            abort_on_error,
+            arguments,
+            body,
+            span,
+        }
+    }
+
+    fn resolve_call(&self, ctx: &mut Context) -> Resolved {
+        let mut variables = HashMap::with_capacity(self.arguments.len());
+        for (parameter, argument) in &self.arguments {
+            variables.insert(parameter.clone(), argument.resolve(ctx)?);
+        }
+
+        let caller_variables = ctx.state_mut().swap_variables(variables);
+        let result = self.body.resolve(ctx);
+        ctx.state_mut().swap_variables(caller_variables);
+
+        result.map_err(|err| match err {
+            #[cfg(feature = "expr-abort")]
+            abort @ ExpressionError::Abort { .. } => abort,
+            ExpressionError::Error {
+                message,
+                mut labels,
+                notes,
+            } => {
+                labels.push(Label::primary(message.clone(), self.span));
+
+                ExpressionError::Error {
+                    message: format!(
+                        r#"function call error for "{}" at ({}:{}): {}"#,
+                        self.ident,
+                        self.span.start(),
+                        self.span.end(),
+                        message
+                    ),
+                    labels,
+                    notes,
+                }
+            }
+        })
+    }
+}
+
+/// Returns the local environment of the body of a function, holding the type
+/// of each argument, and updates `state` with any side-effects of evaluating
+/// the arguments.
+///
+/// Arguments with a static value keep it, so that the body can pass them on to
+/// parameters that require a literal.
+pub(crate) fn bind_parameters<'a>(
+    arguments: impl IntoIterator<Item = (&'a Ident, &'a Expr)>,
+    state: &mut TypeState,
+) -> LocalEnv {
+    let mut local = LocalEnv::default();
+
+    for (parameter, argument) in arguments {
+        let details = Details {
+            type_def: argument.apply_type_info(state).infallible(),
+            value: argument.as_value(),
+        };
+        local.insert_variable(parameter.clone(), details);
+    }
+
+    local
+}
+
+impl Expression for UserFunctionCall {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let start = ctx.is_profiling().then(Instant::now);
+        let result = self.resolve_call(ctx);
+        if let Some(start) = start {
+            ctx.profile_function_call(self.span, &self.ident, start.elapsed());
+        }
+
+        result
+    }
+
+    fn type_info(&self, state: &TypeState) -> TypeInfo {
+        let mut state = state.clone();
+
+        let parameters = bind_parameters(
+            self.arguments
+                .iter()
+                .map(|(parameter, argument)| (parameter, argument)),
+            &mut state,
+        );
+
+        // The body only sees its parameters, and its variables are discarded
+        // once it returns, but changes to the target are kept.
+        let caller_local = std::mem::replace(&mut state.local, parameters);
+        let mut result = self.body.apply_type_info(&mut state);
+        state.local = caller_local;
+
+        if self.abort_on_error {
+            result = result.with_fallibility(false);
+        }
+
+        TypeInfo::new(state, result)
+    }
+}
+
+impl fmt::Display for UserFunctionCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.ident.fmt(f)?;
+        if self.abort_on_error {
+            f.write_str("!")?;
+        }
+        f.write_str("(")?;
+
+        let mut iter = self.arguments.iter().peekable();
+        while let Some((parameter, argument)) = iter.next() {
+            write!(f, "{}: {}", parameter, argument)?;
+
+            if iter.peek().is_some() {
+                f.write_str(", ")?;
+            }
+        }
+
+        f.write_str(")")
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("function already defined")]
+    AlreadyDefined { ident_span: Span, builtin: bool },
+
+    #[error("duplicate function parameter")]
+    DuplicateParameter { parameter_span: Span },
+
+    #[error("recursive function call")]
+    Recursive { call_span: Span, ident: Ident },
+
+    #[error("wrong number of function arguments")]
+    WrongNumberOfArgs { arguments_span: Span, max: usize },
+
+    #[error("unknown function argument keyword")]
+    UnknownKeyword {
+        keyword_span: Span,
+        ident_span: Span,
+        keywords: Vec<Ident>,
+    },
+
+    #[error("duplicate function argument")]
+    DuplicateArgument { keyword_span: Span },
+
+    #[error("missing function argument")]
+    MissingArgument {
+        call_span: Span,
+        keyword: Ident,
+        position: usize,
+    },
+
+    #[error("can't abort infallible function")]
+    AbortInfallible { ident_span: Span, abort_span: Span },
+
+    #[error("fallible argument")]
+    FallibleArgument { expr_span: Span },
+
+    #[error("unexpected closure")]
+    UnexpectedClosure { call_span: Span, closure_span: Span },
+}
+
+impl DiagnosticMessage for Error {
+    fn code(&self) -> usize {
+        use Error::{
+            AbortInfallible, AlreadyDefined, DuplicateArgument, DuplicateParameter,
+            FallibleArgument, MissingArgument, Recursive, UnexpectedClosure, UnknownKeyword,
+            WrongNumberOfArgs,
+        };
+
+        match self {
+            WrongNumberOfArgs { .. } => 106,
+            MissingArgument { .. } => 107,
+            UnknownKeyword { .. } | DuplicateArgument { .. } => 108,
+            UnexpectedClosure { .. } => 109,
+            AlreadyDefined { .. } => 112,
+            DuplicateParameter { .. } => 113,
+            Recursive { .. } => 114,
+            AbortInfallible { .. } => 620,
+            FallibleArgument { .. } => 630,
+        }
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        use Error::{
+            AbortInfallible, AlreadyDefined, DuplicateArgument, DuplicateParameter,
+            FallibleArgument, MissingArgument, Recursive, UnexpectedClosure, UnknownKeyword,
+            WrongNumberOfArgs,
+        };
+
+        match self {
+            AlreadyDefined {
+                ident_span,
+                builtin,
+            } => {
+                let context = if *builtin {
+                    "a built-in function already has this name"
+                } else {
+                    "another function in the program already has this name"
+                };
+
+                vec![
+                    Label::primary("function already defined", ident_span),
+                    Label::context(context, ident_span),
+                ]
+            }
+
+            DuplicateParameter { parameter_span } => vec![Label::primary(
+                "this parameter is already defined",
+                parameter_span,
+            )],
+
+            Recursive { call_span, ident } => vec![
+                Label::primary("recursive function call", call_span),
+                Label::context(
+                    format!(r#"function "{}" is called from its own body"#, ident),
+                    call_span,
+                ),
+            ],
+
+            WrongNumberOfArgs {
+                arguments_span,
+                max,
+            } => {
+                let arg = if *max == 1 { "argument" } else { "arguments" };
+
+                vec![
+                    Label::primary("too many function arguments", arguments_span),
+                    Label::context(
+                        format!("this function takes {} {}", max, arg),
+                        arguments_span,
+                    ),
+                ]
+            }
+
+            UnknownKeyword {
+                keyword_span,
+                ident_span,
+                keywords,
+            } => vec![
+                Label::primary("unknown keyword", keyword_span),
+                Label::context(
+                    format!(
+                        "this function accepts the following keywords: {}",
+                        keywords
+                            .iter()
+                            .map(|k| format!(r#""{}""#, k))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    ident_span,
+                ),
+            ],
+
+            DuplicateArgument { keyword_span } => vec![Label::primary(
+                "this parameter already has an argument",
+                keyword_span,
+            )],
+
+            MissingArgument {
+                call_span,
+                keyword,
+                position,
+            } => vec![Label::primary(
+                format!(
+                    r#"required argument missing: "{}" (position {})"#,
+                    keyword, position
+                ),
+                call_span,
+            )],
+
+            AbortInfallible {
+                ident_span,
+                abort_span,
+            } => vec![
+                Label::primary("this function can't fail", ident_span),
+                Label::context("remove this abort-instruction", abort_span),
+            ],
+
+            FallibleArgument { expr_span } => vec![
+                Label::primary("this expression can fail", expr_span),
+                Label::context(
+                    "handle the error before passing it in as an argument",
+                    expr_span,
+                ),
+            ],
+
+            UnexpectedClosure {
+                call_span,
+                closure_span,
+            } => vec![
+                Label::primary("unexpected closure", closure_span),
+                Label::context(
+                    "functions defined in VRL do not accept a closure",
+                    call_span,
+                ),
+            ],
+        }
+    }
+
+    fn notes(&self) -> Vec<Note> {
+        use Error::{AbortInfallible, FallibleArgument, WrongNumberOfArgs};
+
+        match self {
+            WrongNumberOfArgs { .. } => vec![Note::SeeDocs(
+                "function arguments".to_owned(),
+                Urls::expression_docs_url("#arguments"),
+            )],
+            AbortInfallible { .. } | FallibleArgument { .. } => vec![Note::SeeErrorDocs],
+            _ => vec![],
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Resolution of the files imported by a program.
+//!
+//! Imported files may only contain function definitions and other imports. The
+//! diagnostics of an imported file are reported against its own source, as a
+//! single diagnostic attached to the `import` statement or to the call of one
+//! of its functions.
+use std::path::{Path, PathBuf};
+
+use diagnostic::{DiagnosticMessage, Formatter, Label, Note, Severity};
+
+use crate::Span;
+
+/// The source of an imported file.
+#[derive(Debug)]
+pub(crate) struct ImportedSource {
+    pub(crate) path: PathBuf,
+    pub(crate) source: String,
+}
+
+/// Finds the file imported as `path`.
+///
+/// Relative paths are resolved against the directory of the importing file,
+/// if it was itself imported, and then against each of the import paths in
+/// order, falling back to the current working directory.
+pub(crate) fn resolve(path: &str, importer: Option<&Path>, import_paths: &[PathBuf]) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        return path.to_owned();
+    }
+
+    let path = importer
+        .and_then(Path::parent)
+        .into_iter()
+        .chain(import_paths.iter().map(PathBuf::as_path))
+        .map(|dir| dir.join(path))
+        .find(|candidate| candidate.is_file())
+        .unwrap_or_else(|| path.to_owned());
+
+    // Canonicalize the path, so that a file imported through different paths
+    // is only imported once.
+    std::fs::canonicalize(&path).unwrap_or(path)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("unable to read imported file")]
+    Unreadable {
+        span: Span,
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("{} in imported file", if *.severity == Severity::Warning { "warning" } else { "error" })]
+    Diagnostics {
+        span: Span,
+        path: PathBuf,
+        severity: Severity,
+        rendered: String,
+    },
+
+    #[error("unexpected expression in imported file")]
+    UnexpectedExpression { span: Span },
+}
+
+impl Error {
+    /// Wraps the diagnostics of an imported file, rendered against its source.
+    pub(crate) fn diagnostics(
+        span: Span,
+        source: &ImportedSource,
+        diagnostics: Vec<Box<dyn DiagnosticMessage>>,
+    ) -> Self {
+        let severity = if diagnostics
+            .iter()
+            .any(|diagnostic| matches!(diagnostic.severity(), Severity::Bug | Severity::Error))
+        {
+            Severity::Error
+        } else {
+            Severity::Warning
+        };
+        let rendered = Formatter::new(&source.source, diagnostics)
+            .to_string()
+            .trim()
+            .to_owned();
+
+        Self::Diagnostics {
+            span,
+            path: source.path.clone(),
+            severity,
+            rendered,
+        }
+    }
+}
+
+impl DiagnosticMessage for Error {
+    fn code(&self) -> usize {
+        use Error::{Diagnostics, UnexpectedExpression, Unreadable};
+
+        match self {
+            Unreadable { .. } => 115,
+            Diagnostics { .. } => 116,
+            UnexpectedExpression { .. } => 117,
+        }
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        use Error::{Diagnostics, UnexpectedExpression, Unreadable};
+
+        match self {
+            Unreadable { span, path, error } => vec![
+                Label::primary(format!("unable to read {}", path.display()), span),
+                Label::context(error.to_string(), span),
+            ],
+            Diagnostics { span, path, .. } => vec![Label::primary(
+                format!("while compiling {}", path.display()),
+                span,
+            )],
+            UnexpectedExpression { span } => vec![
+                Label::primary("unexpected expression", span),
+                Label::context(
+                    "imported files may only contain function definitions and imports",
+                    span,
+                ),
+            ],
+        }
+    }
+
+    fn notes(&self) -> Vec<Note> {
+        match self {
+            Error::Diagnostics { rendered, .. } => vec![Note::Basic(rendered.clone())],
+            _ => vec![],
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        match self {
+            Error::Diagnostics { severity, .. } => *severity,
+            _ => Severity::Error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn resolve_order() {
+        let root = std::env::temp_dir().join(format!("vrl-import-{}", std::process::id()));
+        let (importer_dir, import_dir) = (root.join("importer"), root.join("imports"));
+        fs::create_dir_all(&importer_dir).unwrap();
+        fs::create_dir_all(&import_dir).unwrap();
+        fs::write(importer_dir.join("main.vrl"), "").unwrap();
+        fs::write(importer_dir.join("both.vrl"), "").unwrap();
+        fs::write(import_dir.join("both.vrl"), "").unwrap();
+        fs::write(import_dir.join("only.vrl"), "").unwrap();
+
+        let importer = importer_dir.join("main.vrl");
+        let import_paths = [import_dir.clone()];
+        let canonical = |path: PathBuf| fs::canonicalize(path).unwrap();
+
+        assert_eq!(
+            resolve("both.vrl", Some(&importer), &import_paths),
+            canonical(importer_dir.join("both.vrl"))
+        );
+        assert_eq!(
+            resolve("both.vrl", None, &import_paths),
+            canonical(import_dir.join("both.vrl"))
+        );
+        assert_eq!(
+            resolve("only.vrl", Some(&importer), &import_paths),
+            canonical(import_dir.join("only.vrl"))
+        );
+        assert_eq!(
+            resolve("missing.vrl", Some(&importer), &import_paths),
+            PathBuf::from("missing.vrl")
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
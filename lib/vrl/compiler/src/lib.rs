@@ -32,8 +32,12 @@ mod compile_config;
 mod compiler;
 mod context;
 mod deprecation_warning;
+mod import;
+mod profile;
 mod program;
+mod state_store;
 mod test_util;
+mod tracer;
 
 pub mod expression;
 pub mod function;
@@ -57,8 +61,11 @@ pub(crate) use diagnostic::Span;
 pub use expression::{Expression, FunctionExpression};
 pub use function::{Function, Parameter};
 pub use paste::paste;
+pub use profile::{Profile, ProfileEntry};
 pub use program::{Program, ProgramInfo};
 pub use state::{TypeInfo, TypeState};
+pub use state_store::{StateError, StateLimits, StateStore};
+pub use tracer::{SourceMap, Tracer, WriteTracer};
 pub use type_def::TypeDef;
 use vector_config::configurable_component;
 
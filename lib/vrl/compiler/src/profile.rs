@@ -0,0 +1,241 @@
+//! Execution cost of the parts of a program, collected while it runs.
+use std::{collections::HashMap, fmt::Write, time::Duration};
+
+use crate::{SourceMap, Span};
+
+/// The number of evaluations and the time spent in the expressions and
+/// function calls of a program, by location in the source.
+///
+/// Durations are inclusive: the time of a function call includes the time
+/// spent evaluating its arguments, and the time of an expression includes the
+/// time of the function calls it makes.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    entries: HashMap<(Span, bool), ProfileEntry>,
+}
+
+/// The cost of a single expression or function call of a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProfileEntry {
+    pub span: Span,
+
+    /// The name of the called function, or `None` for the top-level
+    /// expressions of the program and of its blocks.
+    pub function: Option<String>,
+
+    /// The number of times it was evaluated.
+    pub count: u64,
+
+    /// The total time spent evaluating it.
+    pub duration: Duration,
+}
+
+impl ProfileEntry {
+    fn new(span: Span, function: Option<String>) -> Self {
+        Self {
+            span,
+            function,
+            count: 0,
+            duration: Duration::ZERO,
+        }
+    }
+}
+
+impl Profile {
+    pub(crate) fn record_expression(&mut self, span: Span, duration: Duration) {
+        let entry = self
+            .entries
+            .entry((span, false))
+            .or_insert_with(|| ProfileEntry::new(span, None));
+        entry.count += 1;
+        entry.duration += duration;
+    }
+
+    pub(crate) fn record_function_call(&mut self, span: Span, function: &str, duration: Duration) {
+        let entry = self
+            .entries
+            .entry((span, true))
+            .or_insert_with(|| ProfileEntry::new(span, Some(function.to_owned())));
+        entry.count += 1;
+        entry.duration += duration;
+    }
+
+    /// Returns the entries, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &ProfileEntry> {
+        self.entries.values()
+    }
+
+    /// Returns the entries, from the most to the least expensive.
+    #[must_use]
+    pub fn entries(&self) -> Vec<&ProfileEntry> {
+        let mut entries = self.entries.values().collect::<Vec<_>>();
+        entries.sort_by(|a, b| {
+            b.duration
+                .cmp(&a.duration)
+                .then_with(|| a.span.cmp(&b.span))
+                .then_with(|| a.function.cmp(&b.function))
+        });
+        entries
+    }
+
+    /// Adds the counts and durations of `other` to this profile.
+    pub fn merge(&mut self, other: &Profile) {
+        for (key, other) in &other.entries {
+            let entry = self
+                .entries
+                .entry(*key)
+                .or_insert_with(|| ProfileEntry::new(other.span, other.function.clone()));
+            entry.count += other.count;
+            entry.duration += other.duration;
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Renders a report of the profile, from the most to the least expensive
+    /// entries, mapped back to the lines of `source`.
+    #[must_use]
+    pub fn report(&self, source: &str) -> String {
+        let source_map = SourceMap::new(source);
+        let mut report = format!(
+            "{:<10} {:>10} {:>12} {:>12}  {}\n",
+            "location", "count", "total", "mean", "expression"
+        );
+
+        for entry in self.entries() {
+            let mean = u32::try_from(entry.count)
+                .ok()
+                .filter(|count| *count > 0)
+                .map_or(entry.duration, |count| entry.duration / count);
+            let total = format!("{:.2?}", entry.duration);
+            let mean = format!("{:.2?}", mean);
+            let expression = match &entry.function {
+                Some(function) => format!("{}(…)", function),
+                None => snippet(source, entry.span),
+            };
+
+            let _ = writeln!(
+                report,
+                "{:<10} {:>10} {:>12} {:>12}  {}",
+                source_map.format_span(entry.span),
+                entry.count,
+                total,
+                mean,
+                expression,
+            );
+        }
+
+        report
+    }
+}
+
+/// Returns the first line of the source of `span`, shortened if needed.
+fn snippet(source: &str, span: Span) -> String {
+    const MAX_LENGTH: usize = 60;
+
+    let text = source.get(span.start()..span.end()).unwrap_or_default();
+    let line = text.lines().next().unwrap_or_default();
+
+    if line.chars().count() > MAX_LENGTH || line.len() < text.trim_end().len() {
+        let line = line.chars().take(MAX_LENGTH).collect::<String>();
+        format!("{}…", line.trim_end())
+    } else {
+        line.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use value::Value;
+    use vector_common::TimeZone;
+
+    use super::*;
+    use crate::{state, CompileConfig, Compiler, Context, Function, TypeState};
+
+    #[test]
+    fn merge_and_sort() {
+        let mut profile = Profile::default();
+        profile.record_expression(Span::new(0, 5), Duration::from_millis(1));
+        profile.record_function_call(Span::new(6, 12), "upcase", Duration::from_millis(2));
+
+        let mut other = Profile::default();
+        other.record_expression(Span::new(0, 5), Duration::from_millis(3));
+        profile.merge(&other);
+
+        assert_eq!(
+            profile.entries(),
+            vec![
+                &ProfileEntry {
+                    span: Span::new(0, 5),
+                    function: None,
+                    count: 2,
+                    duration: Duration::from_millis(4),
+                },
+                &ProfileEntry {
+                    span: Span::new(6, 12),
+                    function: Some("upcase".to_owned()),
+                    count: 1,
+                    duration: Duration::from_millis(2),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn profiles_blocks() {
+        let source = ".foo = 1\nif true { .bar = 2 }";
+        let functions: Vec<Box<dyn Function>> = vec![];
+        let program = Compiler::compile(
+            &functions,
+            parser::parse(source).unwrap(),
+            &TypeState::default(),
+            CompileConfig::default(),
+        )
+        .unwrap()
+        .program;
+
+        let mut profile = Profile::default();
+        for _ in 0..3 {
+            let mut target = Value::from(BTreeMap::default());
+            let mut runtime_state = state::Runtime::default();
+            let timezone = TimeZone::default();
+            let mut ctx =
+                Context::new(&mut target, &mut runtime_state, &timezone).with_profile(&mut profile);
+            program.resolve(&mut ctx).unwrap();
+        }
+
+        let mut spans = profile
+            .entries()
+            .into_iter()
+            .map(|entry| (entry.span, entry.count))
+            .collect::<Vec<_>>();
+        spans.sort();
+
+        assert_eq!(
+            spans,
+            vec![
+                (Span::new(0, 8), 3),
+                (Span::new(9, 29), 3),
+                (Span::new(19, 27), 3)
+            ]
+        );
+        assert!(profile.report(source).contains("2:11"));
+    }
+
+    #[test]
+    fn snippet_is_shortened() {
+        let source = "if true {\n  .foo = 1\n}";
+
+        assert_eq!(snippet(source, Span::new(0, 9)), "if true {");
+        assert_eq!(snippet(source, Span::new(0, source.len())), "if true {…");
+    }
+}
@@ -193,6 +193,17 @@ impl Runtime {
         self.variables.remove(ident);
     }
 
+    /// Replaces all variables, returning the previous ones.
+    ///
+    /// This is used to evaluate user-defined functions, which only see their own variables.
+    #[cfg(feature = "expr-function_call")]
+    pub(crate) fn swap_variables(
+        &mut self,
+        variables: HashMap<Ident, Value>,
+    ) -> HashMap<Ident, Value> {
+        std::mem::replace(&mut self.variables, variables)
+    }
+
     pub(crate) fn swap_variable(&mut self, ident: Ident, value: Value) -> Option<Value> {
         match self.variables.entry(ident) {
             Entry::Occupied(mut v) => Some(std::mem::replace(v.get_mut(), value)),
@@ -0,0 +1,376 @@
+//! Key/value state kept between the executions of a program.
+//!
+//! The store is shared by every execution of the same program instance, so
+//! that a program can remember values across events. It is bounded both in
+//! number of entries and in bytes: expired entries are evicted first, and then
+//! the least recently used ones.
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use value::Value;
+use vector_common::byte_size_of::ByteSizeOf;
+
+/// The limits of a [`StateStore`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StateLimits {
+    /// The maximum number of entries.
+    pub max_entries: usize,
+
+    /// The maximum size of all the entries, keys included.
+    pub max_bytes: usize,
+
+    /// The time-to-live of entries set without an explicit one. Entries never
+    /// expire if this is `None`.
+    pub default_ttl: Option<Duration>,
+}
+
+impl Default for StateLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+            max_bytes: 10 * 1024 * 1024,
+            default_ttl: None,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+pub enum StateError {
+    #[error("state value of {size} bytes exceeds the state size limit of {max} bytes")]
+    TooLarge { size: usize, max: usize },
+
+    #[error("state value for key {key:?} is not an integer")]
+    NotAnInteger { key: String },
+
+    #[error("state value for key {key:?} overflows")]
+    Overflow { key: String },
+}
+
+/// A bounded key/value store, cheaply cloneable and shared between clones.
+#[derive(Clone, Debug, Default)]
+pub struct StateStore {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl StateStore {
+    #[must_use]
+    pub fn new(limits: StateLimits) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner::new(limits))),
+        }
+    }
+
+    /// Returns the value of `key`, if it is set and hasn't expired.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<Value> {
+        self.lock().get(key, Instant::now())
+    }
+
+    /// Sets `key` to `value`, expiring after `ttl`, or after the default
+    /// time-to-live of the store if `ttl` is `None`.
+    pub fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StateError> {
+        self.lock().set(key, value, ttl, Instant::now())
+    }
+
+    /// Adds `by` to the integer value of `key`, which is set to `by` if it
+    /// isn't set yet, and returns the new value.
+    ///
+    /// The expiry of an existing entry is only changed if `ttl` is given.
+    pub fn increment(&self, key: &str, by: i64, ttl: Option<Duration>) -> Result<i64, StateError> {
+        self.lock().increment(key, by, ttl, Instant::now())
+    }
+
+    /// Returns the number of entries, including the expired entries that
+    /// haven't been evicted yet.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().expect("state store lock poisoned")
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    value: Value,
+    size: usize,
+    expires_at: Option<Instant>,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    limits: StateLimits,
+    entries: HashMap<String, Entry>,
+    bytes: usize,
+
+    /// Incremented on every access, to order the entries by recency.
+    clock: u64,
+    by_recency: BTreeMap<u64, String>,
+    by_expiry: BTreeSet<(Instant, String)>,
+}
+
+impl Inner {
+    fn new(limits: StateLimits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    fn get(&mut self, key: &str, now: Instant) -> Option<Value> {
+        self.evict_expired(now);
+        self.touch(key);
+        self.entries.get(key).map(|entry| entry.value.clone())
+    }
+
+    fn set(
+        &mut self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        now: Instant,
+    ) -> Result<(), StateError> {
+        let size = key.len() + value.size_of();
+        if size > self.limits.max_bytes {
+            return Err(StateError::TooLarge {
+                size,
+                max: self.limits.max_bytes,
+            });
+        }
+
+        self.evict_expired(now);
+        self.remove(key);
+
+        while self.entries.len() >= self.limits.max_entries
+            || self.bytes + size > self.limits.max_bytes
+        {
+            match self.by_recency.values().next().cloned() {
+                Some(least_recent) => self.remove(&least_recent),
+                None => break,
+            }
+        }
+
+        let expires_at = ttl.or(self.limits.default_ttl).map(|ttl| now + ttl);
+        if let Some(expires_at) = expires_at {
+            self.by_expiry.insert((expires_at, key.to_owned()));
+        }
+        self.clock += 1;
+        self.by_recency.insert(self.clock, key.to_owned());
+        self.bytes += size;
+        self.entries.insert(
+            key.to_owned(),
+            Entry {
+                value,
+                size,
+                expires_at,
+                last_used: self.clock,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn increment(
+        &mut self,
+        key: &str,
+        by: i64,
+        ttl: Option<Duration>,
+        now: Instant,
+    ) -> Result<i64, StateError> {
+        self.evict_expired(now);
+
+        let (current, expires_at) = match self.entries.get(key) {
+            Some(Entry {
+                value: Value::Integer(current),
+                expires_at,
+                ..
+            }) => (*current, *expires_at),
+            Some(_) => {
+                return Err(StateError::NotAnInteger {
+                    key: key.to_owned(),
+                })
+            }
+            None => (0, None),
+        };
+
+        let value = current
+            .checked_add(by)
+            .ok_or_else(|| StateError::Overflow {
+                key: key.to_owned(),
+            })?;
+
+        // Keep the expiry of the existing entry, unless a new one is given.
+        let ttl = ttl.or_else(|| expires_at.map(|expires_at| expires_at - now));
+        self.set(key, Value::Integer(value), ttl, now)?;
+
+        Ok(value)
+    }
+
+    fn touch(&mut self, key: &str) {
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.clock += 1;
+            self.by_recency.remove(&entry.last_used);
+            self.by_recency.insert(self.clock, key.to_owned());
+            entry.last_used = self.clock;
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.bytes -= entry.size;
+            self.by_recency.remove(&entry.last_used);
+            if let Some(expires_at) = entry.expires_at {
+                self.by_expiry.remove(&(expires_at, key.to_owned()));
+            }
+        }
+    }
+
+    fn evict_expired(&mut self, now: Instant) {
+        while let Some((expires_at, key)) = self.by_expiry.iter().next().cloned() {
+            if expires_at > now {
+                break;
+            }
+            self.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(max_entries: usize, max_bytes: usize) -> Inner {
+        Inner::new(StateLimits {
+            max_entries,
+            max_bytes,
+            default_ttl: None,
+        })
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut store = store(10, 1024);
+        let now = Instant::now();
+
+        assert_eq!(store.get("foo", now), None);
+        store.set("foo", "bar".into(), None, now).unwrap();
+        assert_eq!(store.get("foo", now), Some("bar".into()));
+        store.set("foo", 1.into(), None, now).unwrap();
+        assert_eq!(store.get("foo", now), Some(1.into()));
+        assert_eq!(store.entries.len(), 1);
+    }
+
+    #[test]
+    fn ttl_expiry() {
+        let mut store = Inner::new(StateLimits {
+            default_ttl: Some(Duration::from_secs(60)),
+            ..StateLimits::default()
+        });
+        let now = Instant::now();
+
+        store.set("default", true.into(), None, now).unwrap();
+        store
+            .set("short", true.into(), Some(Duration::from_secs(1)), now)
+            .unwrap();
+
+        assert_eq!(store.get("short", now), Some(true.into()));
+        assert_eq!(store.get("short", now + Duration::from_secs(1)), None);
+        assert_eq!(
+            store.get("default", now + Duration::from_secs(59)),
+            Some(true.into())
+        );
+        assert_eq!(store.get("default", now + Duration::from_secs(60)), None);
+        assert!(store.entries.is_empty());
+        assert_eq!(store.bytes, 0);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut store = store(2, 1024);
+        let now = Instant::now();
+
+        store.set("a", 1.into(), None, now).unwrap();
+        store.set("b", 2.into(), None, now).unwrap();
+        store.get("a", now);
+        store.set("c", 3.into(), None, now).unwrap();
+
+        assert_eq!(store.get("a", now), Some(1.into()));
+        assert_eq!(store.get("b", now), None);
+        assert_eq!(store.get("c", now), Some(3.into()));
+    }
+
+    #[test]
+    fn evicts_to_fit_bytes() {
+        let value = Value::from("x".repeat(100));
+        let size = "a".len() + value.size_of();
+        let mut store = store(10, size * 2);
+        let now = Instant::now();
+
+        store.set("a", value.clone(), None, now).unwrap();
+        store.set("b", value.clone(), None, now).unwrap();
+        store.set("c", value.clone(), None, now).unwrap();
+
+        assert_eq!(store.get("a", now), None);
+        assert_eq!(store.entries.len(), 2);
+        assert_eq!(store.bytes, size * 2);
+        assert_eq!(
+            store.set("d", Value::from("x".repeat(1000)), None, now),
+            Err(StateError::TooLarge {
+                size: 1 + Value::from("x".repeat(1000)).size_of(),
+                max: size * 2
+            })
+        );
+    }
+
+    #[test]
+    fn increment() {
+        let mut store = store(10, 1024);
+        let now = Instant::now();
+
+        assert_eq!(store.increment("count", 1, None, now), Ok(1));
+        assert_eq!(store.increment("count", 5, None, now), Ok(6));
+        assert_eq!(store.increment("count", -2, None, now), Ok(4));
+
+        store.set("text", "foo".into(), None, now).unwrap();
+        assert_eq!(
+            store.increment("text", 1, None, now),
+            Err(StateError::NotAnInteger { key: "text".into() })
+        );
+
+        store.set("max", i64::MAX.into(), None, now).unwrap();
+        assert_eq!(
+            store.increment("max", 1, None, now),
+            Err(StateError::Overflow { key: "max".into() })
+        );
+    }
+
+    #[test]
+    fn increment_keeps_expiry() {
+        let mut store = store(10, 1024);
+        let now = Instant::now();
+
+        store
+            .increment("count", 1, Some(Duration::from_secs(10)), now)
+            .unwrap();
+        store
+            .increment("count", 1, None, now + Duration::from_secs(5))
+            .unwrap();
+
+        assert_eq!(
+            store.get("count", now + Duration::from_secs(9)),
+            Some(2.into())
+        );
+        assert_eq!(store.get("count", now + Duration::from_secs(10)), None);
+    }
+}
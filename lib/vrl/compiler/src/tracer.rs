@@ -0,0 +1,179 @@
+//! Hooks to observe a program while it runs, used to trace or step through it.
+use std::fmt;
+
+use value::Value;
+
+use crate::{
+    expression::{Expr, Resolved},
+    Span, Target,
+};
+
+/// Observes the evaluation of a [`Program`](crate::Program).
+///
+/// Only the expressions of blocks are reported: the top-level expressions of
+/// the program, and those of `if` statements, closures and function bodies.
+/// Assignments are reported wherever they happen.
+pub trait Tracer {
+    /// Called before `expr` is evaluated, with the target as it is before the
+    /// evaluation.
+    fn before_expression(&mut self, _span: Span, _expr: &Expr, _target: &dyn Target) {}
+
+    /// Called after `expr` is evaluated, with its result.
+    fn after_expression(&mut self, _span: Span, _expr: &Expr, _result: &Resolved) {}
+
+    /// Called when `value` is assigned to a variable or to a path of the
+    /// target.
+    fn on_assignment(&mut self, _target: &dyn fmt::Display, _value: &Value) {}
+}
+
+/// Maps the byte offsets of a [`Span`] to lines and columns of the source.
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    line_starts: Vec<usize>,
+    len: usize,
+}
+
+impl SourceMap {
+    #[must_use]
+    pub fn new(source: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        Self {
+            line_starts,
+            len: source.len(),
+        }
+    }
+
+    /// Returns the one-based line and column of `offset`, if it is within the
+    /// source.
+    #[must_use]
+    pub fn line_column(&self, offset: usize) -> Option<(usize, usize)> {
+        if offset > self.len {
+            return None;
+        }
+
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        };
+
+        Some((line + 1, offset - self.line_starts[line] + 1))
+    }
+
+    /// Formats `span` as `line:column`, or as a byte range if it isn't within
+    /// the source, as is the case for functions imported from other files.
+    #[must_use]
+    pub fn format_span(&self, span: Span) -> String {
+        match self.line_column(span.start()) {
+            Some((line, column)) if span.end() <= self.len => format!("{}:{}", line, column),
+            _ => format!("{}..{}", span.start(), span.end()),
+        }
+    }
+}
+
+/// A [`Tracer`] writing every evaluated expression with its location, followed
+/// by the assignments it makes and its result, indented under it.
+pub struct WriteTracer<W> {
+    writer: W,
+    source_map: SourceMap,
+    depth: usize,
+}
+
+impl<W: std::io::Write> WriteTracer<W> {
+    /// Creates a tracer for a program compiled from `source`.
+    #[must_use]
+    pub fn new(writer: W, source: &str) -> Self {
+        Self {
+            writer,
+            source_map: SourceMap::new(source),
+            depth: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_line(&mut self, line: fmt::Arguments<'_>) {
+        // Tracing is best-effort, and must not interrupt the program.
+        let _ = writeln!(
+            self.writer,
+            "{:indent$}{}",
+            "",
+            line,
+            indent = self.depth * 2
+        );
+    }
+}
+
+impl<W: std::io::Write> Tracer for WriteTracer<W> {
+    fn before_expression(&mut self, span: Span, expr: &Expr, _target: &dyn Target) {
+        let location = self.source_map.format_span(span);
+        self.write_line(format_args!("{}: {}", location, expr));
+        self.depth += 1;
+    }
+
+    fn after_expression(&mut self, _span: Span, _expr: &Expr, result: &Resolved) {
+        match result {
+            Ok(value) => self.write_line(format_args!("=> {}", value)),
+            Err(error) => self.write_line(format_args!("=> error: {}", error)),
+        }
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    fn on_assignment(&mut self, target: &dyn fmt::Display, value: &Value) {
+        self.write_line(format_args!("{} = {}", target, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use vector_common::TimeZone;
+
+    use super::*;
+    use crate::{state, CompileConfig, Compiler, Context, TypeState};
+
+    #[test]
+    fn write_tracer() {
+        let source = ".foo = 1\nif true { x = 2 }";
+        let program = Compiler::compile(
+            &[],
+            parser::parse(source).unwrap(),
+            &TypeState::default(),
+            CompileConfig::default(),
+        )
+        .unwrap()
+        .program;
+
+        let mut target = Value::from(BTreeMap::default());
+        let mut runtime_state = state::Runtime::default();
+        let timezone = TimeZone::default();
+        let mut tracer = WriteTracer::new(Vec::new(), source);
+        let mut ctx =
+            Context::new(&mut target, &mut runtime_state, &timezone).with_tracer(&mut tracer);
+
+        assert_eq!(program.resolve(&mut ctx).unwrap(), Value::from(2));
+
+        let trace = String::from_utf8(tracer.into_inner()).unwrap();
+        assert!(trace.starts_with("1:1: .foo = 1\n  .foo = 1\n  => 1\n2:1: if true"));
+        assert!(trace.ends_with("  2:11: x = 2\n    x = 2\n    => 2\n  => 2\n"));
+    }
+
+    #[test]
+    fn source_map_line_column() {
+        let map = SourceMap::new(".foo = 1\n\n.bar = 2\n");
+
+        assert_eq!(map.line_column(0), Some((1, 1)));
+        assert_eq!(map.line_column(7), Some((1, 8)));
+        assert_eq!(map.line_column(9), Some((2, 1)));
+        assert_eq!(map.line_column(11), Some((3, 2)));
+        assert_eq!(map.line_column(100), None);
+        assert_eq!(map.format_span(Span::new(10, 18)), "3:1");
+        assert_eq!(map.format_span(Span::new(10, 100)), "10..100");
+    }
+}
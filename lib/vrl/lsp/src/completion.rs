@@ -0,0 +1,133 @@
+use std::collections::HashSet;
+
+use diagnostic::Span;
+use lsp_types::{CompletionItem, CompletionItemKind, CompletionTextEdit, Documentation, TextEdit};
+use vrl::Function;
+
+use crate::{definition, document::Document, hover::signature};
+
+/// Returns the completions at `offset`.
+///
+/// Event paths are completed after a `.` or `%`. Otherwise, function names and
+/// variables are completed, along with the parameters of the function whose
+/// argument list surrounds `offset`.
+pub(crate) fn completions(
+    document: &Document,
+    offset: usize,
+    functions: &[Box<dyn Function>],
+) -> Vec<CompletionItem> {
+    let source = document.source();
+
+    let path_start = source[..offset]
+        .rfind(|char: char| !(char.is_ascii_alphanumeric() || matches!(char, '_' | '.' | '%')))
+        .map_or(0, |index| index + 1);
+    if matches!(source[path_start..offset].chars().next(), Some('.' | '%')) {
+        return path_completions(document, Span::new(path_start, offset));
+    }
+
+    let mut items = vec![];
+
+    if let Some(function) = enclosing_call(source, offset)
+        .and_then(|ident| functions.iter().find(|f| f.identifier() == ident))
+    {
+        items.extend(
+            function
+                .parameters()
+                .iter()
+                .map(|parameter| CompletionItem {
+                    label: format!("{}:", parameter.keyword),
+                    kind: Some(CompletionItemKind::PROPERTY),
+                    detail: Some(format!("<{}>", parameter.kind())),
+                    insert_text: Some(format!("{}: ", parameter.keyword)),
+                    sort_text: Some(format!("0{}", parameter.keyword)),
+                    ..Default::default()
+                }),
+        );
+    }
+
+    let mut variables = HashSet::new();
+    for definition in definition::definitions(source) {
+        if definition.span.start() < offset && variables.insert(definition.ident.clone()) {
+            items.push(CompletionItem {
+                sort_text: Some(format!("1{}", definition.ident)),
+                label: definition.ident,
+                kind: Some(CompletionItemKind::VARIABLE),
+                ..Default::default()
+            });
+        }
+    }
+
+    items.extend(functions.iter().map(|function| {
+        CompletionItem {
+            label: function.identifier().to_owned(),
+            kind: Some(CompletionItemKind::FUNCTION),
+            detail: Some(signature(function.as_ref())),
+            documentation: (function.summary() != "TODO")
+                .then(|| Documentation::String(function.summary().to_owned())),
+            sort_text: Some(format!("2{}", function.identifier())),
+            ..Default::default()
+        }
+    }));
+
+    items
+}
+
+/// Completes the event paths used by the program, replacing the partial path
+/// in `span`.
+fn path_completions(document: &Document, span: Span) -> Vec<CompletionItem> {
+    let range = document.range(span);
+
+    document
+        .paths
+        .iter()
+        .map(|path| CompletionItem {
+            label: path.clone(),
+            kind: Some(CompletionItemKind::FIELD),
+            text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(range, path.clone()))),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Returns the name of the function whose argument list surrounds `offset`.
+///
+/// This is a best-effort scan of the source, which also works on programs
+/// that don't parse yet. Brackets inside string literals aren't accounted for.
+fn enclosing_call(source: &str, offset: usize) -> Option<&str> {
+    let mut depth = 0_usize;
+
+    for (index, char) in source[..offset].char_indices().rev() {
+        match char {
+            ')' | ']' | '}' => depth += 1,
+            '[' | '{' if depth == 0 => return None,
+            '(' if depth == 0 => {
+                let before = source[..index].trim_end_matches('!');
+                let start = before
+                    .rfind(|char: char| !(char.is_ascii_alphanumeric() || char == '_'))
+                    .map_or(0, |index| index + 1);
+
+                return (start < before.len()).then(|| &before[start..]);
+            }
+            '(' | '[' | '{' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enclosing_calls() {
+        let source = "upcase(.message) + replace!(.foo, [\"a\"], ";
+
+        assert_eq!(enclosing_call(source, 7), Some("upcase"));
+        assert_eq!(enclosing_call(source, 16), None);
+        assert_eq!(enclosing_call(source, source.len()), Some("replace"));
+        assert_eq!(enclosing_call(source, source.find("\"a").unwrap()), None);
+        assert_eq!(enclosing_call("(1 + ", 5), None);
+    }
+}
@@ -0,0 +1,178 @@
+use diagnostic::Span;
+use parser::ast::{
+    Assignment, AssignmentTarget, Container, Expr, FunctionCall, Node, Predicate, QueryTarget,
+    RootExpr, Unary,
+};
+
+/// A variable, closure variable, function parameter or user-defined function,
+/// with the span of its definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Definition {
+    pub(crate) ident: String,
+    pub(crate) span: Span,
+}
+
+/// Returns the definitions in a program, in source order.
+///
+/// Programs that don't parse have no definitions.
+pub(crate) fn definitions(source: &str) -> Vec<Definition> {
+    let mut definitions = vec![];
+
+    if let Ok(program) = parser::parse(source) {
+        for root_expr in program {
+            match root_expr.into_inner() {
+                RootExpr::Expr(node) => walk(node, &mut definitions),
+                RootExpr::FunctionDefinition(node) => {
+                    let definition = node.into_inner();
+                    define(&definition.ident, &mut definitions);
+                    for parameter in &definition.parameters {
+                        define(parameter, &mut definitions);
+                    }
+                    walk_all(definition.block.into_inner().into_inner(), &mut definitions);
+                }
+                RootExpr::Import(_) | RootExpr::Error(_) => {}
+            }
+        }
+    }
+
+    definitions.sort_by_key(|definition| definition.span.start());
+    definitions
+}
+
+/// Returns the span of the first definition of `ident`.
+pub(crate) fn find(definitions: &[Definition], ident: &str) -> Option<Span> {
+    definitions
+        .iter()
+        .find(|definition| definition.ident == ident)
+        .map(|definition| definition.span)
+}
+
+fn define(ident: &Node<parser::ast::Ident>, definitions: &mut Vec<Definition>) {
+    definitions.push(Definition {
+        ident: ident.inner().to_string(),
+        span: ident.span(),
+    });
+}
+
+fn define_target(target: Node<AssignmentTarget>, definitions: &mut Vec<Definition>) {
+    let (span, target) = target.take();
+
+    if let AssignmentTarget::Internal(ident, _) = target {
+        definitions.push(Definition {
+            ident: ident.to_string(),
+            span,
+        });
+    }
+}
+
+fn walk_all(nodes: impl IntoIterator<Item = Node<Expr>>, definitions: &mut Vec<Definition>) {
+    for node in nodes {
+        walk(node, definitions);
+    }
+}
+
+fn walk(node: Node<Expr>, definitions: &mut Vec<Definition>) {
+    match node.into_inner() {
+        Expr::Literal(_) | Expr::Variable(_) => {}
+        Expr::Container(node) => walk_container(node.into_inner(), definitions),
+        Expr::IfStatement(node) => {
+            let statement = node.into_inner();
+            match statement.predicate.into_inner() {
+                Predicate::One(node) => walk(*node, definitions),
+                Predicate::Many(nodes) => walk_all(nodes, definitions),
+            }
+            walk_all(statement.if_node.into_inner().into_inner(), definitions);
+            if let Some(else_node) = statement.else_node {
+                walk_all(else_node.into_inner().into_inner(), definitions);
+            }
+        }
+        Expr::Op(node) => {
+            let op = node.into_inner();
+            walk(*op.0, definitions);
+            walk(*op.2, definitions);
+        }
+        Expr::Assignment(node) => match node.into_inner() {
+            Assignment::Single { target, expr, .. } => {
+                define_target(target, definitions);
+                walk(*expr, definitions);
+            }
+            Assignment::Infallible { ok, err, expr, .. } => {
+                define_target(ok, definitions);
+                define_target(err, definitions);
+                walk(*expr, definitions);
+            }
+        },
+        Expr::Query(node) => match node.into_inner().target.into_inner() {
+            QueryTarget::FunctionCall(call) => walk_call(call, definitions),
+            QueryTarget::Container(container) => walk_container(container, definitions),
+            QueryTarget::Internal(_) | QueryTarget::External(_) => {}
+        },
+        Expr::FunctionCall(node) => walk_call(node.into_inner(), definitions),
+        Expr::Unary(node) => match node.into_inner() {
+            Unary::Not(node) => walk(*node.into_inner().take().1, definitions),
+        },
+        Expr::Abort(node) => {
+            if let Some(message) = node.into_inner().message {
+                walk(*message, definitions);
+            }
+        }
+    }
+}
+
+fn walk_container(container: Container, definitions: &mut Vec<Definition>) {
+    match container {
+        Container::Group(node) => walk(node.into_inner().into_inner(), definitions),
+        Container::Block(node) => walk_all(node.into_inner().into_inner(), definitions),
+        Container::Array(node) => walk_all(node.into_inner(), definitions),
+        Container::Object(node) => walk_all(
+            node.into_inner().into_iter().map(|(_, expr)| expr),
+            definitions,
+        ),
+    }
+}
+
+fn walk_call(call: FunctionCall, definitions: &mut Vec<Definition>) {
+    for argument in call.arguments {
+        walk(argument.into_inner().expr, definitions);
+    }
+
+    if let Some(closure) = call.closure {
+        let closure = closure.into_inner();
+        for variable in &closure.variables {
+            define(variable, definitions);
+        }
+        walk_all(closure.block.into_inner().into_inner(), definitions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn program_definitions() {
+        let source = indoc::indoc! {r#"
+            foo = 1
+            if true {
+                bar, err = parse_json(.message)
+            }
+            for_each(.) -> |key, _value| { baz = key }
+        "#};
+
+        let idents = definitions(source)
+            .into_iter()
+            .map(|definition| definition.ident)
+            .collect::<Vec<_>>();
+
+        assert_eq!(idents, ["foo", "bar", "err", "key", "_value", "baz"]);
+    }
+
+    #[test]
+    fn find_first_definition() {
+        let source = "foo = 1\nfoo = foo + 1\nfoo";
+        let definitions = definitions(source);
+
+        assert_eq!(find(&definitions, "foo"), Some(Span::new(0, 3)));
+        assert_eq!(find(&definitions, "bar"), None);
+    }
+}
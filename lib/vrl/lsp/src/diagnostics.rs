@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+
+use diagnostic::{Diagnostic, Severity};
+use lsp_types::{DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString, Url};
+use vrl::{state::TypeState, CompilationResult, CompileConfig, Function};
+
+use crate::document::Document;
+
+/// The error reported by the enrichment table functions when compiled without
+/// the enrichment tables, which are only known to a running Vector instance.
+///
+/// It is wrapped in a function compilation error, with code 610.
+const TABLES_NOT_LOADED: (usize, &str) = (610, "enrichment tables not loaded");
+
+/// Compiles a document, returning its diagnostics.
+///
+/// If the program compiles, the event paths it uses are stored in the
+/// document for completion.
+pub(crate) fn analyze(
+    uri: &Url,
+    document: &mut Document,
+    functions: &[Box<dyn Function>],
+) -> Vec<lsp_types::Diagnostic> {
+    let mut config = CompileConfig::default();
+    if let Some(dir) = uri
+        .to_file_path()
+        .ok()
+        .and_then(|path| path.parent().map(PathBuf::from))
+    {
+        config.set_import_paths(vec![dir]);
+    }
+
+    let diagnostics = match vrl::compile_with_state(
+        document.source(),
+        functions,
+        &TypeState::default(),
+        config,
+    ) {
+        Ok(CompilationResult {
+            program, warnings, ..
+        }) => {
+            let info = program.info();
+            let mut paths = info
+                .target_queries
+                .iter()
+                .chain(&info.target_assignments)
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            paths.sort();
+            paths.dedup();
+            document.paths = paths;
+
+            warnings
+        }
+        Err(errors) => errors,
+    };
+
+    diagnostics
+        .into_iter()
+        .filter(|diagnostic| {
+            let (code, message) = TABLES_NOT_LOADED;
+            !(diagnostic.code == code && diagnostic.message.ends_with(message))
+        })
+        .map(|diagnostic| to_lsp(uri, document, diagnostic))
+        .collect()
+}
+
+fn to_lsp(uri: &Url, document: &Document, diagnostic: Diagnostic) -> lsp_types::Diagnostic {
+    let Diagnostic {
+        severity,
+        code,
+        message,
+        labels,
+        notes,
+    } = diagnostic;
+
+    let severity = match severity {
+        Severity::Bug | Severity::Error => DiagnosticSeverity::ERROR,
+        Severity::Warning => DiagnosticSeverity::WARNING,
+        Severity::Note => DiagnosticSeverity::INFORMATION,
+    };
+
+    // The primary label locates the diagnostic, and the other labels are
+    // reported as related information.
+    let (primary, context): (Vec<_>, Vec<_>) = labels.into_iter().partition(|label| label.primary);
+    let range = primary
+        .first()
+        .or_else(|| context.first())
+        .map(|label| document.range(label.span))
+        .unwrap_or_default();
+
+    let message = std::iter::once(message)
+        .chain(
+            primary
+                .into_iter()
+                .map(|label| label.message)
+                .filter(|message| !message.is_empty()),
+        )
+        .chain(notes.iter().map(ToString::to_string))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let related_information = context
+        .into_iter()
+        .map(|label| DiagnosticRelatedInformation {
+            location: Location::new(uri.clone(), document.range(label.span)),
+            message: label.message,
+        })
+        .collect::<Vec<_>>();
+
+    lsp_types::Diagnostic {
+        range,
+        severity: Some(severity),
+        code: Some(NumberOrString::String(format!("E{:03}", code))),
+        source: Some("vrl".to_owned()),
+        message,
+        related_information: (!related_information.is_empty()).then_some(related_information),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn functions() -> Vec<Box<dyn Function>> {
+        let mut functions = stdlib::all();
+        functions.append(&mut enrichment::vrl_functions());
+        functions
+    }
+
+    #[test]
+    fn enrichment_table_functions() {
+        let uri = Url::parse("file:///tmp/program.vrl").unwrap();
+        let mut document = Document::new(
+            r#"get_enrichment_table_record!("users", { "id": .user_id })"#.to_owned(),
+        );
+
+        assert!(analyze(&uri, &mut document, &functions()).is_empty());
+    }
+
+    #[test]
+    fn undefined_function() {
+        let uri = Url::parse("file:///tmp/program.vrl").unwrap();
+        let mut document = Document::new("get_table_record!(.user_id)".to_owned());
+
+        let diagnostics = analyze(&uri, &mut document, &functions());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].code,
+            Some(NumberOrString::String("E105".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use diagnostic::Span;
+use lsp_types::{Position, Range};
+
+/// An open VRL program, with the information needed to map between byte
+/// offsets and the line/character positions used by the protocol.
+#[derive(Debug)]
+pub(crate) struct Document {
+    source: String,
+
+    /// The byte offset of the start of each line.
+    line_starts: Vec<usize>,
+
+    /// The event paths used by the last version of the program that compiled,
+    /// kept around to complete paths while the program is being edited.
+    pub(crate) paths: Vec<String>,
+}
+
+impl Document {
+    pub(crate) fn new(source: String) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        Self {
+            source,
+            line_starts,
+            paths: vec![],
+        }
+    }
+
+    /// Replaces the source of the document, keeping the known event paths.
+    pub(crate) fn update(&mut self, source: String) {
+        let paths = std::mem::take(&mut self.paths);
+        *self = Self::new(source);
+        self.paths = paths;
+    }
+
+    pub(crate) fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Converts a byte offset to a position, counting characters in UTF-16
+    /// code units as required by the protocol.
+    pub(crate) fn position(&self, offset: usize) -> Position {
+        let offset = self.clamp(offset);
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let character = self.source[self.line_starts[line]..offset]
+            .encode_utf16()
+            .count();
+
+        Position::new(
+            u32::try_from(line).unwrap_or(u32::MAX),
+            u32::try_from(character).unwrap_or(u32::MAX),
+        )
+    }
+
+    /// Converts a position to a byte offset. Positions past the end of a line
+    /// map to the end of that line.
+    pub(crate) fn offset(&self, position: Position) -> usize {
+        let line = position.line as usize;
+        let start = match self.line_starts.get(line) {
+            Some(start) => *start,
+            None => return self.source.len(),
+        };
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.source.len(), |next| next - 1);
+
+        let mut units = 0;
+        for (index, char) in self.source[start..end].char_indices() {
+            if units >= position.character as usize {
+                return start + index;
+            }
+            units += char.len_utf16();
+        }
+
+        end
+    }
+
+    pub(crate) fn range(&self, span: Span) -> Range {
+        Range::new(self.position(span.start()), self.position(span.end()))
+    }
+
+    /// Returns the span of the identifier at `offset`, if any.
+    pub(crate) fn ident_at(&self, offset: usize) -> Option<Span> {
+        let offset = self.clamp(offset);
+        let is_ident = |char: char| char.is_ascii_alphanumeric() || char == '_';
+
+        let start = self.source[..offset]
+            .rfind(|char| !is_ident(char))
+            .map_or(0, |index| index + 1);
+        let end = self.source[offset..]
+            .find(|char| !is_ident(char))
+            .map_or(self.source.len(), |index| offset + index);
+
+        (start < end).then(|| Span::new(start, end))
+    }
+
+    fn clamp(&self, offset: usize) -> usize {
+        let mut offset = offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions() {
+        let document = Document::new(".message = \"héllo\"\n.😀 = true\n".to_owned());
+
+        let offset = document.source().find("llo").unwrap();
+        assert_eq!(document.position(offset), Position::new(0, 14));
+        assert_eq!(document.offset(Position::new(0, 14)), offset);
+
+        let offset = document.source().find(" = true").unwrap();
+        assert_eq!(document.position(offset), Position::new(1, 3));
+        assert_eq!(document.offset(Position::new(1, 3)), offset);
+
+        assert_eq!(
+            document.offset(Position::new(0, 100)),
+            document.source().find('\n').unwrap()
+        );
+        assert_eq!(
+            document.offset(Position::new(5, 0)),
+            document.source().len()
+        );
+    }
+
+    #[test]
+    fn ident_at() {
+        let document = Document::new("foo = upcase(bar_baz)".to_owned());
+
+        assert_eq!(document.ident_at(1), Some(Span::new(0, 3)));
+        assert_eq!(document.ident_at(15), Some(Span::new(13, 20)));
+        assert_eq!(document.ident_at(4), None);
+    }
+}
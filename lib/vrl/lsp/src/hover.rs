@@ -0,0 +1,81 @@
+use diagnostic::Urls;
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind};
+use vrl::Function;
+
+use crate::document::Document;
+
+/// Returns the documentation of the function called at `offset`.
+pub(crate) fn hover(
+    document: &Document,
+    offset: usize,
+    functions: &[Box<dyn Function>],
+) -> Option<Hover> {
+    let span = document.ident_at(offset)?;
+
+    // Only identifiers followed by an argument list are function calls.
+    let rest = document.source()[span.end()..].trim_start_matches('!');
+    if !rest.starts_with('(') {
+        return None;
+    }
+
+    let ident = &document.source()[span.start()..span.end()];
+    let function = functions.iter().find(|f| f.identifier() == ident)?;
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: documentation(function.as_ref()),
+        }),
+        range: Some(document.range(span)),
+    })
+}
+
+/// Returns the signature of a function, with optional parameters in brackets.
+pub(crate) fn signature(function: &dyn Function) -> String {
+    let parameters = function
+        .parameters()
+        .iter()
+        .map(|parameter| {
+            if parameter.required {
+                format!("{}: <{}>", parameter.keyword, parameter.kind())
+            } else {
+                format!("[{}: <{}>]", parameter.keyword, parameter.kind())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("{}({})", function.identifier(), parameters)
+}
+
+fn documentation(function: &dyn Function) -> String {
+    let mut docs = vec![format!("```vrl\n{}\n```", signature(function))];
+
+    for text in [function.summary(), function.usage()] {
+        if !text.is_empty() && text != "TODO" {
+            docs.push(text.trim().to_owned());
+        }
+    }
+
+    if !function.examples().is_empty() {
+        docs.push("**Examples**".to_owned());
+    }
+    for example in function.examples() {
+        let result = match example.result {
+            Ok(result) => format!("Returns `{}`", result),
+            Err(error) => format!("Fails with `{}`", error),
+        };
+
+        docs.push(format!(
+            "_{}_\n```vrl\n{}\n```\n{}",
+            example.title, example.source, result
+        ));
+    }
+
+    docs.push(format!(
+        "[Documentation]({})",
+        Urls::func_docs(function.identifier())
+    ));
+
+    docs.join("\n\n")
+}
@@ -0,0 +1,44 @@
+#![deny(
+    warnings,
+    clippy::all,
+    clippy::pedantic,
+    unreachable_pub,
+    unused_allocation,
+    unused_extern_crates,
+    unused_assignments,
+    unused_comparisons
+)]
+#![allow(clippy::module_name_repetitions, clippy::semicolon_if_nothing_returned)]
+
+//! A language server for VRL programs.
+//!
+//! The server speaks the [Language Server Protocol][lsp] over stdio, and
+//! provides diagnostics on open and save, completion of function names,
+//! parameters, variables and event paths, hover documentation for functions,
+//! and go-to-definition for variables and user-defined functions.
+//!
+//! [lsp]: https://microsoft.github.io/language-server-protocol/
+
+mod completion;
+mod definition;
+mod diagnostics;
+mod document;
+mod hover;
+mod server;
+
+pub use server::run;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("io error: {}", .0)]
+    Io(#[from] std::io::Error),
+
+    #[error("protocol error: {}", .0)]
+    Protocol(#[from] lsp_server::ProtocolError),
+
+    #[error("invalid message: {}", .0)]
+    Json(#[from] serde_json::Error),
+
+    #[error("connection closed: {}", .0)]
+    Send(#[from] crossbeam_channel::SendError<lsp_server::Message>),
+}
@@ -0,0 +1,11 @@
+extern crate vrl_lsp;
+
+fn main() {
+    if let Err(err) = vrl_lsp::run() {
+        #[allow(clippy::print_stderr)]
+        {
+            eprintln!("{}", err);
+        }
+        std::process::exit(exitcode::SOFTWARE);
+    }
+}
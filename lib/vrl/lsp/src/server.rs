@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{Completion, GotoDefinition, HoverRequest, Request as _},
+    CompletionOptions, CompletionParams, CompletionResponse, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverParams, HoverProviderCapability,
+    Location, OneOf, PublishDiagnosticsParams, SaveOptions, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, Url,
+};
+use vrl::Function;
+
+use crate::{completion, definition, diagnostics, document::Document, hover, Error};
+
+/// Serves a single client over stdio, until it shuts the server down.
+pub fn run() -> Result<(), Error> {
+    let (connection, io_threads) = Connection::stdio();
+
+    connection.initialize(serde_json::to_value(capabilities())?)?;
+    Server::new(&connection).serve()?;
+    io_threads.join()?;
+
+    Ok(())
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                    include_text: Some(true),
+                })),
+                ..Default::default()
+            },
+        )),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![".".to_owned(), "%".to_owned(), "(".to_owned()]),
+            ..Default::default()
+        }),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    }
+}
+
+struct Server<'a> {
+    connection: &'a Connection,
+    functions: Vec<Box<dyn Function>>,
+    documents: HashMap<Url, Document>,
+}
+
+impl<'a> Server<'a> {
+    fn new(connection: &'a Connection) -> Self {
+        let mut functions = stdlib::all();
+        functions.append(&mut vector_vrl_functions::vrl_functions());
+        functions.append(&mut enrichment::vrl_functions());
+
+        Self {
+            connection,
+            functions,
+            documents: HashMap::new(),
+        }
+    }
+
+    fn serve(&mut self) -> Result<(), Error> {
+        let connection = self.connection;
+
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    self.handle_request(request)?;
+                }
+                Message::Notification(notification) => self.handle_notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_request(&self, request: Request) -> Result<(), Error> {
+        match request.method.as_str() {
+            Completion::METHOD => self.reply::<Completion>(request, Self::completion),
+            HoverRequest::METHOD => self.reply::<HoverRequest>(request, Self::hover),
+            GotoDefinition::METHOD => self.reply::<GotoDefinition>(request, Self::definition),
+            method => {
+                let message = format!("unsupported method: {}", method);
+                self.send(Response::new_err(
+                    request.id,
+                    ErrorCode::MethodNotFound as i32,
+                    message,
+                ))
+            }
+        }
+    }
+
+    fn reply<R: lsp_types::request::Request>(
+        &self,
+        request: Request,
+        handler: impl FnOnce(&Self, R::Params) -> R::Result,
+    ) -> Result<(), Error> {
+        let params = serde_json::from_value(request.params)?;
+        let result = serde_json::to_value(handler(self, params))?;
+
+        self.send(Response::new_ok(request.id, result))
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<(), Error> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let document = params.text_document;
+
+                self.documents
+                    .insert(document.uri.clone(), Document::new(document.text));
+                self.publish_diagnostics(document.uri)?;
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+
+                // The server only supports full document syncs, so the last
+                // change holds the whole document.
+                if let (Some(document), Some(change)) = (
+                    self.documents.get_mut(&params.text_document.uri),
+                    params.content_changes.into_iter().last(),
+                ) {
+                    document.update(change.text);
+                }
+            }
+            DidSaveTextDocument::METHOD => {
+                let params: DidSaveTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+
+                if let (Some(document), Some(text)) = (self.documents.get_mut(&uri), params.text) {
+                    document.update(text);
+                }
+                self.publish_diagnostics(uri)?;
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+
+                self.documents.remove(&uri);
+                self.notify::<PublishDiagnostics>(PublishDiagnosticsParams::new(
+                    uri,
+                    vec![],
+                    None,
+                ))?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn publish_diagnostics(&mut self, uri: Url) -> Result<(), Error> {
+        let diagnostics = match self.documents.get_mut(&uri) {
+            Some(document) => diagnostics::analyze(&uri, document, &self.functions),
+            None => return Ok(()),
+        };
+
+        self.notify::<PublishDiagnostics>(PublishDiagnosticsParams::new(uri, diagnostics, None))
+    }
+
+    fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let position = params.text_document_position;
+        let document = self.documents.get(&position.text_document.uri)?;
+        let offset = document.offset(position.position);
+
+        Some(CompletionResponse::Array(completion::completions(
+            document,
+            offset,
+            &self.functions,
+        )))
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let position = params.text_document_position_params;
+        let document = self.documents.get(&position.text_document.uri)?;
+        let offset = document.offset(position.position);
+
+        hover::hover(document, offset, &self.functions)
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let position = params.text_document_position_params;
+        let uri = position.text_document.uri;
+        let document = self.documents.get(&uri)?;
+
+        let span = document.ident_at(document.offset(position.position))?;
+        let ident = &document.source()[span.start()..span.end()];
+        let definitions = definition::definitions(document.source());
+        let span = definition::find(&definitions, ident)?;
+
+        Some(GotoDefinitionResponse::Scalar(Location::new(
+            uri,
+            document.range(span),
+        )))
+    }
+
+    fn notify<N: lsp_types::notification::Notification>(
+        &self,
+        params: N::Params,
+    ) -> Result<(), Error> {
+        let notification = Notification::new(N::METHOD.to_owned(), params);
+        self.connection.sender.send(notification.into())?;
+
+        Ok(())
+    }
+
+    fn send(&self, response: Response) -> Result<(), Error> {
+        self.connection.sender.send(response.into())?;
+
+        Ok(())
+    }
+}
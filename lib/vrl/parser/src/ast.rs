@@ -187,6 +187,13 @@ impl IntoIterator for Program {
 pub enum RootExpr {
     Expr(Node<Expr>),
 
+    /// A user-defined function, which can be called by any expression in the
+    /// program.
+    FunctionDefinition(Node<FunctionDefinition>),
+
+    /// An import of the function definitions of another program.
+    Import(Node<Import>),
+
     /// A special expression that is returned if a given expression could not be
     /// parsed. This allows the parser to continue on to the next expression.
     Error(Error),
@@ -194,10 +201,12 @@ pub enum RootExpr {
 
 impl fmt::Debug for RootExpr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        use RootExpr::{Error, Expr};
+        use RootExpr::{Error, Expr, FunctionDefinition, Import};
 
         let value = match self {
             Expr(v) => format!("{:?}", v),
+            FunctionDefinition(v) => format!("{:?}", v),
+            Import(v) => format!("{:?}", v),
             Error(v) => format!("{:?}", v),
         };
 
@@ -207,10 +216,12 @@ impl fmt::Debug for RootExpr {
 
 impl fmt::Display for RootExpr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        use RootExpr::{Error, Expr};
+        use RootExpr::{Error, Expr, FunctionDefinition, Import};
 
         match self {
             Expr(v) => v.fmt(f),
+            FunctionDefinition(v) => v.fmt(f),
+            Import(v) => v.fmt(f),
             Error(v) => v.fmt(f),
         }
     }
@@ -1121,6 +1132,71 @@ impl fmt::Debug for FunctionClosure {
     }
 }
 
+// -----------------------------------------------------------------------------
+// function definition
+// -----------------------------------------------------------------------------
+
+/// A user-defined function.
+///
+/// The block is evaluated with only the parameters as its variables, and its
+/// last expression is the result of calling the function.
+#[derive(Clone, PartialEq)]
+pub struct FunctionDefinition {
+    pub ident: Node<Ident>,
+    pub parameters: Vec<Node<Ident>>,
+    pub block: Node<Block>,
+}
+
+impl fmt::Display for FunctionDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "fn {}(", self.ident)?;
+
+        let mut iter = self.parameters.iter().peekable();
+        while let Some(parameter) = iter.next() {
+            parameter.fmt(f)?;
+
+            if iter.peek().is_some() {
+                f.write_str(", ")?;
+            }
+        }
+
+        f.write_str(") ")?;
+        self.block.fmt(f)
+    }
+}
+
+impl fmt::Debug for FunctionDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "FunctionDefinition({:?}({:?}) {:?})",
+            self.ident, self.parameters, self.block
+        )
+    }
+}
+
+// -----------------------------------------------------------------------------
+// import
+// -----------------------------------------------------------------------------
+
+/// An import of the function definitions in another file.
+#[derive(Clone, PartialEq)]
+pub struct Import {
+    pub path: Node<String>,
+}
+
+impl fmt::Display for Import {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "import {:?}", self.path.as_ref())
+    }
+}
+
+impl fmt::Debug for Import {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Import({:?})", self.path.as_ref())
+    }
+}
+
 // -----------------------------------------------------------------------------
 // unary
 // -----------------------------------------------------------------------------
@@ -0,0 +1,601 @@
+//! A formatter printing VRL programs in a canonical style.
+//!
+//! Programs are printed from their AST, using four spaces of indentation, one
+//! expression per line, and blocks spread over multiple lines. Arrays, objects
+//! and function arguments stay on a single line, unless they are too long or
+//! contain comments, in which case each element goes on its own line followed
+//! by a comma. Literals, paths and assignment targets are printed as written.
+//!
+//! Comments are kept on their own line before the expression or element that
+//! follows them, or at the end of the line of the expression or element they
+//! follow. A single blank line between two expressions is kept.
+
+use diagnostic::Span;
+
+use crate::{
+    ast::{
+        Assignment, Block, Container, Expr, FunctionArgument, FunctionCall, IfStatement, Node,
+        Predicate, Query, QueryTarget, RootExpr, Unary,
+    },
+    lex::Lexer,
+    Error,
+};
+
+const INDENT: &str = "    ";
+
+/// The width past which lists are spread over multiple lines.
+const MAX_WIDTH: usize = 100;
+
+/// Formats a program, returning an error if it doesn't parse.
+pub fn format(source: &str) -> Result<String, Error> {
+    let mut root_exprs = vec![];
+    for node in crate::parse(source)? {
+        let (span, root_expr) = node.take();
+
+        // The parser recovers from some errors, which still make the program
+        // invalid.
+        if let RootExpr::Error(error) = root_expr {
+            return Err(error);
+        }
+        root_exprs.push(Node::new(span, root_expr));
+    }
+
+    let mut printer = Printer::new(source)?;
+    let spans = root_exprs.iter().map(Node::span).collect::<Vec<_>>();
+    let mut output = printer.lines(&spans, source.len(), 0, "", |printer, index, indent| {
+        printer.root_expr(&root_exprs[index], indent)
+    });
+
+    if !output.is_empty() {
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+struct Comment<'a> {
+    span: Span,
+    text: &'a str,
+    printed: bool,
+}
+
+struct Printer<'a> {
+    source: &'a str,
+    comments: Vec<Comment<'a>>,
+}
+
+impl<'a> Printer<'a> {
+    /// Finds the comments of a program, which are the only text outside of
+    /// its tokens, apart from whitespace.
+    fn new(source: &'a str) -> Result<Self, Error> {
+        let mut comments = vec![];
+        let mut position = 0;
+
+        let mut take_comments = |end: usize, position: &mut usize| {
+            while *position < end {
+                match source[*position..end].find('#') {
+                    Some(index) => {
+                        let start = *position + index;
+                        let end = source[start..]
+                            .find('\n')
+                            .map_or(source.len(), |index| start + index);
+
+                        comments.push(Comment {
+                            span: Span::new(start, end),
+                            text: source[start..end].trim_end(),
+                            printed: false,
+                        });
+                        *position = end;
+                    }
+                    None => *position = end,
+                }
+            }
+        };
+
+        for token in Lexer::new(source) {
+            let (start, _, end) = token?;
+
+            take_comments(start, &mut position);
+            position = position.max(end);
+        }
+        take_comments(source.len(), &mut position);
+
+        Ok(Self { source, comments })
+    }
+
+    fn verbatim(&self, span: Span) -> &'a str {
+        self.source[span.start()..span.end()].trim()
+    }
+
+    /// Takes the comments that start before `position`.
+    fn comments_before(&mut self, position: usize) -> Vec<(Span, &'a str)> {
+        self.comments
+            .iter_mut()
+            .filter(|comment| !comment.printed && comment.span.start() < position)
+            .map(|comment| {
+                comment.printed = true;
+                (comment.span, comment.text)
+            })
+            .collect()
+    }
+
+    /// Takes the comment at the end of the line ending at `position`, if any.
+    fn trailing_comment(&mut self, position: usize) -> Option<(Span, &'a str)> {
+        let source = self.source;
+
+        self.comments
+            .iter_mut()
+            .find(|comment| !comment.printed && comment.span.start() >= position)
+            .filter(|comment| {
+                source[position..comment.span.start()]
+                    .chars()
+                    .all(|char| matches!(char, ' ' | '\t' | ',' | ';'))
+            })
+            .map(|comment| {
+                comment.printed = true;
+                (comment.span, comment.text)
+            })
+    }
+
+    fn has_comments(&self, start: usize, end: usize) -> bool {
+        self.comments.iter().any(|comment| {
+            !comment.printed && comment.span.start() >= start && comment.span.start() < end
+        })
+    }
+
+    /// Prints a sequence of elements with the given spans one per line, each
+    /// followed by `separator`, along with the comments before `end`.
+    fn lines(
+        &mut self,
+        spans: &[Span],
+        end: usize,
+        indent: usize,
+        separator: &str,
+        mut print: impl FnMut(&mut Self, usize, usize) -> String,
+    ) -> String {
+        let mut lines: Vec<(Span, String)> = vec![];
+
+        for (index, span) in spans.iter().enumerate() {
+            for (span, text) in self.comments_before(span.start()) {
+                lines.push((span, text.to_owned()));
+            }
+
+            let mut line = print(self, index, indent) + separator;
+            let mut line_span = *span;
+            if let Some((span, text)) = self.trailing_comment(span.end()) {
+                line = format!("{} {}", line, text);
+                line_span = Span::new(line_span.start(), span.end());
+            }
+            lines.push((line_span, line));
+        }
+
+        for (span, text) in self.comments_before(end) {
+            lines.push((span, text.to_owned()));
+        }
+
+        let mut output = String::new();
+        let mut previous_end = None;
+        for (span, line) in lines {
+            if let Some(previous_end) = previous_end {
+                output.push('\n');
+
+                // Keep a single blank line between two lines separated by at
+                // least one blank line.
+                if previous_end < span.start()
+                    && self.source[previous_end..span.start()]
+                        .matches('\n')
+                        .count()
+                        > 1
+                {
+                    output.push('\n');
+                }
+            }
+
+            output.push_str(&INDENT.repeat(indent));
+            output.push_str(&line);
+            previous_end = Some(span.end());
+        }
+
+        output
+    }
+
+    /// Prints a list on a single line if it fits, or spreads it over multiple
+    /// lines otherwise.
+    fn list(
+        &mut self,
+        (open, close): (&str, &str),
+        spans: &[Span],
+        (start, end): (usize, usize),
+        indent: usize,
+        mut print: impl FnMut(&mut Self, usize, usize) -> String,
+    ) -> String {
+        if spans.is_empty() && !self.has_comments(start, end) {
+            return format!("{}{}", open.trim_end(), close.trim_start());
+        }
+
+        if !self.has_comments(start, end) {
+            let elements = (0..spans.len())
+                .map(|index| print(self, index, indent))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let line = format!("{}{}{}", open, elements, close);
+
+            if !line.contains('\n') && INDENT.len() * indent + line.len() <= MAX_WIDTH {
+                return line;
+            }
+        }
+
+        let elements = self.lines(spans, end, indent + 1, ",", |printer, index, indent| {
+            print(printer, index, indent)
+        });
+
+        format!(
+            "{}\n{}\n{}{}",
+            open.trim_end(),
+            elements,
+            INDENT.repeat(indent),
+            close.trim_start()
+        )
+    }
+
+    fn root_expr(&mut self, node: &Node<RootExpr>, indent: usize) -> String {
+        match node.inner() {
+            RootExpr::Expr(node) => self.expr(node, indent),
+            RootExpr::FunctionDefinition(node) => {
+                let definition = node.inner();
+                let parameters = definition
+                    .parameters
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                format!(
+                    "fn {}({}) {}",
+                    definition.ident,
+                    parameters,
+                    self.block(&definition.block, indent)
+                )
+            }
+            RootExpr::Import(node) => format!("import {}", self.verbatim(node.inner().path.span())),
+            RootExpr::Error(_) => self.verbatim(node.span()).to_owned(),
+        }
+    }
+
+    fn expr(&mut self, node: &Node<Expr>, indent: usize) -> String {
+        match node.inner() {
+            Expr::Literal(node) => self.verbatim(node.span()).to_owned(),
+            Expr::Container(node) => self.container(node, indent),
+            Expr::IfStatement(node) => self.if_statement(node.inner(), indent),
+            Expr::Op(node) => {
+                let op = node.inner();
+                format!(
+                    "{} {} {}",
+                    self.expr(&op.0, indent),
+                    op.1,
+                    self.expr(&op.2, indent)
+                )
+            }
+            Expr::Assignment(node) => match node.inner() {
+                Assignment::Single { target, op, expr } => format!(
+                    "{} {} {}",
+                    self.verbatim(target.span()),
+                    op,
+                    self.expr(expr, indent)
+                ),
+                Assignment::Infallible { ok, err, op, expr } => format!(
+                    "{}, {} {} {}",
+                    self.verbatim(ok.span()),
+                    self.verbatim(err.span()),
+                    op,
+                    self.expr(expr, indent)
+                ),
+            },
+            Expr::Query(node) => self.query(node, indent),
+            Expr::FunctionCall(node) => self.function_call(node.inner(), node.span(), indent),
+            Expr::Variable(node) => node.to_string(),
+            Expr::Unary(node) => match node.inner() {
+                Unary::Not(node) => format!("!{}", self.expr(&node.inner().1, indent)),
+            },
+            Expr::Abort(node) => match &node.inner().message {
+                Some(message) => format!("abort {}", self.expr(message, indent)),
+                None => "abort".to_owned(),
+            },
+        }
+    }
+
+    fn container(&mut self, node: &Node<Container>, indent: usize) -> String {
+        let span = node.span();
+
+        match node.inner() {
+            Container::Group(node) => format!("({})", self.expr(&node.inner().0, indent)),
+            Container::Block(node) => self.block(node, indent),
+            Container::Array(node) => {
+                let exprs = &node.inner().0;
+                let spans = exprs.iter().map(Node::span).collect::<Vec<_>>();
+
+                self.list(
+                    ("[", "]"),
+                    &spans,
+                    (span.start(), span.end()),
+                    indent,
+                    |printer, index, indent| printer.expr(&exprs[index], indent),
+                )
+            }
+            Container::Object(node) => {
+                // Objects are stored by key, so their entries are put back in
+                // source order.
+                let mut entries = node.inner().0.iter().collect::<Vec<_>>();
+                entries.sort_by_key(|(key, _)| key.span().start());
+                let spans = entries
+                    .iter()
+                    .map(|(key, expr)| Span::new(key.span().start(), expr.span().end()))
+                    .collect::<Vec<_>>();
+
+                self.list(
+                    ("{ ", " }"),
+                    &spans,
+                    (span.start(), span.end()),
+                    indent,
+                    |printer, index, indent| {
+                        let (key, expr) = entries[index];
+                        format!(
+                            "{}: {}",
+                            printer.verbatim(key.span()),
+                            printer.expr(expr, indent)
+                        )
+                    },
+                )
+            }
+        }
+    }
+
+    fn block(&mut self, node: &Node<Block>, indent: usize) -> String {
+        let exprs = &node.inner().0;
+        let spans = exprs.iter().map(Node::span).collect::<Vec<_>>();
+        let body = self.lines(
+            &spans,
+            node.span().end(),
+            indent + 1,
+            "",
+            |printer, index, indent| printer.expr(&exprs[index], indent),
+        );
+
+        format!("{{\n{}\n{}}}", body, INDENT.repeat(indent))
+    }
+
+    fn if_statement(&mut self, statement: &IfStatement, indent: usize) -> String {
+        let predicate = match statement.predicate.inner() {
+            Predicate::One(expr) => self.expr(expr, indent),
+            Predicate::Many(exprs) => {
+                let exprs = exprs
+                    .iter()
+                    .map(|expr| self.expr(expr, indent))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                format!("({})", exprs)
+            }
+        };
+        let mut output = format!(
+            "if {} {}",
+            predicate,
+            self.block(&statement.if_node, indent)
+        );
+
+        if let Some(else_node) = &statement.else_node {
+            output.push_str(" else ");
+
+            // The parser turns `else if` into an `else` block holding the next
+            // if-statement, with the same span.
+            match &else_node.inner().0[..] {
+                [expr] if expr.span() == else_node.span() => match expr.inner() {
+                    Expr::IfStatement(node) => {
+                        output.push_str(&self.if_statement(node.inner(), indent));
+                    }
+                    _ => output.push_str(&self.block(else_node, indent)),
+                },
+                _ => output.push_str(&self.block(else_node, indent)),
+            }
+        }
+
+        output
+    }
+
+    fn query(&mut self, node: &Node<Query>, indent: usize) -> String {
+        let query = node.inner();
+        let target_span = query.target.span();
+
+        let target = match query.target.inner() {
+            QueryTarget::FunctionCall(call) => self.function_call(call, target_span, indent),
+            QueryTarget::Container(container) => {
+                self.container(&Node::new(target_span, container.clone()), indent)
+            }
+            QueryTarget::Internal(_) | QueryTarget::External(_) => {
+                return self.verbatim(node.span()).to_owned()
+            }
+        };
+
+        format!(
+            "{}{}",
+            target,
+            self.verbatim(Span::new(target_span.end(), node.span().end()))
+        )
+    }
+
+    fn function_call(&mut self, call: &FunctionCall, span: Span, indent: usize) -> String {
+        let open = format!(
+            "{}{}(",
+            call.ident,
+            if call.abort_on_error { "!" } else { "" }
+        );
+
+        // The arguments end where the closure starts.
+        let end = call
+            .closure
+            .as_ref()
+            .map_or(span.end(), |closure| closure.span().start());
+        let spans = call.arguments.iter().map(Node::span).collect::<Vec<_>>();
+
+        let mut output = self.list(
+            (&open, ")"),
+            &spans,
+            (span.start(), end),
+            indent,
+            |printer, index, indent| printer.function_argument(&call.arguments[index], indent),
+        );
+
+        if let Some(closure) = &call.closure {
+            let closure = closure.inner();
+            let variables = closure
+                .variables
+                .iter()
+                .map(|variable| match &**variable.inner() {
+                    "" => "_".to_owned(),
+                    variable => variable.to_owned(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            output.push_str(&format!(
+                " -> |{}| {}",
+                variables,
+                self.block(&closure.block, indent)
+            ));
+        }
+
+        output
+    }
+
+    fn function_argument(&mut self, node: &Node<FunctionArgument>, indent: usize) -> String {
+        let argument = node.inner();
+        let expr = self.expr(&argument.expr, indent);
+
+        match &argument.ident {
+            Some(ident) => format!("{}: {}", ident, expr),
+            None => expr,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn canonical_style() {
+        let source = indoc! {r#"
+            .foo=upcase( .bar )
+            .baz,err=parse_json(.message);.qux=[1,2 ,3]
+            if .foo=="BAR"{.ok=true}else if exists(.x) {del(.x)} else{abort}
+            .obj = {"b":1,"a":{"c":null}}
+            map_values(.) -> |value|{ string(value) ?? "" }
+        "#};
+
+        let expected = indoc! {r#"
+            .foo = upcase(.bar)
+            .baz, err = parse_json(.message)
+            .qux = [1, 2, 3]
+            if .foo == "BAR" {
+                .ok = true
+            } else if exists(.x) {
+                del(.x)
+            } else {
+                abort
+            }
+            .obj = { "b": 1, "a": { "c": null } }
+            map_values(.) -> |value| {
+                string(value) ?? ""
+            }
+        "#};
+
+        assert_eq!(format(source).unwrap(), expected);
+    }
+
+    #[test]
+    fn comments_and_blank_lines() {
+        let source = indoc! {r#"
+            # leading comment
+            .a = 1 # trailing comment
+
+
+            .b = [
+              # element comment
+              1,
+              2 # element trailing comment
+            ]
+            if true {
+              # block comment
+              .c = 3
+              # end of block comment
+            }
+            # final comment
+        "#};
+
+        let expected = indoc! {r#"
+            # leading comment
+            .a = 1 # trailing comment
+
+            .b = [
+                # element comment
+                1,
+                2, # element trailing comment
+            ]
+            if true {
+                # block comment
+                .c = 3
+                # end of block comment
+            }
+            # final comment
+        "#};
+
+        assert_eq!(format(source).unwrap(), expected);
+    }
+
+    #[test]
+    fn long_lists() {
+        let source = r#"merge(., { "first_long_field_name": "some value", "second_long_field_name": "some other value" })"#;
+
+        let expected = indoc! {r#"
+            merge(
+                .,
+                { "first_long_field_name": "some value", "second_long_field_name": "some other value" },
+            )
+        "#};
+
+        assert_eq!(format(source).unwrap(), expected);
+    }
+
+    #[test]
+    fn literals_are_kept() {
+        let source = "s'raw # not a comment' + \"line\\n\" + r'\\d+'\n";
+
+        assert_eq!(format(source).unwrap(), source);
+    }
+
+    #[test]
+    fn idempotent() {
+        let source = indoc! {r#"
+            fn greet(greeting, name) {
+                # say hello
+                greeting + ", " + name
+            }
+
+            .message = greet("hello", name: .name) # greet
+            .tags = {
+                # the source
+                "source": "vector",
+            }
+        "#};
+
+        let formatted = format(source).unwrap();
+        assert_eq!(formatted, source);
+        assert_eq!(format(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn invalid_program() {
+        assert!(format(".foo = ").is_err());
+    }
+}
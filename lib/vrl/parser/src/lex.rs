@@ -116,6 +116,8 @@ impl DiagnosticMessage for Error {
                         r#""null""#,
                         r#""true""#,
                         r#""if""#,
+                        r#""fn""#,
+                        r#""import""#,
                     ];
                     let is_any_ident = any_ident
                         .iter()
@@ -371,6 +373,8 @@ pub enum Token<S> {
     False,
     True,
     Abort,
+    Fn,
+    Import,
 
     // tokens
     Colon,
@@ -433,9 +437,9 @@ impl<S> Token<S> {
     pub(crate) fn map<R>(self, f: impl Fn(S) -> R) -> Token<R> {
         use self::Token::{
             Abort, Ampersand, Arrow, Bang, Colon, Comma, Dot, Else, Equals, Escape, False,
-            FloatLiteral, FunctionCall, Identifier, If, IntegerLiteral, InvalidToken, LBrace,
-            LBracket, LParen, LQuery, MergeEquals, Newline, Null, Operator, PathField, Percent,
-            Question, RBrace, RBracket, RParen, RQuery, RawStringLiteral, RegexLiteral,
+            FloatLiteral, Fn, FunctionCall, Identifier, If, Import, IntegerLiteral, InvalidToken,
+            LBrace, LBracket, LParen, LQuery, MergeEquals, Newline, Null, Operator, PathField,
+            Percent, Question, RBrace, RBracket, RParen, RQuery, RawStringLiteral, RegexLiteral,
             ReservedIdentifier, SemiColon, StringLiteral, TimestampLiteral, True, Underscore,
         };
 
@@ -465,6 +469,8 @@ impl<S> Token<S> {
             Null => Null,
             True => True,
             Abort => Abort,
+            Fn => Fn,
+            Import => Import,
 
             // tokens
             Colon => Colon,
@@ -502,9 +508,9 @@ where
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::Token::{
             Abort, Ampersand, Arrow, Bang, Colon, Comma, Dot, Else, Equals, Escape, False,
-            FloatLiteral, FunctionCall, Identifier, If, IntegerLiteral, InvalidToken, LBrace,
-            LBracket, LParen, LQuery, MergeEquals, Newline, Null, Operator, PathField, Percent,
-            Question, RBrace, RBracket, RParen, RQuery, RawStringLiteral, RegexLiteral,
+            FloatLiteral, Fn, FunctionCall, Identifier, If, Import, IntegerLiteral, InvalidToken,
+            LBrace, LBracket, LParen, LQuery, MergeEquals, Newline, Null, Operator, PathField,
+            Percent, Question, RBrace, RBracket, RParen, RQuery, RawStringLiteral, RegexLiteral,
             ReservedIdentifier, SemiColon, StringLiteral, TimestampLiteral, True, Underscore,
         };
 
@@ -528,6 +534,8 @@ where
             Null => "Null",
             True => "True",
             Abort => "Abort",
+            Fn => "Fn",
+            Import => "Import",
 
             // tokens
             Colon => "Colon",
@@ -1126,6 +1134,8 @@ impl<'input> Lexer<'input> {
 
         let token = if self.test_peek(|ch| ch == '(' || ch == '!') {
             Token::FunctionCall(ident)
+        } else if let Some(keyword) = self.definition_keyword(ident, end) {
+            keyword
         } else {
             Token::ident(ident)
         };
@@ -1133,6 +1143,24 @@ impl<'input> Lexer<'input> {
         (start, token, end)
     }
 
+    /// Returns the keyword of a function definition or an import, if the identifier starts one.
+    ///
+    /// `fn` and `import` are only keywords when followed by a function name and its parameters,
+    /// or by the path of the imported file. Elsewhere they remain identifiers, so that programs
+    /// using them as variable names keep working.
+    fn definition_keyword(&self, ident: &str, end: usize) -> Option<Tok<'input>> {
+        let rest = self.input[end..].trim_start_matches(|ch| ch == ' ' || ch == '\t');
+        match ident {
+            "fn" => {
+                let after_name = rest.trim_start_matches(is_ident_continue);
+                (rest.starts_with(is_ident_start) && after_name.starts_with('('))
+                    .then_some(Token::Fn)
+            }
+            "import" => rest.starts_with('"').then_some(Token::Import),
+            _ => None,
+        }
+    }
+
     fn operator(&mut self, start: usize) -> Spanned<'input, usize> {
         let (end, op) = self.take_while(start, is_operator);
 
@@ -1328,9 +1356,9 @@ mod test {
 
     use super::*;
     use crate::lex::Token::{
-        Arrow, Bang, Colon, Comma, Dot, Else, Equals, FloatLiteral, FunctionCall, Identifier, If,
-        IntegerLiteral, LBrace, LBracket, LParen, LQuery, Newline, Operator, PathField, Percent,
-        RBrace, RBracket, RParen, RQuery, RawStringLiteral, RegexLiteral, StringLiteral,
+        Arrow, Bang, Colon, Comma, Dot, Else, Equals, FloatLiteral, Fn, FunctionCall, Identifier,
+        If, Import, IntegerLiteral, LBrace, LBracket, LParen, LQuery, Newline, Operator, PathField,
+        Percent, RBrace, RBracket, RParen, RQuery, RawStringLiteral, RegexLiteral, StringLiteral,
         TimestampLiteral, True,
     };
 
@@ -1655,6 +1683,41 @@ mod test {
         );
     }
 
+    #[test]
+    fn function_definitions() {
+        use StringLiteral as L;
+        use StringLiteralToken as S;
+
+        test(
+            data(r#"import "lib.vrl"; fn foo(x) { x }"#),
+            vec![
+                (r#"~~~~~~                           "#, Import),
+                (r#"       ~~~~~~~~~                 "#, L(S("lib.vrl"))),
+                (r#"                ~                "#, SemiColon),
+                (r#"                  ~~             "#, Fn),
+                (r#"                     ~~~         "#, FunctionCall("foo")),
+                (r#"                        ~        "#, LParen),
+                (r#"                         ~       "#, Identifier("x")),
+                (r#"                          ~      "#, RParen),
+                (r#"                            ~    "#, LBrace),
+                (r#"                              ~  "#, Identifier("x")),
+                (r#"                                ~"#, RBrace),
+            ],
+        );
+    }
+
+    #[test]
+    fn definition_keywords_as_identifiers() {
+        test(
+            data(r#"fn = import"#),
+            vec![
+                (r#"~~         "#, Identifier("fn")),
+                (r#"   ~       "#, Equals),
+                (r#"     ~~~~~~"#, Identifier("import")),
+            ],
+        );
+    }
+
     #[test]
     fn single_query() {
         test(
@@ -37,11 +37,13 @@ mod arbitrary;
 #[cfg(feature = "fuzz")]
 mod arbitrary_depth;
 pub mod ast;
+mod format;
 mod lex;
 mod template_string;
 
 pub use ast::{Literal, Program};
 pub use diagnostic::Span;
+pub use format::format;
 pub use lex::{Error, Token};
 
 pub fn parse(input: impl AsRef<str>) -> Result<Program, Error> {
@@ -0,0 +1,325 @@
+use std::net::IpAddr;
+
+use ::sha1::{Digest, Sha1};
+use ::value::Value;
+use vrl::prelude::*;
+
+const ICMP: u8 = 1;
+const ICMPV6: u8 = 58;
+
+/// Returns the type of the reply to (or request for) an ICMP message of the
+/// given type, if the message is part of a two-way exchange.
+fn icmp_counterpart(protocol: u8, icmp_type: u16) -> Option<u16> {
+    let counterpart = match (protocol, icmp_type) {
+        // Echo, router, timestamp, information and address mask messages.
+        (ICMP, 8) => 0,
+        (ICMP, 0) => 8,
+        (ICMP, 10) => 9,
+        (ICMP, 9) => 10,
+        (ICMP, 13) => 14,
+        (ICMP, 14) => 13,
+        (ICMP, 15) => 16,
+        (ICMP, 16) => 15,
+        (ICMP, 17) => 18,
+        (ICMP, 18) => 17,
+        // Echo, multicast listener, router, neighbor, node information and
+        // home agent address discovery messages.
+        (ICMPV6, 128) => 129,
+        (ICMPV6, 129) => 128,
+        (ICMPV6, 130) => 131,
+        (ICMPV6, 131) => 130,
+        (ICMPV6, 133) => 134,
+        (ICMPV6, 134) => 133,
+        (ICMPV6, 135) => 136,
+        (ICMPV6, 136) => 135,
+        (ICMPV6, 139) => 140,
+        (ICMPV6, 140) => 139,
+        (ICMPV6, 144) => 145,
+        (ICMPV6, 145) => 144,
+        _ => return None,
+    };
+
+    Some(counterpart)
+}
+
+fn octets(ip: IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => ip.octets().to_vec(),
+        IpAddr::V6(ip) => ip.octets().to_vec(),
+    }
+}
+
+fn parse_ip(value: Value) -> Result<IpAddr> {
+    value
+        .try_bytes_utf8_lossy()?
+        .parse()
+        .map_err(|err| format!("unable to parse IP address: {}", err).into())
+}
+
+fn parse_port(value: Value) -> Result<u16> {
+    let port = value.try_integer()?;
+    u16::try_from(port)
+        .map_err(|_| format!("port must be between 0 and 65535, got {}", port).into())
+}
+
+fn community_id(
+    source_ip: Value,
+    destination_ip: Value,
+    protocol: Value,
+    ports: Option<(Value, Value)>,
+    seed: Option<Value>,
+) -> Resolved {
+    let source_ip = parse_ip(source_ip)?;
+    let destination_ip = parse_ip(destination_ip)?;
+    if source_ip.is_ipv4() != destination_ip.is_ipv4() {
+        return Err("source and destination IP addresses must be of the same family".into());
+    }
+
+    let protocol = protocol.try_integer()?;
+    let protocol = u8::try_from(protocol)
+        .map_err(|_| format!("protocol must be between 0 and 255, got {}", protocol))?;
+
+    let seed = match seed {
+        Some(seed) => {
+            let seed = seed.try_integer()?;
+            u16::try_from(seed)
+                .map_err(|_| format!("seed must be between 0 and 65535, got {}", seed))?
+        }
+        None => 0,
+    };
+
+    let mut ports = match ports {
+        Some((source_port, destination_port)) => {
+            Some((parse_port(source_port)?, parse_port(destination_port)?))
+        }
+        None => None,
+    };
+
+    // For ICMP the ports are the message type and code. Two-way exchanges use
+    // the type of the counterpart message instead of the code, so that both
+    // directions of the exchange hash to the same ID.
+    let mut one_way = false;
+    if protocol == ICMP || protocol == ICMPV6 {
+        if let Some((icmp_type, icmp_code)) = ports.as_mut() {
+            match icmp_counterpart(protocol, *icmp_type) {
+                Some(counterpart) => *icmp_code = counterpart,
+                None => one_way = true,
+            }
+        }
+    }
+
+    let mut source = (octets(source_ip), ports.map(|(port, _)| port));
+    let mut destination = (octets(destination_ip), ports.map(|(_, port)| port));
+    if !one_way && source > destination {
+        std::mem::swap(&mut source, &mut destination);
+    }
+
+    let mut hasher = Sha1::new();
+    hasher.update(seed.to_be_bytes());
+    hasher.update(&source.0);
+    hasher.update(&destination.0);
+    hasher.update([protocol, 0]);
+    if let (Some(source_port), Some(destination_port)) = (source.1, destination.1) {
+        hasher.update(source_port.to_be_bytes());
+        hasher.update(destination_port.to_be_bytes());
+    }
+
+    Ok(format!("1:{}", base64::encode(hasher.finalize())).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CommunityId;
+
+impl Function for CommunityId {
+    fn identifier(&self) -> &'static str {
+        "community_id"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "source_ip",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "destination_ip",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "protocol",
+                kind: kind::INTEGER,
+                required: true,
+            },
+            Parameter {
+                keyword: "source_port",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "destination_port",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "seed",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "TCP",
+                source: r#"community_id!(source_ip: "1.2.3.4", destination_ip: "5.6.7.8", source_port: 1122, destination_port: 3344, protocol: 6)"#,
+                result: Ok("1:wCb3OG7yAFWelaUydu0D+125CLM="),
+            },
+            Example {
+                title: "ICMP echo reply",
+                source: r#"community_id!(source_ip: "5.6.7.8", destination_ip: "1.2.3.4", source_port: 0, destination_port: 0, protocol: 1)"#,
+                result: Ok("1:crodRHL2FEsHjbv3UkRrfbs4bZ0="),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let source_ip = arguments.required("source_ip");
+        let destination_ip = arguments.required("destination_ip");
+        let protocol = arguments.required("protocol");
+        let source_port = arguments.optional("source_port");
+        let destination_port = arguments.optional("destination_port");
+        let seed = arguments.optional("seed");
+
+        Ok(CommunityIdFn {
+            source_ip,
+            destination_ip,
+            protocol,
+            source_port,
+            destination_port,
+            seed,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CommunityIdFn {
+    source_ip: Box<dyn Expression>,
+    destination_ip: Box<dyn Expression>,
+    protocol: Box<dyn Expression>,
+    source_port: Option<Box<dyn Expression>>,
+    destination_port: Option<Box<dyn Expression>>,
+    seed: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for CommunityIdFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let source_ip = self.source_ip.resolve(ctx)?;
+        let destination_ip = self.destination_ip.resolve(ctx)?;
+        let protocol = self.protocol.resolve(ctx)?;
+        let source_port = self
+            .source_port
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
+        let destination_port = self
+            .destination_port
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
+        let seed = self
+            .seed
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
+
+        let ports = match (source_port, destination_port) {
+            (Some(source_port), Some(destination_port)) => Some((source_port, destination_port)),
+            (None, None) => None,
+            _ => return Err("source_port and destination_port must be set together".into()),
+        };
+
+        community_id(source_ip, destination_ip, protocol, ports, seed)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        community_id => CommunityId;
+
+        tcp {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 6, source_port: 1122, destination_port: 3344],
+            want: Ok(value!("1:wCb3OG7yAFWelaUydu0D+125CLM=")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        tcp_reverse {
+            args: func_args![source_ip: "5.6.7.8", destination_ip: "1.2.3.4", protocol: 6, source_port: 3344, destination_port: 1122],
+            want: Ok(value!("1:wCb3OG7yAFWelaUydu0D+125CLM=")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        udp_seed {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 17, source_port: 1122, destination_port: 3344, seed: 123],
+            want: Ok(value!("1:2y+51vKB5pvrhjtwtYg5e/mf0oA=")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        icmp_request {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 1, source_port: 8, destination_port: 0],
+            want: Ok(value!("1:crodRHL2FEsHjbv3UkRrfbs4bZ0=")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        icmp_reply {
+            args: func_args![source_ip: "5.6.7.8", destination_ip: "1.2.3.4", protocol: 1, source_port: 0, destination_port: 0],
+            want: Ok(value!("1:crodRHL2FEsHjbv3UkRrfbs4bZ0=")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        icmp_one_way {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 1, source_port: 3, destination_port: 1],
+            want: Ok(value!("1:W4aFotGvd33My5d10oV5afM5O+E=")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        no_ports {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 46],
+            want: Ok(value!("1:ikv3kmf89luf73WPz1jOs49S768=")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv6 {
+            args: func_args![source_ip: "fe80::1", destination_ip: "fe80::2", protocol: 6, source_port: 1122, destination_port: 3344],
+            want: Ok(value!("1:z4Cp91yf8hKQyZMv3qo7NH6sE3k=")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        mixed_families {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "fe80::2", protocol: 6, source_port: 1122, destination_port: 3344],
+            want: Err("source and destination IP addresses must be of the same family"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        missing_port {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 6, source_port: 1122],
+            want: Err("source_port and destination_port must be set together"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
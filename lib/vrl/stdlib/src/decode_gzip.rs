@@ -0,0 +1,112 @@
+use ::value::Value;
+use flate2::read::MultiGzDecoder;
+use vrl::prelude::expression::FunctionExpression;
+use vrl::prelude::*;
+
+use crate::util::{max_decompressed_size, read_decompressed};
+
+fn decode_gzip(value: Value, max_size: Option<Value>) -> Resolved {
+    let max_size = max_decompressed_size(max_size)?;
+    let value = value.try_bytes()?;
+
+    read_decompressed(MultiGzDecoder::new(value.as_ref()), max_size, "gzip")
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeGzip;
+
+impl Function for DecodeGzip {
+    fn identifier(&self) -> &'static str {
+        "decode_gzip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "max_size",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let max_size = arguments.optional("max_size");
+
+        Ok(DecodeGzipFn { value, max_size }.as_expr())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"decode_gzip!(decode_base64!("H4sIAAAAAAACA6vML1XISCxLVSguTU5OLS5OK83JqVRISU3OT0lNUchNBQD7BGDaIAAAAA=="))"#,
+            result: Ok("you have successfully decoded me"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeGzipFn {
+    value: Box<dyn Expression>,
+    max_size: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for DecodeGzipFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let max_size = self.max_size.as_ref().map(|m| m.resolve(ctx)).transpose()?;
+
+        decode_gzip(value, max_size)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        // Always fallible due to the possibility of decoding errors that VRL can't detect in
+        // advance, and of the decompressed value exceeding the maximum size.
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decode_base64(text: &str) -> Vec<u8> {
+        base64::decode(text).expect("Cannot decode from Base64")
+    }
+
+    test_function![
+        decode_gzip => DecodeGzip;
+
+        right_gzip {
+            args: func_args![value: Value::Bytes(decode_base64("H4sIAAAAAAACA6vML1XISCxLVSguTU5OLS5OK83JqVRISU3OT0lNUchNBQD7BGDaIAAAAA==").into())],
+            want: Ok(value!("you have successfully decoded me")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        wrong_gzip {
+            args: func_args![value: value!("some string value")],
+            want: Err("unable to decode value with gzip decoder: invalid gzip header"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        too_large {
+            args: func_args![
+                value: Value::Bytes(decode_base64("H4sIAAAAAAACA6vML1XISCxLVSguTU5OLS5OK83JqVRISU3OT0lNUchNBQD7BGDaIAAAAA==").into()),
+                max_size: 31,
+            ],
+            want: Err("decompressed value exceeds the maximum size of 31 bytes"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
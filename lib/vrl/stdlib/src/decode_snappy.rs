@@ -0,0 +1,127 @@
+use ::value::Value;
+use snap::raw::{decompress_len, Decoder};
+use vrl::prelude::expression::FunctionExpression;
+use vrl::prelude::*;
+
+use crate::util::max_decompressed_size;
+
+fn decode_snappy(value: Value, max_size: Option<Value>) -> Resolved {
+    let max_size = max_decompressed_size(max_size)?;
+    let value = value.try_bytes()?;
+
+    // The size of the decompressed value is encoded at the start of the
+    // compressed one, so it can be checked before decompressing.
+    let len = decompress_len(&value)
+        .map_err(|err| format!("unable to decode value with snappy decoder: {}", err))?;
+    if len > max_size {
+        return Err(format!(
+            "decompressed value exceeds the maximum size of {} bytes",
+            max_size
+        )
+        .into());
+    }
+
+    Decoder::new()
+        .decompress_vec(&value)
+        .map(|decompressed| Value::Bytes(decompressed.into()))
+        .map_err(|err| format!("unable to decode value with snappy decoder: {}", err).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeSnappy;
+
+impl Function for DecodeSnappy {
+    fn identifier(&self) -> &'static str {
+        "decode_snappy"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "max_size",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let max_size = arguments.optional("max_size");
+
+        Ok(DecodeSnappyFn { value, max_size }.as_expr())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"decode_snappy!(decode_base64!("IHx5b3UgaGF2ZSBzdWNjZXNzZnVsbHkgZGVjb2RlZCBtZQ=="))"#,
+            result: Ok("you have successfully decoded me"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeSnappyFn {
+    value: Box<dyn Expression>,
+    max_size: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for DecodeSnappyFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let max_size = self.max_size.as_ref().map(|m| m.resolve(ctx)).transpose()?;
+
+        decode_snappy(value, max_size)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        // Always fallible due to the possibility of decoding errors that VRL can't detect in
+        // advance, and of the decompressed value exceeding the maximum size.
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decode_base64(text: &str) -> Vec<u8> {
+        base64::decode(text).expect("Cannot decode from Base64")
+    }
+
+    test_function![
+        decode_snappy => DecodeSnappy;
+
+        right_snappy {
+            args: func_args![value: Value::Bytes(decode_base64("IHx5b3UgaGF2ZSBzdWNjZXNzZnVsbHkgZGVjb2RlZCBtZQ==").into())],
+            want: Ok(value!("you have successfully decoded me")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        too_large {
+            args: func_args![
+                value: Value::Bytes(decode_base64("IHx5b3UgaGF2ZSBzdWNjZXNzZnVsbHkgZGVjb2RlZCBtZQ==").into()),
+                max_size: 31,
+            ],
+            want: Err("decompressed value exceeds the maximum size of 31 bytes"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        declared_too_large {
+            args: func_args![value: Value::Bytes(Bytes::from_static(&[0x80, 0x80, 0x80, 0x80, 0x04]))],
+            want: Err("decompressed value exceeds the maximum size of 10485760 bytes"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
@@ -0,0 +1,112 @@
+use ::value::Value;
+use flate2::read::ZlibDecoder;
+use vrl::prelude::expression::FunctionExpression;
+use vrl::prelude::*;
+
+use crate::util::{max_decompressed_size, read_decompressed};
+
+fn decode_zlib(value: Value, max_size: Option<Value>) -> Resolved {
+    let max_size = max_decompressed_size(max_size)?;
+    let value = value.try_bytes()?;
+
+    read_decompressed(ZlibDecoder::new(value.as_ref()), max_size, "zlib")
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeZlib;
+
+impl Function for DecodeZlib {
+    fn identifier(&self) -> &'static str {
+        "decode_zlib"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "max_size",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let max_size = arguments.optional("max_size");
+
+        Ok(DecodeZlibFn { value, max_size }.as_expr())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"decode_zlib!(decode_base64!("eJyrzC9VyEgsS1UoLk1OTi0uTivNyalUSElNzk9JTVHITQUAzLIMQQ=="))"#,
+            result: Ok("you have successfully decoded me"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeZlibFn {
+    value: Box<dyn Expression>,
+    max_size: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for DecodeZlibFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let max_size = self.max_size.as_ref().map(|m| m.resolve(ctx)).transpose()?;
+
+        decode_zlib(value, max_size)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        // Always fallible due to the possibility of decoding errors that VRL can't detect in
+        // advance, and of the decompressed value exceeding the maximum size.
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decode_base64(text: &str) -> Vec<u8> {
+        base64::decode(text).expect("Cannot decode from Base64")
+    }
+
+    test_function![
+        decode_zlib => DecodeZlib;
+
+        right_zlib {
+            args: func_args![value: Value::Bytes(decode_base64("eJyrzC9VyEgsS1UoLk1OTi0uTivNyalUSElNzk9JTVHITQUAzLIMQQ==").into())],
+            want: Ok(value!("you have successfully decoded me")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        wrong_zlib {
+            args: func_args![value: value!("some string value")],
+            want: Err("unable to decode value with zlib decoder: corrupt deflate stream"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        too_large {
+            args: func_args![
+                value: Value::Bytes(decode_base64("eJyrzC9VyEgsS1UoLk1OTi0uTivNyalUSElNzk9JTVHITQUAzLIMQQ==").into()),
+                max_size: 31,
+            ],
+            want: Err("decompressed value exceeds the maximum size of 31 bytes"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
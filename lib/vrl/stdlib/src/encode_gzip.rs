@@ -0,0 +1,152 @@
+use std::io::Write;
+
+use ::value::Value;
+use flate2::{write::GzEncoder, Compression};
+use vrl::prelude::expression::FunctionExpression;
+use vrl::prelude::*;
+
+const MAX_COMPRESSION_LEVEL: i64 = 9;
+
+fn encode_gzip(value: Value, compression_level: Option<Value>) -> Resolved {
+    let level = match compression_level {
+        Some(level) => {
+            let level = level.try_integer()?;
+            if !(0..=MAX_COMPRESSION_LEVEL).contains(&level) {
+                return Err(format!(
+                    "compression level must be between 0 and {}, got {}",
+                    MAX_COMPRESSION_LEVEL, level
+                )
+                .into());
+            }
+            Compression::new(level as u32)
+        }
+        None => Compression::default(),
+    };
+    let value = value.try_bytes()?;
+
+    let mut encoder = GzEncoder::new(Vec::new(), level);
+    encoder
+        .write_all(&value)
+        .expect("writing to a vector never fails");
+    let encoded = encoder.finish().expect("writing to a vector never fails");
+
+    Ok(Value::Bytes(encoded.into()))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeGzip;
+
+impl Function for EncodeGzip {
+    fn identifier(&self) -> &'static str {
+        "encode_gzip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "compression_level",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let compression_level = arguments.optional("compression_level");
+
+        Ok(EncodeGzipFn {
+            value,
+            compression_level,
+        }
+        .as_expr())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "round trip",
+            source: r#"decode_gzip!(encode_gzip("please encode me"))"#,
+            result: Ok("please encode me"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeGzipFn {
+    value: Box<dyn Expression>,
+    compression_level: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for EncodeGzipFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let compression_level = self
+            .compression_level
+            .as_ref()
+            .map(|level| level.resolve(ctx))
+            .transpose()?;
+
+        encode_gzip(value, compression_level)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        let is_compression_level_valid_constant = match &self.compression_level {
+            Some(level) => match level.as_value() {
+                Some(Value::Integer(level)) => (0..=MAX_COMPRESSION_LEVEL).contains(&level),
+                _ => false,
+            },
+            None => true,
+        };
+
+        TypeDef::bytes().with_fallibility(!is_compression_level_valid_constant)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+
+    use super::*;
+
+    fn decode_gzip(value: Value) -> String {
+        let mut decoded = String::new();
+        GzDecoder::new(value.try_bytes().unwrap().as_ref())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        decoded
+    }
+
+    #[test]
+    fn round_trip() {
+        for level in [None, Some(0), Some(9)] {
+            let encoded = encode_gzip(
+                value!("please encode me, please encode me"),
+                level.map(Value::from),
+            )
+            .unwrap();
+            assert_eq!(decode_gzip(encoded), "please encode me, please encode me");
+        }
+    }
+
+    #[test]
+    fn invalid_compression_level() {
+        assert_eq!(
+            encode_gzip(value!("please encode me"), Some(value!(10)))
+                .unwrap_err()
+                .to_string(),
+            "compression level must be between 0 and 9, got 10"
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use ::value::Value;
+use prost::Message;
+use prost_reflect::MessageDescriptor;
+use vrl::prelude::*;
+
+use crate::protobuf::{message_descriptor, value_to_message};
+
+fn encode_protobuf(value: Value, descriptor: &MessageDescriptor) -> Resolved {
+    let message = value_to_message(value, descriptor)?;
+
+    Ok(Value::Bytes(message.encode_to_vec().into()))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeProtobuf;
+
+impl Function for EncodeProtobuf {
+    fn identifier(&self) -> &'static str {
+        "encode_protobuf"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::OBJECT,
+                required: true,
+            },
+            Parameter {
+                keyword: "desc_file",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "message_type",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "message",
+            source: indoc! {r#"
+                encode_base64(encode_protobuf!(
+                    { "name": "someone", "phones": [{ "number": "123456" }] },
+                    desc_file: "../stdlib/data/protobuf/test_protobuf.desc",
+                    message_type: "test_protobuf.Person"
+                ))
+            "#},
+            result: Ok("Cgdzb21lb25lIggKBjEyMzQ1Ng=="),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let descriptor = message_descriptor(&arguments)?;
+
+        Ok(EncodeProtobufFn { value, descriptor }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncodeProtobufFn {
+    value: Box<dyn Expression>,
+    descriptor: MessageDescriptor,
+}
+
+impl FunctionExpression for EncodeProtobufFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        encode_protobuf(value, &self.descriptor)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESC_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/data/protobuf/test_protobuf.desc"
+    );
+
+    test_function![
+        encode_protobuf => EncodeProtobuf;
+
+        message {
+            args: func_args![
+                value: value!({
+                    name: "someone",
+                    id: 42,
+                    phones: [{ number: "123456", "type": "HOME" }],
+                    data: { foo: "bar" },
+                    email: null,
+                    active: true,
+                }),
+                desc_file: DESC_FILE,
+                message_type: "test_protobuf.Person",
+            ],
+            want: Ok(Value::from(Bytes::from_static(b"\x0a\x07someone\x10\x2a\x22\x0a\x0a\x06123456\x10\x01\x2a\x0a\x0a\x03foo\x12\x03bar\x38\x01"))),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        unknown_field {
+            args: func_args![
+                value: value!({ nickname: "someone" }),
+                desc_file: DESC_FILE,
+                message_type: "test_protobuf.Person",
+            ],
+            want: Err(r#"unknown field "nickname" for message "test_protobuf.Person""#),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_type {
+            args: func_args![
+                value: value!({ id: "42" }),
+                desc_file: DESC_FILE,
+                message_type: "test_protobuf.Person",
+            ],
+            want: Err("expected integer, got string"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        unknown_enum_value {
+            args: func_args![
+                value: value!({ phones: [{ "type": "FAX" }] }),
+                desc_file: DESC_FILE,
+                message_type: "test_protobuf.Person",
+            ],
+            want: Err(r#"unknown value "FAX" for enum "test_protobuf.Person.PhoneType""#),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
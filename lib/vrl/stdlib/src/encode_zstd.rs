@@ -0,0 +1,142 @@
+use ::value::Value;
+use vrl::prelude::expression::FunctionExpression;
+use vrl::prelude::*;
+
+const MIN_COMPRESSION_LEVEL: i64 = 1;
+const MAX_COMPRESSION_LEVEL: i64 = 21;
+
+fn encode_zstd(value: Value, compression_level: Option<Value>) -> Resolved {
+    let level = match compression_level {
+        Some(level) => {
+            let level = level.try_integer()?;
+            if !(MIN_COMPRESSION_LEVEL..=MAX_COMPRESSION_LEVEL).contains(&level) {
+                return Err(format!(
+                    "compression level must be between {} and {}, got {}",
+                    MIN_COMPRESSION_LEVEL, MAX_COMPRESSION_LEVEL, level
+                )
+                .into());
+            }
+            level as i32
+        }
+        None => zstd::DEFAULT_COMPRESSION_LEVEL,
+    };
+    let value = value.try_bytes()?;
+
+    let encoded =
+        zstd::stream::encode_all(value.as_ref(), level).expect("writing to a vector never fails");
+
+    Ok(Value::Bytes(encoded.into()))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeZstd;
+
+impl Function for EncodeZstd {
+    fn identifier(&self) -> &'static str {
+        "encode_zstd"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "compression_level",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let compression_level = arguments.optional("compression_level");
+
+        Ok(EncodeZstdFn {
+            value,
+            compression_level,
+        }
+        .as_expr())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "round trip",
+            source: r#"decode_zstd!(encode_zstd("please encode me"))"#,
+            result: Ok("please encode me"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeZstdFn {
+    value: Box<dyn Expression>,
+    compression_level: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for EncodeZstdFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let compression_level = self
+            .compression_level
+            .as_ref()
+            .map(|level| level.resolve(ctx))
+            .transpose()?;
+
+        encode_zstd(value, compression_level)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        let is_compression_level_valid_constant = match &self.compression_level {
+            Some(level) => match level.as_value() {
+                Some(Value::Integer(level)) => {
+                    (MIN_COMPRESSION_LEVEL..=MAX_COMPRESSION_LEVEL).contains(&level)
+                }
+                _ => false,
+            },
+            None => true,
+        };
+
+        TypeDef::bytes().with_fallibility(!is_compression_level_valid_constant)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decode_zstd(value: Value) -> String {
+        let decoded = zstd::stream::decode_all(value.try_bytes().unwrap().as_ref()).unwrap();
+        String::from_utf8(decoded).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        for level in [None, Some(1), Some(21)] {
+            let encoded = encode_zstd(
+                value!("please encode me, please encode me"),
+                level.map(Value::from),
+            )
+            .unwrap();
+            assert_eq!(decode_zstd(encoded), "please encode me, please encode me");
+        }
+    }
+
+    #[test]
+    fn invalid_compression_level() {
+        assert_eq!(
+            encode_zstd(value!("please encode me"), Some(value!(0)))
+                .unwrap_err()
+                .to_string(),
+            "compression level must be between 1 and 21, got 0"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use ::value::Value;
+use vrl::prelude::*;
+
+fn get_state(ctx: &Context, key: Value) -> Resolved {
+    let key = key.try_bytes_utf8_lossy()?;
+    Ok(ctx
+        .state_store()
+        .and_then(|store| store.get(&key))
+        .unwrap_or(Value::Null))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GetState;
+
+impl Function for GetState {
+    fn identifier(&self) -> &'static str {
+        "get_state"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "key",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "missing key",
+                source: r#"get_state("last_seen")"#,
+                result: Ok("null"),
+            },
+            Example {
+                title: "set key",
+                source: r#"set_state!("last_seen", "2021-03-12T00:00:00Z"); get_state("last_seen")"#,
+                result: Ok("2021-03-12T00:00:00Z"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let key = arguments.required("key");
+
+        Ok(GetStateFn { key }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GetStateFn {
+    key: Box<dyn Expression>,
+}
+
+impl FunctionExpression for GetStateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = self.key.resolve(ctx)?;
+        get_state(ctx, key)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::any().infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        get_state => GetState;
+
+        without_state_store {
+            args: func_args![key: "foo"],
+            want: Ok(Value::Null),
+            tdef: TypeDef::any().infallible(),
+        }
+    ];
+}
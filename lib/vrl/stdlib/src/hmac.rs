@@ -0,0 +1,151 @@
+use ::hmac::Mac;
+use ::sha1::Sha1;
+use ::value::Value;
+use sha_2::{Sha224, Sha256, Sha384, Sha512};
+use vrl::prelude::*;
+
+macro_rules! hmac {
+    ($algorithm:ty, $key:expr, $value:expr) => {{
+        let mut mac = <::hmac::Hmac<$algorithm>>::new_from_slice($key.as_ref())
+            .expect("HMAC can take a key of any size");
+        mac.update($value.as_ref());
+        mac.finalize().into_bytes().to_vec()
+    }};
+}
+
+fn hmac(value: Value, key: Value, algorithm: &Bytes) -> Resolved {
+    let value = value.try_bytes()?;
+    let key = key.try_bytes()?;
+    let code = match algorithm.as_ref() {
+        b"SHA1" => hmac!(Sha1, key, value),
+        b"SHA-224" => hmac!(Sha224, key, value),
+        b"SHA-256" => hmac!(Sha256, key, value),
+        b"SHA-384" => hmac!(Sha384, key, value),
+        b"SHA-512" => hmac!(Sha512, key, value),
+        _ => unreachable!("enum invariant"),
+    };
+    Ok(Value::Bytes(code.into()))
+}
+
+fn algorithms() -> Vec<Value> {
+    vec![
+        value!("SHA1"),
+        value!("SHA-224"),
+        value!("SHA-256"),
+        value!("SHA-384"),
+        value!("SHA-512"),
+    ]
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Hmac;
+
+impl Function for Hmac {
+    fn identifier(&self) -> &'static str {
+        "hmac"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "algorithm",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "default algorithm",
+                source: r#"encode_base64(hmac("Hello there", "super-secret-key"))"#,
+                result: Ok("eLGE8YMviv85NPXgISRUZxstBNSU47JQdcXkUWcClmI="),
+            },
+            Example {
+                title: "custom algorithm",
+                source: r#"encode_base64(hmac("Hello there", "super-secret-key", algorithm: "SHA1"))"#,
+                result: Ok("MiyBIHO8Set9+6crALiwkS0yFPE="),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let key = arguments.required("key");
+        let algorithm = arguments
+            .optional_enum("algorithm", &algorithms())?
+            .unwrap_or_else(|| value!("SHA-256"))
+            .try_bytes()
+            .expect("algorithm not bytes");
+
+        Ok(HmacFn {
+            value,
+            key,
+            algorithm,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HmacFn {
+    value: Box<dyn Expression>,
+    key: Box<dyn Expression>,
+    algorithm: Bytes,
+}
+
+impl FunctionExpression for HmacFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let key = self.key.resolve(ctx)?;
+
+        hmac(value, key, &self.algorithm)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::bytes().infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        hmac => Hmac;
+
+        sha256 {
+            args: func_args![value: "Hello there", key: "super-secret-key"],
+            want: Ok(Value::Bytes(Bytes::from_static(&[
+                120, 177, 132, 241, 131, 47, 138, 255, 57, 52, 245, 224, 33, 36, 84, 103, 27, 45,
+                4, 212, 148, 227, 178, 80, 117, 197, 228, 81, 103, 2, 150, 98,
+            ]))),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        sha1 {
+            args: func_args![value: "Hello there", key: "super-secret-key", algorithm: "SHA1"],
+            want: Ok(Value::Bytes(Bytes::from_static(&[
+                50, 44, 129, 32, 115, 188, 73, 235, 125, 251, 167, 43, 0, 184, 176, 145, 45, 50,
+                20, 241,
+            ]))),
+            tdef: TypeDef::bytes().infallible(),
+        }
+    ];
+}
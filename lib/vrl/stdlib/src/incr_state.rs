@@ -0,0 +1,118 @@
+use ::value::Value;
+use vrl::prelude::*;
+
+use crate::util::state_ttl;
+
+fn incr_state(ctx: &Context, key: Value, by: Option<Value>, ttl: Option<Value>) -> Resolved {
+    let key = key.try_bytes_utf8_lossy()?;
+    let by = by
+        .map(VrlValueConvert::try_integer)
+        .transpose()?
+        .unwrap_or(1);
+    let ttl = ttl.map(state_ttl).transpose()?;
+    let store = ctx
+        .state_store()
+        .ok_or("state is not available to this program")?;
+
+    store
+        .increment(&key, by, ttl)
+        .map(Into::into)
+        .map_err(|err| err.to_string().into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct IncrState;
+
+impl Function for IncrState {
+    fn identifier(&self) -> &'static str {
+        "incr_state"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "by",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "ttl",
+                kind: kind::INTEGER | kind::FLOAT,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "new counter",
+                source: r#"incr_state!("requests")"#,
+                result: Ok("1"),
+            },
+            Example {
+                title: "existing counter",
+                source: r#"incr_state!("requests"); incr_state!("requests", by: 10, ttl: 60)"#,
+                result: Ok("11"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let key = arguments.required("key");
+        let by = arguments.optional("by");
+        let ttl = arguments.optional("ttl");
+
+        Ok(IncrStateFn { key, by, ttl }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IncrStateFn {
+    key: Box<dyn Expression>,
+    by: Option<Box<dyn Expression>>,
+    ttl: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for IncrStateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = self.key.resolve(ctx)?;
+        let by = self.by.as_ref().map(|expr| expr.resolve(ctx)).transpose()?;
+        let ttl = self
+            .ttl
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
+
+        incr_state(ctx, key, by, ttl)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::integer().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        incr_state => IncrState;
+
+        without_state_store {
+            args: func_args![key: "foo"],
+            want: Err("state is not available to this program"),
+            tdef: TypeDef::integer().fallible(),
+        }
+    ];
+}
@@ -0,0 +1,197 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use ::value::Value;
+use aes::{
+    cipher::{BlockEncrypt, KeyInit},
+    Aes128, Block,
+};
+use vrl::prelude::*;
+
+const KEY_SIZE: usize = 32;
+
+/// Anonymizes an address with Crypto-PAn, which maps addresses sharing a
+/// prefix of `n` bits to addresses sharing a prefix of `n` bits too.
+///
+/// The first half of the key is the AES key, and the second half is encrypted
+/// to derive the padding used to fill in the bits following each prefix.
+struct CryptoPan {
+    cipher: Aes128,
+    pad: u128,
+}
+
+impl CryptoPan {
+    fn new(key: &[u8; KEY_SIZE]) -> Self {
+        let cipher = Aes128::new_from_slice(&key[..16]).expect("key size is valid");
+        let mut pad = Block::clone_from_slice(&key[16..]);
+        cipher.encrypt_block(&mut pad);
+
+        Self {
+            cipher,
+            pad: u128::from_be_bytes(pad.as_slice().try_into().expect("block size is valid")),
+        }
+    }
+
+    /// Anonymizes the `bits` low bits of `address`.
+    fn anonymize(&self, address: u128, bits: u32) -> u128 {
+        let original = address << (128 - bits);
+        let mut flips = 0;
+
+        for position in 0..bits {
+            // Keep the first `position` bits of the address, followed by the padding.
+            let mask = (!0u128).checked_shl(128 - position).unwrap_or(0);
+            let input = (original & mask) | (self.pad & !mask);
+
+            let mut block = Block::from(input.to_be_bytes());
+            self.cipher.encrypt_block(&mut block);
+            flips |= u128::from(block[0] >> 7) << (bits - 1 - position);
+        }
+
+        address ^ flips
+    }
+}
+
+fn ip_anonymize(value: Value, key: Value) -> Resolved {
+    let ip: IpAddr = value
+        .try_bytes_utf8_lossy()?
+        .parse()
+        .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+    let key = key.try_bytes()?;
+    let key: &[u8; KEY_SIZE] = key.as_ref().try_into().map_err(|_| {
+        format!(
+            "Invalid key size. Expected {} bytes. Found {} bytes",
+            KEY_SIZE,
+            key.len()
+        )
+    })?;
+    let pan = CryptoPan::new(key);
+
+    let anonymized = match ip {
+        IpAddr::V4(ip) => {
+            let address = pan.anonymize(u128::from(u32::from(ip)), 32);
+            IpAddr::V4(Ipv4Addr::from(address as u32))
+        }
+        IpAddr::V6(ip) => IpAddr::V6(Ipv6Addr::from(pan.anonymize(u128::from(ip), 128))),
+    };
+
+    Ok(anonymized.to_string().into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpAnonymize;
+
+impl Function for IpAnonymize {
+    fn identifier(&self) -> &'static str {
+        "ip_anonymize"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "IPv4",
+                source: r#"ip_anonymize!("192.168.1.1", key: "boojahyoo3vaeToong0Eijee7Ahz3yee")"#,
+                result: Ok("206.171.6.128"),
+            },
+            Example {
+                title: "IPv6",
+                source: r#"ip_anonymize!("2001:db8::1", key: "boojahyoo3vaeToong0Eijee7Ahz3yee")"#,
+                result: Ok("3041:e87:cfc0:1800:187f:e4d0:7f1c:701"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let key = arguments.required("key");
+
+        Ok(IpAnonymizeFn { value, key }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpAnonymizeFn {
+    value: Box<dyn Expression>,
+    key: Box<dyn Expression>,
+}
+
+impl FunctionExpression for IpAnonymizeFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let key = self.key.resolve(ctx)?;
+
+        ip_anonymize(value, key)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The key of the sample data distributed with the reference implementation.
+    const KEY: [u8; KEY_SIZE] = [
+        21, 34, 23, 141, 51, 164, 207, 128, 19, 10, 91, 22, 73, 144, 125, 16, 216, 152, 143, 131,
+        121, 121, 101, 39, 98, 87, 76, 45, 42, 132, 34, 2,
+    ];
+
+    fn key() -> Value {
+        Value::Bytes(Bytes::from_static(&KEY))
+    }
+
+    test_function![
+        ip_anonymize => IpAnonymize;
+
+        ipv4 {
+            args: func_args![value: "128.11.68.132", key: key()],
+            want: Ok(value!("135.242.180.132")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv4_shared_prefix {
+            args: func_args![value: "129.118.74.4", key: key()],
+            want: Ok(value!("134.136.186.123")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv4_private {
+            args: func_args![value: "192.168.1.1", key: key()],
+            want: Ok(value!("252.103.242.114")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv6 {
+            args: func_args![value: "2001:db8::1", key: key()],
+            want: Ok(value!("4401:2bc:603f:d91d:27f:ff8e:e6f1:dc1e")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_key {
+            args: func_args![value: "192.168.1.1", key: "too short"],
+            want: Err("Invalid key size. Expected 32 bytes. Found 9 bytes"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
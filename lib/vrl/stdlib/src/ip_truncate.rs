@@ -0,0 +1,185 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use ::value::Value;
+use vrl::prelude::*;
+
+const DEFAULT_IPV4_PREFIX: i64 = 24;
+const DEFAULT_IPV6_PREFIX: i64 = 48;
+
+fn ip_truncate(value: Value, ipv4_prefix: Option<Value>, ipv6_prefix: Option<Value>) -> Resolved {
+    let ip: IpAddr = value
+        .try_bytes_utf8_lossy()?
+        .parse()
+        .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+    let truncated = match ip {
+        IpAddr::V4(ip) => {
+            let prefix = prefix(ipv4_prefix, DEFAULT_IPV4_PREFIX, 32)?;
+            let mask = (!0u32).checked_shl(32 - prefix).unwrap_or(0);
+            IpAddr::V4(Ipv4Addr::from(u32::from(ip) & mask))
+        }
+        IpAddr::V6(ip) => {
+            let prefix = prefix(ipv6_prefix, DEFAULT_IPV6_PREFIX, 128)?;
+            let mask = (!0u128).checked_shl(128 - prefix).unwrap_or(0);
+            IpAddr::V6(Ipv6Addr::from(u128::from(ip) & mask))
+        }
+    };
+
+    Ok(truncated.to_string().into())
+}
+
+/// Returns the prefix length to keep, which can't exceed the length of the
+/// address in bits.
+fn prefix(value: Option<Value>, default: i64, bits: u32) -> Result<u32> {
+    let prefix = match value {
+        Some(value) => value.try_integer()?,
+        None => default,
+    };
+
+    u32::try_from(prefix)
+        .ok()
+        .filter(|prefix| *prefix <= bits)
+        .ok_or_else(|| format!("prefix must be between 0 and {}, got {}", bits, prefix).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpTruncate;
+
+impl Function for IpTruncate {
+    fn identifier(&self) -> &'static str {
+        "ip_truncate"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "ipv4_prefix",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "ipv6_prefix",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "IPv4",
+                source: r#"ip_truncate!("192.168.10.23")"#,
+                result: Ok("192.168.10.0"),
+            },
+            Example {
+                title: "IPv6",
+                source: r#"ip_truncate!("2404:6800:4003:c02::64", ipv6_prefix: 32)"#,
+                result: Ok("2404:6800::"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let ipv4_prefix = arguments.optional("ipv4_prefix");
+        let ipv6_prefix = arguments.optional("ipv6_prefix");
+
+        Ok(IpTruncateFn {
+            value,
+            ipv4_prefix,
+            ipv6_prefix,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpTruncateFn {
+    value: Box<dyn Expression>,
+    ipv4_prefix: Option<Box<dyn Expression>>,
+    ipv6_prefix: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for IpTruncateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let ipv4_prefix = self
+            .ipv4_prefix
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
+        let ipv6_prefix = self
+            .ipv6_prefix
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
+
+        ip_truncate(value, ipv4_prefix, ipv6_prefix)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        ip_truncate => IpTruncate;
+
+        ipv4_default {
+            args: func_args![value: "192.168.10.23"],
+            want: Ok(value!("192.168.10.0")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv4_prefix {
+            args: func_args![value: "192.168.10.23", ipv4_prefix: 12],
+            want: Ok(value!("192.160.0.0")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv4_zero {
+            args: func_args![value: "192.168.10.23", ipv4_prefix: 0],
+            want: Ok(value!("0.0.0.0")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv6_default {
+            args: func_args![value: "2404:6800:4003:c02::64"],
+            want: Ok(value!("2404:6800:4003::")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv6_full {
+            args: func_args![value: "2404:6800:4003:c02::64", ipv6_prefix: 128],
+            want: Ok(value!("2404:6800:4003:c02::64")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_prefix {
+            args: func_args![value: "192.168.10.23", ipv4_prefix: 33],
+            want: Err("prefix must be between 0 and 32, got 33"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_ip {
+            args: func_args![value: "192.168.10"],
+            want: Err("unable to parse IP address: invalid IP address syntax"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
@@ -26,6 +26,8 @@
     clippy::trivially_copy_pass_by_ref, // allowed in initial deny commit
 )]
 
+#[cfg(any(feature = "encode_protobuf", feature = "parse_protobuf"))]
+mod protobuf;
 mod util;
 
 #[cfg(feature = "append")]
@@ -42,16 +44,28 @@ mod boolean;
 mod ceil;
 #[cfg(feature = "chunks")]
 mod chunks;
+#[cfg(feature = "community_id")]
+mod community_id;
 #[cfg(feature = "compact")]
 mod compact;
 #[cfg(feature = "contains")]
 mod contains;
+#[cfg(feature = "crc32")]
+mod crc32;
 #[cfg(feature = "decode_base64")]
 mod decode_base64;
+#[cfg(feature = "decode_gzip")]
+mod decode_gzip;
 #[cfg(feature = "decode_mime_q")]
 mod decode_mime_q;
 #[cfg(feature = "decode_percent")]
 mod decode_percent;
+#[cfg(feature = "decode_snappy")]
+mod decode_snappy;
+#[cfg(feature = "decode_zlib")]
+mod decode_zlib;
+#[cfg(feature = "decode_zstd")]
+mod decode_zstd;
 #[cfg(feature = "decrypt")]
 mod decrypt;
 #[cfg(feature = "del")]
@@ -60,6 +74,8 @@ mod del;
 mod downcase;
 #[cfg(feature = "encode_base64")]
 mod encode_base64;
+#[cfg(feature = "encode_gzip")]
+mod encode_gzip;
 #[cfg(feature = "encode_json")]
 mod encode_json;
 #[cfg(feature = "encode_key_value")]
@@ -68,6 +84,10 @@ mod encode_key_value;
 mod encode_logfmt;
 #[cfg(feature = "encode_percent")]
 mod encode_percent;
+#[cfg(feature = "encode_protobuf")]
+mod encode_protobuf;
+#[cfg(feature = "encode_zstd")]
+mod encode_zstd;
 #[cfg(feature = "encrypt")]
 mod encrypt;
 #[cfg(feature = "ends_with")]
@@ -98,10 +118,18 @@ mod get;
 mod get_env_var;
 #[cfg(feature = "get_hostname")]
 mod get_hostname;
+#[cfg(feature = "get_state")]
+mod get_state;
+#[cfg(feature = "hmac")]
+mod hmac;
 #[cfg(feature = "includes")]
 mod includes;
+#[cfg(feature = "incr_state")]
+mod incr_state;
 #[cfg(feature = "integer")]
 mod integer;
+#[cfg(feature = "ip_anonymize")]
+mod ip_anonymize;
 #[cfg(feature = "ip_aton")]
 mod ip_aton;
 #[cfg(feature = "ip_cidr_contains")]
@@ -116,6 +144,8 @@ mod ip_pton;
 mod ip_subnet;
 #[cfg(feature = "ip_to_ipv6")]
 mod ip_to_ipv6;
+#[cfg(feature = "ip_truncate")]
+mod ip_truncate;
 #[cfg(feature = "ipv6_to_ipv4")]
 mod ipv6_to_ipv4;
 #[cfg(feature = "is_array")]
@@ -220,6 +250,8 @@ mod parse_linux_authorization;
 mod parse_logfmt;
 #[cfg(feature = "parse_nginx_log")]
 mod parse_nginx_log;
+#[cfg(feature = "parse_protobuf")]
+mod parse_protobuf;
 #[cfg(feature = "parse_query_string")]
 mod parse_query_string;
 #[cfg(feature = "parse_regex")]
@@ -254,8 +286,12 @@ mod replace;
 mod reverse_dns;
 #[cfg(feature = "round")]
 mod round;
+#[cfg(feature = "seahash")]
+mod seahash;
 #[cfg(feature = "set")]
 mod set;
+#[cfg(feature = "set_state")]
+mod set_state;
 #[cfg(feature = "sha1")]
 mod sha1;
 #[cfg(feature = "sha2")]
@@ -318,6 +354,8 @@ mod upcase;
 mod uuid_v4;
 #[cfg(feature = "values")]
 mod values;
+#[cfg(feature = "xxhash")]
+mod xxhash;
 
 // -----------------------------------------------------------------------------
 
@@ -333,16 +371,28 @@ pub use boolean::Boolean;
 pub use ceil::Ceil;
 #[cfg(feature = "chunks")]
 pub use chunks::Chunks;
+#[cfg(feature = "community_id")]
+pub use community_id::CommunityId;
 #[cfg(feature = "compact")]
 pub use compact::Compact;
 #[cfg(feature = "contains")]
 pub use contains::Contains;
+#[cfg(feature = "crc32")]
+pub use crc32::Crc32;
 #[cfg(feature = "decode_base64")]
 pub use decode_base64::DecodeBase64;
+#[cfg(feature = "decode_gzip")]
+pub use decode_gzip::DecodeGzip;
 #[cfg(feature = "decode_mime_q")]
 pub use decode_mime_q::DecodeMimeQ;
 #[cfg(feature = "decode_percent")]
 pub use decode_percent::DecodePercent;
+#[cfg(feature = "decode_snappy")]
+pub use decode_snappy::DecodeSnappy;
+#[cfg(feature = "decode_zlib")]
+pub use decode_zlib::DecodeZlib;
+#[cfg(feature = "decode_zstd")]
+pub use decode_zstd::DecodeZstd;
 #[cfg(feature = "decrypt")]
 pub use decrypt::Decrypt;
 #[cfg(feature = "del")]
@@ -351,6 +401,8 @@ pub use del::Del;
 pub use downcase::Downcase;
 #[cfg(feature = "encode_base64")]
 pub use encode_base64::EncodeBase64;
+#[cfg(feature = "encode_gzip")]
+pub use encode_gzip::EncodeGzip;
 #[cfg(feature = "encode_json")]
 pub use encode_json::EncodeJson;
 #[cfg(feature = "encode_key_value")]
@@ -359,6 +411,10 @@ pub use encode_key_value::EncodeKeyValue;
 pub use encode_logfmt::EncodeLogfmt;
 #[cfg(feature = "encode_percent")]
 pub use encode_percent::EncodePercent;
+#[cfg(feature = "encode_protobuf")]
+pub use encode_protobuf::EncodeProtobuf;
+#[cfg(feature = "encode_zstd")]
+pub use encode_zstd::EncodeZstd;
 #[cfg(feature = "encrypt")]
 pub use encrypt::Encrypt;
 #[cfg(feature = "ends_with")]
@@ -389,10 +445,16 @@ pub use get::Get;
 pub use get_env_var::GetEnvVar;
 #[cfg(feature = "get_hostname")]
 pub use get_hostname::GetHostname;
+#[cfg(feature = "get_state")]
+pub use get_state::GetState;
 #[cfg(feature = "includes")]
 pub use includes::Includes;
+#[cfg(feature = "incr_state")]
+pub use incr_state::IncrState;
 #[cfg(feature = "integer")]
 pub use integer::Integer;
+#[cfg(feature = "ip_anonymize")]
+pub use ip_anonymize::IpAnonymize;
 #[cfg(feature = "ip_aton")]
 pub use ip_aton::IpAton;
 #[cfg(feature = "ip_cidr_contains")]
@@ -407,6 +469,8 @@ pub use ip_pton::IpPton;
 pub use ip_subnet::IpSubnet;
 #[cfg(feature = "ip_to_ipv6")]
 pub use ip_to_ipv6::IpToIpv6;
+#[cfg(feature = "ip_truncate")]
+pub use ip_truncate::IpTruncate;
 #[cfg(feature = "ipv6_to_ipv4")]
 pub use ipv6_to_ipv4::Ipv6ToIpV4;
 #[cfg(feature = "is_array")]
@@ -501,6 +565,8 @@ pub use parse_linux_authorization::ParseLinuxAuthorization;
 pub use parse_logfmt::ParseLogFmt;
 #[cfg(feature = "parse_nginx_log")]
 pub use parse_nginx_log::ParseNginxLog;
+#[cfg(feature = "parse_protobuf")]
+pub use parse_protobuf::ParseProtobuf;
 #[cfg(feature = "parse_query_string")]
 pub use parse_query_string::ParseQueryString;
 #[cfg(feature = "parse_regex")]
@@ -539,6 +605,8 @@ pub use reverse_dns::ReverseDns;
 pub use round::Round;
 #[cfg(feature = "set")]
 pub use set::Set;
+#[cfg(feature = "set_state")]
+pub use set_state::SetState;
 #[cfg(feature = "sha2")]
 pub use sha2::Sha2;
 #[cfg(feature = "sha3")]
@@ -599,11 +667,17 @@ pub use upcase::Upcase;
 pub use uuid_v4::UuidV4;
 #[cfg(feature = "values")]
 pub use values::Values;
+#[cfg(feature = "xxhash")]
+pub use xxhash::Xxhash;
 
 #[cfg(feature = "array")]
 pub use crate::array::Array;
+#[cfg(feature = "hmac")]
+pub use crate::hmac::Hmac;
 #[cfg(feature = "md5")]
 pub use crate::md5::Md5;
+#[cfg(feature = "seahash")]
+pub use crate::seahash::Seahash;
 #[cfg(feature = "sha1")]
 pub use crate::sha1::Sha1;
 
@@ -626,12 +700,24 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(Chunks),
         #[cfg(feature = "compact")]
         Box::new(Compact),
+        #[cfg(feature = "community_id")]
+        Box::new(CommunityId),
         #[cfg(feature = "contains")]
         Box::new(Contains),
+        #[cfg(feature = "crc32")]
+        Box::new(Crc32),
         #[cfg(feature = "decode_base64")]
         Box::new(DecodeBase64),
+        #[cfg(feature = "decode_gzip")]
+        Box::new(DecodeGzip),
         #[cfg(feature = "decode_percent")]
         Box::new(DecodePercent),
+        #[cfg(feature = "decode_snappy")]
+        Box::new(DecodeSnappy),
+        #[cfg(feature = "decode_zlib")]
+        Box::new(DecodeZlib),
+        #[cfg(feature = "decode_zstd")]
+        Box::new(DecodeZstd),
         #[cfg(feature = "decode_mime_q")]
         Box::new(DecodeMimeQ),
         #[cfg(feature = "decrypt")]
@@ -642,6 +728,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(Downcase),
         #[cfg(feature = "encode_base64")]
         Box::new(EncodeBase64),
+        #[cfg(feature = "encode_gzip")]
+        Box::new(EncodeGzip),
         #[cfg(feature = "encode_json")]
         Box::new(EncodeJson),
         #[cfg(feature = "encode_key_value")]
@@ -650,6 +738,10 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(EncodeLogfmt),
         #[cfg(feature = "encode_percent")]
         Box::new(EncodePercent),
+        #[cfg(feature = "encode_protobuf")]
+        Box::new(EncodeProtobuf),
+        #[cfg(feature = "encode_zstd")]
+        Box::new(EncodeZstd),
         #[cfg(feature = "encrypt")]
         Box::new(Encrypt),
         #[cfg(feature = "ends_with")]
@@ -680,10 +772,18 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(GetEnvVar),
         #[cfg(feature = "get_hostname")]
         Box::new(GetHostname),
+        #[cfg(feature = "get_state")]
+        Box::new(GetState),
+        #[cfg(feature = "hmac")]
+        Box::new(Hmac),
         #[cfg(feature = "includes")]
         Box::new(Includes),
+        #[cfg(feature = "incr_state")]
+        Box::new(IncrState),
         #[cfg(feature = "integer")]
         Box::new(Integer),
+        #[cfg(feature = "ip_anonymize")]
+        Box::new(IpAnonymize),
         #[cfg(feature = "ip_aton")]
         Box::new(IpAton),
         #[cfg(feature = "ip_cidr_contains")]
@@ -698,6 +798,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(IpSubnet),
         #[cfg(feature = "ip_to_ipv6")]
         Box::new(IpToIpv6),
+        #[cfg(feature = "ip_truncate")]
+        Box::new(IpTruncate),
         #[cfg(feature = "ipv6_to_ipv4")]
         Box::new(Ipv6ToIpV4),
         #[cfg(feature = "is_array")]
@@ -798,6 +900,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(ParseLogFmt),
         #[cfg(feature = "parse_nginx_log")]
         Box::new(ParseNginxLog),
+        #[cfg(feature = "parse_protobuf")]
+        Box::new(ParseProtobuf),
         #[cfg(feature = "parse_query_string")]
         Box::new(ParseQueryString),
         #[cfg(feature = "parse_regex")]
@@ -832,8 +936,12 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(ReverseDns),
         #[cfg(feature = "round")]
         Box::new(Round),
+        #[cfg(feature = "seahash")]
+        Box::new(Seahash),
         #[cfg(feature = "set")]
         Box::new(Set),
+        #[cfg(feature = "set_state")]
+        Box::new(SetState),
         #[cfg(feature = "sha1")]
         Box::new(Sha1),
         #[cfg(feature = "sha2")]
@@ -896,5 +1004,7 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(UuidV4),
         #[cfg(feature = "values")]
         Box::new(Values),
+        #[cfg(feature = "xxhash")]
+        Box::new(Xxhash),
     ]
 }
@@ -0,0 +1,153 @@
+use ::value::Value;
+use prost_reflect::{DynamicMessage, MessageDescriptor};
+use vrl::prelude::*;
+
+use crate::protobuf::{message_descriptor, message_kind, message_to_value};
+
+fn parse_protobuf(value: Value, descriptor: &MessageDescriptor) -> Resolved {
+    let bytes = value.try_bytes()?;
+    let message = DynamicMessage::decode(descriptor.clone(), bytes)
+        .map_err(|err| format!("unable to decode protobuf message: {}", err))?;
+
+    message_to_value(&message)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseProtobuf;
+
+impl Function for ParseProtobuf {
+    fn identifier(&self) -> &'static str {
+        "parse_protobuf"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "desc_file",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "message_type",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "message",
+            source: indoc! {r#"
+                parse_protobuf!(
+                    decode_base64!("Cgdzb21lb25lIggKBjEyMzQ1Ng=="),
+                    desc_file: "../stdlib/data/protobuf/test_protobuf.desc",
+                    message_type: "test_protobuf.Person"
+                )
+            "#},
+            result: Ok(r#"{ "name": "someone", "phones": [{ "number": "123456" }] }"#),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let descriptor = message_descriptor(&arguments)?;
+
+        Ok(ParseProtobufFn { value, descriptor }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseProtobufFn {
+    value: Box<dyn Expression>,
+    descriptor: MessageDescriptor,
+}
+
+impl FunctionExpression for ParseProtobufFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        parse_protobuf(value, &self.descriptor)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::object(message_kind(&self.descriptor)).fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESC_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/data/protobuf/test_protobuf.desc"
+    );
+
+    fn person_kind() -> Collection<Field> {
+        let phone = BTreeMap::from([
+            (Field::from("number"), Kind::bytes().or_undefined()),
+            (
+                Field::from("type"),
+                Kind::bytes().or_integer().or_undefined(),
+            ),
+        ]);
+
+        BTreeMap::from([
+            (Field::from("name"), Kind::bytes().or_undefined()),
+            (Field::from("id"), Kind::integer().or_undefined()),
+            (Field::from("email"), Kind::bytes().or_undefined()),
+            (
+                Field::from("phones"),
+                Kind::array(Collection::from_unknown(Kind::object(phone))).or_undefined(),
+            ),
+            (
+                Field::from("data"),
+                Kind::object(Collection::from_unknown(Kind::bytes())).or_undefined(),
+            ),
+            (Field::from("score"), Kind::float().or_undefined()),
+            (Field::from("active"), Kind::boolean().or_undefined()),
+            (Field::from("avatar"), Kind::bytes().or_undefined()),
+        ])
+        .into()
+    }
+
+    test_function![
+        parse_protobuf => ParseProtobuf;
+
+        message {
+            args: func_args![
+                value: Bytes::from_static(b"\x0a\x07someone\x10\x2a\x22\x0a\x0a\x06123456\x10\x01\x2a\x0a\x0a\x03foo\x12\x03bar\x38\x01"),
+                desc_file: DESC_FILE,
+                message_type: "test_protobuf.Person",
+            ],
+            want: Ok(value!({
+                name: "someone",
+                id: 42,
+                phones: [{ number: "123456", "type": "HOME" }],
+                data: { foo: "bar" },
+                active: true,
+            })),
+            tdef: TypeDef::object(person_kind()).fallible(),
+        }
+
+        unknown_enum_value {
+            args: func_args![
+                value: Bytes::from_static(b"\x22\x04\x10\x07"),
+                desc_file: DESC_FILE,
+                message_type: "test_protobuf.Person",
+            ],
+            want: Ok(value!({ phones: [{ "type": 7 }] })),
+            tdef: TypeDef::object(person_kind()).fallible(),
+        }
+    ];
+}
@@ -0,0 +1,388 @@
+//! Conversions between VRL values and protobuf messages, shared by the
+//! `parse_protobuf` and `encode_protobuf` functions.
+use std::{collections::HashMap, path::PathBuf};
+
+use ::value::Value;
+use prost_reflect::{
+    DescriptorPool, DynamicMessage, FieldDescriptor, Kind as ProtobufKind, MapKey,
+    MessageDescriptor, Value as ProtobufValue,
+};
+use vrl::{
+    diagnostic::{Label, Span},
+    prelude::*,
+};
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    UnreadableDescriptorFile {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    InvalidDescriptorFile {
+        path: PathBuf,
+        error: prost_reflect::DescriptorError,
+    },
+    UnknownMessageType {
+        path: PathBuf,
+        message_type: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnreadableDescriptorFile { path, error } => write!(
+                f,
+                "unable to read descriptor file {}: {}",
+                path.display(),
+                error
+            ),
+            Error::InvalidDescriptorFile { path, error } => {
+                write!(f, "invalid descriptor file {}: {}", path.display(), error)
+            }
+            Error::UnknownMessageType { path, message_type } => write!(
+                f,
+                r#"message type "{}" not found in descriptor file {}"#,
+                message_type,
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl DiagnosticMessage for Error {
+    fn code(&self) -> usize {
+        403
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        vec![Label::primary(self.to_string(), Span::default())]
+    }
+}
+
+/// Loads the descriptor of the message type given by the `message_type`
+/// argument, from the descriptor set file given by the `desc_file` argument.
+///
+/// The descriptor set is a `FileDescriptorSet`, as written by
+/// `protoc --descriptor_set_out`.
+pub(crate) fn message_descriptor(
+    arguments: &ArgumentList,
+) -> std::result::Result<MessageDescriptor, Box<dyn DiagnosticMessage>> {
+    let path = PathBuf::from(
+        arguments
+            .required_literal("desc_file")?
+            .to_value()
+            .try_bytes_utf8_lossy()
+            .expect("desc_file not bytes")
+            .into_owned(),
+    );
+    let message_type = arguments
+        .required_literal("message_type")?
+        .to_value()
+        .try_bytes_utf8_lossy()
+        .expect("message_type not bytes")
+        .into_owned();
+
+    let bytes = std::fs::read(&path).map_err(|error| {
+        Box::new(Error::UnreadableDescriptorFile {
+            path: path.clone(),
+            error,
+        }) as Box<dyn DiagnosticMessage>
+    })?;
+    let pool = DescriptorPool::decode(bytes.as_slice()).map_err(|error| {
+        Box::new(Error::InvalidDescriptorFile {
+            path: path.clone(),
+            error,
+        }) as Box<dyn DiagnosticMessage>
+    })?;
+
+    pool.get_message_by_name(&message_type).ok_or_else(|| {
+        Box::new(Error::UnknownMessageType { path, message_type }) as Box<dyn DiagnosticMessage>
+    })
+}
+
+/// Returns the kind of the object a message of the given type is parsed into.
+///
+/// Fields that aren't set are missing from the object, so all of them may be
+/// undefined.
+pub(crate) fn message_kind(message: &MessageDescriptor) -> Collection<Field> {
+    message_kind_inner(message, &mut Vec::new())
+}
+
+fn message_kind_inner(
+    message: &MessageDescriptor,
+    visiting: &mut Vec<String>,
+) -> Collection<Field> {
+    // Recursive message types can't be described, beyond their first level.
+    if visiting.iter().any(|name| name == message.full_name()) {
+        return Collection::any();
+    }
+    visiting.push(message.full_name().to_owned());
+
+    let fields = message
+        .fields()
+        .map(|field| {
+            let kind = if field.is_map() {
+                Kind::object(Collection::from_unknown(map_value_kind(&field, visiting)))
+            } else if field.is_list() {
+                Kind::array(Collection::from_unknown(kind_of(&field.kind(), visiting)))
+            } else {
+                kind_of(&field.kind(), visiting)
+            };
+
+            (Field::from(field.name().to_owned()), kind.or_undefined())
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    visiting.pop();
+    fields.into()
+}
+
+fn map_value_kind(field: &FieldDescriptor, visiting: &mut Vec<String>) -> Kind {
+    match field.kind() {
+        ProtobufKind::Message(entry) => kind_of(&entry.map_entry_value_field().kind(), visiting),
+        _ => Kind::any(),
+    }
+}
+
+fn kind_of(kind: &ProtobufKind, visiting: &mut Vec<String>) -> Kind {
+    match kind {
+        ProtobufKind::Double | ProtobufKind::Float => Kind::float(),
+        ProtobufKind::Int32
+        | ProtobufKind::Int64
+        | ProtobufKind::Uint32
+        | ProtobufKind::Uint64
+        | ProtobufKind::Sint32
+        | ProtobufKind::Sint64
+        | ProtobufKind::Fixed32
+        | ProtobufKind::Fixed64
+        | ProtobufKind::Sfixed32
+        | ProtobufKind::Sfixed64 => Kind::integer(),
+        ProtobufKind::Bool => Kind::boolean(),
+        ProtobufKind::String | ProtobufKind::Bytes => Kind::bytes(),
+        // Unknown enum values are kept as their number.
+        ProtobufKind::Enum(_) => Kind::bytes().or_integer(),
+        ProtobufKind::Message(message) => Kind::object(message_kind_inner(message, visiting)),
+    }
+}
+
+/// Converts a message to an object, keyed by field name.
+pub(crate) fn message_to_value(message: &DynamicMessage) -> Resolved {
+    let mut object = BTreeMap::new();
+    for (field, value) in message.fields() {
+        object.insert(field.name().to_owned(), field_to_value(&field, value)?);
+    }
+
+    Ok(Value::Object(object))
+}
+
+fn field_to_value(field: &FieldDescriptor, value: &ProtobufValue) -> Resolved {
+    match value {
+        ProtobufValue::List(values) => values
+            .iter()
+            .map(|value| to_value(value, &field.kind()))
+            .collect::<Result<Vec<_>>>()
+            .map(Value::Array),
+        ProtobufValue::Map(map) => {
+            let kind = match field.kind() {
+                ProtobufKind::Message(entry) => entry.map_entry_value_field().kind(),
+                kind => kind,
+            };
+
+            let mut object = BTreeMap::new();
+            for (key, value) in map {
+                let key = match key {
+                    MapKey::Bool(key) => key.to_string(),
+                    MapKey::I32(key) => key.to_string(),
+                    MapKey::I64(key) => key.to_string(),
+                    MapKey::U32(key) => key.to_string(),
+                    MapKey::U64(key) => key.to_string(),
+                    MapKey::String(key) => key.clone(),
+                };
+                object.insert(key, to_value(value, &kind)?);
+            }
+
+            Ok(Value::Object(object))
+        }
+        value => to_value(value, &field.kind()),
+    }
+}
+
+fn to_value(value: &ProtobufValue, kind: &ProtobufKind) -> Resolved {
+    Ok(match value {
+        ProtobufValue::Bool(value) => (*value).into(),
+        ProtobufValue::I32(value) => i64::from(*value).into(),
+        ProtobufValue::I64(value) => (*value).into(),
+        ProtobufValue::U32(value) => i64::from(*value).into(),
+        ProtobufValue::U64(value) => i64::try_from(*value)
+            .map_err(|_| format!("integer {} is out of range", value))?
+            .into(),
+        ProtobufValue::F32(value) => Value::from_f64_or_zero(f64::from(*value)),
+        ProtobufValue::F64(value) => Value::from_f64_or_zero(*value),
+        ProtobufValue::String(value) => value.as_str().into(),
+        ProtobufValue::Bytes(value) => Value::Bytes(value.clone()),
+        ProtobufValue::EnumNumber(number) => match kind {
+            ProtobufKind::Enum(descriptor) => descriptor
+                .get_value(*number)
+                .map_or_else(|| i64::from(*number).into(), |value| value.name().into()),
+            _ => i64::from(*number).into(),
+        },
+        ProtobufValue::Message(message) => message_to_value(message)?,
+        ProtobufValue::List(_) | ProtobufValue::Map(_) => {
+            return Err("unexpected nested repeated field".into())
+        }
+    })
+}
+
+/// Converts an object to a message of the given type. Fields set to `null`
+/// are left unset.
+pub(crate) fn value_to_message(
+    value: Value,
+    descriptor: &MessageDescriptor,
+) -> Result<DynamicMessage> {
+    let object = match value {
+        Value::Object(object) => object,
+        value => {
+            return Err(format!(
+                r#"expected object for message "{}", got {}"#,
+                descriptor.full_name(),
+                value.kind()
+            )
+            .into())
+        }
+    };
+
+    let mut message = DynamicMessage::new(descriptor.clone());
+    for (name, value) in object {
+        if value.is_null() {
+            continue;
+        }
+
+        let field = descriptor
+            .get_field_by_name(&name)
+            .or_else(|| descriptor.get_field_by_json_name(&name))
+            .ok_or_else(|| {
+                format!(
+                    r#"unknown field "{}" for message "{}""#,
+                    name,
+                    descriptor.full_name()
+                )
+            })?;
+        let value = value_to_field(&field, value)?;
+        message.set_field(&field, value);
+    }
+
+    Ok(message)
+}
+
+fn value_to_field(field: &FieldDescriptor, value: Value) -> Result<ProtobufValue> {
+    if field.is_map() {
+        let entry = match field.kind() {
+            ProtobufKind::Message(entry) => entry,
+            _ => unreachable!("map field without entry message"),
+        };
+        let (key_kind, value_kind) = (
+            entry.map_entry_key_field().kind(),
+            entry.map_entry_value_field().kind(),
+        );
+
+        let object = value.try_object()?;
+        let mut map = HashMap::with_capacity(object.len());
+        for (key, value) in object {
+            let key = to_map_key(&key, &key_kind)
+                .ok_or_else(|| format!(r#"invalid key "{}" for field "{}""#, key, field.name()))?;
+            map.insert(key, from_value(value, &value_kind)?);
+        }
+
+        Ok(ProtobufValue::Map(map))
+    } else if field.is_list() {
+        let kind = field.kind();
+        value
+            .try_array()?
+            .into_iter()
+            .map(|value| from_value(value, &kind))
+            .collect::<Result<Vec<_>>>()
+            .map(ProtobufValue::List)
+    } else {
+        from_value(value, &field.kind())
+    }
+}
+
+fn to_map_key(key: &str, kind: &ProtobufKind) -> Option<MapKey> {
+    Some(match kind {
+        ProtobufKind::String => MapKey::String(key.to_owned()),
+        ProtobufKind::Bool => MapKey::Bool(key.parse().ok()?),
+        ProtobufKind::Int32 | ProtobufKind::Sint32 | ProtobufKind::Sfixed32 => {
+            MapKey::I32(key.parse().ok()?)
+        }
+        ProtobufKind::Int64 | ProtobufKind::Sint64 | ProtobufKind::Sfixed64 => {
+            MapKey::I64(key.parse().ok()?)
+        }
+        ProtobufKind::Uint32 | ProtobufKind::Fixed32 => MapKey::U32(key.parse().ok()?),
+        ProtobufKind::Uint64 | ProtobufKind::Fixed64 => MapKey::U64(key.parse().ok()?),
+        _ => return None,
+    })
+}
+
+fn from_value(value: Value, kind: &ProtobufKind) -> Result<ProtobufValue> {
+    let out_of_range = |value: i64| format!("integer {} is out of range", value);
+
+    Ok(match (kind, value) {
+        (ProtobufKind::Double, Value::Float(value)) => ProtobufValue::F64(value.into_inner()),
+        (ProtobufKind::Double, Value::Integer(value)) => ProtobufValue::F64(value as f64),
+        (ProtobufKind::Float, Value::Float(value)) => ProtobufValue::F32(value.into_inner() as f32),
+        (ProtobufKind::Float, Value::Integer(value)) => ProtobufValue::F32(value as f32),
+        (
+            ProtobufKind::Int32 | ProtobufKind::Sint32 | ProtobufKind::Sfixed32,
+            Value::Integer(value),
+        ) => ProtobufValue::I32(i32::try_from(value).map_err(|_| out_of_range(value))?),
+        (
+            ProtobufKind::Int64 | ProtobufKind::Sint64 | ProtobufKind::Sfixed64,
+            Value::Integer(value),
+        ) => ProtobufValue::I64(value),
+        (ProtobufKind::Uint32 | ProtobufKind::Fixed32, Value::Integer(value)) => {
+            ProtobufValue::U32(u32::try_from(value).map_err(|_| out_of_range(value))?)
+        }
+        (ProtobufKind::Uint64 | ProtobufKind::Fixed64, Value::Integer(value)) => {
+            ProtobufValue::U64(u64::try_from(value).map_err(|_| out_of_range(value))?)
+        }
+        (ProtobufKind::Bool, Value::Boolean(value)) => ProtobufValue::Bool(value),
+        (ProtobufKind::String, Value::Bytes(value)) => {
+            ProtobufValue::String(String::from_utf8_lossy(&value).into_owned())
+        }
+        (ProtobufKind::Bytes, Value::Bytes(value)) => ProtobufValue::Bytes(value),
+        (ProtobufKind::Enum(descriptor), Value::Bytes(name)) => {
+            let name = String::from_utf8_lossy(&name);
+            let value = descriptor.get_value_by_name(&name).ok_or_else(|| {
+                format!(
+                    r#"unknown value "{}" for enum "{}""#,
+                    name,
+                    descriptor.full_name()
+                )
+            })?;
+            ProtobufValue::EnumNumber(value.number())
+        }
+        (ProtobufKind::Enum(_), Value::Integer(value)) => {
+            ProtobufValue::EnumNumber(i32::try_from(value).map_err(|_| out_of_range(value))?)
+        }
+        (ProtobufKind::Message(descriptor), value) => {
+            ProtobufValue::Message(value_to_message(value, descriptor)?)
+        }
+        (kind, value) => {
+            return Err(format!("expected {}, got {}", kind_name(kind), value.kind()).into())
+        }
+    })
+}
+
+fn kind_name(kind: &ProtobufKind) -> &'static str {
+    match kind {
+        ProtobufKind::Double | ProtobufKind::Float => "float",
+        ProtobufKind::Bool => "boolean",
+        ProtobufKind::String | ProtobufKind::Bytes => "string",
+        ProtobufKind::Enum(_) => "string or integer",
+        ProtobufKind::Message(_) => "object",
+        _ => "integer",
+    }
+}
@@ -1,11 +1,14 @@
 use std::{
     borrow::Cow,
     convert::{TryFrom, TryInto},
-    str::FromStr,
+    net::{Ipv4Addr, Ipv6Addr},
 };
 
+use ::hmac::{Hmac, Mac};
 use ::value::Value;
 use once_cell::sync::Lazy;
+use regex::Captures;
+use sha_2::{Digest, Sha224, Sha256, Sha384, Sha512};
 use vrl::prelude::*;
 
 // https://www.oreilly.com/library/view/regular-expressions-cookbook/9781449327453/ch04s12.html
@@ -20,6 +23,52 @@ static US_SOCIAL_SECURITY_NUMBER: Lazy<regex::Regex> = Lazy::new(|| {
     "#).unwrap()
 });
 
+// 13 to 19 digits, optionally grouped with spaces or dashes. Matches are
+// checked with the Luhn algorithm before being redacted.
+static CREDIT_CARD: Lazy<regex::Regex> =
+    Lazy::new(|| regex::Regex::new(r"\b(?:\d[ -]?){12,18}\d\b").unwrap());
+
+static EMAIL_ADDRESS: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b")
+        .unwrap()
+});
+
+// Candidates for IP addresses, which are parsed before being redacted.
+static IPV4_ADDRESS: Lazy<regex::Regex> =
+    Lazy::new(|| regex::Regex::new(r"\b(?:\d{1,3}\.){3}\d{1,3}\b").unwrap());
+
+// The character before the address is captured, as the regex crate has no
+// lookbehind, so that addresses are not matched inside words such as `std::fs`.
+static IPV6_ADDRESS: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(
+        r"(?i)(^|[^0-9a-z:])((?:[0-9a-f]{0,4}:){2,7}(?:(?:\d{1,3}\.){3}\d{1,3}|[0-9a-f]{0,4}))",
+    )
+    .unwrap()
+});
+
+static PHONE_NUMBER: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(
+    r#"(?x)                                       # Ignore whitespace and comments in the regex expression.
+    \+[1-9]\d{7,14}\b                             # E.164 number: +15551234567
+    |
+    (?:\+\d{1,3}[\ .-]?)?                         # Optional country code
+    (?:\(\d{2,4}\)[\ .-]?|\b\d{2,4}[\ .-])        # Area code, optionally in parentheses
+    \d{3,4}[\ .-]?\d{4}\b                         # Subscriber number
+    "#).unwrap()
+});
+
+// Matches are checked with the ISO 13616 checksum before being redacted.
+static IBAN: Lazy<regex::Regex> =
+    Lazy::new(|| regex::Regex::new(r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]){11,30}\b").unwrap());
+
+// The header of a JWT is a base64 encoded JSON object, which starts with `eyJ`.
+static JWT: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(r"\beyJ[A-Za-z0-9_-]*\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]*").unwrap()
+});
+
+static BEARER_TOKEN: Lazy<regex::Regex> =
+    Lazy::new(|| regex::Regex::new(r"(?i)\b(bearer\s+)[A-Za-z0-9._~+/-]+=*").unwrap());
+
 #[derive(Clone, Copy, Debug)]
 pub struct Redact;
 
@@ -40,6 +89,11 @@ impl Function for Redact {
                 kind: kind::ARRAY,
                 required: true,
             },
+            Parameter {
+                keyword: "redactor",
+                kind: kind::BYTES | kind::OBJECT,
+                required: false,
+            },
         ]
     }
 
@@ -55,6 +109,18 @@ impl Function for Redact {
                 source: r#"redact({ "name": "John Doe", "ssn": "123-12-1234"}, filters: ["us_social_security_number"])"#,
                 result: Ok(r#"{ "name": "John Doe", "ssn": "[REDACTED]" }"#),
             },
+            Example {
+                title: "credit_card_number with partial masking",
+                source: r#"redact("paid with 4111 1111 1111 1111", filters: ["credit_card_number"], redactor: { "type": "mask", "keep_last": 4 })"#,
+                result: Ok(r#"paid with **** **** **** 1111"#),
+            },
+            Example {
+                title: "email_address with keyed hash",
+                source: r#"redact("sent by jane@example.com", filters: ["email_address"], redactor: { "type": "sha2", "key": "secret" })"#,
+                result: Ok(
+                    r#"sent by fb817989d942e7ffb3d4b8b204f7abca29f4c25c3fa46574da84c50f30d07513"#,
+                ),
+            },
         ]
     }
 
@@ -89,7 +155,20 @@ impl Function for Redact {
             })
             .collect::<std::result::Result<Vec<Filter>, _>>()?;
 
-        let redactor = Redactor::Full;
+        let redactor = arguments
+            .optional_value("redactor")?
+            .map(|value| {
+                value
+                    .clone()
+                    .try_into()
+                    .map_err(|error| vrl::function::Error::InvalidArgument {
+                        keyword: "redactor",
+                        value,
+                        error,
+                    })
+            })
+            .transpose()?
+            .unwrap_or_default();
 
         Ok(RedactFn {
             value,
@@ -157,6 +236,14 @@ impl FunctionExpression for RedactFn {
 enum Filter {
     Pattern(Vec<Pattern>),
     UsSocialSecurityNumber,
+    CreditCardNumber,
+    EmailAddress,
+    Ipv4Address,
+    Ipv6Address,
+    PhoneNumber,
+    Iban,
+    Jwt,
+    BearerToken,
 }
 
 #[derive(Debug, Clone)]
@@ -165,6 +252,25 @@ enum Pattern {
     String(String),
 }
 
+impl Filter {
+    /// Returns the built-in filter with the given name.
+    fn named(name: &[u8]) -> std::result::Result<Self, &'static str> {
+        match name {
+            b"pattern" => Err("pattern cannot be used without arguments"),
+            b"us_social_security_number" => Ok(Filter::UsSocialSecurityNumber),
+            b"credit_card_number" => Ok(Filter::CreditCardNumber),
+            b"email_address" => Ok(Filter::EmailAddress),
+            b"ipv4_address" => Ok(Filter::Ipv4Address),
+            b"ipv6_address" => Ok(Filter::Ipv6Address),
+            b"phone_number" => Ok(Filter::PhoneNumber),
+            b"iban" => Ok(Filter::Iban),
+            b"jwt" => Ok(Filter::Jwt),
+            b"bearer_token" => Ok(Filter::BearerToken),
+            _ => Err("unknown filter name"),
+        }
+    }
+}
+
 impl TryFrom<Value> for Filter {
     type Error = &'static str;
 
@@ -180,7 +286,6 @@ impl TryFrom<Value> for Filter {
                 }?;
 
                 match r#type.as_ref() {
-                    b"us_social_security_number" => Ok(Filter::UsSocialSecurityNumber),
                     b"pattern" => {
                         let patterns = match object
                             .get("patterns")
@@ -200,14 +305,10 @@ impl TryFrom<Value> for Filter {
                         }?;
                         Ok(Filter::Pattern(patterns))
                     }
-                    _ => Err("unknown filter name"),
+                    name => Filter::named(name),
                 }
             }
-            Value::Bytes(bytes) => match bytes.as_ref() {
-                b"pattern" => Err("pattern cannot be used without arguments"),
-                b"us_social_security_number" => Ok(Filter::UsSocialSecurityNumber),
-                _ => Err("unknown filter name"),
-            },
+            Value::Bytes(bytes) => Filter::named(bytes.as_ref()),
             Value::Regex(regex) => Ok(Filter::Pattern(vec![Pattern::Regex((*regex).clone())])),
             _ => Err("unknown literal for filter, must be a regex, filter name, or object"),
         }
@@ -222,35 +323,292 @@ impl Filter {
                     .iter()
                     .fold(Cow::Borrowed(input), |input, pattern| match pattern {
                         Pattern::Regex(regex) => regex
-                            .replace_all(&input, redactor.pattern())
+                            .replace_all(&input, |captures: &Captures| {
+                                redactor.redact(&captures[0])
+                            })
                             .into_owned()
                             .into(),
                         Pattern::String(pattern) => {
-                            input.replace(pattern, redactor.pattern()).into()
+                            input.replace(pattern, &redactor.redact(pattern)).into()
                         }
                     })
             }
             Filter::UsSocialSecurityNumber => {
-                US_SOCIAL_SECURITY_NUMBER.replace_all(input, redactor.pattern())
+                redact_matches(&US_SOCIAL_SECURITY_NUMBER, input, redactor, |_| true)
+            }
+            Filter::CreditCardNumber => {
+                redact_matches(&CREDIT_CARD, input, redactor, is_luhn_valid)
+            }
+            Filter::EmailAddress => redact_matches(&EMAIL_ADDRESS, input, redactor, |_| true),
+            Filter::Ipv4Address => redact_matches(&IPV4_ADDRESS, input, redactor, |candidate| {
+                candidate.parse::<Ipv4Addr>().is_ok()
+            }),
+            Filter::Ipv6Address => IPV6_ADDRESS.replace_all(input, |captures: &Captures| {
+                let boundary = &captures[1];
+                let candidate = captures.get(2).expect("address is always captured");
+                let followed_by_word = input[candidate.end()..]
+                    .starts_with(|c: char| c.is_ascii_alphanumeric() || c == ':');
+                if !followed_by_word && is_ipv6_address(candidate.as_str()) {
+                    format!("{}{}", boundary, redactor.redact(candidate.as_str()))
+                } else {
+                    captures[0].to_owned()
+                }
+            }),
+            Filter::PhoneNumber => redact_matches(&PHONE_NUMBER, input, redactor, |_| true),
+            Filter::Iban => redact_matches(&IBAN, input, redactor, is_iban_valid),
+            Filter::Jwt => redact_matches(&JWT, input, redactor, |_| true),
+            Filter::BearerToken => {
+                // Only the token is redacted, the scheme is kept.
+                BEARER_TOKEN.replace_all(input, |captures: &Captures| {
+                    let scheme = &captures[1];
+                    let token = &captures[0][scheme.len()..];
+                    format!("{}{}", scheme, redactor.redact(token))
+                })
             }
         }
     }
 }
 
+/// Redacts the matches of `regex` for which `is_valid` returns `true`.
+fn redact_matches<'t>(
+    regex: &regex::Regex,
+    input: &'t str,
+    redactor: &Redactor,
+    is_valid: impl Fn(&str) -> bool,
+) -> Cow<'t, str> {
+    regex.replace_all(input, |captures: &Captures| {
+        let matched = &captures[0];
+        if is_valid(matched) {
+            redactor.redact(matched)
+        } else {
+            matched.to_owned()
+        }
+    })
+}
+
+/// Checks that a candidate is an IPv6 address with at least two groups, one of
+/// which has a decimal digit, so that paths such as `a::b` are not redacted.
+fn is_ipv6_address(candidate: &str) -> bool {
+    candidate.parse::<Ipv6Addr>().is_ok()
+        && candidate.contains(|c: char| c.is_ascii_digit())
+        && candidate
+            .split(':')
+            .filter(|group| !group.is_empty())
+            .count()
+            >= 2
+}
+
+/// Checks the digits of a card number with the Luhn algorithm.
+fn is_luhn_valid(number: &str) -> bool {
+    let digits = number
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .collect::<Vec<_>>();
+
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| match (i % 2, digit * 2) {
+            (0, _) => digit,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+
+    sum % 10 == 0
+}
+
+/// Checks an IBAN with its ISO 13616 mod-97 checksum.
+fn is_iban_valid(iban: &str) -> bool {
+    let iban = iban.replace(' ', "");
+    let (country, rest) = iban.split_at(4);
+
+    rest.chars()
+        .chain(country.chars())
+        .try_fold(0, |remainder: u32, c| {
+            let value = c.to_digit(36)?;
+            // Letters stand for two digits, from 10 for `A` to 35 for `Z`.
+            let remainder = if value > 9 {
+                remainder * 100 + value
+            } else {
+                remainder * 10 + value
+            };
+            Some(remainder % 97)
+        })
+        == Some(1)
+}
+
 /// The recipe for redacting the matched filters.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum Redactor {
+    /// Replaces matches with `[REDACTED]`.
     Full,
+
+    /// Replaces matches with a fixed string.
+    Text(String),
+
+    /// Replaces matches with their SHA-2 hash, keyed with HMAC if a key is
+    /// given, so that redacted values can still be correlated.
+    Sha2 {
+        key: Option<Bytes>,
+        variant: Sha2Variant,
+        encoding: Encoding,
+    },
+
+    /// Masks the letters and digits of matches, except for the first
+    /// `keep_first` and the last `keep_last` ones. Other characters, such as
+    /// separators, are kept.
+    Mask {
+        keep_first: usize,
+        keep_last: usize,
+        character: char,
+    },
 }
 
-impl Redactor {
-    fn pattern(&self) -> &str {
-        use Redactor::Full;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sha2Variant {
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Base16,
+    Base64,
+}
+
+macro_rules! digest {
+    ($algorithm:ty, $key:expr, $input:expr) => {
+        match $key {
+            Some(key) => {
+                let mut mac = <Hmac<$algorithm>>::new_from_slice(key)
+                    .expect("HMAC can take a key of any size");
+                mac.update($input);
+                mac.finalize().into_bytes().to_vec()
+            }
+            None => <$algorithm>::digest($input).to_vec(),
+        }
+    };
+}
 
+impl Redactor {
+    fn redact(&self, matched: &str) -> String {
         match self {
-            Full => "[REDACTED]",
+            Redactor::Full => "[REDACTED]".to_owned(),
+            Redactor::Text(text) => text.clone(),
+            Redactor::Sha2 {
+                key,
+                variant,
+                encoding,
+            } => {
+                let key = key.as_deref();
+                let input = matched.as_bytes();
+                let hash = match variant {
+                    Sha2Variant::Sha224 => digest!(Sha224, key, input),
+                    Sha2Variant::Sha256 => digest!(Sha256, key, input),
+                    Sha2Variant::Sha384 => digest!(Sha384, key, input),
+                    Sha2Variant::Sha512 => digest!(Sha512, key, input),
+                };
+
+                match encoding {
+                    Encoding::Base16 => hex::encode(hash),
+                    Encoding::Base64 => base64::encode(hash),
+                }
+            }
+            Redactor::Mask {
+                keep_first,
+                keep_last,
+                character,
+            } => {
+                let total = matched.chars().filter(char::is_ascii_alphanumeric).count();
+                let mask_until = total.saturating_sub(*keep_last);
+
+                let mut position = 0;
+                matched
+                    .chars()
+                    .map(|c| {
+                        if !c.is_ascii_alphanumeric() {
+                            return c;
+                        }
+                        position += 1;
+                        if position <= *keep_first || position > mask_until {
+                            c
+                        } else {
+                            *character
+                        }
+                    })
+                    .collect()
+            }
         }
     }
+
+    fn sha2(object: &BTreeMap<String, Value>) -> std::result::Result<Self, &'static str> {
+        let key = match object.get("key") {
+            Some(Value::Bytes(key)) => Some(key.clone()),
+            Some(_) => return Err("`key` must be a string"),
+            None => None,
+        };
+
+        let variant = match object.get("variant") {
+            Some(Value::Bytes(variant)) => match variant.as_ref() {
+                b"SHA-224" => Sha2Variant::Sha224,
+                b"SHA-256" => Sha2Variant::Sha256,
+                b"SHA-384" => Sha2Variant::Sha384,
+                b"SHA-512" => Sha2Variant::Sha512,
+                _ => return Err("unknown `variant`"),
+            },
+            Some(_) => return Err("`variant` must be a string"),
+            None => Sha2Variant::Sha256,
+        };
+
+        let encoding = match object.get("encoding") {
+            Some(Value::Bytes(encoding)) => match encoding.as_ref() {
+                b"base16" => Encoding::Base16,
+                b"base64" => Encoding::Base64,
+                _ => return Err("unknown `encoding`"),
+            },
+            Some(_) => return Err("`encoding` must be a string"),
+            None => Encoding::Base16,
+        };
+
+        Ok(Redactor::Sha2 {
+            key,
+            variant,
+            encoding,
+        })
+    }
+
+    fn mask(object: &BTreeMap<String, Value>) -> std::result::Result<Self, &'static str> {
+        let keep = |field: &str| match object.get(field) {
+            Some(Value::Integer(keep)) => {
+                usize::try_from(*keep).map_err(|_| "kept characters can't be negative")
+            }
+            Some(_) => Err("kept characters must be integers"),
+            None => Ok(0),
+        };
+
+        let character = match object.get("character") {
+            Some(Value::Bytes(character)) => {
+                let character = String::from_utf8_lossy(character);
+                let mut chars = character.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(character), None) => character,
+                    _ => return Err("`character` must be a single character"),
+                }
+            }
+            Some(_) => return Err("`character` must be a string"),
+            None => '*',
+        };
+
+        Ok(Redactor::Mask {
+            keep_first: keep("keep_first")?,
+            keep_last: keep("keep_last")?,
+            character,
+        })
+    }
 }
 
 impl Default for Redactor {
@@ -259,15 +617,44 @@ impl Default for Redactor {
     }
 }
 
-impl FromStr for Redactor {
-    type Err = &'static str;
+impl TryFrom<Value> for Redactor {
+    type Error = &'static str;
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        use Redactor::Full;
+    fn try_from(value: Value) -> std::result::Result<Self, Self::Error> {
+        match value {
+            Value::Object(object) => {
+                let r#type = match object
+                    .get("type")
+                    .ok_or("redactors specified as objects must have type parameter")?
+                {
+                    Value::Bytes(bytes) => Ok(bytes.clone()),
+                    _ => Err("type key in redactor must be a string"),
+                }?;
 
-        match s {
-            "full" => Ok(Full),
-            _ => Err("unknown redactor"),
+                match r#type.as_ref() {
+                    b"full" => Ok(Redactor::Full),
+                    b"text" => match object
+                        .get("replacement")
+                        .ok_or("text redactor must have `replacement` specified")?
+                    {
+                        Value::Bytes(bytes) => {
+                            Ok(Redactor::Text(String::from_utf8_lossy(bytes).into_owned()))
+                        }
+                        _ => Err("`replacement` must be a string"),
+                    },
+                    b"sha2" => Redactor::sha2(&object),
+                    b"mask" => Redactor::mask(&object),
+                    _ => Err("unknown redactor name"),
+                }
+            }
+            Value::Bytes(bytes) => match bytes.as_ref() {
+                b"full" => Ok(Redactor::Full),
+                b"text" => Err("text cannot be used without arguments"),
+                b"sha2" => Redactor::sha2(&BTreeMap::new()),
+                b"mask" => Redactor::mask(&BTreeMap::new()),
+                _ => Err("unknown redactor name"),
+            },
+            _ => Err("unknown literal for redactor, must be a redactor name or object"),
         }
     }
 }
@@ -313,6 +700,137 @@ mod test {
              tdef: TypeDef::bytes().infallible(),
         }
 
+        credit_card_number {
+             args: func_args![
+                 value: "paid with 4111 1111 1111 1111, not 4111-1111-1111-1112",
+                 filters: vec!["credit_card_number"],
+             ],
+             want: Ok("paid with [REDACTED], not 4111-1111-1111-1112"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        email_address {
+             args: func_args![
+                 value: "sent by jane.doe@example.co.uk",
+                 filters: vec!["email_address"],
+             ],
+             want: Ok("sent by [REDACTED]"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        ipv4_address {
+             args: func_args![
+                 value: "from 192.168.1.1, not 999.1.1.1",
+                 filters: vec!["ipv4_address"],
+             ],
+             want: Ok("from [REDACTED], not 999.1.1.1"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        ipv6_address {
+             args: func_args![
+                 value: "from 2001:db8::1 at 12:30:45",
+                 filters: vec!["ipv6_address"],
+             ],
+             want: Ok("from [REDACTED] at 12:30:45"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        ipv6_address_boundaries {
+             args: func_args![
+                 value: "fe80::1%eth0,::ffff:192.0.2.1 not std::fs::read, Foo::bar, a::b or ::",
+                 filters: vec!["ipv6_address"],
+             ],
+             want: Ok("[REDACTED]%eth0,[REDACTED] not std::fs::read, Foo::bar, a::b or ::"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        phone_number {
+             args: func_args![
+                 value: "call +1 (555) 123-4567 or +15551234567",
+                 filters: vec!["phone_number"],
+             ],
+             want: Ok("call [REDACTED] or [REDACTED]"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        iban {
+             args: func_args![
+                 value: "to GB82 WEST 1234 5698 7654 32, not GB82 WEST 1234 5698 7654 33",
+                 filters: vec!["iban"],
+             ],
+             want: Ok("to [REDACTED], not GB82 WEST 1234 5698 7654 33"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        jwt {
+             args: func_args![
+                 value: "token eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIn0.c2ln end",
+                 filters: vec!["jwt"],
+             ],
+             want: Ok("token [REDACTED] end"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        bearer_token {
+             args: func_args![
+                 value: "Authorization: Bearer abc.def-123=",
+                 filters: vec!["bearer_token"],
+             ],
+             want: Ok("Authorization: Bearer [REDACTED]"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        text_redactor {
+             args: func_args![
+                 value: "hello 123-12-1234 world",
+                 filters: vec!["us_social_security_number"],
+                 redactor: value!({ "type": "text", "replacement": "***" }),
+             ],
+             want: Ok("hello *** world"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        sha2_redactor {
+             args: func_args![
+                 value: "hello 123-12-1234 world",
+                 filters: vec!["us_social_security_number"],
+                 redactor: "sha2",
+             ],
+             want: Ok("hello 30e6897f76dc102e32ee1d781c43417d259e586eac15c963d75ab8b5187769da world"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        keyed_sha2_redactor {
+             args: func_args![
+                 value: "hello 123-12-1234 world",
+                 filters: vec!["us_social_security_number"],
+                 redactor: value!({ "type": "sha2", "key": "secret" }),
+             ],
+             want: Ok("hello ef9d96bdf9defc19d135329eebfc0cf34f82b0b2e48e9536c102484ab3b51f9f world"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        keyed_sha2_redactor_base64 {
+             args: func_args![
+                 value: "sent by jane@example.com",
+                 filters: vec!["email_address"],
+                 redactor: value!({ "type": "sha2", "key": "secret", "encoding": "base64" }),
+             ],
+             want: Ok("sent by +4F5idlC5/+z1LiyBPeryin0wlw/pGV02oTFDzDQdRM="),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        mask_redactor {
+             args: func_args![
+                 value: "paid with 4111 1111 1111 1111",
+                 filters: vec!["credit_card_number"],
+                 redactor: value!({ "type": "mask", "keep_first": 1, "keep_last": 4, "character": "#" }),
+             ],
+             want: Ok("paid with 4### #### #### 1111"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
         invalid_filter {
              args: func_args![
                  value: "hello 123456 world",
@@ -334,5 +852,29 @@ mod test {
              want: Err("invalid argument"),
              tdef: TypeDef::bytes().infallible(),
         }
+
+        invalid_redactor {
+             args: func_args![
+                 value: "hello 123456 world",
+                 filters: vec![Regex::new(r"\d+").unwrap()],
+                 redactor: value!({ "type": "text" }),
+             ],
+             want: Err("invalid argument"),
+             tdef: TypeDef::bytes().infallible(),
+        }
     ];
+
+    #[test]
+    fn luhn() {
+        assert!(is_luhn_valid("4111 1111 1111 1111"));
+        assert!(is_luhn_valid("5500-0000-0000-0004"));
+        assert!(!is_luhn_valid("4111 1111 1111 1112"));
+    }
+
+    #[test]
+    fn iban_checksum() {
+        assert!(is_iban_valid("GB82 WEST 1234 5698 7654 32"));
+        assert!(is_iban_valid("DE89370400440532013000"));
+        assert!(!is_iban_valid("DE89370400440532013001"));
+    }
 }
@@ -0,0 +1,88 @@
+use ::value::Value;
+use vrl::prelude::*;
+
+fn seahash(value: Value) -> Resolved {
+    let value = value.try_bytes()?;
+    // The hash is reinterpreted as a signed integer, wrapping around above `i64::MAX`.
+    Ok((::seahash::hash(&value) as i64).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Seahash;
+
+impl Function for Seahash {
+    fn identifier(&self) -> &'static str {
+        "seahash"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "seahash",
+                source: r#"seahash("foobar")"#,
+                result: Ok("5348458858952426560"),
+            },
+            Example {
+                title: "negative seahash",
+                source: r#"seahash("bar")"#,
+                result: Ok("-2796170501982571315"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(SeahashFn { value }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SeahashFn {
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for SeahashFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        seahash(value)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::integer().infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        seahash => Seahash;
+
+        seahash {
+            args: func_args![value: "foo"],
+            want: Ok(value!(4_413_582_353_838_009_230_i64)),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        wrapping {
+            args: func_args![value: "bar"],
+            want: Ok(value!(-2_796_170_501_982_571_315_i64)),
+            tdef: TypeDef::integer().infallible(),
+        }
+    ];
+}
@@ -0,0 +1,120 @@
+use ::value::Value;
+use vrl::prelude::*;
+
+use crate::util::state_ttl;
+
+fn set_state(ctx: &Context, key: Value, value: Value, ttl: Option<Value>) -> Resolved {
+    let key = key.try_bytes_utf8_lossy()?;
+    let ttl = ttl.map(state_ttl).transpose()?;
+    let store = ctx
+        .state_store()
+        .ok_or("state is not available to this program")?;
+
+    store
+        .set(&key, value, ttl)
+        .map(|_| Value::Null)
+        .map_err(|err| err.to_string().into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SetState;
+
+impl Function for SetState {
+    fn identifier(&self) -> &'static str {
+        "set_state"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "value",
+                kind: kind::ANY,
+                required: true,
+            },
+            Parameter {
+                keyword: "ttl",
+                kind: kind::INTEGER | kind::FLOAT,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "set key",
+                source: r#"set_state!("first_seen", true)"#,
+                result: Ok("null"),
+            },
+            Example {
+                title: "set key with ttl",
+                source: r#"set_state!("session", {"id": 1}, ttl: 300); get_state("session")"#,
+                result: Ok(r#"{"id": 1}"#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let key = arguments.required("key");
+        let value = arguments.required("value");
+        let ttl = arguments.optional("ttl");
+
+        Ok(SetStateFn { key, value, ttl }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SetStateFn {
+    key: Box<dyn Expression>,
+    value: Box<dyn Expression>,
+    ttl: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for SetStateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = self.key.resolve(ctx)?;
+        let value = self.value.resolve(ctx)?;
+        let ttl = self
+            .ttl
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
+
+        set_state(ctx, key, value, ttl)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::null().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        set_state => SetState;
+
+        without_state_store {
+            args: func_args![key: "foo", value: true],
+            want: Err("state is not available to this program"),
+            tdef: TypeDef::null().fallible(),
+        }
+
+        invalid_ttl {
+            args: func_args![key: "foo", value: true, ttl: 0],
+            want: Err("invalid ttl 0: must be a positive number of seconds"),
+            tdef: TypeDef::null().fallible(),
+        }
+    ];
+}
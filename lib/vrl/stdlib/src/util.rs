@@ -122,3 +122,86 @@ impl std::str::FromStr for Base64Charset {
         }
     }
 }
+
+/// Converts a time-to-live in seconds to a [`Duration`](std::time::Duration).
+#[cfg(any(feature = "incr_state", feature = "set_state"))]
+pub(crate) fn state_ttl(
+    value: ::value::Value,
+) -> Result<std::time::Duration, vrl::prelude::ExpressionError> {
+    let seconds = match value {
+        ::value::Value::Float(seconds) => seconds.into_inner(),
+        value => vrl::prelude::VrlValueConvert::try_integer(value)? as f64,
+    };
+
+    if seconds <= 0.0 || !seconds.is_finite() {
+        return Err(format!(
+            "invalid ttl {}: must be a positive number of seconds",
+            seconds
+        )
+        .into());
+    }
+
+    Ok(std::time::Duration::from_secs_f64(seconds))
+}
+
+/// The default limit of the size of decompressed values, which guards against
+/// decompression bombs.
+#[cfg(any(
+    feature = "decode_gzip",
+    feature = "decode_snappy",
+    feature = "decode_zlib",
+    feature = "decode_zstd"
+))]
+pub(crate) const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 10 * 1024 * 1024;
+
+/// Converts the `max_size` argument of the decompression functions to a
+/// number of bytes.
+#[cfg(any(
+    feature = "decode_gzip",
+    feature = "decode_snappy",
+    feature = "decode_zlib",
+    feature = "decode_zstd"
+))]
+pub(crate) fn max_decompressed_size(
+    max_size: Option<::value::Value>,
+) -> Result<usize, vrl::prelude::ExpressionError> {
+    match max_size {
+        Some(max_size) => {
+            let max_size = vrl::prelude::VrlValueConvert::try_integer(max_size)?;
+            usize::try_from(max_size)
+                .map_err(|_| format!("invalid max_size {}: must not be negative", max_size).into())
+        }
+        None => Ok(DEFAULT_MAX_DECOMPRESSED_SIZE),
+    }
+}
+
+/// Reads a decompressed value, failing as soon as it exceeds `max_size` bytes
+/// rather than decompressing it in full.
+#[cfg(any(
+    feature = "decode_gzip",
+    feature = "decode_zlib",
+    feature = "decode_zstd"
+))]
+pub(crate) fn read_decompressed(
+    reader: impl std::io::Read,
+    max_size: usize,
+    format: &str,
+) -> Result<::value::Value, vrl::prelude::ExpressionError> {
+    use std::io::Read;
+
+    let mut decompressed = Vec::new();
+    reader
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|err| format!("unable to decode value with {} decoder: {}", format, err))?;
+
+    if decompressed.len() > max_size {
+        return Err(format!(
+            "decompressed value exceeds the maximum size of {} bytes",
+            max_size
+        )
+        .into());
+    }
+
+    Ok(::value::Value::Bytes(decompressed.into()))
+}
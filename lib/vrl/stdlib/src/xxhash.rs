@@ -0,0 +1,129 @@
+use std::hash::Hasher;
+
+use ::value::Value;
+use twox_hash::{XxHash32, XxHash64};
+use vrl::prelude::*;
+
+fn xxhash(value: Value, variant: &Bytes) -> Resolved {
+    let value = value.try_bytes()?;
+    let hash = match variant.as_ref() {
+        b"XXH32" => encode::<XxHash32>(&value),
+        b"XXH64" => encode::<XxHash64>(&value),
+        _ => unreachable!("enum invariant"),
+    };
+
+    // 64-bit hashes are reinterpreted as signed integers, wrapping around above `i64::MAX`.
+    Ok((hash as i64).into())
+}
+
+fn variants() -> Vec<Value> {
+    vec![value!("XXH32"), value!("XXH64")]
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Xxhash;
+
+impl Function for Xxhash {
+    fn identifier(&self) -> &'static str {
+        "xxhash"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "variant",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "default variant",
+                source: r#"xxhash("foo")"#,
+                result: Ok("3792637401"),
+            },
+            Example {
+                title: "custom variant",
+                source: r#"xxhash("foo", "XXH64")"#,
+                result: Ok("3728699739546630719"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let variant = arguments
+            .optional_enum("variant", &variants())?
+            .unwrap_or_else(|| value!("XXH32"))
+            .try_bytes()
+            .expect("variant not bytes");
+
+        Ok(XxhashFn { value, variant }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct XxhashFn {
+    value: Box<dyn Expression>,
+    variant: Bytes,
+}
+
+impl FunctionExpression for XxhashFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let variant = &self.variant;
+
+        xxhash(value, variant)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::integer().infallible()
+    }
+}
+
+#[inline]
+fn encode<T: Hasher + Default>(value: &[u8]) -> u64 {
+    let mut hasher = T::default();
+    hasher.write(value);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        xxhash => Xxhash;
+
+        xxh32 {
+            args: func_args![value: "foobar"],
+            want: Ok(value!(3_986_901_679_i64)),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        xxh64 {
+            args: func_args![value: "the quick brown fox jumps over the lazy dog", variant: "XXH64"],
+            want: Ok(value!(-1_337_214_824_225_331_310_i64)),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        xxh64_wrapping {
+            args: func_args![value: "foobar", variant: "XXH64"],
+            want: Ok(value!(-6_725_556_575_634_347_271_i64)),
+            tdef: TypeDef::integer().infallible(),
+        }
+    ];
+}
@@ -23,6 +23,9 @@ const SKIP_FUNCTION_EXAMPLES: &[&str] = &[
     "get_metadata_field",
     "set_metadata_field",
     "remove_metadata_field",
+    // Depend on a descriptor file.
+    "parse_protobuf",
+    "encode_protobuf",
 ];
 
 #[derive(Debug, Deserialize)]
@@ -16,8 +16,8 @@ use vector_common::TimeZone;
 use vrl::{
     diagnostic::Formatter,
     prelude::{BTreeMap, VrlValueConvert},
-    state, CompilationResult, CompileConfig, Runtime, SecretTarget, TargetValueRef, Terminate,
-    VrlRuntime,
+    state, CompilationResult, CompileConfig, Runtime, SecretTarget, StateStore, TargetValueRef,
+    Terminate, VrlRuntime,
 };
 use vrl_tests::{docs, Test};
 
@@ -175,7 +175,7 @@ fn main() {
         }
 
         let state = state::Runtime::default();
-        let runtime = Runtime::new(state);
+        let runtime = Runtime::new(state).with_state_store(StateStore::default());
         let mut functions = stdlib::all();
         functions.append(&mut enrichment::vrl_functions());
         functions.append(&mut vector_vrl_functions::vrl_functions());
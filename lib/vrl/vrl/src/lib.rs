@@ -12,7 +12,8 @@ mod runtime;
 
 pub use compiler::{
     function, state, value, CompilationResult, CompileConfig, Compiler, Context, Expression,
-    Function, Program, ProgramInfo, SecretTarget, Target, TargetValue, TargetValueRef, VrlRuntime,
+    Function, Profile, ProfileEntry, Program, ProgramInfo, SecretTarget, SourceMap, StateError,
+    StateLimits, StateStore, Target, TargetValue, TargetValueRef, Tracer, VrlRuntime, WriteTracer,
 };
 pub use diagnostic;
 pub use runtime::{Runtime, RuntimeResult, Terminate};
@@ -4,13 +4,14 @@ use compiler::ExpressionError;
 use lookup::OwnedTargetPath;
 use value::Value;
 
-use crate::{state, Context, Program, Target, TimeZone};
+use crate::{state, Context, Profile, Program, StateStore, Target, TimeZone, Tracer};
 
 pub type RuntimeResult = Result<Value, Terminate>;
 
 #[derive(Debug, Default)]
 pub struct Runtime {
     state: state::Runtime,
+    state_store: Option<StateStore>,
 }
 
 /// The error raised if the runtime is terminated.
@@ -53,7 +54,22 @@ impl Error for Terminate {
 
 impl Runtime {
     pub fn new(state: state::Runtime) -> Self {
-        Self { state }
+        Self {
+            state,
+            state_store: None,
+        }
+    }
+
+    /// Sets the [`StateStore`] in which programs keep state between
+    /// executions.
+    #[must_use]
+    pub fn with_state_store(mut self, state_store: StateStore) -> Self {
+        self.state_store = Some(state_store);
+        self
+    }
+
+    pub fn state_store(&self) -> Option<&StateStore> {
+        self.state_store.as_ref()
     }
 
     pub fn is_empty(&self) -> bool {
@@ -71,6 +87,43 @@ impl Runtime {
         target: &mut dyn Target,
         program: &Program,
         timezone: &TimeZone,
+    ) -> RuntimeResult {
+        self.resolve_with(target, program, timezone, None, None)
+    }
+
+    /// Same as [`Runtime::resolve`], reporting the evaluation of the program
+    /// to `tracer`.
+    pub fn resolve_with_tracer(
+        &mut self,
+        target: &mut dyn Target,
+        program: &Program,
+        timezone: &TimeZone,
+        tracer: &mut dyn Tracer,
+    ) -> RuntimeResult {
+        self.resolve_with(target, program, timezone, Some(tracer), None)
+    }
+
+    /// Same as [`Runtime::resolve`], adding the cost of evaluating the program
+    /// to `profile`.
+    pub fn resolve_with_profile(
+        &mut self,
+        target: &mut dyn Target,
+        program: &Program,
+        timezone: &TimeZone,
+        profile: &mut Profile,
+    ) -> RuntimeResult {
+        self.resolve_with(target, program, timezone, None, Some(profile))
+    }
+
+    /// Same as [`Runtime::resolve`], optionally tracing the evaluation of the
+    /// program, and collecting its cost.
+    pub fn resolve_with(
+        &mut self,
+        target: &mut dyn Target,
+        program: &Program,
+        timezone: &TimeZone,
+        tracer: Option<&mut dyn Tracer>,
+        profile: Option<&mut Profile>,
     ) -> RuntimeResult {
         // Validate that the path is a value.
         match target.target_get(&OwnedTargetPath::event_root()) {
@@ -88,6 +141,15 @@ impl Runtime {
         };
 
         let mut ctx = Context::new(target, &mut self.state, timezone);
+        if let Some(state_store) = &self.state_store {
+            ctx = ctx.with_state_store(state_store);
+        }
+        if let Some(tracer) = tracer {
+            ctx = ctx.with_tracer(tracer);
+        }
+        if let Some(profile) = profile {
+            ctx = ctx.with_profile(profile);
+        }
 
         program.resolve(&mut ctx).map_err(|err| match err {
             #[cfg(feature = "expr-abort")]
@@ -19,6 +19,8 @@ use crate::config::enterprise::{
 use crate::metrics;
 #[cfg(windows)]
 use crate::service;
+#[cfg(all(feature = "vrl-cli", feature = "transforms-remap"))]
+use crate::vrl_fmt;
 #[cfg(feature = "api")]
 use crate::{api, internal_events::ApiStarted};
 use crate::{
@@ -167,6 +169,8 @@ impl Application {
                         SubCommand::Validate(v) => validate::validate(&v, color).await,
                         #[cfg(feature = "vrl-cli")]
                         SubCommand::Vrl(s) => vrl_cli::cmd::cmd(&s),
+                        #[cfg(all(feature = "vrl-cli", feature = "transforms-remap"))]
+                        SubCommand::VrlFmt(f) => vrl_fmt::cmd(&f),
                     };
 
                     return Err(code);
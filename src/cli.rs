@@ -8,6 +8,8 @@ use crate::service;
 use crate::tap;
 #[cfg(feature = "api-client")]
 use crate::top;
+#[cfg(all(feature = "vrl-cli", feature = "transforms-remap"))]
+use crate::vrl_fmt;
 use crate::{config, generate, get_version, graph, list, unit_test, validate};
 
 #[derive(Parser, Debug)]
@@ -223,6 +225,10 @@ pub enum SubCommand {
     /// Vector Remap Language CLI
     #[cfg(feature = "vrl-cli")]
     Vrl(vrl_cli::Opts),
+
+    /// Format the VRL programs and conditions in a Vector configuration, or check that they are formatted
+    #[cfg(all(feature = "vrl-cli", feature = "transforms-remap"))]
+    VrlFmt(vrl_fmt::Opts),
 }
 
 #[derive(clap::ValueEnum, Debug, Clone, PartialEq, Eq)]
@@ -192,6 +192,15 @@ impl AnyCondition {
             AnyCondition::Map(m) => m.build(enrichment_tables),
         }
     }
+
+    /// Gets the source of the condition, if it is a VRL condition.
+    pub(crate) fn vrl_source(&self) -> Option<&str> {
+        match self {
+            AnyCondition::String(source)
+            | AnyCondition::Map(ConditionConfig::Vrl(VrlConfig { source, .. })) => Some(source),
+            AnyCondition::Map(_) => None,
+        }
+    }
 }
 
 impl From<ConditionConfig> for AnyCondition {
@@ -49,6 +49,7 @@ impl ConditionalConfig for VrlConfig {
         let mut config = CompileConfig::default();
         config.set_custom(enrichment_tables.clone());
         config.set_read_only();
+        config.set_import_paths(crate::config::config_dirs());
 
         let CompilationResult {
             program,
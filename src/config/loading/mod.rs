@@ -27,6 +27,26 @@ use crate::{config::ProviderConfig, signal};
 
 pub static CONFIG_PATHS: Mutex<Vec<ConfigPath>> = Mutex::new(Vec::new());
 
+/// Returns the directories of the config paths Vector was started with.
+///
+/// Components use these to resolve the relative paths of the files they load.
+pub fn config_dirs() -> Vec<PathBuf> {
+    match CONFIG_PATHS.lock().ok() {
+        Some(config_paths) => config_paths
+            .iter()
+            .map(|config_path| match config_path {
+                ConfigPath::File(path, _format) => {
+                    let mut path = path.clone();
+                    path.pop();
+                    path
+                }
+                ConfigPath::Dir(path) => path.clone(),
+            })
+            .collect(),
+        None => vec![],
+    }
+}
+
 pub(super) fn read_dir<P: AsRef<Path> + Debug>(path: P) -> Result<ReadDir, Vec<String>> {
     path.as_ref()
         .read_dir()
@@ -11,7 +11,7 @@ use vector_core::{
     transform::Transform,
 };
 
-use crate::transforms::Transforms;
+use crate::{conditions::AnyCondition, transforms::Transforms};
 
 use super::{id::Inputs, ComponentKey};
 
@@ -140,6 +140,10 @@ pub struct TransformContext {
     /// information, such as the `remap` transform, which passes this information along to the VRL
     /// compiler such that type coercion becomes less of a need for operators writing VRL programs.
     pub merged_schema_definition: schema::Definition,
+
+    /// Whether the transforms running VRL programs print the evaluation of each expression, as
+    /// enabled by `vector test --trace`.
+    pub trace_programs: bool,
 }
 
 impl Default for TransformContext {
@@ -150,6 +154,7 @@ impl Default for TransformContext {
             enrichment_tables: Default::default(),
             schema_definitions: HashMap::from([(None, schema::Definition::any())]),
             merged_schema_definition: schema::Definition::any(),
+            trace_programs: false,
         }
     }
 }
@@ -234,6 +239,13 @@ pub trait TransformConfig: NamedComponent + core::fmt::Debug + Send + Sync {
         true
     }
 
+    /// Gets the conditions of this transform, along with the option they are configured in.
+    ///
+    /// This is used to find the VRL embedded in the configuration, such as when formatting it.
+    fn conditions(&self) -> Vec<(String, &AnyCondition)> {
+        Vec::new()
+    }
+
     /// Attempts to expand the transform into a subtopology of transforms.
     ///
     /// This mechanism allows a transform to act like a macro pattern, where only one transform is
@@ -74,9 +74,12 @@ impl UnitTest {
     }
 }
 
+/// Builds the unit tests of the configuration at `paths`. When `trace_programs` is set, the
+/// transforms print the evaluation of their VRL programs.
 pub async fn build_unit_tests_main(
     paths: &[ConfigPath],
     signal_handler: &mut signal::SignalHandler,
+    trace_programs: bool,
 ) -> Result<Vec<UnitTest>, Vec<String>> {
     config::init_log_schema(paths, false)?;
     let (mut secrets_backends_loader, _) = loading::load_secret_backends_from_paths(paths)?;
@@ -89,11 +92,16 @@ pub async fn build_unit_tests_main(
         loading::load_builder_from_paths(paths)?
     };
 
-    build_unit_tests(config_builder).await
+    build_unit_tests_inner(config_builder, trace_programs).await
 }
 
-pub async fn build_unit_tests(
+pub async fn build_unit_tests(config_builder: ConfigBuilder) -> Result<Vec<UnitTest>, Vec<String>> {
+    build_unit_tests_inner(config_builder, false).await
+}
+
+async fn build_unit_tests_inner(
     mut config_builder: ConfigBuilder,
+    trace_programs: bool,
 ) -> Result<Vec<UnitTest>, Vec<String>> {
     // Sanitize config by removing existing sources and sinks
     config_builder.sources = Default::default();
@@ -111,7 +119,14 @@ pub async fn build_unit_tests(
         if let Some(input) = legacy_input {
             test_definition.inputs.push(input);
         }
-        match build_unit_test(&metadata, test_definition, config_builder.clone()).await {
+        match build_unit_test(
+            &metadata,
+            test_definition,
+            config_builder.clone(),
+            trace_programs,
+        )
+        .await
+        {
             Ok(test) => tests.push(test),
             Err(errors) => {
                 let mut test_error = errors.join("\n");
@@ -359,6 +374,7 @@ async fn build_unit_test(
     metadata: &UnitTestBuildMetadata,
     test: TestDefinition<String>,
     mut config_builder: ConfigBuilder,
+    trace_programs: bool,
 ) -> Result<UnitTest, Vec<String>> {
     let mut transform_only_config = config_builder.clone();
     let expansions = expand_macros(&mut transform_only_config)?;
@@ -441,7 +457,7 @@ async fn build_unit_test(
     }
     let config = config_builder.build()?;
     let diff = config::ConfigDiff::initial(&config);
-    let pieces = builder::build_pieces(&config, &diff, HashMap::new()).await?;
+    let pieces = builder::build_unit_test_pieces(&config, &diff, trace_programs).await?;
 
     Ok(UnitTest {
         name: test.name,
@@ -38,6 +38,11 @@ pub enum HttpError {
     CallRequest { source: hyper::Error },
     #[snafu(display("Failed to build HTTP request: {}", source))]
     BuildRequest { source: http::Error },
+    #[snafu(display("Server is unavailable, responded with {}: {}", status, body))]
+    ServerUnavailable {
+        status: http::StatusCode,
+        body: String,
+    },
 }
 
 impl HttpError {
@@ -45,6 +50,7 @@ impl HttpError {
         match self {
             HttpError::BuildRequest { .. } | HttpError::MakeProxyConnector { .. } => false,
             HttpError::CallRequest { .. }
+            | HttpError::ServerUnavailable { .. }
             | HttpError::BuildTlsConnector { .. }
             | HttpError::MakeHttpsConnector { .. } => true,
         }
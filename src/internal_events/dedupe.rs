@@ -1,5 +1,5 @@
 use crate::emit;
-use metrics::counter;
+use metrics::{counter, gauge};
 use vector_core::internal_event::{ComponentEventsDropped, InternalEvent, INTENTIONAL};
 
 #[derive(Debug)]
@@ -16,3 +16,16 @@ impl InternalEvent for DedupeEventsDropped {
         counter!("events_discarded_total", self.count as u64); // Deprecated
     }
 }
+
+#[derive(Debug)]
+pub struct DedupeCacheSize {
+    pub events: usize,
+    pub bytes: usize,
+}
+
+impl InternalEvent for DedupeCacheSize {
+    fn emit(self) {
+        gauge!("dedupe_cache_events", self.events as f64);
+        gauge!("dedupe_cache_bytes", self.bytes as f64);
+    }
+}
@@ -0,0 +1,27 @@
+use metrics::gauge;
+use vector_core::internal_event::{ComponentEventsDropped, InternalEvent, INTENTIONAL};
+
+use crate::emit;
+
+#[derive(Debug)]
+pub struct JoinMissingKey;
+
+impl InternalEvent for JoinMissingKey {
+    fn emit(self) {
+        emit!(ComponentEventsDropped::<INTENTIONAL> {
+            count: 1,
+            reason: "Event of the right side is missing fields of its join key."
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct JoinTableSize {
+    pub entries: usize,
+}
+
+impl InternalEvent for JoinTableSize {
+    fn emit(self) {
+        gauge!("join_table_entries", self.entries as f64);
+    }
+}
@@ -176,3 +176,27 @@ impl InternalEvent for KafkaHeaderExtractionError<'_> {
         counter!("kafka_header_extraction_failures_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct KafkaTransactionError {
+    pub error: rdkafka::error::KafkaError,
+}
+
+impl InternalEvent for KafkaTransactionError {
+    fn emit(self) {
+        error!(
+            message = "Kafka transaction failed.",
+            error = %self.error,
+            error_code = "kafka_transaction",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "kafka_transaction",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -73,3 +73,29 @@ impl<'a> InternalEvent for LogToMetricParseFloatError<'a> {
         emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason })
     }
 }
+
+pub struct LogToMetricParseError {
+    pub error: serde_json::Error,
+}
+
+impl InternalEvent for LogToMetricParseError {
+    fn emit(self) {
+        let reason = "Failed to parse log as a metric.";
+        error!(
+            message = reason,
+            error = %self.error,
+            error_code = "failed_parsing_metric",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_parsing_metric",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason })
+    }
+}
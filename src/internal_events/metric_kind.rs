@@ -0,0 +1,30 @@
+use metrics::counter;
+use vector_core::{event::metric::MetricSeries, internal_event::InternalEvent};
+
+#[derive(Debug)]
+pub struct MetricKindCounterReset<'a> {
+    pub series: &'a MetricSeries,
+}
+
+impl<'a> InternalEvent for MetricKindCounterReset<'a> {
+    fn emit(self) {
+        debug!(
+            message = "Absolute counter decreased, assuming it was reset.",
+            series = %self.series,
+            internal_log_rate_limit = true,
+        );
+        counter!("counter_resets_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct MetricKindSeriesExpired {
+    pub count: usize,
+}
+
+impl InternalEvent for MetricKindSeriesExpired {
+    fn emit(self) {
+        debug!(message = "Expired stale series.", count = self.count);
+        counter!("expired_series_total", self.count as u64);
+    }
+}
@@ -0,0 +1,15 @@
+use vector_core::internal_event::{ComponentEventsDropped, InternalEvent, INTENTIONAL};
+
+use crate::emit;
+
+#[derive(Debug)]
+pub struct MetricRelabelEventDropped;
+
+impl InternalEvent for MetricRelabelEventDropped {
+    fn emit(self) {
+        emit!(ComponentEventsDropped::<INTENTIONAL> {
+            count: 1,
+            reason: "Metric dropped by a relabeling rule."
+        })
+    }
+}
@@ -69,6 +69,8 @@ mod influxdb;
 mod internal_logs;
 #[cfg(feature = "sources-internal_metrics")]
 mod internal_metrics;
+#[cfg(feature = "transforms-join")]
+mod join;
 #[cfg(all(unix, feature = "sources-journald"))]
 mod journald;
 #[cfg(any(feature = "sources-kafka", feature = "sinks-kafka"))]
@@ -81,6 +83,10 @@ mod logplex;
 mod loki;
 #[cfg(feature = "transforms-lua")]
 mod lua;
+#[cfg(feature = "transforms-metric_kind")]
+mod metric_kind;
+#[cfg(feature = "transforms-metric_relabel")]
+mod metric_relabel;
 #[cfg(feature = "transforms-metric_to_log")]
 mod metric_to_log;
 #[cfg(feature = "sources-mongodb_metrics")]
@@ -113,6 +119,8 @@ mod splunk_hec;
 mod statsd_sink;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 mod tag_cardinality_limit;
+#[cfg(feature = "transforms-tail_sampling")]
+mod tail_sampling;
 mod tcp;
 mod template;
 #[cfg(feature = "transforms-throttle")]
@@ -121,6 +129,8 @@ mod udp;
 mod unix;
 #[cfg(feature = "sinks-websocket")]
 mod websocket;
+#[cfg(feature = "transforms-window")]
+mod window;
 
 #[cfg(any(
     feature = "sources-file",
@@ -208,6 +218,8 @@ pub(crate) use self::influxdb::*;
 pub(crate) use self::internal_logs::*;
 #[cfg(feature = "sources-internal_metrics")]
 pub(crate) use self::internal_metrics::*;
+#[cfg(feature = "transforms-join")]
+pub(crate) use self::join::*;
 #[cfg(all(unix, feature = "sources-journald"))]
 pub(crate) use self::journald::*;
 #[cfg(any(feature = "sources-kafka", feature = "sinks-kafka"))]
@@ -221,6 +233,10 @@ pub(crate) use self::logplex::*;
 pub(crate) use self::loki::*;
 #[cfg(feature = "transforms-lua")]
 pub(crate) use self::lua::*;
+#[cfg(feature = "transforms-metric_kind")]
+pub(crate) use self::metric_kind::*;
+#[cfg(feature = "transforms-metric_relabel")]
+pub(crate) use self::metric_relabel::*;
 #[cfg(feature = "transforms-metric_to_log")]
 pub(crate) use self::metric_to_log::*;
 #[cfg(feature = "sinks-nats")]
@@ -250,6 +266,8 @@ pub(crate) use self::splunk_hec::*;
 pub(crate) use self::statsd_sink::*;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub(crate) use self::tag_cardinality_limit::*;
+#[cfg(feature = "transforms-tail_sampling")]
+pub(crate) use self::tail_sampling::*;
 #[cfg(feature = "transforms-throttle")]
 pub(crate) use self::throttle::*;
 #[cfg(all(
@@ -267,6 +285,8 @@ pub(crate) use self::throttle::*;
 pub(crate) use self::unix::*;
 #[cfg(feature = "sinks-websocket")]
 pub(crate) use self::websocket::*;
+#[cfg(feature = "transforms-window")]
+pub(crate) use self::window::*;
 #[cfg(windows)]
 pub(crate) use self::windows::*;
 pub(crate) use self::{
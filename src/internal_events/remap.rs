@@ -1,6 +1,7 @@
 use crate::emit;
-use metrics::counter;
+use metrics::{counter, histogram};
 use vector_core::internal_event::InternalEvent;
+use vrl::{Profile, SourceMap};
 
 use vector_common::internal_event::{
     error_stage, error_type, ComponentEventsDropped, INTENTIONAL, UNINTENTIONAL,
@@ -61,3 +62,46 @@ impl InternalEvent for RemapMappingAbort {
         }
     }
 }
+
+/// The cost of the expressions and function calls of a program for an event.
+#[derive(Debug)]
+pub struct RemapProfile<'a> {
+    pub profile: &'a Profile,
+    pub source_map: &'a SourceMap,
+}
+
+impl InternalEvent for RemapProfile<'_> {
+    fn emit(self) {
+        for entry in self.profile.iter() {
+            let location = self.source_map.format_span(entry.span);
+            match &entry.function {
+                Some(function) => {
+                    counter!(
+                        "vrl_evaluations_total", entry.count,
+                        "kind" => "function_call",
+                        "function" => function.clone(),
+                        "location" => location.clone(),
+                    );
+                    histogram!(
+                        "vrl_evaluation_duration_seconds", entry.duration,
+                        "kind" => "function_call",
+                        "function" => function.clone(),
+                        "location" => location,
+                    );
+                }
+                None => {
+                    counter!(
+                        "vrl_evaluations_total", entry.count,
+                        "kind" => "expression",
+                        "location" => location.clone(),
+                    );
+                    histogram!(
+                        "vrl_evaluation_duration_seconds", entry.duration,
+                        "kind" => "expression",
+                        "location" => location,
+                    );
+                }
+            }
+        }
+    }
+}
@@ -14,3 +14,14 @@ impl InternalEvent for SampleEventDiscarded {
         })
     }
 }
+
+#[derive(Debug)]
+pub struct SampleRatesAdjusted {
+    pub keys: usize,
+}
+
+impl InternalEvent for SampleRatesAdjusted {
+    fn emit(self) {
+        debug!(message = "Adjusted dynamic sample rates.", keys = self.keys);
+    }
+}
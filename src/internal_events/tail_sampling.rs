@@ -0,0 +1,53 @@
+use metrics::counter;
+use vector_core::internal_event::{ComponentEventsDropped, InternalEvent, INTENTIONAL};
+
+use crate::emit;
+
+#[derive(Debug)]
+pub struct TailSamplingDecision {
+    pub kept: bool,
+    pub policy: Option<&'static str>,
+}
+
+impl InternalEvent for TailSamplingDecision {
+    fn emit(self) {
+        let decision = if self.kept { "kept" } else { "dropped" };
+        let policy = self.policy.unwrap_or("none");
+        trace!(message = "Trace sampling decided.", decision, policy);
+        counter!(
+            "sampled_traces_total", 1,
+            "decision" => decision,
+            "policy" => policy,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct TailSamplingEarlyDecision {
+    pub reason: &'static str,
+}
+
+impl InternalEvent for TailSamplingEarlyDecision {
+    fn emit(self) {
+        debug!(
+            message = "Deciding a trace before the end of its wait.",
+            reason = %self.reason,
+            internal_log_rate_limit = true,
+        );
+        counter!("early_trace_decisions_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct TailSamplingEventsDropped {
+    pub count: usize,
+}
+
+impl InternalEvent for TailSamplingEventsDropped {
+    fn emit(self) {
+        emit!(ComponentEventsDropped::<INTENTIONAL> {
+            count: self.count,
+            reason: "Trace not sampled."
+        })
+    }
+}
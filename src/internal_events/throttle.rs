@@ -21,3 +21,19 @@ impl InternalEvent for ThrottleEventDiscarded {
         })
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct ThrottleEventThrottled<'a> {
+    pub key: &'a str,
+    pub limit: &'static str,
+}
+
+impl<'a> InternalEvent for ThrottleEventThrottled<'a> {
+    fn emit(self) {
+        counter!(
+            "throttled_events_total", 1,
+            "key" => self.key.to_owned(),
+            "limit" => self.limit,
+        );
+    }
+}
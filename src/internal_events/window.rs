@@ -0,0 +1,31 @@
+use metrics::gauge;
+use vector_core::internal_event::{ComponentEventsDropped, InternalEvent, INTENTIONAL};
+
+use crate::emit;
+
+#[derive(Debug)]
+pub struct WindowBufferSize {
+    pub events: usize,
+    pub bytes: usize,
+}
+
+impl InternalEvent for WindowBufferSize {
+    fn emit(self) {
+        gauge!("buffered_events", self.events as f64);
+        gauge!("buffered_bytes", self.bytes as f64);
+    }
+}
+
+#[derive(Debug)]
+pub struct WindowEventsDropped {
+    pub count: usize,
+}
+
+impl InternalEvent for WindowEventsDropped {
+    fn emit(self) {
+        emit!(ComponentEventsDropped::<INTENTIONAL> {
+            count: self.count,
+            reason: "Event not within a window around a trigger."
+        })
+    }
+}
@@ -113,6 +113,8 @@ pub(crate) mod utilization;
 pub mod validate;
 #[cfg(windows)]
 pub mod vector_windows;
+#[cfg(all(feature = "vrl-cli", feature = "transforms-remap"))]
+pub(crate) mod vrl_fmt;
 
 pub use source_sender::SourceSender;
 pub use vector_common::{shutdown, Error, Result};
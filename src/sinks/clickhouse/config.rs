@@ -11,6 +11,7 @@ use crate::{
         },
         Healthcheck, VectorSink,
     },
+    template::Template,
     tls::TlsConfig,
 };
 
@@ -26,12 +27,23 @@ pub struct ClickhouseConfig {
     pub endpoint: UriSerde,
 
     /// The table that data will be inserted into.
-    pub table: String,
+    ///
+    /// Events are batched separately for each table this renders to.
+    pub table: Template,
 
     /// The database that contains the table that data will be inserted into.
-    pub database: Option<String>,
+    ///
+    /// Events are batched separately for each database this renders to. Defaults to `default`.
+    pub database: Option<Template>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub format: Format,
 
     /// Sets `input_format_skip_unknown_fields`, allowing Clickhouse to discard fields not present in the table schema.
+    ///
+    /// Only applies to the `json_each_row` format, as fields that are not present in the table schema are always
+    /// discarded by the other formats.
     #[serde(default)]
     pub skip_unknown_fields: bool,
 
@@ -69,13 +81,50 @@ pub struct ClickhouseConfig {
     pub acknowledgements: AcknowledgementsConfig,
 }
 
+/// The format data is inserted in.
+///
+/// For the `row_binary` and `native` formats, the schema of each table is read with `DESCRIBE TABLE` (by the
+/// healthcheck for a static table, or before its first insert otherwise), and event values are converted to the types
+/// of their columns. It is read again after an insert fails because the table changed.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    /// Insert rows as newline-delimited JSON objects.
+    ///
+    /// Values of fields that do not match a column are sent as they are.
+    #[derivative(Default)]
+    JsonEachRow,
+
+    /// Insert rows in the `RowBinary` format.
+    ///
+    /// Every column of the table is written, and fields that do not match a column are discarded. Missing values are
+    /// written as `NULL` for `Nullable` columns, and as the default value of the column type otherwise, rather than
+    /// as the column's `DEFAULT` expression.
+    RowBinary,
+
+    /// Insert rows in the column-oriented `Native` format.
+    ///
+    /// Values are written the same way as for `row_binary`. `LowCardinality` columns are not supported.
+    Native,
+}
+
+impl Format {
+    pub(super) const fn as_str(self) -> &'static str {
+        match self {
+            Self::JsonEachRow => "JSONEachRow",
+            Self::RowBinary => "RowBinary",
+            Self::Native => "Native",
+        }
+    }
+}
+
 impl_generate_config_from_default!(ClickhouseConfig);
 
 #[async_trait::async_trait]
 impl SinkConfig for ClickhouseConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
-        // later we can build different sink(http, native) here
-        // according to the clickhouseConfig
         build_http_sink(self, cx).await
     }
 
@@ -0,0 +1,339 @@
+//! Encoding of batches of rows in the formats supported by the `clickhouse` sink.
+use bytes::{BufMut, BytesMut};
+use serde_json::{Map, Value};
+
+use super::{
+    config::Format,
+    schema::{
+        days_since_epoch, to_bool, to_date, to_f64, to_i128, to_string, to_ticks, to_timestamp,
+        Column, ColumnType, SchemaError, TableSchema,
+    },
+};
+
+pub(super) type Row = Map<String, Value>;
+
+/// Encodes rows as newline-delimited JSON objects, leaving the conversion of values to ClickHouse.
+pub(super) fn encode_json_each_row(rows: &[Row]) -> BytesMut {
+    let mut body = BytesMut::new();
+    for row in rows {
+        serde_json::to_writer((&mut body).writer(), row).expect("JSON values always serialize");
+        body.put_u8(b'\n');
+    }
+    body
+}
+
+/// Encodes rows in the `RowBinary` format, with a value for every column of the table.
+///
+/// Missing values are written as `NULL` for `Nullable` columns, and as the default value of the type otherwise.
+pub(super) fn encode_row_binary(
+    schema: &TableSchema,
+    rows: &[Row],
+) -> Result<BytesMut, SchemaError> {
+    let mut body = BytesMut::new();
+    for row in rows {
+        for column in &schema.columns {
+            write_value(
+                &mut body,
+                column,
+                &column.column_type,
+                row.get(&column.name),
+            )?;
+        }
+    }
+    Ok(body)
+}
+
+/// Encodes rows as a single block in the `Native` format, with a value for every column of the table.
+///
+/// Missing values are handled the same way as for `RowBinary`.
+pub(super) fn encode_native(schema: &TableSchema, rows: &[Row]) -> Result<BytesMut, SchemaError> {
+    let mut body = BytesMut::new();
+    write_varuint(&mut body, schema.columns.len() as u64);
+    write_varuint(&mut body, rows.len() as u64);
+    for column in &schema.columns {
+        write_string(&mut body, &column.name);
+        write_string(&mut body, &column.type_name);
+        let values = rows
+            .iter()
+            .map(|row| row.get(&column.name))
+            .collect::<Vec<_>>();
+        write_column(&mut body, column, &column.column_type, &values)?;
+    }
+    Ok(body)
+}
+
+fn write_value(
+    body: &mut BytesMut,
+    column: &Column,
+    column_type: &ColumnType,
+    value: Option<&Value>,
+) -> Result<(), SchemaError> {
+    let value = value.filter(|value| !value.is_null());
+    match column_type {
+        ColumnType::Nullable(inner) => {
+            body.put_u8(value.is_none() as u8);
+            match value {
+                Some(value) => write_value(body, column, inner, Some(value)),
+                None => Ok(()),
+            }
+        }
+        ColumnType::LowCardinality(inner) => write_value(body, column, inner, value),
+        ColumnType::Array(inner) => {
+            let items = array_items(column, value)?;
+            write_varuint(body, items.len() as u64);
+            items
+                .iter()
+                .try_for_each(|item| write_value(body, column, inner, Some(item)))
+        }
+        scalar => write_scalar(body, column, scalar, value),
+    }
+}
+
+fn write_column(
+    body: &mut BytesMut,
+    column: &Column,
+    column_type: &ColumnType,
+    values: &[Option<&Value>],
+) -> Result<(), SchemaError> {
+    match column_type {
+        ColumnType::Nullable(inner) => {
+            for value in values {
+                body.put_u8(value.map_or(true, Value::is_null) as u8);
+            }
+            write_column(body, column, inner, values)
+        }
+        ColumnType::Array(inner) => {
+            let mut items = Vec::new();
+            for value in values {
+                items.extend(array_items(column, *value)?.iter().map(Some));
+                body.put_u64_le(items.len() as u64);
+            }
+            write_column(body, column, inner, &items)
+        }
+        ColumnType::LowCardinality(_) => Err(SchemaError::UnsupportedType {
+            column: column.name.clone(),
+            type_name: column.type_name.clone(),
+            format: Format::Native.as_str(),
+        }),
+        scalar => values.iter().try_for_each(|value| {
+            write_scalar(body, column, scalar, value.filter(|value| !value.is_null()))
+        }),
+    }
+}
+
+fn array_items<'a>(column: &Column, value: Option<&'a Value>) -> Result<&'a [Value], SchemaError> {
+    match value {
+        None | Some(Value::Null) => Ok(&[]),
+        Some(Value::Array(items)) => Ok(items),
+        Some(value) => Err(column.invalid_value(value.clone())),
+    }
+}
+
+/// Writes a single value of a scalar type, or the default value of the type if there is none.
+fn write_scalar(
+    body: &mut BytesMut,
+    column: &Column,
+    column_type: &ColumnType,
+    value: Option<&Value>,
+) -> Result<(), SchemaError> {
+    let invalid = || column.invalid_value(value.cloned().unwrap_or_default());
+
+    match column_type {
+        ColumnType::Bool => {
+            let boolean = value.map_or(Some(false), to_bool).ok_or_else(invalid)?;
+            body.put_u8(boolean as u8);
+        }
+        ColumnType::Float32 => {
+            let float = value.map_or(Some(0.0), to_f64).ok_or_else(invalid)?;
+            body.put_f32_le(float as f32);
+        }
+        ColumnType::Float64 => {
+            let float = value.map_or(Some(0.0), to_f64).ok_or_else(invalid)?;
+            body.put_f64_le(float);
+        }
+        ColumnType::String => {
+            write_string(body, &value.cloned().map(to_string).unwrap_or_default());
+        }
+        ColumnType::FixedString(length) => {
+            let string = value.cloned().map(to_string).unwrap_or_default();
+            if string.len() > *length {
+                return Err(invalid());
+            }
+            body.put_slice(string.as_bytes());
+            body.put_bytes(0, length - string.len());
+        }
+        ColumnType::Date | ColumnType::Date32 => {
+            let days = value
+                .map_or(Some(0), |value| to_date(value).map(days_since_epoch))
+                .ok_or_else(invalid)?;
+            if *column_type == ColumnType::Date {
+                body.put_u16_le(u16::try_from(days).map_err(|_| invalid())?);
+            } else {
+                body.put_i32_le(i32::try_from(days).map_err(|_| invalid())?);
+            }
+        }
+        ColumnType::DateTime => {
+            let seconds = value
+                .map_or(Some(0), |value| {
+                    to_timestamp(value).map(|timestamp| timestamp.timestamp())
+                })
+                .ok_or_else(invalid)?;
+            body.put_u32_le(u32::try_from(seconds).map_err(|_| invalid())?);
+        }
+        ColumnType::DateTime64(precision) => {
+            let ticks = value
+                .map_or(Some(0), |value| {
+                    to_timestamp(value).and_then(|timestamp| to_ticks(timestamp, *precision))
+                })
+                .ok_or_else(invalid)?;
+            body.put_i64_le(ticks);
+        }
+        integer => {
+            let integer_value = value
+                .map_or(Some(0), to_i128)
+                .filter(|integer_value| integer.integer_range().contains(integer_value))
+                .ok_or_else(invalid)?;
+            match integer {
+                ColumnType::UInt8 => body.put_u8(integer_value as u8),
+                ColumnType::UInt16 => body.put_u16_le(integer_value as u16),
+                ColumnType::UInt32 => body.put_u32_le(integer_value as u32),
+                ColumnType::UInt64 => body.put_u64_le(integer_value as u64),
+                ColumnType::Int8 => body.put_i8(integer_value as i8),
+                ColumnType::Int16 => body.put_i16_le(integer_value as i16),
+                ColumnType::Int32 => body.put_i32_le(integer_value as i32),
+                ColumnType::Int64 => body.put_i64_le(integer_value as i64),
+                // Rejected by `TableSchema::validate`.
+                _ => {
+                    return Err(SchemaError::UnsupportedType {
+                        column: column.name.clone(),
+                        type_name: column.type_name.clone(),
+                        format: "binary",
+                    })
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_varuint(body: &mut BytesMut, mut value: u64) {
+    while value >= 0x80 {
+        body.put_u8((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    body.put_u8(value as u8);
+}
+
+fn write_string(body: &mut BytesMut, string: &str) {
+    write_varuint(body, string.len() as u64);
+    body.put_slice(string.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema(columns: &[(&str, &str)]) -> TableSchema {
+        TableSchema {
+            columns: columns
+                .iter()
+                .map(|(name, type_name)| Column {
+                    name: name.to_string(),
+                    type_name: type_name.to_string(),
+                    column_type: ColumnType::parse(type_name),
+                })
+                .collect(),
+        }
+    }
+
+    fn row(value: Value) -> Row {
+        match value {
+            Value::Object(row) => row,
+            _ => panic!("not an object"),
+        }
+    }
+
+    #[test]
+    fn json_each_row() {
+        let body = encode_json_each_row(&[
+            row(json!({"timestamp": "2022-10-11T12:13:14.5Z", "count": "7"})),
+            row(json!({"unknown": "kept"})),
+        ]);
+
+        let encoded = serde_json::Deserializer::from_slice(&body)
+            .into_iter::<Value>()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            encoded,
+            vec![
+                json!({"timestamp": "2022-10-11T12:13:14.5Z", "count": "7"}),
+                json!({"unknown": "kept"}),
+            ]
+        );
+        assert!(body.ends_with(b"\n"));
+    }
+
+    #[test]
+    fn row_binary() {
+        let schema = schema(&[
+            ("id", "UInt16"),
+            ("message", "String"),
+            ("level", "Nullable(Int8)"),
+            ("tags", "Array(LowCardinality(String))"),
+            ("timestamp", "DateTime"),
+        ]);
+        let body = encode_row_binary(
+            &schema,
+            &[row(json!({
+                "id": 258,
+                "message": "hi",
+                "tags": ["a"],
+                "timestamp": "1970-01-01T00:00:10Z"
+            }))],
+        )
+        .unwrap();
+
+        assert_eq!(
+            &body[..],
+            &[
+                2, 1, // id
+                2, b'h', b'i', // message
+                1,    // level is NULL
+                1, 1, b'a', // tags
+                10, 0, 0, 0, // timestamp
+            ][..]
+        );
+    }
+
+    #[test]
+    fn native() {
+        let schema = schema(&[("value", "Nullable(UInt8)"), ("items", "Array(Int16)")]);
+        let body = encode_native(
+            &schema,
+            &[
+                row(json!({"value": 1, "items": [1, 2]})),
+                row(json!({"items": [3]})),
+            ],
+        )
+        .unwrap();
+
+        let mut expected = vec![2, 2];
+        expected.extend_from_slice(b"\x05value\x0fNullable(UInt8)");
+        expected.extend_from_slice(&[0, 1, 1, 0]);
+        expected.extend_from_slice(b"\x05items\x0cArray(Int16)");
+        expected.extend_from_slice(&2_u64.to_le_bytes());
+        expected.extend_from_slice(&3_u64.to_le_bytes());
+        expected.extend_from_slice(&[1, 0, 2, 0, 3, 0]);
+        assert_eq!(&body[..], &expected[..]);
+    }
+
+    #[test]
+    fn invalid_values_fail_the_batch() {
+        let schema = schema(&[("id", "UInt8")]);
+        assert!(encode_row_binary(&schema, &[row(json!({"id": "many"}))]).is_err());
+        assert!(encode_native(&schema, &[row(json!({"id": -1}))]).is_err());
+    }
+}
@@ -1,23 +1,38 @@
-use bytes::{BufMut, Bytes, BytesMut};
+use std::{
+    collections::HashMap,
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
 use futures::{FutureExt, SinkExt};
 use http::{Request, StatusCode, Uri};
 use hyper::Body;
+use serde_json::Value;
 use snafu::ResultExt;
 
-use super::ClickhouseConfig;
+use super::{
+    config::Format,
+    format::{encode_json_each_row, encode_native, encode_row_binary, Row},
+    schema::{DescribeRow, TableSchema},
+    ClickhouseConfig,
+};
 use crate::{
     codecs::Transformer,
     config::SinkContext,
     event::Event,
-    http::{HttpClient, HttpError, MaybeAuth},
+    http::{CallRequestSnafu, HttpClient, HttpError, MaybeAuth},
+    internal_events::TemplateRenderingError,
     sinks::{
         util::{
-            http::{BatchedHttpSink, HttpEventEncoder, HttpRetryLogic, HttpSink},
+            http::{HttpEventEncoder, HttpRetryLogic, HttpSink, PartitionHttpSink},
             retries::{RetryAction, RetryLogic},
-            Buffer, TowerRequestConfig,
+            BoxedRawValue, Compressor, JsonArrayBuffer, PartitionBuffer, PartitionInnerBuffer,
+            TowerRequestConfig,
         },
         Healthcheck, HealthcheckError, UriParseSnafu, VectorSink,
     },
+    template::Template,
     tls::TlsSettings,
 };
 
@@ -34,74 +49,223 @@ pub(crate) async fn build_http_sink(
         auth: cfg.auth.choose_one(&cfg.endpoint.auth)?,
         ..cfg.clone()
     };
+    let sink = ClickhouseSink {
+        config,
+        client: client.clone(),
+        schemas: Default::default(),
+    };
+    let retry_logic = ClickhouseRetryLogic {
+        inner: HttpRetryLogic,
+        schemas: Arc::clone(&sink.schemas),
+    };
+
+    let healthcheck = healthcheck(sink.clone()).boxed();
 
-    let sink = BatchedHttpSink::with_logic(
-        config.clone(),
-        Buffer::new(batch.size, cfg.compression),
-        ClickhouseRetryLogic::default(),
+    let sink = PartitionHttpSink::with_retry_logic(
+        sink,
+        PartitionBuffer::new(JsonArrayBuffer::new(batch.size)),
+        retry_logic,
         request,
         batch.timeout,
-        client.clone(),
+        client,
     )
     .sink_map_err(|error| error!(message = "Fatal clickhouse sink error.", %error));
 
-    let healthcheck = healthcheck(client, config).boxed();
-
     Ok((VectorSink::from_event_sink(sink), healthcheck))
 }
 
+/// The table a batch of events is inserted into.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TableKey {
+    database: String,
+    table: String,
+}
+
+type Schemas = Arc<Mutex<HashMap<TableKey, Arc<TableSchema>>>>;
+
+#[derive(Clone)]
+struct ClickhouseSink {
+    config: ClickhouseConfig,
+    client: HttpClient,
+    /// The schemas of the tables inserted into so far, for the formats that need them.
+    schemas: Schemas,
+}
+
+impl ClickhouseSink {
+    /// Returns the schema of a table, reading it if it has not been read yet.
+    async fn schema(&self, key: &TableKey) -> crate::Result<Arc<TableSchema>> {
+        if let Some(schema) = self.schemas.lock().expect("poisoned lock").get(key) {
+            return Ok(Arc::clone(schema));
+        }
+
+        let schema = Arc::new(self.describe(key).await?);
+        self.schemas
+            .lock()
+            .expect("poisoned lock")
+            .insert(key.clone(), Arc::clone(&schema));
+        Ok(schema)
+    }
+
+    /// Reads the schema of a table.
+    ///
+    /// Failures to reach the server are returned as an `HttpError`, so that the batch waiting for
+    /// the schema is retried. Only a missing table or an unsupported schema fail it for good.
+    async fn describe(&self, key: &TableKey) -> crate::Result<TableSchema> {
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair(
+                "query",
+                &format!(
+                    "DESCRIBE TABLE {} FORMAT JSONEachRow",
+                    table_name(&key.database, &key.table)
+                ),
+            )
+            .finish();
+        let uri = format!("{}/?{}", self.config.endpoint.with_default_parts(), query);
+        let mut request = Request::get(uri).body(Body::empty()).unwrap();
+
+        if let Some(auth) = &self.config.auth {
+            auth.apply(&mut request);
+        }
+
+        let response = self.client.send(request).await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .context(CallRequestSnafu)?;
+        if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(Box::new(HttpError::ServerUnavailable {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            }));
+        }
+        if status != StatusCode::OK {
+            return Err(format!(
+                "Failed to describe table {}: {}",
+                table_name(&key.database, &key.table),
+                String::from_utf8_lossy(&body)
+            )
+            .into());
+        }
+
+        let rows = serde_json::Deserializer::from_slice(&body)
+            .into_iter::<DescribeRow>()
+            .collect::<Result<Vec<_>, _>>()?;
+        let schema = TableSchema::from_describe(rows);
+        schema.validate(self.config.format)?;
+        Ok(schema)
+    }
+}
+
 pub struct ClickhouseEventEncoder {
     transformer: Transformer,
+    database: Option<Template>,
+    table: Template,
 }
 
-impl HttpEventEncoder<BytesMut> for ClickhouseEventEncoder {
-    fn encode_event(&mut self, mut event: Event) -> Option<BytesMut> {
+impl ClickhouseEventEncoder {
+    fn render_key(&self, event: &Event) -> Option<TableKey> {
+        let render = |template: &Template, field| {
+            template
+                .render_string(event)
+                .map_err(|error| {
+                    emit!(TemplateRenderingError {
+                        error,
+                        field: Some(field),
+                        drop_event: true,
+                    });
+                })
+                .ok()
+        };
+
+        let database = match &self.database {
+            Some(database) => render(database, "database")?,
+            None => "default".to_owned(),
+        };
+        let table = render(&self.table, "table")?;
+        Some(TableKey { database, table })
+    }
+}
+
+impl HttpEventEncoder<PartitionInnerBuffer<Value, TableKey>> for ClickhouseEventEncoder {
+    fn encode_event(&mut self, mut event: Event) -> Option<PartitionInnerBuffer<Value, TableKey>> {
+        let key = self.render_key(&event)?;
+
         self.transformer.transform(&mut event);
         let log = event.into_log();
+        let value = serde_json::to_value(&log).expect("Events should be valid json!");
 
-        let mut body = crate::serde::json::to_bytes(&log).expect("Events should be valid json!");
-        body.put_u8(b'\n');
-
-        Some(body)
+        Some(PartitionInnerBuffer::new(value, key))
     }
 }
 
 #[async_trait::async_trait]
-impl HttpSink for ClickhouseConfig {
-    type Input = BytesMut;
-    type Output = BytesMut;
+impl HttpSink for ClickhouseSink {
+    type Input = PartitionInnerBuffer<Value, TableKey>;
+    type Output = PartitionInnerBuffer<Vec<BoxedRawValue>, TableKey>;
     type Encoder = ClickhouseEventEncoder;
 
     fn build_encoder(&self) -> Self::Encoder {
         ClickhouseEventEncoder {
-            transformer: self.encoding.clone(),
+            transformer: self.config.encoding.clone(),
+            database: self.config.database.clone(),
+            table: self.config.table.clone(),
         }
     }
 
-    async fn build_request(&self, events: Self::Output) -> crate::Result<http::Request<Bytes>> {
-        let database = if let Some(database) = &self.database {
-            database.as_str()
-        } else {
-            "default"
+    async fn build_request(&self, output: Self::Output) -> crate::Result<http::Request<Bytes>> {
+        let (events, key) = output.into_parts();
+        let rows = events
+            .iter()
+            .map(|event| serde_json::from_str::<Row>(event.get()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Only the binary formats need the schema, ClickHouse converts JSON values itself.
+        let format = self.config.format;
+        let schema = match format {
+            Format::JsonEachRow => None,
+            Format::RowBinary | Format::Native => Some(self.schema(&key).await?),
+        };
+        let (body, content_type) = match (format, &schema) {
+            (Format::RowBinary, Some(schema)) => (
+                encode_row_binary(schema, &rows)?,
+                "application/octet-stream",
+            ),
+            (Format::Native, Some(schema)) => {
+                (encode_native(schema, &rows)?, "application/octet-stream")
+            }
+            _ => (encode_json_each_row(&rows), "application/x-ndjson"),
         };
+        let mut compressor = Compressor::from(self.config.compression);
+        compressor.write_all(&body)?;
+        let body = compressor.finish()?.freeze();
+
+        let columns = schema.as_ref().map(|schema| {
+            schema
+                .columns
+                .iter()
+                .map(|column| column.name.as_str())
+                .collect::<Vec<_>>()
+        });
 
         let uri = set_uri_query(
-            &self.endpoint.with_default_parts().uri,
-            database,
-            &self.table,
-            self.skip_unknown_fields,
+            &self.config.endpoint.with_default_parts().uri,
+            &key.database,
+            &key.table,
+            columns.as_deref(),
+            format,
+            self.config.skip_unknown_fields,
         )
         .expect("Unable to encode uri");
 
-        let mut builder = Request::post(&uri).header("Content-Type", "application/x-ndjson");
+        let mut builder = Request::post(&uri).header("Content-Type", content_type);
 
-        if let Some(ce) = self.compression.content_encoding() {
+        if let Some(ce) = self.config.compression.content_encoding() {
             builder = builder.header("Content-Encoding", ce);
         }
 
-        let mut request = builder.body(events.freeze()).unwrap();
+        let mut request = builder.body(body).unwrap();
 
-        if let Some(auth) = &self.auth {
+        if let Some(auth) = &self.config.auth {
             auth.apply(&mut request);
         }
 
@@ -109,8 +273,8 @@ impl HttpSink for ClickhouseConfig {
     }
 }
 
-async fn healthcheck(client: HttpClient, config: ClickhouseConfig) -> crate::Result<()> {
-    // TODO: check if table exists?
+async fn healthcheck(sink: ClickhouseSink) -> crate::Result<()> {
+    let config = &sink.config;
     let uri = format!("{}/?query=SELECT%201", config.endpoint.with_default_parts());
     let mut request = Request::get(uri).body(Body::empty()).unwrap();
 
@@ -118,22 +282,66 @@ async fn healthcheck(client: HttpClient, config: ClickhouseConfig) -> crate::Res
         auth.apply(&mut request);
     }
 
-    let response = client.send(request).await?;
+    let response = sink.client.send(request).await?;
 
     match response.status() {
-        StatusCode::OK => Ok(()),
-        status => Err(HealthcheckError::UnexpectedStatus { status }.into()),
+        StatusCode::OK => {}
+        status => return Err(HealthcheckError::UnexpectedStatus { status }.into()),
+    }
+
+    if config.format == Format::JsonEachRow {
+        return Ok(());
+    }
+
+    // The schemas of templated tables are read before their first insert instead.
+    let database = config
+        .database
+        .as_ref()
+        .map_or(Some("default"), |database| {
+            (!database.is_dynamic()).then(|| database.get_ref())
+        });
+    if let (Some(database), false) = (database, config.table.is_dynamic()) {
+        let key = TableKey {
+            database: database.to_owned(),
+            table: config.table.get_ref().to_owned(),
+        };
+        sink.schema(&key).await?;
     }
+
+    Ok(())
+}
+
+fn table_name(database: &str, table: &str) -> String {
+    format!(
+        "\"{}\".\"{}\"",
+        database.replace('\"', "\\\""),
+        table.replace('\"', "\\\"")
+    )
 }
 
-fn set_uri_query(uri: &Uri, database: &str, table: &str, skip_unknown: bool) -> crate::Result<Uri> {
+fn set_uri_query(
+    uri: &Uri,
+    database: &str,
+    table: &str,
+    columns: Option<&[&str]>,
+    format: Format,
+    skip_unknown: bool,
+) -> crate::Result<Uri> {
+    let columns = columns.map_or_else(String::new, |columns| {
+        let columns = columns
+            .iter()
+            .map(|column| format!("\"{}\"", column.replace('\"', "\\\"")))
+            .collect::<Vec<_>>();
+        format!(" ({})", columns.join(", "))
+    });
     let query = url::form_urlencoded::Serializer::new(String::new())
         .append_pair(
             "query",
             format!(
-                "INSERT INTO \"{}\".\"{}\" FORMAT JSONEachRow",
-                database,
-                table.replace('\"', "\\\"")
+                "INSERT INTO {}{} FORMAT {}",
+                table_name(database, table),
+                columns,
+                format.as_str()
             )
             .as_str(),
         )
@@ -154,9 +362,43 @@ fn set_uri_query(uri: &Uri, database: &str, table: &str, skip_unknown: bool) ->
         .map_err(Into::into)
 }
 
-#[derive(Debug, Default, Clone)]
+/// ClickHouse error codes meaning that the table differs from the schema that was read, such as after an
+/// `ALTER TABLE`.
+///
+/// Reference: https://github.com/ClickHouse/ClickHouse/blob/master/src/Common/ErrorCodes.cpp
+const SCHEMA_ERROR_CODES: &[u32] = &[
+    8,   // THERE_IS_NO_COLUMN
+    16,  // NO_SUCH_COLUMN_IN_TABLE
+    33,  // CANNOT_READ_ALL_DATA
+    47,  // UNKNOWN_IDENTIFIER
+    53,  // TYPE_MISMATCH
+    60,  // UNKNOWN_TABLE
+    117, // INCORRECT_DATA
+];
+
+#[derive(Debug, Clone)]
 struct ClickhouseRetryLogic {
     inner: HttpRetryLogic,
+    schemas: Schemas,
+}
+
+impl ClickhouseRetryLogic {
+    /// Forgets the schemas read so far when an insert fails because of a schema change, so that they are read again
+    /// before the next insert.
+    ///
+    /// The response does not tell which table the insert was into, so the schemas of all tables are forgotten.
+    fn invalidate_schemas(&self, body: &[u8]) {
+        if error_code(body).map_or(false, |code| SCHEMA_ERROR_CODES.contains(&code)) {
+            self.schemas.lock().expect("poisoned lock").clear();
+        }
+    }
+}
+
+/// Parses the code of a ClickHouse error response, which starts with `Code: {code}`.
+fn error_code(body: &[u8]) -> Option<u32> {
+    let code = body.strip_prefix(b"Code: ")?;
+    let digits = code.iter().take_while(|byte| byte.is_ascii_digit()).count();
+    std::str::from_utf8(&code[..digits]).ok()?.parse().ok()
 }
 
 impl RetryLogic for ClickhouseRetryLogic {
@@ -171,6 +413,7 @@ impl RetryLogic for ClickhouseRetryLogic {
         match response.status() {
             StatusCode::INTERNAL_SERVER_ERROR => {
                 let body = response.body();
+                self.invalidate_schemas(body);
 
                 // Currently, clickhouse returns 500's incorrect data and type mismatch errors.
                 // This attempts to check if the body starts with `Code: {code_num}` and to not
@@ -184,11 +427,16 @@ impl RetryLogic for ClickhouseRetryLogic {
                     RetryAction::DontRetry("incorrect data".into())
                 } else if body.starts_with(b"Code: 53") {
                     RetryAction::DontRetry("type mismatch".into())
+                } else if body.starts_with(b"Code: 33") {
+                    RetryAction::DontRetry("cannot read all data".into())
                 } else {
                     RetryAction::Retry(String::from_utf8_lossy(body).to_string().into())
                 }
             }
-            _ => self.inner.should_retry_response(response),
+            _ => {
+                self.invalidate_schemas(response.body());
+                self.inner.should_retry_response(response)
+            }
         }
     }
 }
@@ -196,6 +444,7 @@ impl RetryLogic for ClickhouseRetryLogic {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::event::LogEvent;
 
     #[test]
     fn generate_config() {
@@ -208,6 +457,8 @@ mod tests {
             &"http://localhost:80".parse().unwrap(),
             "my_database",
             "my_table",
+            None,
+            Format::JsonEachRow,
             false,
         )
         .unwrap();
@@ -217,20 +468,144 @@ mod tests {
             &"http://localhost:80".parse().unwrap(),
             "my_database",
             "my_\"table\"",
+            None,
+            Format::JsonEachRow,
             false,
         )
         .unwrap();
         assert_eq!(uri.to_string(), "http://localhost:80/?input_format_import_nested_json=1&query=INSERT+INTO+%22my_database%22.%22my_%5C%22table%5C%22%22+FORMAT+JSONEachRow");
     }
 
+    #[test]
+    fn encode_valid_binary() {
+        let uri = set_uri_query(
+            &"http://localhost:80".parse().unwrap(),
+            "my_database",
+            "my_table",
+            Some(&["host", "message"]),
+            Format::RowBinary,
+            false,
+        )
+        .unwrap();
+        assert_eq!(uri.to_string(), "http://localhost:80/?input_format_import_nested_json=1&query=INSERT+INTO+%22my_database%22.%22my_table%22+%28%22host%22%2C+%22message%22%29+FORMAT+RowBinary");
+    }
+
     #[test]
     fn encode_invalid() {
         set_uri_query(
             &"localhost:80".parse().unwrap(),
             "my_database",
             "my_table",
+            None,
+            Format::JsonEachRow,
             false,
         )
         .unwrap_err();
     }
+
+    #[test]
+    fn schema_errors_invalidate_schemas() {
+        let key = TableKey {
+            database: "default".into(),
+            table: "logs".into(),
+        };
+        let retry_logic = ClickhouseRetryLogic {
+            inner: HttpRetryLogic,
+            schemas: Default::default(),
+        };
+        let response = |status, body: &'static str| {
+            http::Response::builder()
+                .status(status)
+                .body(Bytes::from(body))
+                .unwrap()
+        };
+        let cache = || {
+            retry_logic
+                .schemas
+                .lock()
+                .unwrap()
+                .insert(key.clone(), Default::default())
+        };
+
+        cache();
+        retry_logic.should_retry_response(&response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Code: 209. DB::Exception: Timeout exceeded",
+        ));
+        assert_eq!(retry_logic.schemas.lock().unwrap().len(), 1);
+
+        retry_logic.should_retry_response(&response(
+            StatusCode::BAD_REQUEST,
+            "Code: 16. DB::Exception: No such column host in table default.logs",
+        ));
+        assert!(retry_logic.schemas.lock().unwrap().is_empty());
+
+        cache();
+        let action = retry_logic.should_retry_response(&response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Code: 53, e.displayText() = DB::Exception: Type mismatch",
+        ));
+        assert!(matches!(action, RetryAction::DontRetry(_)));
+        assert!(retry_logic.schemas.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn describe_retries_unavailable_server() {
+        let addr = crate::test_util::next_addr();
+        let config = toml::from_str::<ClickhouseConfig>(&format!(
+            "endpoint = \"http://{}\"\ntable = \"logs\"\nformat = \"row_binary\"",
+            addr
+        ))
+        .unwrap();
+        let sink = ClickhouseSink {
+            config,
+            client: HttpClient::new(None, &Default::default()).unwrap(),
+            schemas: Default::default(),
+        };
+        let retry_logic = ClickhouseRetryLogic {
+            inner: HttpRetryLogic,
+            schemas: Default::default(),
+        };
+        let key = TableKey {
+            database: "default".into(),
+            table: "logs".into(),
+        };
+
+        let (_rx, _trigger, server) = crate::sinks::util::test::build_test_server_status(
+            addr,
+            StatusCode::SERVICE_UNAVAILABLE,
+        );
+        tokio::spawn(server);
+
+        let error = sink.describe(&key).await.unwrap_err();
+        let error = error
+            .downcast_ref::<HttpError>()
+            .expect("unavailable server is an HTTP error");
+        assert!(matches!(error, HttpError::ServerUnavailable { .. }));
+        assert!(retry_logic.is_retriable_error(error));
+    }
+
+    #[test]
+    fn partition_by_rendered_table() {
+        let mut encoder = ClickhouseEventEncoder {
+            transformer: Default::default(),
+            database: Some(Template::try_from("logs").unwrap()),
+            table: Template::try_from("{{ service }}_events").unwrap(),
+        };
+
+        let mut event = LogEvent::from("hello");
+        event.insert("service", "api");
+        let (_, key) = encoder.encode_event(event.into()).unwrap().into_parts();
+        assert_eq!(
+            key,
+            TableKey {
+                database: "logs".into(),
+                table: "api_events".into(),
+            }
+        );
+
+        assert!(encoder
+            .encode_event(LogEvent::from("no service").into())
+            .is_none());
+    }
 }
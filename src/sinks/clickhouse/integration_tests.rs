@@ -45,7 +45,7 @@ async fn insert_events() {
 
     let config = ClickhouseConfig {
         endpoint: host.parse().unwrap(),
-        table: table.clone(),
+        table: table.as_str().try_into().unwrap(),
         compression: Compression::None,
         batch,
         request: TowerRequestConfig {
@@ -98,7 +98,7 @@ async fn skip_unknown_fields() {
 
     let config = ClickhouseConfig {
         endpoint: host.parse().unwrap(),
-        table: table.clone(),
+        table: table.as_str().try_into().unwrap(),
         skip_unknown_fields: true,
         compression: Compression::None,
         batch,
@@ -148,7 +148,7 @@ async fn insert_events_unix_timestamps() {
 
     let config = ClickhouseConfig {
         endpoint: host.parse().unwrap(),
-        table: table.clone(),
+        table: table.as_str().try_into().unwrap(),
         compression: Compression::None,
         encoding: Transformer::new(None, None, Some(TimestampFormat::Unix)).unwrap(),
         batch,
@@ -261,6 +261,187 @@ timestamp_format = "unix""#,
     assert_eq!(expected, output.data[0]);
 }
 
+#[tokio::test]
+async fn insert_events_datetime64() {
+    trace_init();
+
+    let table = gen_table();
+    let host = clickhouse_address();
+
+    let config: ClickhouseConfig = toml::from_str(&format!(
+        r#"
+endpoint = "{}"
+table = "{}"
+format = "row_binary"
+compression = "none"
+[batch]
+max_events = 1"#,
+        host, table
+    ))
+    .unwrap();
+
+    let client = ClickhouseClient::new(host);
+    client
+        .create_table(
+            &table,
+            "host String, timestamp DateTime64(6, 'UTC'), message String",
+        )
+        .await;
+
+    let (sink, hc) = config.build(SinkContext::new_test()).await.unwrap();
+    hc.await.unwrap();
+
+    let (mut input_event, mut receiver) = make_event();
+
+    run_and_assert_sink_compliance(
+        sink,
+        stream::once(ready(input_event.clone())),
+        &HTTP_SINK_TAGS,
+    )
+    .await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let output = client.select_all(&table).await;
+    assert_eq!(1, output.rows);
+
+    let exp_event = input_event.as_mut_log();
+    let timestamp = exp_event
+        .get(log_schema().timestamp_key())
+        .unwrap()
+        .as_timestamp()
+        .unwrap()
+        .format("%Y-%m-%d %H:%M:%S%.6f")
+        .to_string();
+    exp_event.insert(log_schema().timestamp_key(), timestamp);
+
+    let expected = serde_json::to_value(exp_event).unwrap();
+    assert_eq!(expected, output.data[0]);
+}
+
+async fn insert_events_in_format(format: &str) {
+    trace_init();
+
+    let table = gen_table();
+    let host = clickhouse_address();
+
+    let config: ClickhouseConfig = toml::from_str(&format!(
+        r#"
+endpoint = "{}"
+table = "{}"
+format = "{}"
+[batch]
+max_events = 2"#,
+        host, table, format
+    ))
+    .unwrap();
+
+    let client = ClickhouseClient::new(host);
+    client
+        .create_table(
+            &table,
+            "host String, timestamp DateTime64(3, 'UTC'), message String, level Nullable(UInt8), items Array(String)",
+        )
+        .await;
+
+    let (sink, hc) = config.build(SinkContext::new_test()).await.unwrap();
+    hc.await.unwrap();
+
+    let (mut first, mut receiver) = make_event();
+    first.as_mut_log().insert("level", "3");
+    first.as_mut_log().insert("items", vec!["item1", "item2"]);
+    first.as_mut_log().insert("unknown", "discarded");
+    let (second, _) = make_event();
+
+    run_and_assert_sink_compliance(sink, stream::iter(vec![first, second]), &HTTP_SINK_TAGS).await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let output = client.select_all(&table).await;
+    assert_eq!(2, output.rows);
+
+    let mut levels = output
+        .data
+        .iter()
+        .map(|row| {
+            (
+                row["level"].clone(),
+                row["items"].clone(),
+                row["message"].clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    levels.sort_by_key(|(level, _, _)| level.is_null());
+    assert_eq!(
+        levels,
+        vec![
+            (
+                Value::from(3),
+                Value::from(vec!["item1", "item2"]),
+                Value::from("raw log line")
+            ),
+            (
+                Value::Null,
+                Value::Array(vec![]),
+                Value::from("raw log line")
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn insert_events_row_binary() {
+    insert_events_in_format("row_binary").await;
+}
+
+#[tokio::test]
+async fn insert_events_native() {
+    insert_events_in_format("native").await;
+}
+
+#[tokio::test]
+async fn templated_table() {
+    trace_init();
+
+    let prefix = gen_table();
+    let host = clickhouse_address();
+
+    let config: ClickhouseConfig = toml::from_str(&format!(
+        r#"
+endpoint = "{}"
+table = "{}_{{{{ service }}}}"
+compression = "none"
+"#,
+        host, prefix
+    ))
+    .unwrap();
+
+    let client = ClickhouseClient::new(host);
+    for service in ["api", "web"] {
+        client
+            .create_table(
+                &format!("{}_{}", prefix, service),
+                "host String, timestamp String, message String, service String",
+            )
+            .await;
+    }
+
+    let (sink, hc) = config.build(SinkContext::new_test()).await.unwrap();
+    hc.await.unwrap();
+
+    let events = ["api", "web", "api"]
+        .into_iter()
+        .map(|service| {
+            let (mut event, _) = make_event();
+            event.as_mut_log().insert("service", service);
+            event
+        })
+        .collect::<Vec<_>>();
+
+    run_and_assert_sink_compliance(sink, stream::iter(events), &HTTP_SINK_TAGS).await;
+
+    assert_eq!(client.select_all(&format!("{}_api", prefix)).await.rows, 2);
+    assert_eq!(client.select_all(&format!("{}_web", prefix)).await.rows, 1);
+}
+
 #[tokio::test]
 async fn no_retry_on_incorrect_data() {
     trace_init();
@@ -273,7 +454,7 @@ async fn no_retry_on_incorrect_data() {
 
     let config = ClickhouseConfig {
         endpoint: host.parse().unwrap(),
-        table: table.clone(),
+        table: table.as_str().try_into().unwrap(),
         compression: Compression::None,
         batch,
         ..Default::default()
@@ -324,7 +505,7 @@ async fn no_retry_on_incorrect_data_warp() {
 
     let config = ClickhouseConfig {
         endpoint: host.parse().unwrap(),
-        table: gen_table(),
+        table: gen_table().try_into().unwrap(),
         batch,
         ..Default::default()
     };
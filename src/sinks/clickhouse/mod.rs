@@ -1,5 +1,7 @@
 mod config;
+mod format;
 mod http_sink;
 #[cfg(all(test, feature = "clickhouse-integration-tests"))]
 mod integration_tests;
+mod schema;
 pub use self::config::ClickhouseConfig;
@@ -0,0 +1,362 @@
+//! Table schemas for the `clickhouse` sink, and the conversion of event values to their column types.
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use snafu::Snafu;
+
+use super::config::Format;
+
+#[derive(Debug, Snafu)]
+pub(super) enum SchemaError {
+    #[snafu(display(
+        "Column `{}` has type `{}`, which is not supported by the `{}` format.",
+        column,
+        type_name,
+        format
+    ))]
+    UnsupportedType {
+        column: String,
+        type_name: String,
+        format: &'static str,
+    },
+
+    #[snafu(display("Cannot convert {} to `{}` for column `{}`.", value, type_name, column))]
+    InvalidValue {
+        column: String,
+        type_name: String,
+        value: Value,
+    },
+}
+
+/// A row of the output of `DESCRIBE TABLE`.
+#[derive(Debug, Deserialize)]
+pub(super) struct DescribeRow {
+    name: String,
+    #[serde(rename = "type")]
+    type_name: String,
+    #[serde(default)]
+    default_type: String,
+}
+
+/// A column that values can be inserted into.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Column {
+    pub(super) name: String,
+    pub(super) type_name: String,
+    pub(super) column_type: ColumnType,
+}
+
+impl Column {
+    pub(super) fn invalid_value(&self, value: Value) -> SchemaError {
+        SchemaError::InvalidValue {
+            column: self.name.clone(),
+            type_name: self.type_name.clone(),
+            value,
+        }
+    }
+}
+
+/// The insertable columns of a table, in table order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct TableSchema {
+    pub(super) columns: Vec<Column>,
+}
+
+impl TableSchema {
+    /// Builds the schema from the output of `DESCRIBE TABLE`.
+    ///
+    /// `MATERIALIZED` and `ALIAS` columns are computed by the server and cannot be inserted into, so they are skipped.
+    pub(super) fn from_describe(rows: Vec<DescribeRow>) -> Self {
+        let columns = rows
+            .into_iter()
+            .filter(|row| !matches!(row.default_type.as_str(), "MATERIALIZED" | "ALIAS"))
+            .map(|row| Column {
+                column_type: ColumnType::parse(&row.type_name),
+                name: row.name,
+                type_name: row.type_name,
+            })
+            .collect();
+        Self { columns }
+    }
+
+    /// Checks that every column can be encoded in the given format.
+    pub(super) fn validate(&self, format: Format) -> Result<(), SchemaError> {
+        for column in &self.columns {
+            if !column.column_type.is_supported_by(format) {
+                return Err(SchemaError::UnsupportedType {
+                    column: column.name.clone(),
+                    type_name: column.type_name.clone(),
+                    format: format.as_str(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The type of a column, as far as conversion of values is concerned.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum ColumnType {
+    Bool,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    String,
+    FixedString(usize),
+    Date,
+    Date32,
+    DateTime,
+    DateTime64(u32),
+    Nullable(Box<ColumnType>),
+    Array(Box<ColumnType>),
+    LowCardinality(Box<ColumnType>),
+
+    /// A type whose values are passed through as they are.
+    Other,
+}
+
+impl ColumnType {
+    pub(super) fn parse(type_name: &str) -> Self {
+        let type_name = type_name.trim();
+        let (name, args) = match type_name.find('(') {
+            Some(start) if type_name.ends_with(')') => (
+                &type_name[..start],
+                Some(&type_name[start + 1..type_name.len() - 1]),
+            ),
+            _ => (type_name, None),
+        };
+
+        match (name, args) {
+            ("Bool", None) => Self::Bool,
+            ("UInt8", None) => Self::UInt8,
+            ("UInt16", None) => Self::UInt16,
+            ("UInt32", None) => Self::UInt32,
+            ("UInt64", None) => Self::UInt64,
+            ("Int8", None) => Self::Int8,
+            ("Int16", None) => Self::Int16,
+            ("Int32", None) => Self::Int32,
+            ("Int64", None) => Self::Int64,
+            ("Float32", None) => Self::Float32,
+            ("Float64", None) => Self::Float64,
+            ("String", None) => Self::String,
+            ("FixedString", Some(length)) => {
+                length.trim().parse().map_or(Self::Other, Self::FixedString)
+            }
+            ("Date", None) => Self::Date,
+            ("Date32", None) => Self::Date32,
+            // The time zone only affects how values are displayed.
+            ("DateTime", _) => Self::DateTime,
+            ("DateTime64", Some(args)) => args
+                .split(',')
+                .next()
+                .and_then(|precision| precision.trim().parse().ok())
+                .filter(|precision| *precision <= 9)
+                .map_or(Self::Other, Self::DateTime64),
+            ("Nullable", Some(inner)) => Self::Nullable(Box::new(Self::parse(inner))),
+            ("Array", Some(inner)) => Self::Array(Box::new(Self::parse(inner))),
+            ("LowCardinality", Some(inner)) => Self::LowCardinality(Box::new(Self::parse(inner))),
+            _ => Self::Other,
+        }
+    }
+
+    fn is_supported_by(&self, format: Format) -> bool {
+        match self {
+            Self::Other => format == Format::JsonEachRow,
+            // `LowCardinality` columns are dictionary encoded in the `Native` format.
+            Self::LowCardinality(inner) => {
+                format != Format::Native && inner.is_supported_by(format)
+            }
+            Self::Nullable(inner) | Self::Array(inner) => inner.is_supported_by(format),
+            _ => true,
+        }
+    }
+
+    /// The range of values of an integer type.
+    pub(super) fn integer_range(&self) -> std::ops::RangeInclusive<i128> {
+        match self {
+            Self::UInt8 => 0..=u8::MAX as i128,
+            Self::UInt16 => 0..=u16::MAX as i128,
+            Self::UInt32 => 0..=u32::MAX as i128,
+            Self::UInt64 => 0..=u64::MAX as i128,
+            Self::Int8 => i8::MIN as i128..=i8::MAX as i128,
+            Self::Int16 => i16::MIN as i128..=i16::MAX as i128,
+            Self::Int32 => i32::MIN as i128..=i32::MAX as i128,
+            Self::Int64 => i64::MIN as i128..=i64::MAX as i128,
+            _ => 0..=-1,
+        }
+    }
+}
+
+pub(super) fn to_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(value) => Some(*value),
+        Value::Number(number) => number.as_f64().map(|number| number != 0.0),
+        Value::String(string) => match string.trim() {
+            "true" | "1" => Some(true),
+            "false" | "0" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub(super) fn to_i128(value: &Value) -> Option<i128> {
+    match value {
+        Value::Bool(value) => Some(*value as i128),
+        Value::Number(number) => number
+            .as_i64()
+            .map(Into::into)
+            .or_else(|| number.as_u64().map(Into::into))
+            .or_else(|| number.as_f64().and_then(float_to_i128)),
+        Value::String(string) => {
+            let string = string.trim();
+            string
+                .parse()
+                .ok()
+                .or_else(|| string.parse().ok().and_then(float_to_i128))
+        }
+        _ => None,
+    }
+}
+
+fn float_to_i128(float: f64) -> Option<i128> {
+    (float.is_finite() && float.fract() == 0.0).then_some(float as i128)
+}
+
+pub(super) fn to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Bool(value) => Some(*value as u8 as f64),
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => string.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+pub(super) fn to_string(value: Value) -> String {
+    match value {
+        Value::String(string) => string,
+        value => value.to_string(),
+    }
+}
+
+/// Interprets a value as a timestamp, either in RFC 3339 format or as a number of seconds since the Unix epoch.
+pub(super) fn to_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    let seconds = match value {
+        Value::String(string) => {
+            if let Ok(timestamp) = DateTime::parse_from_rfc3339(string.trim()) {
+                return Some(timestamp.with_timezone(&Utc));
+            }
+            string.trim().parse::<f64>().ok()?
+        }
+        Value::Number(number) => number.as_f64()?,
+        _ => return None,
+    };
+
+    if !seconds.is_finite() {
+        return None;
+    }
+    let whole = seconds.floor();
+    let nanos = ((seconds - whole) * 1e9).round().min(999_999_999.0) as u32;
+    Utc.timestamp_opt(whole as i64, nanos).single()
+}
+
+pub(super) fn to_date(value: &Value) -> Option<NaiveDate> {
+    match value {
+        Value::String(string) => NaiveDate::parse_from_str(string.trim(), "%Y-%m-%d")
+            .ok()
+            .or_else(|| to_timestamp(value).map(|timestamp| timestamp.naive_utc().date())),
+        value => to_timestamp(value).map(|timestamp| timestamp.naive_utc().date()),
+    }
+}
+
+/// The number of days since the Unix epoch.
+pub(super) fn days_since_epoch(date: NaiveDate) -> i64 {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid date");
+    date.signed_duration_since(epoch).num_days()
+}
+
+/// The number of `10^-precision` second ticks since the Unix epoch.
+pub(super) fn to_ticks(timestamp: DateTime<Utc>, precision: u32) -> Option<i64> {
+    let scale = 10_i64.pow(precision);
+    let fraction = i64::from(timestamp.timestamp_subsec_nanos()) / 10_i64.pow(9 - precision);
+    timestamp
+        .timestamp()
+        .checked_mul(scale)?
+        .checked_add(fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(type_name: &str) -> Column {
+        Column {
+            name: "column".into(),
+            type_name: type_name.into(),
+            column_type: ColumnType::parse(type_name),
+        }
+    }
+
+    #[test]
+    fn parse_types() {
+        assert_eq!(ColumnType::parse("UInt32"), ColumnType::UInt32);
+        assert_eq!(ColumnType::parse("DateTime('UTC')"), ColumnType::DateTime);
+        assert_eq!(
+            ColumnType::parse("DateTime64(3, 'Europe/Paris')"),
+            ColumnType::DateTime64(3)
+        );
+        assert_eq!(
+            ColumnType::parse("Array(Nullable(FixedString(4)))"),
+            ColumnType::Array(Box::new(ColumnType::Nullable(Box::new(
+                ColumnType::FixedString(4)
+            ))))
+        );
+        assert_eq!(
+            ColumnType::parse("LowCardinality(String)"),
+            ColumnType::LowCardinality(Box::new(ColumnType::String))
+        );
+        assert_eq!(ColumnType::parse("Map(String, UInt8)"), ColumnType::Other);
+        assert_eq!(ColumnType::parse("Decimal(9, 2)"), ColumnType::Other);
+    }
+
+    #[test]
+    fn skips_computed_columns() {
+        let schema = TableSchema::from_describe(vec![
+            DescribeRow {
+                name: "message".into(),
+                type_name: "String".into(),
+                default_type: "".into(),
+            },
+            DescribeRow {
+                name: "length".into(),
+                type_name: "UInt64".into(),
+                default_type: "MATERIALIZED".into(),
+            },
+        ]);
+        assert_eq!(schema.columns.len(), 1);
+        assert_eq!(schema.columns[0].name, "message");
+    }
+
+    #[test]
+    fn validates_formats() {
+        let schema = TableSchema {
+            columns: vec![column("LowCardinality(String)")],
+        };
+        assert!(schema.validate(Format::JsonEachRow).is_ok());
+        assert!(schema.validate(Format::RowBinary).is_ok());
+        assert!(schema.validate(Format::Native).is_err());
+
+        let schema = TableSchema {
+            columns: vec![column("Map(String, String)")],
+        };
+        assert!(schema.validate(Format::JsonEachRow).is_ok());
+        assert!(schema.validate(Format::RowBinary).is_err());
+    }
+}
@@ -30,6 +30,8 @@ pub struct KafkaSinkConfig {
     pub bootstrap_servers: String,
 
     /// The Kafka topic name to write events to.
+    ///
+    /// Templating can be used to route each event to a topic derived from its fields.
     #[configurable(metadata(docs::templateable))]
     pub topic: String,
 
@@ -40,6 +42,12 @@ pub struct KafkaSinkConfig {
     /// Kafka uses a hash of the key to choose the partition or uses round-robin if the record has no key.
     pub key_field: Option<String>,
 
+    /// The log field name or tags key to use for the partition to write each event to.
+    ///
+    /// The value must be an integer. If the field does not exist in the log or in tags, or its value is not an
+    /// integer, the partition is chosen from the key as usual.
+    pub partition_field: Option<String>,
+
     #[configurable(derived)]
     pub encoding: EncodingConfig,
 
@@ -78,6 +86,9 @@ pub struct KafkaSinkConfig {
     #[serde(alias = "headers_field")] // accidentally released as `headers_field` in 0.18
     pub headers_key: Option<String>,
 
+    #[configurable(derived)]
+    pub transaction: Option<KafkaTransactionConfig>,
+
     #[configurable(derived)]
     #[serde(
         default,
@@ -87,6 +98,31 @@ pub struct KafkaSinkConfig {
     pub acknowledgements: AcknowledgementsConfig,
 }
 
+/// Transactional producer configuration.
+///
+/// When enabled, events are written to Kafka in transactions, one per batch as configured by `batch`, and are only
+/// acknowledged once their transaction is committed. If a transaction fails it is aborted, and consumers using the
+/// `read_committed` isolation level never see its messages. If the producer fails with a fatal error, for example
+/// because another producer with the same transactional ID fenced it, the sink stops.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct KafkaTransactionConfig {
+    /// The transactional ID of the producer.
+    ///
+    /// This must be unique to each running instance of the sink, and stable across restarts, so that the transactions
+    /// left open by a previous instance are fenced off when a new one starts.
+    pub transactional_id: String,
+
+    /// The maximum time, in milliseconds, a transaction may remain open before the broker aborts it.
+    #[serde(default = "default_transaction_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+const fn default_transaction_timeout_ms() -> u64 {
+    60000 // default in librdkafka
+}
+
 const fn default_socket_timeout_ms() -> u64 {
     60000 // default in librdkafka
 }
@@ -120,6 +156,15 @@ impl KafkaSinkConfig {
                     .set("compression.codec", &to_string(self.compression))
                     .set("message.timeout.ms", &self.message_timeout_ms.to_string());
 
+                if let Some(transaction) = &self.transaction {
+                    client_config
+                        .set("transactional.id", &transaction.transactional_id)
+                        .set(
+                            "transaction.timeout.ms",
+                            &transaction.timeout_ms.to_string(),
+                        );
+                }
+
                 if let Some(value) = self.batch.timeout_secs {
                     // Delay in milliseconds to wait for messages in the producer queue to accumulate before
                     // constructing message batches (MessageSets) to transmit to brokers. A higher value
@@ -201,6 +246,7 @@ impl GenerateConfig for KafkaSinkConfig {
             bootstrap_servers: "10.14.22.123:9092,10.14.23.332:9092".to_owned(),
             topic: "topic-1234".to_owned(),
             key_field: Some("user_id".to_owned()),
+            partition_field: None,
             encoding: JsonSerializerConfig::new().into(),
             batch: Default::default(),
             compression: KafkaCompression::None,
@@ -209,6 +255,7 @@ impl GenerateConfig for KafkaSinkConfig {
             message_timeout_ms: default_message_timeout_ms(),
             librdkafka_options: Default::default(),
             headers_key: None,
+            transaction: None,
             acknowledgements: Default::default(),
         })
         .unwrap()
@@ -240,4 +287,25 @@ mod tests {
     fn generate_config() {
         KafkaSinkConfig::generate_config();
     }
+
+    #[test]
+    fn transactional_producer_config() {
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topic = "logs-{{ service }}"
+            partition_field = "partition"
+            encoding.codec = "json"
+            transaction.transactional_id = "vector-1"
+            "#,
+        )
+        .unwrap();
+
+        let client_config = config.to_rdkafka(KafkaRole::Producer).unwrap();
+        assert_eq!(client_config.get("transactional.id"), Some("vector-1"));
+        assert_eq!(client_config.get("transaction.timeout.ms"), Some("60000"));
+
+        let client_config = config.to_rdkafka(KafkaRole::Consumer).unwrap();
+        assert_eq!(client_config.get("transactional.id"), None);
+    }
 }
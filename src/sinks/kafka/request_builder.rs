@@ -18,6 +18,7 @@ use crate::{
 
 pub struct KafkaRequestBuilder {
     pub key_field: Option<String>,
+    pub partition_field: Option<String>,
     pub headers_key: Option<String>,
     pub topic_template: Template,
     pub transformer: Transformer,
@@ -44,6 +45,7 @@ impl KafkaRequestBuilder {
         let metadata = KafkaRequestMetadata {
             finalizers: event.take_finalizers(),
             key: get_key(&event, &self.key_field),
+            partition: get_partition(&event, &self.partition_field),
             timestamp_millis: get_timestamp_millis(&event, self.log_schema),
             headers: get_headers(&event, &self.headers_key),
             topic,
@@ -77,6 +79,25 @@ fn get_key(event: &Event, key_field: &Option<String>) -> Option<Bytes> {
     })
 }
 
+fn get_partition(event: &Event, partition_field: &Option<String>) -> Option<i32> {
+    partition_field
+        .as_ref()
+        .and_then(|partition_field| match event {
+            Event::Log(log) => log
+                .get(partition_field.as_str())
+                .and_then(|value| match value {
+                    Value::Integer(partition) => i32::try_from(*partition).ok(),
+                    Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.parse().ok(),
+                    _ => None,
+                }),
+            Event::Metric(metric) => metric
+                .tags()
+                .and_then(|tags| tags.get(partition_field))
+                .and_then(|value| value.parse().ok()),
+            _ => None,
+        })
+}
+
 fn get_timestamp_millis(event: &Event, log_schema: &'static LogSchema) -> Option<i64> {
     match &event {
         Event::Log(log) => log
@@ -148,4 +169,25 @@ mod tests {
         assert_eq!(headers.get(1).key, "b-key");
         assert_eq!(headers.get(1).value.unwrap(), "b-value".as_bytes());
     }
+
+    #[test]
+    fn kafka_get_partition() {
+        let partition_field = Some("partition".to_string());
+
+        let mut event = Event::Log(LogEvent::from("hello"));
+        assert_eq!(get_partition(&event, &partition_field), None);
+
+        event.as_mut_log().insert("partition", 3);
+        assert_eq!(get_partition(&event, &partition_field), Some(3));
+        assert_eq!(get_partition(&event, &None), None);
+
+        event.as_mut_log().insert("partition", "7");
+        assert_eq!(get_partition(&event, &partition_field), Some(7));
+
+        event.as_mut_log().insert("partition", "seven");
+        assert_eq!(get_partition(&event, &partition_field), None);
+
+        event.as_mut_log().insert("partition", i64::MAX);
+        assert_eq!(get_partition(&event, &partition_field), None);
+    }
 }
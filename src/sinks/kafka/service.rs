@@ -1,11 +1,15 @@
-use std::task::{Context, Poll};
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
 
 use bytes::Bytes;
-use futures::future::BoxFuture;
+use futures::future::{self, BoxFuture};
 use rdkafka::{
-    error::KafkaError,
+    error::{KafkaError, KafkaResult},
     message::OwnedHeaders,
-    producer::{FutureProducer, FutureRecord},
+    producer::{FutureProducer, FutureRecord, Producer},
     util::Timeout,
 };
 use tower::Service;
@@ -19,6 +23,7 @@ use vector_core::{
 
 use crate::{
     event::{EventFinalizers, EventStatus, Finalizable},
+    internal_events::KafkaTransactionError,
     kafka::KafkaStatisticsContext,
 };
 
@@ -31,12 +36,41 @@ pub struct KafkaRequest {
 pub struct KafkaRequestMetadata {
     pub finalizers: EventFinalizers,
     pub key: Option<Bytes>,
+    pub partition: Option<i32>,
     pub timestamp_millis: Option<i64>,
     pub headers: Option<OwnedHeaders>,
     pub topic: String,
 }
 
+/// A batch of requests written to Kafka within a single transaction.
+pub struct KafkaTransactionRequest {
+    pub requests: Vec<KafkaRequest>,
+    pub finalizers: EventFinalizers,
+    pub request_metadata: RequestMetadata,
+}
+
+impl From<Vec<KafkaRequest>> for KafkaTransactionRequest {
+    fn from(mut requests: Vec<KafkaRequest>) -> Self {
+        let finalizers =
+            requests
+                .iter_mut()
+                .fold(EventFinalizers::default(), |mut finalizers, request| {
+                    finalizers.merge(request.take_finalizers());
+                    finalizers
+                });
+        let request_metadata =
+            RequestMetadata::from_batch(requests.iter().map(|request| request.request_metadata));
+
+        Self {
+            requests,
+            finalizers,
+            request_metadata,
+        }
+    }
+}
+
 pub struct KafkaResponse {
+    event_count: usize,
     event_byte_size: usize,
 }
 
@@ -46,7 +80,7 @@ impl DriverResponse for KafkaResponse {
     }
 
     fn events_sent(&self) -> CountByteSize {
-        CountByteSize(1, self.event_byte_size)
+        CountByteSize(self.event_count, self.event_byte_size)
     }
 }
 
@@ -62,6 +96,41 @@ impl MetaDescriptive for KafkaRequest {
     }
 }
 
+impl Finalizable for KafkaTransactionRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+impl MetaDescriptive for KafkaTransactionRequest {
+    fn get_metadata(&self) -> RequestMetadata {
+        self.request_metadata
+    }
+}
+
+impl KafkaRequest {
+    fn record(&self) -> FutureRecord<'_, [u8], [u8]> {
+        let mut record = FutureRecord::to(&self.metadata.topic).payload(self.body.as_ref());
+        if let Some(key) = &self.metadata.key {
+            record = record.key(&key[..]);
+        }
+        if let Some(partition) = self.metadata.partition {
+            record = record.partition(partition);
+        }
+        if let Some(timestamp) = self.metadata.timestamp_millis {
+            record = record.timestamp(timestamp);
+        }
+        if let Some(headers) = &self.metadata.headers {
+            record = record.headers(headers.clone());
+        }
+        record
+    }
+
+    fn wire_size(&self) -> usize {
+        self.body.len() + self.metadata.key.as_ref().map(|x| x.len()).unwrap_or(0)
+    }
+}
+
 #[derive(Clone)]
 pub struct KafkaService {
     kafka_producer: FutureProducer<KafkaStatisticsContext>,
@@ -92,28 +161,232 @@ impl Service<KafkaRequest> for KafkaService {
         Box::pin(async move {
             let event_byte_size = request.get_metadata().events_byte_size();
 
-            let mut record =
-                FutureRecord::to(&request.metadata.topic).payload(request.body.as_ref());
-            if let Some(key) = &request.metadata.key {
-                record = record.key(&key[..]);
+            // rdkafka will internally retry forever if the queue is full
+            match this
+                .kafka_producer
+                .send(request.record(), Timeout::Never)
+                .await
+            {
+                Ok((_partition, _offset)) => {
+                    this.bytes_sent.emit(ByteSize(request.wire_size()));
+                    Ok(KafkaResponse {
+                        event_count: 1,
+                        event_byte_size,
+                    })
+                }
+                Err((kafka_err, _original_record)) => Err(kafka_err),
             }
-            if let Some(timestamp) = request.metadata.timestamp_millis {
-                record = record.timestamp(timestamp);
+        })
+    }
+}
+
+/// The number of times a transactional operation failing with a retriable error is attempted.
+const TRANSACTION_ATTEMPTS: usize = 3;
+
+/// Writes each batch of requests to Kafka within a transaction.
+///
+/// Transactions on a producer are strictly sequential, so this service must not be called again before the previous
+/// call completes.
+///
+/// Once the producer fails with a fatal error, such as being fenced by another producer with the same transactional
+/// ID, it cannot be used anymore and the service stops being ready, which stops the sink.
+#[derive(Clone)]
+pub struct KafkaTransactionService {
+    kafka_producer: FutureProducer<KafkaStatisticsContext>,
+    timeout: Duration,
+    bytes_sent: Registered<BytesSent>,
+    fatal_error: Arc<Mutex<Option<KafkaError>>>,
+}
+
+/// How a transaction is recovered from an error.
+#[derive(Debug, Eq, PartialEq)]
+enum Recovery {
+    /// The transaction must be aborted before the producer can start a new one.
+    Abort,
+    /// The producer cannot be used anymore.
+    Fatal,
+    /// The producer can start a new transaction as is.
+    None,
+}
+
+impl Recovery {
+    fn of(error: &KafkaError) -> Self {
+        match error {
+            KafkaError::Transaction(error) if error.is_fatal() => Self::Fatal,
+            KafkaError::Transaction(error) if error.txn_requires_abort() => Self::Abort,
+            // Retriable errors are only returned once all attempts failed, leaving the transaction in an unknown state.
+            KafkaError::Transaction(error) if error.is_retriable() => Self::Fatal,
+            KafkaError::Transaction(_) => Self::None,
+            // Failing to deliver a message within a transaction makes it abortable.
+            KafkaError::MessageProduction(_) | KafkaError::Canceled => Self::Abort,
+            _ => Self::None,
+        }
+    }
+}
+
+impl KafkaTransactionService {
+    /// Initializes transactions on the producer, fencing off any transaction left open by a previous producer with the
+    /// same transactional ID.
+    pub(crate) async fn new(
+        kafka_producer: FutureProducer<KafkaStatisticsContext>,
+        timeout: Duration,
+    ) -> Result<KafkaTransactionService, KafkaError> {
+        let producer = kafka_producer.clone();
+        tokio::task::spawn_blocking(move || producer.init_transactions(timeout))
+            .await
+            .expect("Initializing transactions should not panic")?;
+
+        Ok(KafkaTransactionService {
+            kafka_producer,
+            timeout,
+            bytes_sent: register!(BytesSent::from(Protocol("kafka".into()))),
+            fatal_error: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Runs a blocking transactional operation, attempting it again as long as it fails with a retriable error.
+    async fn retry<F>(&self, operation: F) -> Result<(), KafkaError>
+    where
+        F: Fn(&FutureProducer<KafkaStatisticsContext>, Duration) -> KafkaResult<()>
+            + Clone
+            + Send
+            + 'static,
+    {
+        let mut attempts = 1;
+        loop {
+            let producer = self.kafka_producer.clone();
+            let timeout = self.timeout;
+            let operation = operation.clone();
+            let result = tokio::task::spawn_blocking(move || operation(&producer, timeout))
+                .await
+                .expect("Transactional operations should not panic");
+
+            match result {
+                Err(KafkaError::Transaction(error))
+                    if error.is_retriable()
+                        && !error.is_fatal()
+                        && attempts < TRANSACTION_ATTEMPTS =>
+                {
+                    attempts += 1;
+                }
+                result => return result,
             }
-            if let Some(headers) = request.metadata.headers {
-                record = record.headers(headers);
+        }
+    }
+
+    async fn commit(&self, requests: &[KafkaRequest]) -> Result<(), KafkaError> {
+        self.kafka_producer.begin_transaction()?;
+
+        let mut deliveries = Vec::with_capacity(requests.len());
+        for request in requests {
+            let delivery = self
+                .kafka_producer
+                .send_result(request.record())
+                .map_err(|(error, _record)| error)?;
+            deliveries.push(delivery);
+        }
+
+        for delivery in future::join_all(deliveries).await {
+            match delivery {
+                Ok(Ok(_)) => {}
+                Ok(Err((error, _message))) => return Err(error),
+                Err(_canceled) => return Err(KafkaError::Canceled),
             }
+        }
 
-            // rdkafka will internally retry forever if the queue is full
-            match this.kafka_producer.send(record, Timeout::Never).await {
-                Ok((_partition, _offset)) => {
+        self.retry(|producer, timeout| producer.commit_transaction(timeout))
+            .await
+    }
+
+    /// Recovers the producer from an error, so that it can start a new transaction.
+    async fn recover(&self, error: KafkaError) {
+        let error = match Recovery::of(&error) {
+            Recovery::None => return,
+            Recovery::Fatal => error,
+            Recovery::Abort => match self
+                .retry(|producer, timeout| producer.abort_transaction(timeout))
+                .await
+            {
+                Ok(()) => return,
+                Err(error) => {
+                    emit!(KafkaTransactionError {
+                        error: error.clone()
+                    });
+                    // A transaction which cannot be aborted prevents starting any other one.
+                    error
+                }
+            },
+        };
+
+        *self
+            .fatal_error
+            .lock()
+            .expect("Kafka transaction error lock poisoned") = Some(error);
+    }
+}
+
+impl Service<KafkaTransactionRequest> for KafkaTransactionService {
+    type Response = KafkaResponse;
+    type Error = KafkaError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let fatal_error = self
+            .fatal_error
+            .lock()
+            .expect("Kafka transaction error lock poisoned");
+        match &*fatal_error {
+            Some(error) => Poll::Ready(Err(error.clone())),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn call(&mut self, request: KafkaTransactionRequest) -> Self::Future {
+        let this = self.clone();
+
+        Box::pin(async move {
+            let metadata = request.get_metadata();
+
+            match this.commit(&request.requests).await {
+                Ok(()) => {
                     this.bytes_sent.emit(ByteSize(
-                        request.body.len() + request.metadata.key.map(|x| x.len()).unwrap_or(0),
+                        request.requests.iter().map(KafkaRequest::wire_size).sum(),
                     ));
-                    Ok(KafkaResponse { event_byte_size })
+                    Ok(KafkaResponse {
+                        event_count: metadata.event_count(),
+                        event_byte_size: metadata.events_byte_size(),
+                    })
+                }
+                Err(error) => {
+                    emit!(KafkaTransactionError {
+                        error: error.clone()
+                    });
+                    this.recover(error.clone()).await;
+                    Err(error)
                 }
-                Err((kafka_err, _original_record)) => Err(kafka_err),
             }
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rdkafka::types::RDKafkaErrorCode;
+
+    use super::*;
+
+    #[test]
+    fn recovery_of_delivery_errors() {
+        assert_eq!(
+            Recovery::of(&KafkaError::MessageProduction(
+                RDKafkaErrorCode::MessageTimedOut
+            )),
+            Recovery::Abort
+        );
+        assert_eq!(Recovery::of(&KafkaError::Canceled), Recovery::Abort);
+        assert_eq!(
+            Recovery::of(&KafkaError::ClientCreation("test".into())),
+            Recovery::None
+        );
+    }
+}
@@ -11,17 +11,21 @@ use rdkafka::{
 use snafu::{ResultExt, Snafu};
 use tokio::time::Duration;
 use tower::limit::ConcurrencyLimit;
-use vector_core::config::log_schema;
+use vector_core::{config::log_schema, stream::BatcherSettings};
 
-use super::config::{KafkaRole, KafkaSinkConfig};
+use super::config::{KafkaRole, KafkaSinkConfig, KafkaTransactionConfig};
 use crate::{
     codecs::{Encoder, Transformer},
     event::{Event, LogEvent},
+    internal_events::KafkaTransactionError,
     kafka::KafkaStatisticsContext,
     sinks::{
         kafka::{
-            config::QUEUED_MIN_MESSAGES, request_builder::KafkaRequestBuilder,
-            service::KafkaService,
+            config::QUEUED_MIN_MESSAGES,
+            request_builder::KafkaRequestBuilder,
+            service::{
+                KafkaRequest, KafkaService, KafkaTransactionRequest, KafkaTransactionService,
+            },
         },
         util::{builder::SinkBuilderExt, StreamSink},
     },
@@ -39,10 +43,12 @@ pub(super) enum BuildError {
 pub struct KafkaSink {
     transformer: Transformer,
     encoder: Encoder<()>,
-    service: KafkaService,
+    producer: FutureProducer<KafkaStatisticsContext>,
     topic: Template,
     key_field: Option<String>,
+    partition_field: Option<String>,
     headers_key: Option<String>,
+    transaction: Option<(KafkaTransactionConfig, BatcherSettings)>,
 }
 
 pub(crate) fn create_producer(
@@ -61,22 +67,33 @@ impl KafkaSink {
         let transformer = config.encoding.transformer();
         let serializer = config.encoding.build()?;
         let encoder = Encoder::<()>::new(serializer);
+        // Transaction boundaries follow the batch settings, which otherwise only configure `librdkafka` itself.
+        let transaction = config
+            .transaction
+            .map(|transaction| {
+                config
+                    .batch
+                    .into_batcher_settings()
+                    .map(|settings| (transaction, settings))
+            })
+            .transpose()?;
 
         Ok(KafkaSink {
             headers_key: config.headers_key,
             transformer,
             encoder,
-            service: KafkaService::new(producer),
+            producer,
             topic: Template::try_from(config.topic).context(TopicTemplateSnafu)?,
             key_field: config.key_field,
+            partition_field: config.partition_field,
+            transaction,
         })
     }
 
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
-        // rdkafka will internally retry forever, so we need some limit to prevent this from overflowing
-        let service = ConcurrencyLimit::new(self.service, QUEUED_MIN_MESSAGES as usize);
         let mut request_builder = KafkaRequestBuilder {
             key_field: self.key_field,
+            partition_field: self.partition_field,
             headers_key: self.headers_key,
             topic_template: self.topic,
             transformer: self.transformer,
@@ -84,13 +101,41 @@ impl KafkaSink {
             log_schema: log_schema(),
         };
 
-        let sink = input
-            .filter_map(|event|
-                // request_builder is fallible but the places it can fail are emitting
-                // `Error` and `DroppedEvent` internal events appropriately so no need to here.
-                future::ready(request_builder.build_request(event)))
-            .into_driver(service);
-        sink.run().await
+        let requests = input.filter_map(|event|
+            // request_builder is fallible but the places it can fail are emitting
+            // `Error` and `DroppedEvent` internal events appropriately so no need to here.
+            future::ready(request_builder.build_request(event)));
+
+        match self.transaction {
+            None => {
+                // rdkafka will internally retry forever, so we need some limit to prevent this from overflowing
+                let service = ConcurrencyLimit::new(
+                    KafkaService::new(self.producer),
+                    QUEUED_MIN_MESSAGES as usize,
+                );
+                requests.into_driver(service).run().await
+            }
+            Some((transaction, batch_settings)) => {
+                let service = KafkaTransactionService::new(
+                    self.producer,
+                    Duration::from_millis(transaction.timeout_ms),
+                )
+                .await
+                .map_err(|error| emit!(KafkaTransactionError { error }))?;
+                // Transactions on a producer cannot overlap.
+                let service = ConcurrencyLimit::new(service, 1);
+
+                requests
+                    .batched(
+                        batch_settings
+                            .into_item_size_config(|request: &KafkaRequest| request.body.len()),
+                    )
+                    .map(KafkaTransactionRequest::from)
+                    .into_driver(service)
+                    .run()
+                    .await
+            }
+        }
     }
 }
 
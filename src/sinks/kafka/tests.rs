@@ -25,7 +25,7 @@ mod integration_test {
         kafka::{KafkaAuthConfig, KafkaCompression, KafkaSaslConfig},
         sinks::{
             kafka::{
-                config::{KafkaRole, KafkaSinkConfig},
+                config::{KafkaRole, KafkaSinkConfig, KafkaTransactionConfig},
                 sink::KafkaSink,
                 *,
             },
@@ -57,6 +57,7 @@ mod integration_test {
             bootstrap_servers: kafka_address(9091),
             topic: topic.clone(),
             key_field: None,
+            partition_field: None,
             encoding: TextSerializerConfig::new().into(),
             batch: BatchConfig::default(),
             compression: KafkaCompression::None,
@@ -65,6 +66,7 @@ mod integration_test {
             message_timeout_ms: 300000,
             librdkafka_options: HashMap::new(),
             headers_key: None,
+            transaction: None,
             acknowledgements: Default::default(),
         };
         self::sink::healthcheck(config).await.unwrap();
@@ -111,6 +113,7 @@ mod integration_test {
             compression: KafkaCompression::None,
             encoding: TextSerializerConfig::new().into(),
             key_field: None,
+            partition_field: None,
             auth: KafkaAuthConfig {
                 sasl: None,
                 tls: None,
@@ -120,6 +123,7 @@ mod integration_test {
             batch,
             librdkafka_options,
             headers_key: None,
+            transaction: None,
             acknowledgements: Default::default(),
         };
         config.clone().to_rdkafka(KafkaRole::Consumer)?;
@@ -243,6 +247,7 @@ mod integration_test {
             bootstrap_servers: server.clone(),
             topic: format!("{}-%Y%m%d", topic),
             key_field: None,
+            partition_field: None,
             encoding: TextSerializerConfig::new().into(),
             batch: BatchConfig::default(),
             compression,
@@ -251,6 +256,7 @@ mod integration_test {
             message_timeout_ms: 300000,
             librdkafka_options: HashMap::new(),
             headers_key: Some(headers_key.clone()),
+            transaction: None,
             acknowledgements: Default::default(),
         };
         let topic = format!("{}-{}", topic, chrono::Utc::now().format("%Y%m%d"));
@@ -339,4 +345,80 @@ mod integration_test {
         assert_eq!(out.len(), input.len());
         assert_eq!(out, input);
     }
+
+    #[tokio::test]
+    async fn kafka_transactional_partitioned() {
+        crate::test_util::trace_init();
+
+        let server = kafka_address(9091);
+        let topic = format!("test-{}", random_string(10));
+        let config = KafkaSinkConfig {
+            bootstrap_servers: server.clone(),
+            topic: topic.clone(),
+            key_field: None,
+            partition_field: Some("partition".to_string()),
+            encoding: TextSerializerConfig::new().into(),
+            batch: BatchConfig::default(),
+            compression: KafkaCompression::None,
+            auth: KafkaAuthConfig::default(),
+            socket_timeout_ms: 60000,
+            message_timeout_ms: 300000,
+            librdkafka_options: HashMap::new(),
+            headers_key: None,
+            transaction: Some(KafkaTransactionConfig {
+                transactional_id: format!("test-{}", random_string(10)),
+                timeout_ms: 60000,
+            }),
+            acknowledgements: Default::default(),
+        };
+
+        let num_events = 100;
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (input, events) = random_lines_with_stream(100, num_events, Some(batch));
+        let input_events = events.map(|mut events| {
+            events.iter_logs_mut().for_each(|log| {
+                log.insert("partition", 0);
+            });
+            events
+        });
+
+        assert_sink_compliance(&SINK_TAGS, async move {
+            let sink = KafkaSink::new(config).unwrap();
+            let sink = VectorSink::from_event_streamsink(sink);
+            sink.run(input_events).await
+        })
+        .await
+        .expect("Running sink failed");
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+        let mut client_config = rdkafka::ClientConfig::new();
+        client_config.set("bootstrap.servers", server.as_str());
+        client_config.set("group.id", &random_string(10));
+        client_config.set("isolation.level", "read_committed");
+
+        let mut tpl = TopicPartitionList::new();
+        tpl.add_partition(&topic, 0)
+            .set_offset(Offset::Beginning)
+            .unwrap();
+
+        let consumer: BaseConsumer = client_config.create().unwrap();
+        consumer.assign(&tpl).unwrap();
+
+        let mut failures = 0;
+        let mut out = Vec::new();
+        while failures < 100 && out.len() < input.len() {
+            match consumer.poll(Duration::from_secs(3)) {
+                Some(Ok(msg)) => {
+                    let s: &str = msg.payload_view().unwrap().unwrap();
+                    out.push(s.to_owned());
+                }
+                _ => {
+                    failures += 1;
+                    thread::sleep(Duration::from_millis(50));
+                }
+            }
+        }
+
+        assert_eq!(out, input);
+    }
 }
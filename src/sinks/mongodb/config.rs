@@ -0,0 +1,326 @@
+//! Configuration functionality for the `mongodb` sink.
+use std::{path::PathBuf, time::Duration};
+
+use futures::FutureExt;
+use mongodb::{
+    bson::doc,
+    options::{Acknowledgment, ClientOptions, Tls, TlsOptions, WriteConcern},
+    Client,
+};
+use snafu::ResultExt;
+use tower::ServiceBuilder;
+use vector_common::sensitive_string::SensitiveString;
+use vector_config::configurable_component;
+
+use super::{
+    encoder::MongoDbEncoder,
+    service::{MongoDbRetryLogic, MongoDbService},
+    sink::{MongoDbPartitioner, MongoDbSink},
+    BuildError, InvalidClientOptionsSnafu, InvalidEndpointSnafu,
+};
+use crate::{
+    codecs::Transformer,
+    config::{AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext},
+    sinks::{
+        util::{
+            BatchConfig, RealtimeSizeBasedDefaultBatchSettings, ServiceBuilderExt,
+            TowerRequestConfig,
+        },
+        Healthcheck, VectorSink,
+    },
+    template::Template,
+};
+
+/// Configuration for the `mongodb` sink.
+#[configurable_component(sink("mongodb"))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MongoDbSinkConfig {
+    /// The MongoDB deployment to connect to.
+    ///
+    /// Must be in the [Connection String URI Format](https://www.mongodb.com/docs/manual/reference/connection-string/).
+    pub endpoint: String,
+
+    /// The database to write events to.
+    ///
+    /// Events are batched separately for each database this renders to.
+    pub database: Template,
+
+    /// The collection to write events to.
+    ///
+    /// Events are batched separately for each collection this renders to.
+    pub collection: Template,
+
+    #[configurable(derived)]
+    pub upsert: Option<MongoDbUpsertConfig>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub write_concern: MongoDbWriteConcern,
+
+    #[configurable(derived)]
+    pub auth: Option<MongoDbAuth>,
+
+    #[configurable(derived)]
+    pub tls: Option<MongoDbTlsConfig>,
+
+    #[configurable(derived)]
+    #[serde(
+        default,
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub encoding: Transformer,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub batch: BatchConfig<RealtimeSizeBasedDefaultBatchSettings>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+
+    #[configurable(derived)]
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub acknowledgements: AcknowledgementsConfig,
+}
+
+/// Upsert documents by key, instead of inserting them.
+///
+/// Each event replaces the document with the same key, and is inserted if there is none.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MongoDbUpsertConfig {
+    /// The fields whose values identify a document.
+    ///
+    /// Missing fields match documents where the field is `null` or missing.
+    pub key: Vec<String>,
+}
+
+/// The write concern requested for every write.
+///
+/// Events are only acknowledged once their write has been acknowledged with this write concern.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MongoDbWriteConcern {
+    /// The acknowledgement requested for writes.
+    ///
+    /// Either `majority`, a number of nodes, or the name of a custom write concern. Defaults to the write concern of
+    /// the deployment.
+    pub w: Option<String>,
+
+    /// Whether writes must be written to the on-disk journal before being acknowledged.
+    pub journal: Option<bool>,
+
+    /// How long to wait for the requested acknowledgement, in seconds, before failing the write.
+    pub timeout_secs: Option<u64>,
+}
+
+/// Credentials used to authenticate to MongoDB.
+///
+/// These take precedence over any credentials in the endpoint.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MongoDbAuth {
+    /// The user to authenticate as.
+    pub user: String,
+
+    /// The password of the user.
+    pub password: SensitiveString,
+
+    /// The database the user is defined in.
+    ///
+    /// Defaults to the database of the endpoint, or `admin` if there is none.
+    pub source: Option<String>,
+}
+
+/// Configuration of TLS when connecting to MongoDB.
+///
+/// Enables TLS, even if it is not enabled by the endpoint.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MongoDbTlsConfig {
+    /// Absolute path to the CA certificate file used to verify the server certificate, in PEM format.
+    pub ca_file: Option<PathBuf>,
+
+    /// Absolute path to a file holding both the client certificate and its private key, in PEM format.
+    pub cert_key_file: Option<PathBuf>,
+
+    /// Whether the server certificate is verified.
+    ///
+    /// Do not disable this unless you understand the risks.
+    pub verify_certificate: Option<bool>,
+}
+
+impl GenerateConfig for MongoDbSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "mongodb://localhost:27017"
+            database = "vector"
+            collection = "events""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+impl SinkConfig for MongoDbSinkConfig {
+    async fn build(&self, _cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let upsert_key = match &self.upsert {
+            Some(upsert) if upsert.key.is_empty() => return Err(BuildError::EmptyUpsertKey.into()),
+            Some(upsert) => Some(upsert.key.clone()),
+            None => None,
+        };
+
+        let client = self.build_client().await?;
+        let healthcheck = healthcheck(client.clone()).boxed();
+
+        let request = self.request.unwrap_with(&TowerRequestConfig::default());
+        let service = ServiceBuilder::new()
+            .settings(request, MongoDbRetryLogic)
+            .service(MongoDbService::new(client));
+
+        let sink = MongoDbSink::new(
+            service,
+            MongoDbPartitioner::new(self.database.clone(), self.collection.clone()),
+            MongoDbEncoder::new(self.encoding.clone(), upsert_key),
+            self.batch.into_batcher_settings()?,
+        );
+
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn acknowledgements(&self) -> &AcknowledgementsConfig {
+        &self.acknowledgements
+    }
+}
+
+impl MongoDbSinkConfig {
+    async fn build_client(&self) -> crate::Result<Client> {
+        let mut options = ClientOptions::parse(&self.endpoint)
+            .await
+            .context(InvalidEndpointSnafu)?;
+
+        if let Some(auth) = &self.auth {
+            let mut credential = options.credential.take().unwrap_or_default();
+            credential.username = Some(auth.user.clone());
+            credential.password = Some(auth.password.inner().to_owned());
+            if auth.source.is_some() {
+                credential.source = auth.source.clone();
+            }
+            options.credential = Some(credential);
+        }
+
+        if let Some(tls) = &self.tls {
+            let mut tls_options = TlsOptions::default();
+            tls_options.ca_file_path = tls.ca_file.clone();
+            tls_options.cert_key_file_path = tls.cert_key_file.clone();
+            tls_options.allow_invalid_certificates = tls.verify_certificate.map(|verify| !verify);
+            options.tls = Some(Tls::Enabled(tls_options));
+        }
+
+        if let Some(write_concern) = self.write_concern.build()? {
+            options.write_concern = Some(write_concern);
+        }
+
+        Client::with_options(options)
+            .context(InvalidClientOptionsSnafu)
+            .map_err(Into::into)
+    }
+}
+
+impl MongoDbWriteConcern {
+    /// Builds the write concern, or `None` to use the default write concern of the deployment.
+    fn build(&self) -> crate::Result<Option<WriteConcern>> {
+        if self.w.is_none() && self.journal.is_none() && self.timeout_secs.is_none() {
+            return Ok(None);
+        }
+
+        let mut write_concern = WriteConcern::default();
+        write_concern.w = match self.w.as_deref().map(str::trim) {
+            None => None,
+            Some("0") => return Err(BuildError::UnacknowledgedWriteConcern.into()),
+            Some(w) => Some(match w.parse::<u32>() {
+                Ok(nodes) => Acknowledgment::Nodes(nodes),
+                Err(_) => Acknowledgment::from(w.to_owned()),
+            }),
+        };
+        write_concern.journal = self.journal;
+        write_concern.w_timeout = self.timeout_secs.map(Duration::from_secs);
+        Ok(Some(write_concern))
+    }
+}
+
+async fn healthcheck(client: Client) -> crate::Result<()> {
+    client
+        .database("admin")
+        .run_command(doc! { "ping": 1 }, None)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MongoDbSinkConfig>();
+    }
+
+    #[test]
+    fn write_concern() {
+        let write_concern = MongoDbWriteConcern {
+            w: Some("majority".into()),
+            journal: Some(true),
+            timeout_secs: Some(5),
+        }
+        .build()
+        .unwrap()
+        .unwrap();
+        assert_eq!(write_concern.w, Some(Acknowledgment::Majority));
+        assert_eq!(write_concern.journal, Some(true));
+        assert_eq!(write_concern.w_timeout, Some(Duration::from_secs(5)));
+
+        let write_concern = MongoDbWriteConcern {
+            w: Some("2".into()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap()
+        .unwrap();
+        assert_eq!(write_concern.w, Some(Acknowledgment::Nodes(2)));
+
+        assert!(MongoDbWriteConcern::default().build().unwrap().is_none());
+
+        assert!(MongoDbWriteConcern {
+            w: Some("0".into()),
+            ..Default::default()
+        }
+        .build()
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn upsert_requires_key() {
+        let config: MongoDbSinkConfig = toml::from_str(
+            r#"endpoint = "mongodb://localhost:27017"
+            database = "vector"
+            collection = "events"
+            upsert.key = []"#,
+        )
+        .unwrap();
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+}
@@ -0,0 +1,208 @@
+//! Encoding of batches of events into documents for the `mongodb` sink.
+use std::num::NonZeroUsize;
+
+use mongodb::bson::{self, oid::ObjectId, Bson, Document, RawDocumentBuf};
+
+use super::{
+    service::{MongoDbDocument, MongoDbRequest},
+    sink::MongoDbPartitionKey,
+};
+use crate::{
+    codecs::Transformer,
+    event::{Event, Finalizable, Value},
+    sinks::util::metadata::RequestMetadataBuilder,
+};
+
+#[derive(Clone, Debug)]
+pub(super) struct MongoDbEncoder {
+    transformer: Transformer,
+    upsert_key: Option<Vec<String>>,
+}
+
+impl MongoDbEncoder {
+    pub(super) const fn new(transformer: Transformer, upsert_key: Option<Vec<String>>) -> Self {
+        Self {
+            transformer,
+            upsert_key,
+        }
+    }
+
+    /// Builds the request for a batch of events, or `None` if there is nothing to write.
+    pub(super) fn build_request(
+        &self,
+        key: MongoDbPartitionKey,
+        mut events: Vec<Event>,
+    ) -> Option<MongoDbRequest> {
+        let metadata_builder = RequestMetadataBuilder::from_events(&events);
+        let finalizers = events.take_finalizers();
+
+        let documents = events
+            .into_iter()
+            .filter_map(|event| self.encode_event(event))
+            .collect::<Vec<_>>();
+        let request_size = documents
+            .iter()
+            .map(|document| document.document.as_bytes().len())
+            .sum();
+
+        Some(MongoDbRequest {
+            database: key.database,
+            collection: key.collection,
+            documents,
+            finalizers,
+            metadata: metadata_builder.with_request_size(NonZeroUsize::new(request_size)?),
+        })
+    }
+
+    fn encode_event(&self, mut event: Event) -> Option<MongoDbDocument> {
+        self.transformer.transform(&mut event);
+        let log = event.into_log();
+
+        let filter = self.upsert_key.as_ref().map(|key| {
+            key.iter()
+                .map(|field| {
+                    let value = log.get(field.as_str()).map_or(Bson::Null, to_bson);
+                    (field.clone(), value)
+                })
+                .collect::<Document>()
+        });
+
+        let mut document = match to_bson(log.value()) {
+            Bson::Document(document) => document,
+            _ => Document::new(),
+        };
+        // Inserted documents get their `_id` here rather than from the driver, so that a retried request inserts the
+        // same documents again, and the ones that were already inserted fail with a duplicate key error.
+        let generated_id = (filter.is_none() && !document.contains_key("_id")).then(ObjectId::new);
+        if let Some(id) = generated_id {
+            document.insert("_id", id);
+        }
+        match RawDocumentBuf::from_document(&document) {
+            Ok(document) => Some(MongoDbDocument {
+                document,
+                filter,
+                generated_id,
+            }),
+            Err(error) => {
+                error!(
+                    message = "Failed to encode event as a document.",
+                    %error,
+                    internal_log_rate_limit = true
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Converts a value to BSON, keeping the types that have a BSON equivalent.
+///
+/// Timestamps become BSON dates, which have a precision of a millisecond.
+fn to_bson(value: &Value) -> Bson {
+    match value {
+        Value::Bytes(_) | Value::Regex(_) => Bson::String(value.to_string_lossy().into_owned()),
+        Value::Integer(integer) => Bson::Int64(*integer),
+        Value::Float(float) => Bson::Double(float.into_inner()),
+        Value::Boolean(boolean) => Bson::Boolean(*boolean),
+        Value::Timestamp(timestamp) => {
+            Bson::DateTime(bson::DateTime::from_millis(timestamp.timestamp_millis()))
+        }
+        Value::Object(fields) => Bson::Document(
+            fields
+                .iter()
+                .map(|(key, value)| (key.clone(), to_bson(value)))
+                .collect(),
+        ),
+        Value::Array(items) => Bson::Array(items.iter().map(to_bson).collect()),
+        Value::Null => Bson::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use mongodb::bson::doc;
+
+    use super::*;
+    use crate::event::LogEvent;
+
+    fn key() -> MongoDbPartitionKey {
+        MongoDbPartitionKey {
+            database: "vector".into(),
+            collection: "events".into(),
+        }
+    }
+
+    fn event() -> Event {
+        let mut log = LogEvent::from("login failed");
+        log.insert("user.id", 42);
+        log.insert("user.name", "alice");
+        log.insert("score", 0.5);
+        log.insert("tags", vec!["auth", "alert"]);
+        log.insert(
+            "timestamp",
+            Utc.ymd(2022, 10, 11).and_hms_milli(12, 13, 14, 150),
+        );
+        log.into()
+    }
+
+    #[test]
+    fn converts_events_to_documents() {
+        let encoder = MongoDbEncoder::new(Default::default(), None);
+        let request = encoder.build_request(key(), vec![event()]).unwrap();
+
+        assert_eq!(request.database, "vector");
+        assert_eq!(request.collection, "events");
+        assert_eq!(request.documents.len(), 1);
+        assert!(request.documents[0].filter.is_none());
+
+        let mut document = request.documents[0].document.to_document().unwrap();
+        assert!(document.get_object_id("_id").is_ok());
+        document.remove("_id");
+        assert_eq!(
+            document,
+            doc! {
+                "message": "login failed",
+                "score": 0.5,
+                "tags": ["auth", "alert"],
+                "timestamp": bson::DateTime::from_millis(1_665_490_394_150),
+                "user": { "id": 42_i64, "name": "alice" },
+            }
+        );
+        assert_eq!(
+            request.metadata.request_encoded_size(),
+            request.documents[0].document.as_bytes().len()
+        );
+    }
+
+    #[test]
+    fn builds_upsert_filters() {
+        let encoder = MongoDbEncoder::new(
+            Default::default(),
+            Some(vec!["user.id".into(), "session".into()]),
+        );
+        let request = encoder.build_request(key(), vec![event()]).unwrap();
+
+        assert_eq!(
+            request.documents[0].filter,
+            Some(doc! { "user.id": 42_i64, "session": Bson::Null })
+        );
+        assert!(!request.documents[0]
+            .document
+            .to_document()
+            .unwrap()
+            .contains_key("_id"));
+    }
+
+    #[test]
+    fn keeps_existing_ids() {
+        let encoder = MongoDbEncoder::new(Default::default(), None);
+        let mut event = event();
+        event.as_mut_log().insert("_id", "login-42");
+        let request = encoder.build_request(key(), vec![event]).unwrap();
+
+        let document = request.documents[0].document.to_document().unwrap();
+        assert_eq!(document.get_str("_id"), Ok("login-42"));
+        assert_eq!(request.documents[0].generated_id, None);
+    }
+}
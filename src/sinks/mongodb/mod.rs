@@ -0,0 +1,28 @@
+//! `MongoDB` sink.
+//!
+//! Writes batches of events as documents of a collection, either with `insertMany` or, when an upsert key is
+//! configured, by replacing the document with the same key.
+mod config;
+mod encoder;
+mod service;
+mod sink;
+
+#[cfg(all(test, feature = "mongodb-integration-tests"))]
+mod integration_tests;
+
+pub use config::MongoDbSinkConfig;
+use snafu::Snafu;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("invalid endpoint: {}", source))]
+    InvalidEndpoint { source: mongodb::error::Error },
+    #[snafu(display("invalid client options: {}", source))]
+    InvalidClientOptions { source: mongodb::error::Error },
+    #[snafu(display("`upsert.key` must contain at least one field"))]
+    EmptyUpsertKey,
+    #[snafu(display(
+        "`write_concern.w` must request an acknowledgement, as events are only acknowledged once their write is"
+    ))]
+    UnacknowledgedWriteConcern,
+}
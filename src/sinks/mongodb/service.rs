@@ -0,0 +1,205 @@
+//! The `mongodb` sink service, which writes each request to a single collection.
+use std::task::{Context, Poll};
+
+use futures::future::BoxFuture;
+use mongodb::{
+    bson::{oid::ObjectId, Document, RawDocumentBuf},
+    error::{BulkWriteFailure, Error as MongoError, ErrorKind, RETRYABLE_WRITE_ERROR},
+    options::{InsertManyOptions, ReplaceOptions},
+    Client,
+};
+use tower::Service;
+use vector_common::request_metadata::{MetaDescriptive, RequestMetadata};
+use vector_core::{internal_event::CountByteSize, stream::DriverResponse};
+
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    sinks::util::retries::RetryLogic,
+};
+
+#[derive(Clone, Debug)]
+pub(super) struct MongoDbDocument {
+    pub(super) document: RawDocumentBuf,
+    /// The filter matching the document to replace, when upserting.
+    pub(super) filter: Option<Document>,
+    /// The `_id` given to the document by the sink, when inserting a document without one.
+    pub(super) generated_id: Option<ObjectId>,
+}
+
+#[derive(Clone)]
+pub(super) struct MongoDbRequest {
+    pub(super) database: String,
+    pub(super) collection: String,
+    pub(super) documents: Vec<MongoDbDocument>,
+    pub(super) finalizers: EventFinalizers,
+    pub(super) metadata: RequestMetadata,
+}
+
+impl Finalizable for MongoDbRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+impl MetaDescriptive for MongoDbRequest {
+    fn get_metadata(&self) -> RequestMetadata {
+        self.metadata
+    }
+}
+
+pub(super) struct MongoDbResponse {
+    events_sent: CountByteSize,
+    byte_size: usize,
+}
+
+impl DriverResponse for MongoDbResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> CountByteSize {
+        self.events_sent
+    }
+
+    fn bytes_sent(&self) -> Option<(usize, &str)> {
+        Some((self.byte_size, "mongodb"))
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct MongoDbService {
+    client: Client,
+}
+
+impl MongoDbService {
+    pub(super) const fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Service<MongoDbRequest> for MongoDbService {
+    type Response = MongoDbResponse;
+    type Error = MongoError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: MongoDbRequest) -> Self::Future {
+        let client = self.client.clone();
+
+        Box::pin(async move {
+            let metadata = request.get_metadata();
+            let collection = client
+                .database(&request.database)
+                .collection::<RawDocumentBuf>(&request.collection);
+
+            let (upserts, inserts): (Vec<_>, Vec<_>) = request
+                .documents
+                .into_iter()
+                .partition(|document| document.filter.is_some());
+
+            if !inserts.is_empty() {
+                // Documents are inserted unordered, so that the ones already inserted by an earlier attempt of this
+                // request do not stop the others from being inserted.
+                let options = InsertManyOptions::builder().ordered(false).build();
+                let generated_ids = inserts
+                    .iter()
+                    .map(|document| document.generated_id)
+                    .collect::<Vec<_>>();
+                let result = collection
+                    .insert_many(
+                        inserts.into_iter().map(|document| document.document),
+                        options,
+                    )
+                    .await;
+                if let Err(error) = result {
+                    if !is_reinsert_failure(&error, &generated_ids) {
+                        return Err(error);
+                    }
+                }
+            }
+            for document in upserts {
+                let filter = document.filter.expect("upserts have a filter");
+                let options = ReplaceOptions::builder().upsert(true).build();
+                collection
+                    .replace_one(filter, document.document, options)
+                    .await?;
+            }
+
+            Ok(MongoDbResponse {
+                events_sent: CountByteSize(metadata.event_count(), metadata.events_byte_size()),
+                byte_size: metadata.request_encoded_size(),
+            })
+        })
+    }
+}
+
+/// The code of the error returned when inserting a document whose `_id` already exists.
+const DUPLICATE_KEY: i32 = 11000;
+
+/// Whether the only failures of a bulk write are documents that already exist, which were inserted by an earlier
+/// attempt of the same request.
+///
+/// Only the `_id` values generated by the sink are known to come from an earlier attempt. Duplicates of an `_id` set
+/// by the event, or of another unique index, are failures.
+fn is_reinsert_failure(error: &MongoError, generated_ids: &[Option<ObjectId>]) -> bool {
+    match &*error.kind {
+        ErrorKind::BulkWrite(BulkWriteFailure {
+            write_errors: Some(write_errors),
+            write_concern_error: None,
+            ..
+        }) => write_errors.iter().all(|write_error| {
+            write_error.code == DUPLICATE_KEY
+                && generated_ids
+                    .get(write_error.index)
+                    .copied()
+                    .flatten()
+                    .map_or(false, |id| is_duplicate_id(&write_error.message, id))
+        }),
+        _ => false,
+    }
+}
+
+/// Whether a duplicate key error is about the `_id` index, for the given `_id`.
+fn is_duplicate_id(message: &str, id: ObjectId) -> bool {
+    message.contains("index: _id_ ") && message.contains(&id.to_hex())
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct MongoDbRetryLogic;
+
+impl RetryLogic for MongoDbRetryLogic {
+    type Error = MongoError;
+    type Response = MongoDbResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        error.contains_label(RETRYABLE_WRITE_ERROR)
+            || matches!(
+                *error.kind,
+                ErrorKind::Io(_) | ErrorKind::ServerSelection { .. }
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_ids() {
+        let id = ObjectId::new();
+        let message = |index: &str, id: ObjectId| {
+            format!(
+                "E11000 duplicate key error collection: test.events index: {} dup key: {{ _id: ObjectId('{}') }}",
+                index,
+                id.to_hex()
+            )
+        };
+
+        assert!(is_duplicate_id(&message("_id_", id), id));
+        assert!(!is_duplicate_id(&message("_id_", ObjectId::new()), id));
+        assert!(!is_duplicate_id(&message("login_1", id), id));
+    }
+}
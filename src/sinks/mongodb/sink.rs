@@ -0,0 +1,121 @@
+//! The `mongodb` sink, which batches events by database and collection before writing them.
+use std::fmt;
+
+use async_trait::async_trait;
+use futures::{future, stream::BoxStream, StreamExt};
+use tower::Service;
+use vector_core::{
+    partition::Partitioner,
+    stream::{BatcherSettings, DriverResponse},
+};
+
+use super::{encoder::MongoDbEncoder, service::MongoDbRequest};
+use crate::{
+    event::Event,
+    internal_events::TemplateRenderingError,
+    sinks::util::{builder::SinkBuilderExt, StreamSink},
+    template::Template,
+};
+
+/// The collection a batch of events is written to.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub(super) struct MongoDbPartitionKey {
+    pub(super) database: String,
+    pub(super) collection: String,
+}
+
+/// Partitions events by their rendered database and collection.
+pub(super) struct MongoDbPartitioner {
+    database: Template,
+    collection: Template,
+}
+
+impl MongoDbPartitioner {
+    pub(super) const fn new(database: Template, collection: Template) -> Self {
+        Self {
+            database,
+            collection,
+        }
+    }
+}
+
+impl Partitioner for MongoDbPartitioner {
+    type Item = Event;
+    type Key = Option<MongoDbPartitionKey>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        let render = |template: &Template, field| {
+            template
+                .render_string(item)
+                .map_err(|error| {
+                    emit!(TemplateRenderingError {
+                        error,
+                        field: Some(field),
+                        drop_event: true,
+                    });
+                })
+                .ok()
+        };
+
+        Some(MongoDbPartitionKey {
+            database: render(&self.database, "database")?,
+            collection: render(&self.collection, "collection")?,
+        })
+    }
+}
+
+pub(super) struct MongoDbSink<S> {
+    service: S,
+    partitioner: MongoDbPartitioner,
+    encoder: MongoDbEncoder,
+    batch_settings: BatcherSettings,
+}
+
+impl<S> MongoDbSink<S>
+where
+    S: Service<MongoDbRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    pub(super) const fn new(
+        service: S,
+        partitioner: MongoDbPartitioner,
+        encoder: MongoDbEncoder,
+        batch_settings: BatcherSettings,
+    ) -> Self {
+        Self {
+            service,
+            partitioner,
+            encoder,
+            batch_settings,
+        }
+    }
+
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let encoder = self.encoder;
+
+        input
+            .batched_partitioned(self.partitioner, self.batch_settings)
+            .filter_map(|(key, events)| {
+                // Events whose templates could not be rendered were already reported when partitioning.
+                future::ready(key.and_then(|key| encoder.build_request(key, events)))
+            })
+            .into_driver(self.service)
+            .run()
+            .await
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for MongoDbSink<S>
+where
+    S: Service<MongoDbRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -0,0 +1,300 @@
+//! Configuration functionality for the `postgres` sink.
+use std::{path::PathBuf, str::FromStr};
+
+use futures::FutureExt;
+use openssl::ssl::{SslConnector, SslMethod};
+use postgres_openssl::MakeTlsConnector;
+use snafu::ResultExt;
+use tokio_postgres::{Client, Config, NoTls};
+use tower::ServiceBuilder;
+use vector_config::configurable_component;
+
+use super::{
+    encoder::PostgresEncoder,
+    service::{
+        ConnectionFailedSnafu, PostgresError, PostgresRetryLogic, PostgresService, TlsFailedSnafu,
+    },
+    sink::PostgresSink,
+    BuildError, InvalidEndpointSnafu,
+};
+use crate::{
+    config::{AcknowledgementsConfig, DataType, GenerateConfig, Input, SinkConfig, SinkContext},
+    sinks::{
+        util::{
+            BatchConfig, Concurrency, RealtimeSizeBasedDefaultBatchSettings, ServiceBuilderExt,
+            TowerRequestConfig,
+        },
+        Healthcheck, VectorSink,
+    },
+};
+
+/// Configuration for the `postgres` sink.
+#[configurable_component(sink("postgres"))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PostgresSinkConfig {
+    /// The PostgreSQL server to connect to.
+    ///
+    /// Must be in the [Connection URI
+    /// format](https://www.postgresql.org/docs/current/libpq-connect.html#id-1.7.3.8.3.6).
+    pub endpoint: String,
+
+    /// The table to write events to.
+    ///
+    /// The name may be qualified with a schema, such as `audit.events`.
+    pub table: String,
+
+    /// The columns to write to.
+    ///
+    /// Each column is populated from the top-level event field of the same name, and is set to `NULL` if the event has
+    /// no such field. Columns that are not listed keep their default values.
+    ///
+    /// If empty, all columns of the table are written. Must be set when `mode` is `copy`.
+    #[serde(default)]
+    pub columns: Vec<String>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub mode: PostgresWriteMode,
+
+    #[configurable(derived)]
+    pub on_conflict: Option<OnConflictConfig>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub batch: BatchConfig<RealtimeSizeBasedDefaultBatchSettings>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+
+    #[configurable(derived)]
+    pub tls: Option<PostgresTlsConfig>,
+
+    #[configurable(derived)]
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    pub acknowledgements: AcknowledgementsConfig,
+}
+
+/// The statement used to write each batch of events.
+///
+/// Either way, each batch is written within a single transaction, and its events are only acknowledged once that
+/// transaction is committed.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PostgresWriteMode {
+    /// Write with a multi-row `INSERT` statement.
+    #[derivative(Default)]
+    Insert,
+
+    /// Write with `COPY ... FROM STDIN`.
+    ///
+    /// This is faster for large batches, but conflicts with existing rows cannot be handled.
+    Copy,
+}
+
+/// How rows conflicting with existing ones are handled.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OnConflictConfig {
+    /// The columns of the unique index or constraint on which conflicts are detected.
+    ///
+    /// If empty, conflicts on any unique index or constraint are handled. Must be set when `action` is `do_update`.
+    #[serde(default)]
+    pub target: Vec<String>,
+
+    #[configurable(derived)]
+    pub action: OnConflictAction,
+}
+
+/// The action taken on conflicting rows.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnConflictAction {
+    /// Skip the conflicting row, keeping the existing one.
+    DoNothing,
+
+    /// Update the existing row with the values of the conflicting one.
+    DoUpdate,
+}
+
+/// Configuration of TLS when connecting to PostgreSQL.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PostgresTlsConfig {
+    /// Absolute path to an additional CA certificate file.
+    ///
+    /// The certficate must be in the DER or PEM (X.509) format.
+    pub ca_file: PathBuf,
+}
+
+impl GenerateConfig for PostgresSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "postgres://postgres@localhost:5432/postgres"
+            table = "events"
+            columns = ["timestamp", "host", "message"]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+impl SinkConfig for PostgresSinkConfig {
+    async fn build(&self, _cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        match (self.mode, &self.on_conflict) {
+            (PostgresWriteMode::Copy, _) if self.columns.is_empty() => {
+                return Err(BuildError::CopyWithoutColumns.into())
+            }
+            (PostgresWriteMode::Copy, Some(_)) => return Err(BuildError::CopyWithOnConflict.into()),
+            (PostgresWriteMode::Insert, Some(on_conflict))
+                if on_conflict.action == OnConflictAction::DoUpdate
+                    && (self.columns.is_empty() || on_conflict.target.is_empty()) =>
+            {
+                return Err(BuildError::UpdateWithoutColumns.into())
+            }
+            _ => {}
+        }
+
+        let connector = PostgresConnector::new(&self.endpoint, self.tls.clone())?;
+        let encoder = PostgresEncoder {
+            columns: self.columns.clone(),
+            mode: self.mode,
+        };
+        let statement = encoder.statement(&self.table, self.on_conflict.as_ref());
+        let batch_settings = self.batch.into_batcher_settings()?;
+
+        let healthcheck = healthcheck(connector.clone()).boxed();
+        // Each concurrent request uses its own connection.
+        let request = self.request.unwrap_with(&TowerRequestConfig {
+            concurrency: Concurrency::Fixed(1),
+            ..Default::default()
+        });
+        let service = ServiceBuilder::new()
+            .settings(request, PostgresRetryLogic)
+            .service(PostgresService::new(connector, statement, self.mode));
+        let sink = PostgresSink::new(service, encoder, batch_settings);
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::new(DataType::Log | DataType::Metric)
+    }
+
+    fn acknowledgements(&self) -> &AcknowledgementsConfig {
+        &self.acknowledgements
+    }
+}
+
+/// Opens connections to the configured server.
+#[derive(Clone, Debug)]
+pub(super) struct PostgresConnector {
+    config: Config,
+    tls: Option<PostgresTlsConfig>,
+}
+
+impl PostgresConnector {
+    fn new(endpoint: &str, tls: Option<PostgresTlsConfig>) -> crate::Result<Self> {
+        let config = Config::from_str(endpoint).context(InvalidEndpointSnafu)?;
+        Ok(Self { config, tls })
+    }
+
+    pub(super) async fn connect(&self) -> Result<Client, PostgresError> {
+        let client = match &self.tls {
+            Some(tls) => {
+                let mut builder =
+                    SslConnector::builder(SslMethod::tls_client()).context(TlsFailedSnafu)?;
+                builder
+                    .set_ca_file(tls.ca_file.clone())
+                    .context(TlsFailedSnafu)?;
+                let connector = MakeTlsConnector::new(builder.build());
+
+                let (client, connection) = self
+                    .config
+                    .connect(connector)
+                    .await
+                    .context(ConnectionFailedSnafu)?;
+                tokio::spawn(connection);
+                client
+            }
+            None => {
+                let (client, connection) = self
+                    .config
+                    .connect(NoTls)
+                    .await
+                    .context(ConnectionFailedSnafu)?;
+                tokio::spawn(connection);
+                client
+            }
+        };
+        Ok(client)
+    }
+}
+
+async fn healthcheck(connector: PostgresConnector) -> crate::Result<()> {
+    trace!("Healthcheck started.");
+    let client = connector.connect().await?;
+    client.simple_query("SELECT 1").await?;
+    trace!("Healthcheck completed.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<PostgresSinkConfig>();
+    }
+
+    async fn build(config: &str) -> crate::Result<(VectorSink, Healthcheck)> {
+        let config: PostgresSinkConfig = toml::from_str(config).unwrap();
+        config.build(SinkContext::new_test()).await
+    }
+
+    #[tokio::test]
+    async fn copy_requires_columns() {
+        let result = build(
+            r#"endpoint = "postgres://localhost/postgres"
+            table = "events"
+            mode = "copy""#,
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn copy_rejects_on_conflict() {
+        let result = build(
+            r#"endpoint = "postgres://localhost/postgres"
+            table = "events"
+            columns = ["id"]
+            mode = "copy"
+            on_conflict.action = "do_nothing""#,
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn update_requires_target() {
+        let result = build(
+            r#"endpoint = "postgres://localhost/postgres"
+            table = "events"
+            columns = ["id", "message"]
+            on_conflict.action = "do_update""#,
+        )
+        .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,280 @@
+//! Encoding of batches of events into rows for the `postgres` sink.
+use std::{fmt::Write as _, num::NonZeroUsize};
+
+use bytes::Bytes;
+use serde_json::{Map, Value};
+
+use super::{
+    config::{OnConflictAction, OnConflictConfig, PostgresWriteMode},
+    service::PostgresRequest,
+};
+use crate::{
+    event::{Event, Finalizable},
+    sinks::util::metadata::RequestMetadataBuilder,
+};
+
+#[derive(Clone, Debug)]
+pub(super) struct PostgresEncoder {
+    pub(super) columns: Vec<String>,
+    pub(super) mode: PostgresWriteMode,
+}
+
+impl PostgresEncoder {
+    /// Builds the statement each batch is written with.
+    ///
+    /// `INSERT` statements take the batch as a single JSON array parameter, which `json_populate_recordset` expands
+    /// into rows typed after the table, so that values are converted to the column types by the server.
+    pub(super) fn statement(&self, table: &str, on_conflict: Option<&OnConflictConfig>) -> String {
+        let table = quote_table(table);
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| quote_identifier(column))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match self.mode {
+            PostgresWriteMode::Copy => {
+                format!("COPY {} ({}) FROM STDIN WITH (FORMAT csv)", table, columns)
+            }
+            PostgresWriteMode::Insert => {
+                let mut statement = if self.columns.is_empty() {
+                    format!(
+                        "INSERT INTO {table} SELECT * FROM json_populate_recordset(NULL::{table}, $1::text::json)",
+                        table = table
+                    )
+                } else {
+                    format!(
+                        "INSERT INTO {table} ({columns}) SELECT {columns} FROM json_populate_recordset(NULL::{table}, $1::text::json)",
+                        table = table,
+                        columns = columns
+                    )
+                };
+
+                if let Some(on_conflict) = on_conflict {
+                    statement.push_str(" ON CONFLICT");
+                    if !on_conflict.target.is_empty() {
+                        let target = on_conflict
+                            .target
+                            .iter()
+                            .map(|column| quote_identifier(column))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        write!(statement, " ({})", target).expect("writing to a String");
+                    }
+                    match on_conflict.action {
+                        OnConflictAction::DoNothing => statement.push_str(" DO NOTHING"),
+                        OnConflictAction::DoUpdate => {
+                            let updates = self
+                                .columns
+                                .iter()
+                                .map(|column| {
+                                    let column = quote_identifier(column);
+                                    format!("{} = EXCLUDED.{}", column, column)
+                                })
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            write!(statement, " DO UPDATE SET {}", updates)
+                                .expect("writing to a String");
+                        }
+                    }
+                }
+
+                statement
+            }
+        }
+    }
+
+    /// Builds the request for a batch of events, or `None` if there is nothing to write.
+    pub(super) fn build_request(&self, mut events: Vec<Event>) -> Option<PostgresRequest> {
+        let metadata_builder = RequestMetadataBuilder::from_events(&events);
+        let finalizers = events.take_finalizers();
+
+        let rows = events.into_iter().filter_map(|event| self.row(event));
+        let payload = match self.mode {
+            PostgresWriteMode::Insert => {
+                let rows = rows.map(Value::Object).collect::<Vec<_>>();
+                Bytes::from(serde_json::to_vec(&rows).expect("JSON values always serialize"))
+            }
+            PostgresWriteMode::Copy => {
+                let mut csv = String::new();
+                for row in rows {
+                    self.write_csv_row(&mut csv, &row);
+                }
+                Bytes::from(csv)
+            }
+        };
+
+        let request_size = NonZeroUsize::new(payload.len())?;
+        Some(PostgresRequest {
+            payload,
+            finalizers,
+            metadata: metadata_builder.with_request_size(request_size),
+        })
+    }
+
+    /// Converts an event into a row, keyed by column name.
+    fn row(&self, event: Event) -> Option<Map<String, Value>> {
+        let value = match event {
+            Event::Log(log) => serde_json::to_value(&log),
+            Event::Metric(metric) => serde_json::to_value(&metric),
+            Event::Trace(trace) => serde_json::to_value(&trace),
+        };
+
+        match value {
+            Ok(Value::Object(mut fields)) if !self.columns.is_empty() => Some(
+                self.columns
+                    .iter()
+                    .map(|column| {
+                        let value = fields.remove(column).unwrap_or(Value::Null);
+                        (column.clone(), value)
+                    })
+                    .collect(),
+            ),
+            Ok(Value::Object(fields)) => Some(fields),
+            Ok(_) => None,
+            Err(error) => {
+                error!(
+                    message = "Failed to serialize event.",
+                    %error,
+                    internal_log_rate_limit = true
+                );
+                None
+            }
+        }
+    }
+
+    fn write_csv_row(&self, csv: &mut String, row: &Map<String, Value>) {
+        for (i, column) in self.columns.iter().enumerate() {
+            if i > 0 {
+                csv.push(',');
+            }
+            match row.get(column) {
+                // An unquoted empty value is `NULL` in the CSV format.
+                None | Some(Value::Null) => {}
+                Some(Value::Bool(value)) => write!(csv, "{}", value).expect("writing to a String"),
+                Some(Value::Number(value)) => {
+                    write!(csv, "{}", value).expect("writing to a String")
+                }
+                Some(Value::String(value)) => write_csv_quoted(csv, value),
+                Some(value) => write_csv_quoted(csv, &value.to_string()),
+            }
+        }
+        csv.push('\n');
+    }
+}
+
+fn write_csv_quoted(csv: &mut String, value: &str) {
+    csv.push('"');
+    csv.push_str(&value.replace('"', "\"\""));
+    csv.push('"');
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Quotes each part of a possibly schema-qualified table name.
+fn quote_table(table: &str) -> String {
+    table
+        .split('.')
+        .map(quote_identifier)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+
+    fn encoder(mode: PostgresWriteMode, columns: &[&str]) -> PostgresEncoder {
+        PostgresEncoder {
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            mode,
+        }
+    }
+
+    #[test]
+    fn insert_statement() {
+        let encoder = encoder(PostgresWriteMode::Insert, &["id", "message"]);
+        assert_eq!(
+            encoder.statement("audit.events", None),
+            r#"INSERT INTO "audit"."events" ("id", "message") SELECT "id", "message" FROM json_populate_recordset(NULL::"audit"."events", $1::text::json)"#
+        );
+    }
+
+    #[test]
+    fn insert_statement_all_columns() {
+        let encoder = encoder(PostgresWriteMode::Insert, &[]);
+        assert_eq!(
+            encoder.statement("events", None),
+            r#"INSERT INTO "events" SELECT * FROM json_populate_recordset(NULL::"events", $1::text::json)"#
+        );
+    }
+
+    #[test]
+    fn insert_statement_on_conflict() {
+        let encoder = encoder(PostgresWriteMode::Insert, &["id", "message"]);
+        let on_conflict = OnConflictConfig {
+            target: vec!["id".into()],
+            action: OnConflictAction::DoUpdate,
+        };
+        assert!(encoder
+            .statement("events", Some(&on_conflict))
+            .ends_with(r#" ON CONFLICT ("id") DO UPDATE SET "id" = EXCLUDED."id", "message" = EXCLUDED."message""#));
+
+        let on_conflict = OnConflictConfig {
+            target: vec![],
+            action: OnConflictAction::DoNothing,
+        };
+        assert!(encoder
+            .statement("events", Some(&on_conflict))
+            .ends_with(" ON CONFLICT DO NOTHING"));
+    }
+
+    #[test]
+    fn copy_statement() {
+        let encoder = encoder(PostgresWriteMode::Copy, &["id", "weird\"name"]);
+        assert_eq!(
+            encoder.statement("events", None),
+            r#"COPY "events" ("id", "weird""name") FROM STDIN WITH (FORMAT csv)"#
+        );
+    }
+
+    fn event(id: i64, message: &str) -> Event {
+        let mut log = LogEvent::from(message);
+        log.insert("id", id);
+        log.insert("extra", "ignored");
+        log.into()
+    }
+
+    #[test]
+    fn encodes_insert_rows() {
+        let encoder = encoder(PostgresWriteMode::Insert, &["id", "message", "missing"]);
+        let request = encoder
+            .build_request(vec![event(1, "first"), event(2, "second")])
+            .unwrap();
+
+        assert_eq!(
+            request.payload,
+            Bytes::from(
+                r#"[{"id":1,"message":"first","missing":null},{"id":2,"message":"second","missing":null}]"#
+            )
+        );
+        assert_eq!(request.metadata.event_count(), 2);
+    }
+
+    #[test]
+    fn encodes_copy_rows() {
+        let encoder = encoder(PostgresWriteMode::Copy, &["id", "message", "missing"]);
+        let request = encoder
+            .build_request(vec![event(1, "say \"hi\""), event(2, "a,b")])
+            .unwrap();
+
+        assert_eq!(
+            request.payload,
+            Bytes::from("1,\"say \"\"hi\"\"\",\n2,\"a,b\",\n")
+        );
+    }
+}
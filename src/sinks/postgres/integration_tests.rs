@@ -0,0 +1,196 @@
+use futures::{stream, StreamExt};
+use tokio_postgres::NoTls;
+use vector_core::event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event, LogEvent};
+
+use super::*;
+use crate::{
+    config::{SinkConfig, SinkContext},
+    test_util::{
+        components::{run_and_assert_sink_compliance, SINK_TAGS},
+        random_string, trace_init,
+    },
+};
+
+fn pg_host() -> String {
+    std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".into())
+}
+
+fn pg_url() -> String {
+    std::env::var("PG_URL")
+        .unwrap_or_else(|_| format!("postgres://vector:vector@{}/postgres", pg_host()))
+}
+
+async fn connect() -> tokio_postgres::Client {
+    let (client, connection) = tokio_postgres::connect(&pg_url(), NoTls).await.unwrap();
+    tokio::spawn(connection);
+    client
+}
+
+async fn create_table(client: &tokio_postgres::Client) -> String {
+    let table = format!("test_{}", random_string(10).to_lowercase());
+    client
+        .execute(
+            &format!(
+                "CREATE TABLE {} (id BIGINT PRIMARY KEY, message TEXT, timestamp TIMESTAMPTZ, tags JSONB)",
+                table
+            ),
+            &[],
+        )
+        .await
+        .unwrap();
+    table
+}
+
+fn make_events(ids: std::ops::Range<i64>) -> (Vec<Event>, BatchStatusReceiver) {
+    let (batch, receiver) = BatchNotifier::new_with_receiver();
+    let events = ids
+        .map(|id| {
+            let mut log = LogEvent::from(format!("message {}", id)).with_batch_notifier(&batch);
+            log.insert("id", id);
+            log.insert("tags.source", "test");
+            Event::from(log)
+        })
+        .collect();
+    (events, receiver)
+}
+
+async fn run(config: &str, events: Vec<Event>) {
+    let config: PostgresSinkConfig = toml::from_str(config).unwrap();
+    let (sink, healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+    healthcheck.await.unwrap();
+    run_and_assert_sink_compliance(sink, stream::iter(events), &SINK_TAGS).await;
+}
+
+async fn insert_and_count(mode: &str) {
+    trace_init();
+
+    let client = connect().await;
+    let table = create_table(&client).await;
+    let (events, mut receiver) = make_events(0..10);
+
+    run(
+        &format!(
+            r#"endpoint = "{}"
+            table = "{}"
+            columns = ["id", "message", "timestamp", "tags"]
+            mode = "{}""#,
+            pg_url(),
+            table,
+            mode
+        ),
+        events,
+    )
+    .await;
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+
+    let rows = client
+        .query(
+            &format!(
+                "SELECT id, message, tags->>'source' FROM {} ORDER BY id",
+                table
+            ),
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 10);
+    for (id, row) in rows.iter().enumerate() {
+        assert_eq!(row.get::<_, i64>(0), id as i64);
+        assert_eq!(row.get::<_, &str>(1), format!("message {}", id));
+        assert_eq!(row.get::<_, &str>(2), "test");
+    }
+}
+
+#[tokio::test]
+async fn insert_rows() {
+    insert_and_count("insert").await;
+}
+
+#[tokio::test]
+async fn copy_rows() {
+    insert_and_count("copy").await;
+}
+
+#[tokio::test]
+async fn insert_on_conflict_do_update() {
+    trace_init();
+
+    let client = connect().await;
+    let table = create_table(&client).await;
+    let config = format!(
+        r#"endpoint = "{}"
+        table = "{}"
+        columns = ["id", "message"]
+        on_conflict.target = ["id"]
+        on_conflict.action = "do_update""#,
+        pg_url(),
+        table
+    );
+
+    let (events, _) = make_events(0..5);
+    run(&config, events).await;
+
+    let (mut events, _) = make_events(3..8);
+    for event in &mut events {
+        event.as_mut_log().insert("message", "updated");
+    }
+    run(&config, events).await;
+
+    let rows = client
+        .query(&format!("SELECT message FROM {} ORDER BY id", table), &[])
+        .await
+        .unwrap();
+    let messages = rows
+        .iter()
+        .map(|row| row.get::<_, String>(0))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "message 0",
+            "message 1",
+            "message 2",
+            "updated",
+            "updated",
+            "updated",
+            "updated",
+            "updated"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn failed_transaction_is_rejected() {
+    trace_init();
+
+    let client = connect().await;
+    let table = create_table(&client).await;
+    let (events, mut receiver) = make_events(0..3);
+
+    // A primary key conflict without `on_conflict` fails the whole batch.
+    client
+        .execute(&format!("INSERT INTO {} (id) VALUES (0)", table), &[])
+        .await
+        .unwrap();
+
+    let config: PostgresSinkConfig = toml::from_str(&format!(
+        r#"endpoint = "{}"
+        table = "{}"
+        columns = ["id", "message"]"#,
+        pg_url(),
+        table
+    ))
+    .unwrap();
+    let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+    sink.run(stream::iter(events).map(Into::into))
+        .await
+        .unwrap();
+
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+    let count: i64 = client
+        .query_one(&format!("SELECT count(*) FROM {}", table), &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(count, 1);
+}
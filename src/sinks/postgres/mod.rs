@@ -0,0 +1,28 @@
+//! `PostgreSQL` sink.
+//!
+//! Writes batches of events as rows of a table, with either multi-row `INSERT` statements or `COPY`. Since only
+//! standard statements are used, this also works with extensions such as TimescaleDB.
+mod config;
+mod encoder;
+mod service;
+mod sink;
+
+#[cfg(all(test, feature = "postgres-integration-tests"))]
+mod integration_tests;
+
+pub use config::PostgresSinkConfig;
+use snafu::Snafu;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("invalid endpoint: {}", source))]
+    InvalidEndpoint { source: tokio_postgres::Error },
+    #[snafu(display("`columns` must be set when `mode` is `copy`"))]
+    CopyWithoutColumns,
+    #[snafu(display("`on_conflict` cannot be used when `mode` is `copy`"))]
+    CopyWithOnConflict,
+    #[snafu(display(
+        "`on_conflict.action = \"do_update\"` requires both `columns` and `on_conflict.target` to be set"
+    ))]
+    UpdateWithoutColumns,
+}
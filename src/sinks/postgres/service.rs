@@ -0,0 +1,199 @@
+//! The `postgres` sink service, which writes each request within a transaction.
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::{future::BoxFuture, pin_mut, SinkExt};
+use openssl::error::ErrorStack;
+use snafu::{ResultExt, Snafu};
+use tokio_postgres::{Client, Error as PgError};
+use tower::Service;
+use vector_common::request_metadata::{MetaDescriptive, RequestMetadata};
+use vector_core::{internal_event::CountByteSize, stream::DriverResponse};
+
+use super::config::{PostgresConnector, PostgresWriteMode};
+use crate::{
+    event::{EventFinalizers, EventStatus, Finalizable},
+    sinks::util::retries::RetryLogic,
+};
+
+#[derive(Debug, Snafu)]
+pub(super) enum PostgresError {
+    #[snafu(display("failed to create tls connector: {}", source))]
+    TlsFailed { source: ErrorStack },
+    #[snafu(display("failed to connect: {}", source))]
+    ConnectionFailed { source: PgError },
+    #[snafu(display("failed to write rows: {}", source))]
+    WriteFailed { source: PgError },
+}
+
+impl PostgresError {
+    /// Whether the error comes from the connection rather than from the rows written, in which case the connection is
+    /// discarded and the request can be attempted again.
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::TlsFailed { .. } => false,
+            Self::ConnectionFailed { source } | Self::WriteFailed { source } => {
+                match source.code() {
+                    // Class 08 is connection exceptions, and class 40 transaction rollbacks, such as serialization
+                    // failures and deadlocks.
+                    Some(state) => state.code().starts_with("08") || state.code().starts_with("40"),
+                    // Errors without a SQLSTATE come from the client, for example when the connection is lost.
+                    None => true,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct PostgresRequest {
+    pub(super) payload: Bytes,
+    pub(super) finalizers: EventFinalizers,
+    pub(super) metadata: RequestMetadata,
+}
+
+impl Finalizable for PostgresRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+impl MetaDescriptive for PostgresRequest {
+    fn get_metadata(&self) -> RequestMetadata {
+        self.metadata
+    }
+}
+
+pub(super) struct PostgresResponse {
+    events_sent: CountByteSize,
+    byte_size: usize,
+}
+
+impl DriverResponse for PostgresResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> CountByteSize {
+        self.events_sent
+    }
+
+    fn bytes_sent(&self) -> Option<(usize, &str)> {
+        Some((self.byte_size, "postgres"))
+    }
+}
+
+/// Writes each request over a connection taken from a set of idle ones, or a new one if there is none.
+///
+/// A connection is only made idle again once its request succeeded, or failed because of the rows written. Connections
+/// that failed, or whose request was dropped, for example after a timeout, are closed.
+#[derive(Clone)]
+pub(super) struct PostgresService {
+    connector: PostgresConnector,
+    idle: Arc<Mutex<Vec<Client>>>,
+    statement: Arc<str>,
+    mode: PostgresWriteMode,
+}
+
+impl PostgresService {
+    pub(super) fn new(
+        connector: PostgresConnector,
+        statement: String,
+        mode: PostgresWriteMode,
+    ) -> Self {
+        Self {
+            connector,
+            idle: Arc::new(Mutex::new(Vec::new())),
+            statement: statement.into(),
+            mode,
+        }
+    }
+
+    async fn connection(&self) -> Result<Client, PostgresError> {
+        loop {
+            let client = self
+                .idle
+                .lock()
+                .expect("postgres connections lock poisoned")
+                .pop();
+            match client {
+                Some(client) if client.is_closed() => continue,
+                Some(client) => return Ok(client),
+                None => return self.connector.connect().await,
+            }
+        }
+    }
+
+    fn release(&self, client: Client) {
+        self.idle
+            .lock()
+            .expect("postgres connections lock poisoned")
+            .push(client);
+    }
+
+    async fn write(&self, client: &mut Client, payload: Bytes) -> Result<(), PgError> {
+        let transaction = client.transaction().await?;
+        match self.mode {
+            PostgresWriteMode::Insert => {
+                let rows = std::str::from_utf8(&payload).expect("JSON is always valid UTF-8");
+                transaction.execute(&*self.statement, &[&rows]).await?;
+            }
+            PostgresWriteMode::Copy => {
+                let sink = transaction.copy_in(&*self.statement).await?;
+                pin_mut!(sink);
+                sink.send(payload).await?;
+                sink.finish().await?;
+            }
+        }
+        transaction.commit().await
+    }
+}
+
+impl Service<PostgresRequest> for PostgresService {
+    type Response = PostgresResponse;
+    type Error = PostgresError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: PostgresRequest) -> Self::Future {
+        let this = self.clone();
+
+        Box::pin(async move {
+            let metadata = request.get_metadata();
+
+            let mut client = this.connection().await?;
+            let result = this
+                .write(&mut client, request.payload)
+                .await
+                .context(WriteFailedSnafu);
+            match &result {
+                Err(error) if error.is_transient() => drop(client),
+                _ => this.release(client),
+            }
+            result?;
+
+            Ok(PostgresResponse {
+                events_sent: CountByteSize(metadata.event_count(), metadata.events_byte_size()),
+                byte_size: metadata.request_encoded_size(),
+            })
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct PostgresRetryLogic;
+
+impl RetryLogic for PostgresRetryLogic {
+    type Error = PostgresError;
+    type Response = PostgresResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        error.is_transient()
+    }
+}
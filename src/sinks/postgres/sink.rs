@@ -0,0 +1,63 @@
+//! The `postgres` sink, which batches events and writes each batch within a transaction.
+use std::fmt;
+
+use async_trait::async_trait;
+use futures::{future, stream::BoxStream, StreamExt};
+use tower::Service;
+use vector_core::stream::{BatcherSettings, DriverResponse};
+
+use super::{encoder::PostgresEncoder, service::PostgresRequest};
+use crate::{
+    event::Event,
+    sinks::util::{builder::SinkBuilderExt, StreamSink},
+};
+
+pub(super) struct PostgresSink<S> {
+    service: S,
+    encoder: PostgresEncoder,
+    batch_settings: BatcherSettings,
+}
+
+impl<S> PostgresSink<S>
+where
+    S: Service<PostgresRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    pub(super) const fn new(
+        service: S,
+        encoder: PostgresEncoder,
+        batch_settings: BatcherSettings,
+    ) -> Self {
+        Self {
+            service,
+            encoder,
+            batch_settings,
+        }
+    }
+
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let encoder = self.encoder;
+
+        input
+            .batched(self.batch_settings.into_byte_size_config())
+            .filter_map(|events| future::ready(encoder.build_request(events)))
+            .into_driver(self.service)
+            .run()
+            .await
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for PostgresSink<S>
+where
+    S: Service<PostgresRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -1,7 +1,8 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     io::Cursor,
     sync::Arc,
+    time::Duration,
 };
 
 use async_stream::stream;
@@ -14,9 +15,10 @@ use codecs::{
 use futures::{Stream, StreamExt};
 use once_cell::sync::OnceCell;
 use rdkafka::{
-    consumer::{Consumer, ConsumerContext, Rebalance, StreamConsumer},
+    consumer::{CommitMode, Consumer, ConsumerContext, Rebalance, StreamConsumer},
+    error::KafkaError,
     message::{BorrowedMessage, Headers as _, Message},
-    ClientConfig, ClientContext, Statistics,
+    ClientConfig, ClientContext, Offset, Statistics, TopicPartitionList,
 };
 use snafu::{ResultExt, Snafu};
 use tokio_util::codec::FramedRead;
@@ -48,6 +50,129 @@ enum BuildError {
     KafkaCreateError { source: rdkafka::error::KafkaError },
     #[snafu(display("Could not subscribe to Kafka topics: {}", source))]
     KafkaSubscribeError { source: rdkafka::error::KafkaError },
+    #[snafu(display("Could not fetch metadata for Kafka topic {:?}: {}", topic, source))]
+    KafkaMetadataError {
+        source: rdkafka::error::KafkaError,
+        topic: String,
+    },
+    #[snafu(display("Could not assign Kafka partitions: {}", source))]
+    KafkaAssignError { source: rdkafka::error::KafkaError },
+    #[snafu(display("Could not look up Kafka offsets: {}", source))]
+    KafkaOffsetLookupError { source: rdkafka::error::KafkaError },
+    #[snafu(display("`group_id` must be set unless `partitions` is configured"))]
+    MissingGroupId,
+    #[snafu(display("`start_at` and `stop_at` can only be used together with `partitions`"))]
+    PositionWithoutPartitions,
+}
+
+/// A set of partitions of a Kafka topic.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct KafkaPartitionAssignment {
+    /// The topic to read events from.
+    topic: String,
+
+    /// The partitions of the topic to read events from.
+    ///
+    /// If empty, all partitions of the topic, as reported by the cluster when the source starts, are read.
+    #[serde(default)]
+    partitions: Vec<i32>,
+}
+
+/// An offset within a single Kafka topic partition.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct KafkaPartitionOffset {
+    /// The topic of the partition.
+    topic: String,
+
+    /// The partition number.
+    partition: i32,
+
+    /// The offset within the partition.
+    offset: i64,
+}
+
+/// The position from which explicitly assigned partitions are read.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum KafkaStartPosition {
+    /// Read from the earliest offset still available in each partition.
+    Beginning,
+
+    /// Read only the messages produced after the source starts.
+    End,
+
+    /// Read from the offsets committed for `group_id`, falling back to `auto_offset_reset`.
+    #[derivative(Default)]
+    Stored,
+
+    /// Read from the first message whose timestamp is at or after the given time.
+    Timestamp {
+        /// The time to start reading from.
+        timestamp: DateTime<Utc>,
+    },
+
+    /// Read from explicit offsets.
+    ///
+    /// Partitions without an entry are read from the offsets committed for `group_id`.
+    Offsets {
+        /// The offset to start reading from, for each partition.
+        offsets: Vec<KafkaPartitionOffset>,
+    },
+}
+
+impl KafkaStartPosition {
+    fn offset_for(&self, topic: &str, partition: i32) -> Offset {
+        match self {
+            Self::Beginning => Offset::Beginning,
+            Self::End => Offset::End,
+            Self::Stored | Self::Timestamp { .. } => Offset::Stored,
+            Self::Offsets { offsets } => {
+                find_offset(offsets, topic, partition).map_or(Offset::Stored, Offset::Offset)
+            }
+        }
+    }
+}
+
+/// The position at which explicitly assigned partitions stop being read.
+///
+/// Stop positions are exclusive: the message at the stop offset itself is not read. Once every assigned partition
+/// has reached its stop position, and all events read have been acknowledged, the source finishes.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum KafkaStopPosition {
+    /// Stop at the end of each partition, as observed when the source starts.
+    End,
+
+    /// Stop at the first message whose timestamp is at or after the given time.
+    ///
+    /// Partitions without such a message when the source starts stop at their end, as observed at that time.
+    Timestamp {
+        /// The time to stop reading at.
+        timestamp: DateTime<Utc>,
+    },
+
+    /// Stop at explicit offsets.
+    ///
+    /// Partitions without an entry are read indefinitely, so the source only finishes if every assigned partition
+    /// has one.
+    Offsets {
+        /// The offset to stop reading at, for each partition.
+        offsets: Vec<KafkaPartitionOffset>,
+    },
+}
+
+fn find_offset(offsets: &[KafkaPartitionOffset], topic: &str, partition: i32) -> Option<i64> {
+    offsets
+        .iter()
+        .find(|offset| offset.topic == topic && offset.partition == partition)
+        .map(|offset| offset.offset)
 }
 
 /// Configuration for the `kafka` source.
@@ -67,11 +192,33 @@ pub struct KafkaSourceConfig {
     /// The Kafka topics names to read events from.
     ///
     /// Regular expression syntax is supported if the topic begins with `^`.
+    ///
+    /// Ignored if `partitions` is set.
+    #[serde(default)]
     topics: Vec<String>,
 
     /// The consumer group name to be used to consume events from Kafka.
+    ///
+    /// Required unless `partitions` is set. With explicitly assigned partitions, the group is only used to store and
+    /// commit offsets, and no offsets are committed if it is left empty.
+    #[serde(default)]
     group_id: String,
 
+    /// Topic partitions to read events from, assigned explicitly instead of through consumer group subscription.
+    ///
+    /// When set, `topics` is ignored and the source never takes part in a rebalance.
+    partitions: Option<Vec<KafkaPartitionAssignment>>,
+
+    /// Where to start reading explicitly assigned `partitions`.
+    ///
+    /// By default, reading starts from the offsets committed for `group_id`.
+    start_at: Option<KafkaStartPosition>,
+
+    /// Where to stop reading explicitly assigned `partitions`.
+    ///
+    /// By default, partitions are read indefinitely.
+    stop_at: Option<KafkaStopPosition>,
+
     /// If offsets for consumer group do not exist, set them using this strategy.
     ///
     /// See the [librdkafka documentation](https://github.com/edenhill/librdkafka/blob/master/CONFIGURATION.md) for the `auto.offset.reset` option for further clarification.
@@ -202,7 +349,7 @@ impl_generate_config_from_default!(KafkaSourceConfig);
 #[async_trait::async_trait]
 impl SourceConfig for KafkaSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
-        let consumer = create_consumer(self)?;
+        let (consumer, stop_offsets) = create_consumer(self)?;
         let decoder = DecodingConfig::new(
             self.framing.clone(),
             self.decoding.clone(),
@@ -214,6 +361,7 @@ impl SourceConfig for KafkaSourceConfig {
         Ok(Box::pin(kafka_source(
             self.clone(),
             consumer,
+            stop_offsets,
             decoder,
             cx.shutdown,
             cx.out,
@@ -233,6 +381,7 @@ impl SourceConfig for KafkaSourceConfig {
 async fn kafka_source(
     config: KafkaSourceConfig,
     consumer: StreamConsumer<CustomContext>,
+    mut stop_offsets: Option<StopOffsets>,
     decoder: Decoder,
     mut shutdown: ShutdownSignal,
     mut out: SourceSender,
@@ -252,23 +401,35 @@ async fn kafka_source(
 
     let mut stream = consumer.stream();
     let keys = Keys::from(log_schema(), &config);
+    let mut pending_acks = 0_usize;
 
     loop {
+        if stop_offsets
+            .as_ref()
+            .map_or(false, StopOffsets::is_finished)
+        {
+            break;
+        }
+
         tokio::select! {
             _ = &mut shutdown => break,
             entry = ack_stream.next() => if let Some((status, entry)) = entry {
-                if status == BatchStatus::Delivered {
-                    if let Err(error) =
-                        consumer.store_offset(&entry.topic, entry.partition, entry.offset)
-                    {
-                        emit!(KafkaOffsetUpdateError { error });
-                    }
-                }
+                pending_acks -= 1;
+                handle_ack(&consumer, status, entry);
             },
             message = stream.next() => match message {
                 None => break,  // WHY?
+                Some(Err(KafkaError::PartitionEOF(_))) => if let Some(stop_offsets) = &mut stop_offsets {
+                    stop_offsets.check_positions(&consumer);
+                },
                 Some(Err(error)) => emit!(KafkaReadError { error }),
                 Some(Ok(msg)) => {
+                    if let Some(stop_offsets) = &mut stop_offsets {
+                        if !stop_offsets.admit(&consumer, &msg) {
+                            continue;
+                        }
+                    }
+
                     emit!(KafkaBytesReceived {
                         byte_size: msg.payload_len(),
                         protocol: "tcp",
@@ -276,15 +437,56 @@ async fn kafka_source(
                         partition: msg.partition(),
                     });
 
-                    parse_message(msg, decoder.clone(), keys, &finalizer, &mut out, &consumer).await;
+                    if parse_message(msg, decoder.clone(), keys, &finalizer, &mut out, &consumer).await {
+                        pending_acks += 1;
+                    }
                 }
             },
         }
     }
 
+    if stop_offsets
+        .as_ref()
+        .map_or(false, StopOffsets::is_finished)
+    {
+        // A bounded replay is done. Wait for the events still in flight to be acknowledged, so that their offsets are
+        // stored before the final commit.
+        while pending_acks > 0 {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                entry = ack_stream.next() => match entry {
+                    Some((status, entry)) => {
+                        pending_acks -= 1;
+                        handle_ack(&consumer, status, entry);
+                    }
+                    None => break,
+                },
+            }
+        }
+
+        if !config.group_id.is_empty() {
+            if let Err(error) = consumer.commit_consumer_state(CommitMode::Sync) {
+                emit!(KafkaOffsetUpdateError { error });
+            }
+        }
+    }
+
     Ok(())
 }
 
+fn handle_ack(
+    consumer: &StreamConsumer<CustomContext>,
+    status: BatchStatus,
+    entry: FinalizerEntry,
+) {
+    if status == BatchStatus::Delivered {
+        if let Err(error) = consumer.store_offset(&entry.topic, entry.partition, entry.offset) {
+            emit!(KafkaOffsetUpdateError { error });
+        }
+    }
+}
+
+/// Returns `true` if the events of the message are pending acknowledgement.
 async fn parse_message(
     msg: BorrowedMessage<'_>,
     decoder: Decoder,
@@ -292,7 +494,7 @@ async fn parse_message(
     finalizer: &Option<Arc<OrderedFinalizer<FinalizerEntry>>>,
     out: &mut SourceSender,
     consumer: &Arc<StreamConsumer<CustomContext>>,
-) {
+) -> bool {
     if let Some((count, mut stream)) = parse_stream(&msg, decoder, keys) {
         match finalizer {
             Some(finalizer) => {
@@ -307,6 +509,7 @@ async fn parse_message(
                         // here, when `stream` is dropped and runs the destructor [...]".
                         drop(stream);
                         finalizer.add(msg.into(), receiver);
+                        return true;
                     }
                 }
             }
@@ -324,6 +527,7 @@ async fn parse_message(
             },
         }
     }
+    false
 }
 
 // Turn the received message into a stream of parsed events.
@@ -469,17 +673,32 @@ impl<'a> From<BorrowedMessage<'a>> for FinalizerEntry {
     }
 }
 
-fn create_consumer(config: &KafkaSourceConfig) -> crate::Result<StreamConsumer<CustomContext>> {
+fn create_consumer(
+    config: &KafkaSourceConfig,
+) -> crate::Result<(StreamConsumer<CustomContext>, Option<StopOffsets>)> {
+    if config.partitions.is_none() {
+        if config.group_id.is_empty() {
+            return Err(BuildError::MissingGroupId.into());
+        }
+        if config.start_at.is_some() || config.stop_at.is_some() {
+            return Err(BuildError::PositionWithoutPartitions.into());
+        }
+    }
+
     let mut client_config = ClientConfig::new();
     client_config
-        .set("group.id", &config.group_id)
         .set("bootstrap.servers", &config.bootstrap_servers)
         .set("auto.offset.reset", &config.auto_offset_reset)
         .set("session.timeout.ms", &config.session_timeout_ms.to_string())
         .set("socket.timeout.ms", &config.socket_timeout_ms.to_string())
         .set("fetch.wait.max.ms", &config.fetch_wait_max_ms.to_string())
-        .set("enable.partition.eof", "false")
-        .set("enable.auto.commit", "true")
+        // Bounded replays rely on end of partition notifications to finish partitions whose last offsets are never
+        // delivered, such as transaction markers.
+        .set("enable.partition.eof", config.stop_at.is_some().to_string())
+        .set(
+            "enable.auto.commit",
+            (!config.group_id.is_empty()).to_string(),
+        )
         .set(
             "auto.commit.interval.ms",
             &config.commit_interval_ms.to_string(),
@@ -488,6 +707,10 @@ fn create_consumer(config: &KafkaSourceConfig) -> crate::Result<StreamConsumer<C
         .set("statistics.interval.ms", "1000")
         .set("client.id", "vector");
 
+    if !config.group_id.is_empty() {
+        client_config.set("group.id", &config.group_id);
+    }
+
     config.auth.apply(&mut client_config)?;
 
     if let Some(librdkafka_options) = &config.librdkafka_options {
@@ -499,10 +722,238 @@ fn create_consumer(config: &KafkaSourceConfig) -> crate::Result<StreamConsumer<C
     let consumer = client_config
         .create_with_context::<_, StreamConsumer<_>>(CustomContext::default())
         .context(KafkaCreateSnafu)?;
-    let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
-    consumer.subscribe(&topics).context(KafkaSubscribeSnafu)?;
 
-    Ok(consumer)
+    let stop_offsets = match &config.partitions {
+        None => {
+            let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
+            consumer.subscribe(&topics).context(KafkaSubscribeSnafu)?;
+            None
+        }
+        Some(partitions) => assign_partitions(&consumer, config, partitions)?,
+    };
+
+    Ok((consumer, stop_offsets))
+}
+
+/// Assigns the configured partitions to the consumer, starting at `start_at`, and resolves `stop_at` into the offsets
+/// at which each partition stops being read.
+fn assign_partitions(
+    consumer: &StreamConsumer<CustomContext>,
+    config: &KafkaSourceConfig,
+    assignments: &[KafkaPartitionAssignment],
+) -> crate::Result<Option<StopOffsets>> {
+    let timeout = Duration::from_millis(config.socket_timeout_ms);
+
+    let mut partitions = TopicPartitionList::new();
+    for assignment in assignments {
+        if assignment.partitions.is_empty() {
+            let metadata = consumer
+                .fetch_metadata(Some(&assignment.topic), timeout)
+                .context(KafkaMetadataSnafu {
+                    topic: &assignment.topic,
+                })?;
+            for topic in metadata.topics() {
+                for partition in topic.partitions() {
+                    partitions.add_partition(topic.name(), partition.id());
+                }
+            }
+        } else {
+            for partition in &assignment.partitions {
+                partitions.add_partition(&assignment.topic, *partition);
+            }
+        }
+    }
+
+    let start = match config.start_at.clone().unwrap_or_default() {
+        KafkaStartPosition::Timestamp { timestamp } => {
+            offsets_for_time(consumer, &partitions, timestamp, timeout)?
+        }
+        position => {
+            let mut start = partitions.clone();
+            for elem in partitions.elements() {
+                start
+                    .set_partition_offset(
+                        elem.topic(),
+                        elem.partition(),
+                        position.offset_for(elem.topic(), elem.partition()),
+                    )
+                    .context(KafkaAssignSnafu)?;
+            }
+            start
+        }
+    };
+    consumer.assign(&start).context(KafkaAssignSnafu)?;
+
+    config
+        .stop_at
+        .as_ref()
+        .map(|stop_at| StopOffsets::resolve(consumer, config, stop_at, &start, timeout))
+        .transpose()
+}
+
+/// Looks up, for each partition, the offset of the first message whose timestamp is at or after `timestamp`.
+fn offsets_for_time(
+    consumer: &StreamConsumer<CustomContext>,
+    partitions: &TopicPartitionList,
+    timestamp: DateTime<Utc>,
+    timeout: Duration,
+) -> crate::Result<TopicPartitionList> {
+    let mut timestamps = partitions.clone();
+    timestamps
+        .set_all_offsets(Offset::Offset(timestamp.timestamp_millis()))
+        .context(KafkaOffsetLookupSnafu)?;
+    Ok(consumer
+        .offsets_for_times(timestamps, timeout)
+        .context(KafkaOffsetLookupSnafu)?)
+}
+
+/// Tracks the partitions of a bounded replay that have not yet reached their stop offset.
+#[derive(Debug, Default)]
+struct StopOffsets {
+    /// The offset at which each bounded partition stops being read.
+    offsets: HashMap<(String, i32), i64>,
+    /// The bounded partitions that have not yet reached their stop offset.
+    remaining: HashSet<(String, i32)>,
+    /// Whether any assigned partition is read indefinitely.
+    unbounded: bool,
+}
+
+impl StopOffsets {
+    fn resolve(
+        consumer: &StreamConsumer<CustomContext>,
+        config: &KafkaSourceConfig,
+        stop_at: &KafkaStopPosition,
+        start: &TopicPartitionList,
+        timeout: Duration,
+    ) -> crate::Result<Self> {
+        let by_time = match stop_at {
+            KafkaStopPosition::Timestamp { timestamp } => {
+                Some(offsets_for_time(consumer, start, *timestamp, timeout)?)
+            }
+            _ => None,
+        };
+        let committed = if config.group_id.is_empty() {
+            None
+        } else {
+            Some(
+                consumer
+                    .committed_offsets(start.clone(), timeout)
+                    .context(KafkaOffsetLookupSnafu)?,
+            )
+        };
+
+        let mut stop_offsets = Self::default();
+        for elem in start.elements() {
+            let (topic, partition) = (elem.topic(), elem.partition());
+            let (low, high) = consumer
+                .fetch_watermarks(topic, partition, timeout)
+                .context(KafkaOffsetLookupSnafu)?;
+
+            let stop = match stop_at {
+                KafkaStopPosition::End => high,
+                KafkaStopPosition::Timestamp { .. } => match by_time
+                    .as_ref()
+                    .and_then(|offsets| offsets.find_partition(topic, partition))
+                    .map(|elem| elem.offset())
+                {
+                    Some(Offset::Offset(offset)) => offset,
+                    _ => high,
+                },
+                KafkaStopPosition::Offsets { offsets } => {
+                    match find_offset(offsets, topic, partition) {
+                        Some(offset) => offset,
+                        None => {
+                            stop_offsets.unbounded = true;
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let start = match elem.offset() {
+                Offset::Offset(offset) => offset,
+                Offset::End => high,
+                Offset::Stored => match committed
+                    .as_ref()
+                    .and_then(|offsets| offsets.find_partition(topic, partition))
+                    .map(|elem| elem.offset())
+                {
+                    Some(Offset::Offset(offset)) => offset,
+                    _ => low,
+                },
+                _ => low,
+            };
+
+            let key = (topic.to_owned(), partition);
+            // Partitions with nothing to read before their stop offset are finished from the start.
+            if start.max(low) < stop {
+                stop_offsets.remaining.insert(key.clone());
+            }
+            stop_offsets.offsets.insert(key, stop);
+        }
+
+        Ok(stop_offsets)
+    }
+
+    fn is_finished(&self) -> bool {
+        !self.unbounded && self.remaining.is_empty()
+    }
+
+    /// Returns `true` if the message comes before the stop offset of its partition and should be read. Partitions are
+    /// paused once their stop offset is reached.
+    fn admit(
+        &mut self,
+        consumer: &StreamConsumer<CustomContext>,
+        msg: &BorrowedMessage<'_>,
+    ) -> bool {
+        let key = (msg.topic().to_owned(), msg.partition());
+        let stop = match self.offsets.get(&key) {
+            Some(stop) => *stop,
+            None => return true,
+        };
+
+        if msg.offset() + 1 >= stop {
+            self.finish(consumer, &key);
+        }
+
+        msg.offset() < stop
+    }
+
+    /// Finishes the partitions whose consumer position reached their stop offset.
+    ///
+    /// The last offsets of a partition are not always delivered as messages: on transactional topics, the high
+    /// watermark comes after a commit marker which the consumer skips. This is checked when the consumer reaches the
+    /// end of a partition, as no further message would finish it.
+    fn check_positions(&mut self, consumer: &StreamConsumer<CustomContext>) {
+        let positions = match consumer.position() {
+            Ok(positions) => positions,
+            Err(error) => {
+                emit!(KafkaReadError { error });
+                return;
+            }
+        };
+
+        for elem in positions.elements() {
+            let key = (elem.topic().to_owned(), elem.partition());
+            if let (Some(stop), Offset::Offset(position)) = (self.offsets.get(&key), elem.offset())
+            {
+                if position >= *stop {
+                    self.finish(consumer, &key);
+                }
+            }
+        }
+    }
+
+    /// Marks a partition as finished, and pauses it.
+    fn finish(&mut self, consumer: &StreamConsumer<CustomContext>, key: &(String, i32)) {
+        if self.remaining.remove(key) {
+            let mut partitions = TopicPartitionList::new();
+            partitions.add_partition(&key.0, key.1);
+            if let Err(error) = consumer.pause(&partitions) {
+                emit!(KafkaReadError { error });
+            }
+        }
+    }
 }
 
 #[derive(Default)]
@@ -577,6 +1028,52 @@ mod test {
         };
         assert!(create_consumer(&config).is_err());
     }
+
+    #[tokio::test]
+    async fn consumer_create_requires_group_id_without_partitions() {
+        let config = make_config("topic", "");
+        assert!(create_consumer(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn consumer_create_rejects_stop_at_without_partitions() {
+        let config = KafkaSourceConfig {
+            stop_at: Some(KafkaStopPosition::End),
+            ..make_config("topic", "group")
+        };
+        assert!(create_consumer(&config).is_err());
+    }
+
+    #[test]
+    fn parse_partition_assignment() {
+        let config: KafkaSourceConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            partitions = [{ topic = "logs", partitions = [0, 2] }]
+            start_at.type = "timestamp"
+            start_at.timestamp = "2022-10-01T00:00:00Z"
+            stop_at.type = "offsets"
+            stop_at.offsets = [{ topic = "logs", partition = 0, offset = 100 }]
+            "#,
+        )
+        .unwrap();
+
+        let partitions = config.partitions.unwrap();
+        assert_eq!(partitions[0].topic, "logs");
+        assert_eq!(partitions[0].partitions, vec![0, 2]);
+        assert!(config.group_id.is_empty());
+        assert!(matches!(
+            config.start_at,
+            Some(KafkaStartPosition::Timestamp { .. })
+        ));
+        match config.stop_at {
+            Some(KafkaStopPosition::Offsets { offsets }) => {
+                assert_eq!(find_offset(&offsets, "logs", 0), Some(100));
+                assert_eq!(find_offset(&offsets, "logs", 2), None);
+            }
+            other => panic!("unexpected stop position: {:?}", other),
+        }
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
@@ -592,7 +1089,7 @@ mod integration_test {
         config::{ClientConfig, FromClientConfig},
         consumer::BaseConsumer,
         message::{Header, OwnedHeaders},
-        producer::{FutureProducer, FutureRecord},
+        producer::{FutureProducer, FutureRecord, Producer},
         util::Timeout,
         Offset, TopicPartitionList,
     };
@@ -729,6 +1226,118 @@ mod integration_test {
         }
     }
 
+    #[tokio::test]
+    async fn bounded_replay_finishes_at_stop_offset() {
+        const SEND_COUNT: usize = 10;
+
+        let topic = format!("test-topic-{}", random_string(10));
+        let group_id = format!("test-group-{}", random_string(10));
+        send_events(topic.clone(), SEND_COUNT).await;
+
+        let config = KafkaSourceConfig {
+            partitions: Some(vec![KafkaPartitionAssignment {
+                topic: topic.clone(),
+                partitions: vec![],
+            }]),
+            start_at: Some(KafkaStartPosition::Beginning),
+            stop_at: Some(KafkaStopPosition::End),
+            ..make_config(&topic, &group_id)
+        };
+
+        let (tx, rx) = SourceSender::new_test_errors(|_| false);
+        let (consumer, stop_offsets) = create_consumer(&config).unwrap();
+        let source = tokio::spawn(kafka_source(
+            config,
+            consumer,
+            stop_offsets,
+            Decoder::default(),
+            ShutdownSignal::noop(),
+            tx,
+            true,
+        ));
+
+        let events = collect_n(rx, SEND_COUNT).await;
+        assert_eq!(events.len(), SEND_COUNT);
+
+        tokio::time::timeout(Duration::from_secs(10), source)
+            .await
+            .expect("Source did not finish")
+            .unwrap()
+            .unwrap();
+
+        let offset = fetch_tpl_offset(&group_id, &topic, 0);
+        assert_eq!(offset, Offset::from_raw(SEND_COUNT as i64));
+    }
+
+    #[tokio::test]
+    async fn bounded_replay_finishes_after_transaction_marker() {
+        const SEND_COUNT: usize = 10;
+
+        let topic = format!("test-topic-{}", random_string(10));
+        let group_id = format!("test-group-{}", random_string(10));
+
+        // Committing the transaction writes a control record at the end of the partition, which is never delivered
+        // to consumers.
+        let mut client = ClientConfig::new();
+        client.set("bootstrap.servers", kafka_address(9091));
+        client.set(
+            "transactional.id",
+            format!("test-txn-{}", random_string(10)),
+        );
+        let producer: FutureProducer = client.create().expect("Producer creation error");
+        producer
+            .init_transactions(Duration::from_secs(10))
+            .expect("Initializing transactions failed");
+        producer
+            .begin_transaction()
+            .expect("Beginning the transaction failed");
+        for i in 0..SEND_COUNT {
+            let text = format!("{} {:03}", TEXT, i);
+            let record = FutureRecord::<(), _>::to(&topic).payload(&text);
+            if let Err(error) = producer.send(record, Timeout::Never).await {
+                panic!("Cannot send event to Kafka: {:?}", error);
+            }
+        }
+        producer
+            .commit_transaction(Duration::from_secs(10))
+            .expect("Committing the transaction failed");
+
+        let config = KafkaSourceConfig {
+            partitions: Some(vec![KafkaPartitionAssignment {
+                topic: topic.clone(),
+                partitions: vec![0],
+            }]),
+            start_at: Some(KafkaStartPosition::Beginning),
+            stop_at: Some(KafkaStopPosition::End),
+            ..make_config(&topic, &group_id)
+        };
+
+        let (tx, rx) = SourceSender::new_test_errors(|_| false);
+        let (consumer, stop_offsets) = create_consumer(&config).unwrap();
+        assert_eq!(
+            stop_offsets.as_ref().unwrap().offsets[&(topic.clone(), 0)],
+            SEND_COUNT as i64 + 1
+        );
+        let source = tokio::spawn(kafka_source(
+            config,
+            consumer,
+            stop_offsets,
+            Decoder::default(),
+            ShutdownSignal::noop(),
+            tx,
+            true,
+        ));
+
+        let events = collect_n(rx, SEND_COUNT).await;
+        assert_eq!(events.len(), SEND_COUNT);
+
+        tokio::time::timeout(Duration::from_secs(10), source)
+            .await
+            .expect("Source did not finish")
+            .unwrap()
+            .unwrap();
+    }
+
     fn make_rand_config() -> (String, String, KafkaSourceConfig) {
         let topic = format!("test-topic-{}", random_string(10));
         let group_id = format!("test-group-{}", random_string(10));
@@ -764,10 +1373,11 @@ mod integration_test {
         acknowledgements: bool,
     ) -> (Trigger, Tripwire) {
         let (trigger_shutdown, shutdown, shutdown_done) = ShutdownSignal::new_wired();
-        let consumer = create_consumer(&config).unwrap();
+        let (consumer, stop_offsets) = create_consumer(&config).unwrap();
         tokio::spawn(kafka_source(
             config,
             consumer,
+            stop_offsets,
             Decoder::default(),
             shutdown,
             tx,
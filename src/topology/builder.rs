@@ -137,9 +137,28 @@ pub struct Pieces {
 
 /// Builds only the new pieces, and doesn't check their topology.
 pub async fn build_pieces(
+    config: &super::Config,
+    diff: &ConfigDiff,
+    buffers: HashMap<ComponentKey, BuiltBuffer>,
+) -> Result<Pieces, Vec<String>> {
+    build_pieces_inner(config, diff, buffers, false).await
+}
+
+/// Builds the pieces of a unit test, whose transforms trace the evaluation of their VRL programs
+/// when `trace_programs` is set.
+pub(crate) async fn build_unit_test_pieces(
+    config: &super::Config,
+    diff: &ConfigDiff,
+    trace_programs: bool,
+) -> Result<Pieces, Vec<String>> {
+    build_pieces_inner(config, diff, HashMap::new(), trace_programs).await
+}
+
+async fn build_pieces_inner(
     config: &super::Config,
     diff: &ConfigDiff,
     mut buffers: HashMap<ComponentKey, BuiltBuffer>,
+    trace_programs: bool,
 ) -> Result<Pieces, Vec<String>> {
     let mut inputs = HashMap::new();
     let mut outputs = HashMap::new();
@@ -190,6 +209,13 @@ pub async fn build_pieces(
             let mut rx = builder.add_output(output.clone());
 
             let (mut fanout, control) = Fanout::new();
+            fanout.set_upstream_id(
+                OutputId {
+                    component: key.clone(),
+                    port: output.port.clone(),
+                }
+                .to_string(),
+            );
             let pump = async move {
                 debug!("Source pump starting.");
 
@@ -360,6 +386,7 @@ pub async fn build_pieces(
             enrichment_tables: enrichment_tables.clone(),
             schema_definitions,
             merged_schema_definition: merged_definition.clone(),
+            trace_programs,
         };
 
         let node = TransformNode::from_parts(key.clone(), transform, &merged_definition);
@@ -629,7 +656,7 @@ fn build_sync_transform(
     node: TransformNode,
     input_rx: BufferReceiver<EventArray>,
 ) -> (Task, HashMap<OutputId, fanout::ControlChannel>) {
-    let (outputs, controls) = TransformOutputs::new(node.outputs);
+    let (outputs, controls) = TransformOutputs::new(node.outputs, &node.key);
 
     let runner = Runner::new(t, input_rx, node.input_details.data_type(), outputs);
     let transform = if node.enable_concurrency {
@@ -811,6 +838,7 @@ fn build_task_transform(
     key: &ComponentKey,
 ) -> (Task, HashMap<OutputId, fanout::ControlChannel>) {
     let (mut fanout, control) = Fanout::new();
+    fanout.set_upstream_id(key.to_string());
 
     let input_rx = crate::utilization::wrap(input_rx.into_stream());
 
@@ -1,16 +1,24 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     pin::Pin,
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use async_stream::stream;
 use futures::{Stream, StreamExt};
+use snafu::Snafu;
 use vector_config::configurable_component;
+use vector_core::metrics::AgentDDSketch;
 
 use crate::{
     config::{DataType, Input, Output, TransformConfig, TransformContext},
-    event::{metric, Event, EventMetadata},
+    event::{
+        metric::{
+            self, MetricData, MetricKind, MetricSeries, MetricSketch, MetricTime, MetricValue,
+            Quantile,
+        },
+        Event, EventMetadata,
+    },
     internal_events::{AggregateEventRecorded, AggregateFlushed, AggregateUpdateFailed},
     schema,
     transforms::{TaskTransform, Transform},
@@ -18,20 +26,145 @@ use crate::{
 
 /// Configuration for the `aggregate` transform.
 #[configurable_component(transform("aggregate"))]
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
 #[serde(deny_unknown_fields)]
 pub struct AggregateConfig {
     /// The interval between flushes, in milliseconds.
     ///
     /// Over this period metrics with the same series data (name, namespace, tags, …) will be aggregated.
     #[serde(default = "default_interval_ms")]
+    #[derivative(Default(value = "default_interval_ms()"))]
     pub interval_ms: u64,
+
+    /// The length of sliding windows, in milliseconds.
+    ///
+    /// When set, each flush outputs the aggregation of the metrics received over this period,
+    /// instead of over `interval_ms` only, so that consecutive windows overlap. It must be a
+    /// multiple of `interval_ms`. Since they cover overlapping periods, the metrics output for
+    /// sliding windows are always absolute.
+    #[configurable(metadata(docs::examples = 60000))]
+    pub window_ms: Option<u64>,
+
+    /// Whether to align flushes to wall-clock boundaries.
+    ///
+    /// When enabled, flushes happen at multiples of `interval_ms` since the Unix epoch, for
+    /// example at the start of every minute for an interval of 60 seconds, instead of relative to
+    /// the start of Vector.
+    #[serde(default = "crate::serde::default_false")]
+    pub align_to_wall_clock: bool,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub mode: AggregationMode,
+
+    /// The aggregation modes of specific metrics, by metric name, overriding `mode`.
+    #[serde(default)]
+    pub modes: HashMap<String, AggregationMode>,
+
+    /// The quantiles computed by the `quantiles` aggregation mode.
+    ///
+    /// Each quantile must be between 0.0 and 1.0, inclusive.
+    #[serde(default = "default_quantiles")]
+    #[derivative(Default(value = "default_quantiles()"))]
+    pub quantiles: Vec<f64>,
+
+    #[configurable(derived)]
+    pub group_by: Option<GroupByConfig>,
+}
+
+/// How the values of a metric are aggregated over a window.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationMode {
+    /// Sums incremental metrics, and keeps the latest value of absolute ones.
+    #[derivative(Default)]
+    Auto,
+
+    /// Sums the values.
+    ///
+    /// Incremental values are added together, while only the latest value of each absolute series
+    /// is added, so that absolute counters can be summed across the series merged by `group_by`.
+    /// The result is a counter if all the values are counters, and a gauge otherwise.
+    Sum,
+
+    /// Counts the values, or the samples of distributions, as an incremental counter.
+    Count,
+
+    /// Keeps the minimum value, as an absolute gauge.
+    Min,
+
+    /// Keeps the maximum value, as an absolute gauge.
+    Max,
+
+    /// Averages the values, as an absolute gauge.
+    Mean,
+
+    /// Keeps the latest value.
+    Latest,
+
+    /// Computes the `quantiles` of the values, and of the samples of distributions, as an
+    /// aggregated summary.
+    Quantiles,
+}
+
+/// Regroups metrics by keeping or dropping some of their tags before aggregating them.
+///
+/// The metrics which only differ by the removed tags are aggregated together, which reduces the
+/// cardinality of the output, as in "sum by service".
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct GroupByConfig {
+    /// The tags to keep or drop.
+    #[configurable(metadata(docs::examples = "service"))]
+    pub tags: Vec<String>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub action: GroupByAction,
+}
+
+/// What to do with the `tags` of a regrouping.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupByAction {
+    /// Keeps only the listed tags.
+    #[derivative(Default)]
+    Keep,
+
+    /// Drops the listed tags, and keeps the others.
+    Drop,
 }
 
 const fn default_interval_ms() -> u64 {
     10 * 1000
 }
 
+fn default_quantiles() -> Vec<f64> {
+    vec![0.5, 0.75, 0.9, 0.95, 0.99]
+}
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+enum BuildError {
+    #[snafu(display("`interval_ms` must be greater than zero"))]
+    ZeroInterval,
+
+    #[snafu(display(
+        "`window_ms` ({}) must be a multiple of `interval_ms` ({})",
+        window_ms,
+        interval_ms
+    ))]
+    InvalidWindow { window_ms: u64, interval_ms: u64 },
+
+    #[snafu(display("quantile {} is not between 0.0 and 1.0", quantile))]
+    InvalidQuantile { quantile: f64 },
+}
+
 impl_generate_config_from_default!(AggregateConfig);
 
 #[async_trait::async_trait]
@@ -49,59 +182,408 @@ impl TransformConfig for AggregateConfig {
     }
 }
 
-type MetricEntry = (metric::MetricData, EventMetadata);
+/// The aggregated values of a series over a window.
+#[derive(Clone, Debug)]
+enum Aggregation {
+    /// The sum of incremental values, or the latest absolute value.
+    Auto(MetricData),
+    Sum {
+        incremental: f64,
+        /// The latest value of each absolute series merged into this one.
+        absolute: HashMap<MetricSeries, f64>,
+        counter: bool,
+    },
+    Count(u64),
+    Min(f64),
+    Max(f64),
+    Mean {
+        sum: f64,
+        count: u64,
+    },
+    Latest(MetricData),
+    Quantiles(AgentDDSketch),
+}
+
+/// Calls `f` with each observed value of `value` and the number of times it was observed, or
+/// returns `false` if `value` isn't made of individual observations.
+fn for_each_observation(value: &MetricValue, mut f: impl FnMut(f64, u32)) -> bool {
+    match value {
+        MetricValue::Counter { value } | MetricValue::Gauge { value } => f(*value, 1),
+        MetricValue::Distribution { samples, .. } => {
+            for sample in samples {
+                f(sample.value, sample.rate);
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+impl Aggregation {
+    /// Starts the aggregation of a series with `data`, falling back to the `auto` mode if `data`
+    /// can't be aggregated with `mode`.
+    fn new(mode: AggregationMode, data: MetricData, original: Option<MetricSeries>) -> Self {
+        let mut aggregation = match mode {
+            AggregationMode::Auto => return Self::Auto(data),
+            AggregationMode::Latest => return Self::Latest(data),
+            AggregationMode::Sum => Self::Sum {
+                incremental: 0.0,
+                absolute: HashMap::new(),
+                counter: true,
+            },
+            AggregationMode::Count => Self::Count(0),
+            AggregationMode::Min => Self::Min(f64::INFINITY),
+            AggregationMode::Max => Self::Max(f64::NEG_INFINITY),
+            AggregationMode::Mean => Self::Mean { sum: 0.0, count: 0 },
+            AggregationMode::Quantiles => Self::Quantiles(AgentDDSketch::with_agent_defaults()),
+        };
+
+        if aggregation.update(&data, original) {
+            aggregation
+        } else {
+            Self::Auto(data)
+        }
+    }
+
+    /// Adds `data` to the aggregation, or returns `false` if it can't be added to it.
+    ///
+    /// `original` is the series of `data` before regrouping, only needed to sum absolute values.
+    fn update(&mut self, data: &MetricData, original: Option<MetricSeries>) -> bool {
+        match self {
+            Self::Auto(existing) => match data.kind {
+                // In order to update (add) the new and old kind's must match
+                MetricKind::Incremental => existing.kind == data.kind && existing.update(data),
+                MetricKind::Absolute => {
+                    *existing = data.clone();
+                    true
+                }
+            },
+            Self::Latest(existing) => {
+                *existing = data.clone();
+                true
+            }
+            Self::Sum {
+                incremental,
+                absolute,
+                counter,
+            } => {
+                let mut sum = 0.0;
+                if !for_each_observation(&data.value, |value, count| {
+                    sum += value * f64::from(count);
+                }) {
+                    return false;
+                }
+
+                *counter &= matches!(data.value, MetricValue::Counter { .. });
+                match (data.kind, original) {
+                    (MetricKind::Absolute, Some(original)) => {
+                        absolute.insert(original, sum);
+                    }
+                    _ => *incremental += sum,
+                }
+                true
+            }
+            Self::Count(total) => for_each_observation(&data.value, |_, count| {
+                *total += u64::from(count);
+            }),
+            Self::Min(min) => for_each_observation(&data.value, |value, _| {
+                *min = min.min(value);
+            }),
+            Self::Max(max) => for_each_observation(&data.value, |value, _| {
+                *max = max.max(value);
+            }),
+            Self::Mean { sum, count } => for_each_observation(&data.value, |value, n| {
+                *sum += value * f64::from(n);
+                *count += u64::from(n);
+            }),
+            Self::Quantiles(sketch) => match &data.value {
+                MetricValue::Sketch {
+                    sketch: MetricSketch::AgentDDSketch(other),
+                } => sketch.merge(other).is_ok(),
+                value => for_each_observation(value, |value, count| {
+                    sketch.insert_n(value, count);
+                }),
+            },
+        }
+    }
+
+    /// Merges the aggregation of a later window into this one.
+    fn merge(&mut self, other: &Self) {
+        let merged = match (&mut *self, other) {
+            (Self::Auto(existing), Self::Auto(data)) => {
+                data.kind == MetricKind::Incremental
+                    && existing.kind == data.kind
+                    && existing.update(data)
+            }
+            (
+                Self::Sum {
+                    incremental,
+                    absolute,
+                    counter,
+                },
+                Self::Sum {
+                    incremental: other_incremental,
+                    absolute: other_absolute,
+                    counter: other_counter,
+                },
+            ) => {
+                *incremental += other_incremental;
+                absolute.extend(other_absolute.iter().map(|(k, v)| (k.clone(), *v)));
+                *counter &= other_counter;
+                true
+            }
+            (Self::Count(total), Self::Count(other)) => {
+                *total += other;
+                true
+            }
+            (Self::Min(min), Self::Min(other)) => {
+                *min = min.min(*other);
+                true
+            }
+            (Self::Max(max), Self::Max(other)) => {
+                *max = max.max(*other);
+                true
+            }
+            (
+                Self::Mean { sum, count },
+                Self::Mean {
+                    sum: other_sum,
+                    count: other_count,
+                },
+            ) => {
+                *sum += other_sum;
+                *count += other_count;
+                true
+            }
+            (Self::Quantiles(sketch), Self::Quantiles(other)) => sketch.merge(other).is_ok(),
+            _ => false,
+        };
+
+        // Otherwise, the later aggregation replaces this one, as it does within a window.
+        if !merged {
+            *self = other.clone();
+        }
+    }
+
+    fn into_metric(
+        self,
+        series: MetricSeries,
+        metadata: EventMetadata,
+        quantiles: &[f64],
+        sliding: bool,
+    ) -> metric::Metric {
+        let incremental_kind = if sliding {
+            MetricKind::Absolute
+        } else {
+            MetricKind::Incremental
+        };
+        let (kind, value) = match self {
+            Self::Auto(mut data) | Self::Latest(mut data) => {
+                if sliding {
+                    data.kind = MetricKind::Absolute;
+                }
+                return metric::Metric::from_parts(series, data, metadata);
+            }
+            Self::Sum {
+                incremental,
+                absolute,
+                counter,
+            } => {
+                let kind = if absolute.is_empty() {
+                    incremental_kind
+                } else {
+                    MetricKind::Absolute
+                };
+                let value = incremental + absolute.values().sum::<f64>();
+                let value = if counter {
+                    MetricValue::Counter { value }
+                } else {
+                    MetricValue::Gauge { value }
+                };
+                (kind, value)
+            }
+            Self::Count(count) => (
+                incremental_kind,
+                MetricValue::Counter {
+                    value: count as f64,
+                },
+            ),
+            Self::Min(value) | Self::Max(value) => {
+                (MetricKind::Absolute, MetricValue::Gauge { value })
+            }
+            Self::Mean { sum, count } => (
+                MetricKind::Absolute,
+                MetricValue::Gauge {
+                    value: if count == 0 { 0.0 } else { sum / count as f64 },
+                },
+            ),
+            Self::Quantiles(sketch) => (
+                MetricKind::Absolute,
+                MetricValue::AggregatedSummary {
+                    quantiles: quantiles
+                        .iter()
+                        .map(|&quantile| Quantile {
+                            quantile,
+                            value: sketch.quantile(quantile).unwrap_or(0.0),
+                        })
+                        .collect(),
+                    count: u64::from(sketch.count()),
+                    sum: sketch.sum().unwrap_or(0.0),
+                },
+            ),
+        };
+
+        let time = MetricTime {
+            timestamp: None,
+            interval_ms: None,
+        };
+        metric::Metric::from_parts(series, MetricData::from_parts(time, kind, value), metadata)
+    }
+}
+
+type MetricEntry = (Aggregation, EventMetadata);
 
 #[derive(Debug)]
 pub struct Aggregate {
     interval: Duration,
-    map: HashMap<metric::MetricSeries, MetricEntry>,
+    align_to_wall_clock: bool,
+    mode: AggregationMode,
+    modes: HashMap<String, AggregationMode>,
+    quantiles: Vec<f64>,
+    group_by: Option<(HashSet<String>, GroupByAction)>,
+
+    /// The aggregations of the intervals of the current window, from the oldest to the newest.
+    /// Tumbling windows have a single interval.
+    buckets: VecDeque<HashMap<MetricSeries, MetricEntry>>,
+    window_buckets: usize,
 }
 
 impl Aggregate {
     pub fn new(config: &AggregateConfig) -> crate::Result<Self> {
+        if config.interval_ms == 0 {
+            return Err(Box::new(BuildError::ZeroInterval));
+        }
+        let window_buckets = match config.window_ms {
+            None => 1,
+            Some(window_ms) if window_ms > 0 && window_ms % config.interval_ms == 0 => {
+                (window_ms / config.interval_ms) as usize
+            }
+            Some(window_ms) => {
+                return Err(Box::new(BuildError::InvalidWindow {
+                    window_ms,
+                    interval_ms: config.interval_ms,
+                }))
+            }
+        };
+        if let Some(&quantile) = config
+            .quantiles
+            .iter()
+            .find(|quantile| !(0.0..=1.0).contains(*quantile))
+        {
+            return Err(Box::new(BuildError::InvalidQuantile { quantile }));
+        }
+
         Ok(Self {
             interval: Duration::from_millis(config.interval_ms),
-            map: Default::default(),
+            align_to_wall_clock: config.align_to_wall_clock,
+            mode: config.mode,
+            modes: config.modes.clone(),
+            quantiles: config.quantiles.clone(),
+            group_by: config
+                .group_by
+                .as_ref()
+                .map(|group_by| (group_by.tags.iter().cloned().collect(), group_by.action)),
+            buckets: VecDeque::from([HashMap::new()]),
+            window_buckets,
         })
     }
 
     fn record(&mut self, event: Event) {
-        let (series, data, metadata) = event.into_metric().into_parts();
-
-        match data.kind {
-            metric::MetricKind::Incremental => match self.map.entry(series) {
-                Entry::Occupied(mut entry) => {
-                    let existing = entry.get_mut();
-                    // In order to update (add) the new and old kind's must match
-                    if existing.0.kind == data.kind && existing.0.update(&data) {
-                        existing.1.merge(metadata);
-                    } else {
-                        emit!(AggregateUpdateFailed);
-                        *existing = (data, metadata);
-                    }
-                }
-                Entry::Vacant(entry) => {
-                    entry.insert((data, metadata));
+        let metric = event.into_metric();
+        let mode = self.modes.get(metric.name()).copied().unwrap_or(self.mode);
+        let (mut series, data, metadata) = metric.into_parts();
+
+        // Absolute values are summed once per original series.
+        let original = (mode == AggregationMode::Sum && data.kind == MetricKind::Absolute)
+            .then(|| series.clone());
+        if let Some((tags, action)) = &self.group_by {
+            if let Some(series_tags) = series.tags_mut() {
+                series_tags.retain(|key, _| tags.contains(key) == (*action == GroupByAction::Keep));
+            }
+        }
+
+        let bucket = self.buckets.back_mut().expect("at least one bucket");
+        match bucket.entry(series) {
+            Entry::Occupied(mut entry) => {
+                let existing = entry.get_mut();
+                if existing.0.update(&data, original.clone()) {
+                    existing.1.merge(metadata);
+                } else {
+                    emit!(AggregateUpdateFailed);
+                    *existing = (Aggregation::new(mode, data, original), metadata);
                 }
-            },
-            metric::MetricKind::Absolute => {
-                // Always replace/store
-                self.map.insert(series, (data, metadata));
             }
-        };
+            Entry::Vacant(entry) => {
+                entry.insert((Aggregation::new(mode, data, original), metadata));
+            }
+        }
 
         emit!(AggregateEventRecorded);
     }
 
     fn flush_into(&mut self, output: &mut Vec<Event>) {
-        let map = std::mem::take(&mut self.map);
-        for (series, entry) in map.into_iter() {
-            let metric = metric::Metric::from_parts(series, entry.0, entry.1);
+        let sliding = self.window_buckets > 1;
+        let window = if sliding {
+            let mut window = HashMap::<MetricSeries, MetricEntry>::new();
+            for bucket in &self.buckets {
+                for (series, (aggregation, metadata)) in bucket {
+                    match window.entry(series.clone()) {
+                        Entry::Occupied(mut entry) => {
+                            let existing = entry.get_mut();
+                            existing.0.merge(aggregation);
+                            existing.1.merge(metadata.clone());
+                        }
+                        Entry::Vacant(entry) => {
+                            entry.insert((aggregation.clone(), metadata.clone()));
+                        }
+                    }
+                }
+            }
+
+            self.buckets.push_back(HashMap::new());
+            while self.buckets.len() > self.window_buckets {
+                self.buckets.pop_front();
+            }
+            window
+        } else {
+            std::mem::take(self.buckets.back_mut().expect("at least one bucket"))
+        };
+
+        for (series, (aggregation, metadata)) in window {
+            let metric = aggregation.into_metric(series, metadata, &self.quantiles, sliding);
             output.push(Event::Metric(metric));
         }
 
         emit!(AggregateFlushed);
     }
+
+    /// Returns the delay until the first flush, aligning flushes to wall-clock boundaries if
+    /// enabled.
+    fn first_flush_delay(&self, now: SystemTime) -> Duration {
+        if !self.align_to_wall_clock {
+            return Duration::ZERO;
+        }
+
+        let interval = self.interval.as_millis();
+        let since_epoch = now
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        match since_epoch % interval {
+            0 => Duration::ZERO,
+            elapsed => Duration::from_millis((interval - elapsed) as u64),
+        }
+    }
 }
 
 impl TaskTransform<Event> for Aggregate {
@@ -112,7 +594,8 @@ impl TaskTransform<Event> for Aggregate {
     where
         Self: 'static,
     {
-        let mut flush_stream = tokio::time::interval(self.interval);
+        let start = tokio::time::Instant::now() + self.first_flush_delay(SystemTime::now());
+        let mut flush_stream = tokio::time::interval_at(start, self.interval);
 
         Box::pin(stream! {
             let mut output = Vec::new();
@@ -147,6 +630,7 @@ mod tests {
     use futures::stream;
     use tokio::sync::mpsc;
     use tokio_stream::wrappers::ReceiverStream;
+    use vector_core::metric_tags;
 
     use super::*;
     use crate::{
@@ -172,6 +656,7 @@ mod tests {
     fn incremental() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -242,6 +727,7 @@ mod tests {
     fn absolute() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -307,6 +793,7 @@ mod tests {
     fn conflicting_value_type() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -364,6 +851,7 @@ mod tests {
     fn conflicting_kinds() {
         let mut agg = Aggregate::new(&AggregateConfig {
             interval_ms: 1000_u64,
+            ..Default::default()
         })
         .unwrap();
 
@@ -414,6 +902,215 @@ mod tests {
         assert_eq!(&summed, &out[0]);
     }
 
+    #[test]
+    fn invalid_config() {
+        let window = AggregateConfig {
+            interval_ms: 1000,
+            window_ms: Some(1500),
+            ..Default::default()
+        };
+        assert!(Aggregate::new(&window).is_err());
+
+        let quantiles = AggregateConfig {
+            quantiles: vec![0.5, 1.5],
+            ..Default::default()
+        };
+        assert!(Aggregate::new(&quantiles).is_err());
+    }
+
+    #[test]
+    fn statistics() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            modes: HashMap::from([
+                ("min".to_owned(), AggregationMode::Min),
+                ("max".to_owned(), AggregationMode::Max),
+                ("mean".to_owned(), AggregationMode::Mean),
+                ("count".to_owned(), AggregationMode::Count),
+            ]),
+            ..Default::default()
+        })
+        .unwrap();
+
+        for name in ["min", "max", "mean", "count"] {
+            for value in [3.0, 1.0, 5.0] {
+                agg.record(make_metric(
+                    name,
+                    metric::MetricKind::Absolute,
+                    metric::MetricValue::Gauge { value },
+                ));
+            }
+        }
+        let mut out = vec![];
+        agg.flush_into(&mut out);
+        assert_eq!(4, out.len());
+
+        for event in out {
+            let metric = event.as_metric();
+            let (kind, value) = match metric.name() {
+                "min" => (
+                    metric::MetricKind::Absolute,
+                    metric::MetricValue::Gauge { value: 1.0 },
+                ),
+                "max" => (
+                    metric::MetricKind::Absolute,
+                    metric::MetricValue::Gauge { value: 5.0 },
+                ),
+                "mean" => (
+                    metric::MetricKind::Absolute,
+                    metric::MetricValue::Gauge { value: 3.0 },
+                ),
+                "count" => (
+                    metric::MetricKind::Incremental,
+                    metric::MetricValue::Counter { value: 3.0 },
+                ),
+                _ => panic!("Unexpected metric name in aggregate output"),
+            };
+            assert_eq!(kind, metric.kind());
+            assert_eq!(&value, metric.value());
+        }
+    }
+
+    #[test]
+    fn quantiles() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            mode: AggregationMode::Quantiles,
+            quantiles: vec![0.0, 1.0],
+            ..Default::default()
+        })
+        .unwrap();
+
+        agg.record(make_metric(
+            "latency",
+            metric::MetricKind::Incremental,
+            metric::MetricValue::Distribution {
+                samples: vector_core::samples![1.0 => 2, 4.0 => 1],
+                statistic: metric::StatisticKind::Histogram,
+            },
+        ));
+        let mut out = vec![];
+        agg.flush_into(&mut out);
+        assert_eq!(1, out.len());
+
+        let metric = out[0].as_metric();
+        assert_eq!(metric::MetricKind::Absolute, metric.kind());
+        match metric.value() {
+            metric::MetricValue::AggregatedSummary {
+                quantiles,
+                count,
+                sum,
+            } => {
+                assert_eq!(3, *count);
+                assert!((sum - 6.0).abs() < 0.1);
+                assert_eq!(2, quantiles.len());
+                assert!((quantiles[0].value - 1.0).abs() < 0.1);
+                assert!((quantiles[1].value - 4.0).abs() < 0.1);
+            }
+            value => panic!("Unexpected value in aggregate output: {:?}", value),
+        }
+    }
+
+    #[test]
+    fn group_by_sum() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            mode: AggregationMode::Sum,
+            group_by: Some(GroupByConfig {
+                tags: vec!["service".to_owned()],
+                action: GroupByAction::Keep,
+            }),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let counter = |host: &str, kind, value| {
+            Event::Metric(
+                Metric::new("requests", kind, metric::MetricValue::Counter { value })
+                    .with_tags(Some(metric_tags!("service" => "api", "host" => host))),
+            )
+        };
+        // Absolute counters only count with their latest value, once per host.
+        agg.record(counter("a", metric::MetricKind::Absolute, 10.0));
+        agg.record(counter("a", metric::MetricKind::Absolute, 12.0));
+        agg.record(counter("b", metric::MetricKind::Absolute, 5.0));
+        agg.record(counter("c", metric::MetricKind::Incremental, 1.0));
+
+        let mut out = vec![];
+        agg.flush_into(&mut out);
+        assert_eq!(1, out.len());
+
+        let metric = out[0].as_metric();
+        assert_eq!(
+            Some(metric_tags!("service" => "api")).as_ref(),
+            metric.tags()
+        );
+        assert_eq!(metric::MetricKind::Absolute, metric.kind());
+        assert_eq!(
+            &metric::MetricValue::Counter { value: 18.0 },
+            metric.value()
+        );
+    }
+
+    #[test]
+    fn sliding_window() {
+        let mut agg = Aggregate::new(&AggregateConfig {
+            interval_ms: 1000,
+            window_ms: Some(3000),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let counter = |value| {
+            make_metric(
+                "counter_a",
+                metric::MetricKind::Incremental,
+                metric::MetricValue::Counter { value },
+            )
+        };
+        let expected = |value| {
+            make_metric(
+                "counter_a",
+                metric::MetricKind::Absolute,
+                metric::MetricValue::Counter { value },
+            )
+        };
+
+        let mut out = vec![];
+        for (value, sum) in [(1.0, 1.0), (2.0, 3.0), (4.0, 7.0), (8.0, 14.0)] {
+            agg.record(counter(value));
+            out.clear();
+            agg.flush_into(&mut out);
+            assert_eq!(vec![expected(sum)], out);
+        }
+
+        // The values leave the window one interval at a time.
+        for sum in [12.0, 8.0] {
+            out.clear();
+            agg.flush_into(&mut out);
+            assert_eq!(vec![expected(sum)], out);
+        }
+        out.clear();
+        agg.flush_into(&mut out);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn wall_clock_alignment() {
+        let config = AggregateConfig {
+            interval_ms: 60_000,
+            ..Default::default()
+        };
+        let now = UNIX_EPOCH + Duration::from_millis(10 * 60_000 + 15_000);
+
+        let agg = Aggregate::new(&config).unwrap();
+        assert_eq!(Duration::ZERO, agg.first_flush_delay(now));
+
+        let agg = Aggregate::new(&AggregateConfig {
+            align_to_wall_clock: true,
+            ..config
+        })
+        .unwrap();
+        assert_eq!(Duration::from_secs(45), agg.first_flush_delay(now));
+    }
+
     #[tokio::test]
     async fn transform_shutdown() {
         let agg = toml::from_str::<AggregateConfig>(
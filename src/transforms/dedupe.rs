@@ -1,8 +1,15 @@
-use std::{future::ready, num::NonZeroUsize, pin::Pin};
+use std::{
+    future::ready,
+    hash::Hasher,
+    num::NonZeroUsize,
+    pin::Pin,
+    time::{Duration, Instant},
+};
 
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
 use lru::LruCache;
+use seahash::SeaHasher;
 use vector_config::configurable_component;
 
 use crate::{
@@ -10,7 +17,7 @@ use crate::{
         log_schema, DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
     },
     event::{Event, Value},
-    internal_events::DedupeEventsDropped,
+    internal_events::{DedupeCacheSize, DedupeEventsDropped},
     schema,
     transforms::{TaskTransform, Transform},
 };
@@ -66,6 +73,29 @@ pub enum FieldMatchConfig {
 pub struct CacheConfig {
     /// Number of events to cache and use for comparing incoming events to previously seen events.
     pub num_events: NonZeroUsize,
+
+    /// How long an event is cached, in seconds.
+    ///
+    /// When set, an event is only considered a duplicate of the events received within this
+    /// duration before it, regardless of the number of events received in between, as long as they
+    /// fit in the cache.
+    #[serde(default)]
+    pub time_window_secs: Option<u64>,
+
+    /// The maximum size of the cache, in bytes.
+    ///
+    /// When set, the least recently used events are removed from the cache once the size of the
+    /// cached fields exceeds this limit, even if it holds fewer than `num_events` events.
+    #[serde(default)]
+    pub max_bytes: Option<NonZeroUsize>,
+
+    /// Whether to cache a hash of the fields of events, instead of their values.
+    ///
+    /// This bounds the memory used by each cached event, regardless of the size of its fields, and
+    /// provides a key which is stable across Vector instances. Distinct events may be considered
+    /// duplicates if their hashes collide, which is very unlikely.
+    #[serde(default)]
+    pub hash_fields: bool,
 }
 
 /// Configuration for the `dedupe` transform.
@@ -85,6 +115,9 @@ pub struct DedupeConfig {
 fn default_cache_config() -> CacheConfig {
     CacheConfig {
         num_events: NonZeroUsize::new(5000).expect("static non-zero number"),
+        time_window_secs: None,
+        max_bytes: None,
+        hash_fields: false,
     }
 }
 
@@ -126,7 +159,22 @@ impl DedupeConfig {
 
 pub struct Dedupe {
     fields: FieldMatchConfig,
-    cache: LruCache<CacheEntry, bool>,
+    time_window: Option<Duration>,
+    max_bytes: Option<usize>,
+    hash_fields: bool,
+    cache: LruCache<CacheEntry, CachedEvent>,
+    cache_bytes: usize,
+    /// The size of the cache last reported, in events and bytes, and when.
+    last_report: Option<(usize, usize, Instant)>,
+}
+
+/// The minimum interval between reports of the size of the cache.
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What is known of a cached event.
+struct CachedEvent {
+    received: Instant,
+    size: usize,
 }
 
 impl GenerateConfig for DedupeConfig {
@@ -179,10 +227,67 @@ type TypeId = u8;
 /// iterating over the fields of the incoming Events, we know that the
 /// CacheEntries for 2 equivalent events will always contain the fields in the
 /// same order.
+///
+/// When hashing fields, a CacheEntry only contains a hash of the CacheEntry
+/// which would be built otherwise.
 #[derive(PartialEq, Eq, Hash)]
 enum CacheEntry {
     Match(Vec<Option<(TypeId, Bytes)>>),
     Ignore(Vec<(String, TypeId, Bytes)>),
+    Hash(u64),
+}
+
+impl CacheEntry {
+    /// Returns a hash of the entry which only depends on its contents, so that it is the same
+    /// across Vector instances.
+    fn stable_hash(&self) -> u64 {
+        let mut hasher = SeaHasher::new();
+        match self {
+            Self::Match(fields) => {
+                for field in fields {
+                    match field {
+                        Some((type_id, bytes)) => {
+                            hasher.write_u8(1);
+                            hasher.write_u8(*type_id);
+                            hasher.write_u64(bytes.len() as u64);
+                            hasher.write(bytes);
+                        }
+                        None => hasher.write_u8(0),
+                    }
+                }
+            }
+            Self::Ignore(fields) => {
+                for (name, type_id, bytes) in fields {
+                    hasher.write_u64(name.len() as u64);
+                    hasher.write(name.as_bytes());
+                    hasher.write_u8(*type_id);
+                    hasher.write_u64(bytes.len() as u64);
+                    hasher.write(bytes);
+                }
+            }
+            Self::Hash(hash) => return *hash,
+        }
+        hasher.finish()
+    }
+
+    /// Returns the approximate number of bytes used by the entry.
+    fn size(&self) -> usize {
+        let fields = match self {
+            Self::Match(fields) => fields
+                .iter()
+                .map(|field| {
+                    std::mem::size_of_val(field)
+                        + field.as_ref().map_or(0, |(_, bytes)| bytes.len())
+                })
+                .sum(),
+            Self::Ignore(fields) => fields
+                .iter()
+                .map(|field| std::mem::size_of_val(field) + field.0.len() + field.2.len())
+                .sum(),
+            Self::Hash(_) => 0,
+        };
+        std::mem::size_of::<Self>() + fields
+    }
 }
 
 /// Assigns a unique number to each of the types supported by Event::Value.
@@ -206,17 +311,108 @@ impl Dedupe {
         let fields = config.fill_default_fields_match();
         Self {
             fields,
+            time_window: config.cache.time_window_secs.map(Duration::from_secs),
+            max_bytes: config.cache.max_bytes.map(NonZeroUsize::get),
+            hash_fields: config.cache.hash_fields,
             cache: LruCache::new(num_entries),
+            cache_bytes: 0,
+            last_report: None,
         }
     }
 
     fn transform_one(&mut self, event: Event) -> Option<Event> {
-        let cache_entry = build_cache_entry(&event, &self.fields);
-        if self.cache.put(cache_entry, true).is_some() {
-            emit!(DedupeEventsDropped { count: 1 });
-            None
+        self.transform_one_at(event, Instant::now())
+    }
+
+    fn transform_one_at(&mut self, event: Event, now: Instant) -> Option<Event> {
+        self.expire(now);
+
+        let mut cache_entry = build_cache_entry(&event, &self.fields);
+        if self.hash_fields {
+            cache_entry = CacheEntry::Hash(cache_entry.stable_hash());
+        }
+
+        // With a time window, duplicates don't refresh the cached event, so that it still expires
+        // after the window, and the least recently used events are also the oldest ones.
+        let duplicate = if self.time_window.is_some() {
+            self.cache.contains(&cache_entry)
         } else {
-            Some(event)
+            self.cache.get(&cache_entry).is_some()
+        };
+        if duplicate {
+            emit!(DedupeEventsDropped { count: 1 });
+            return None;
+        }
+
+        self.insert(cache_entry, now);
+        Some(event)
+    }
+
+    fn insert(&mut self, cache_entry: CacheEntry, now: Instant) {
+        let size = cache_entry.size();
+        self.cache_bytes += size;
+        let cached = CachedEvent {
+            received: now,
+            size,
+        };
+        if let Some((_, evicted)) = self.cache.push(cache_entry, cached) {
+            self.cache_bytes -= evicted.size;
+        }
+
+        if let Some(max_bytes) = self.max_bytes {
+            while self.cache_bytes > max_bytes {
+                match self.cache.pop_lru() {
+                    Some((_, evicted)) => self.cache_bytes -= evicted.size,
+                    None => break,
+                }
+            }
+        }
+
+        self.report(now);
+    }
+
+    /// Reports the size of the cache when it changed, at most once per report interval, and right
+    /// away when it becomes empty.
+    fn report(&mut self, now: Instant) {
+        let events = self.cache.len();
+        let due = match self.last_report {
+            Some((reported_events, reported_bytes, _))
+                if reported_events == events && reported_bytes == self.cache_bytes =>
+            {
+                false
+            }
+            Some((_, _, reported)) => {
+                events == 0 || now.duration_since(reported) >= REPORT_INTERVAL
+            }
+            None => true,
+        };
+        if due {
+            self.last_report = Some((events, self.cache_bytes, now));
+            emit!(DedupeCacheSize {
+                events,
+                bytes: self.cache_bytes,
+            });
+        }
+    }
+
+    /// Removes the cached events received before the time window.
+    fn expire(&mut self, now: Instant) {
+        let time_window = match self.time_window {
+            Some(time_window) => time_window,
+            None => return,
+        };
+
+        let mut expired = false;
+        while let Some((_, cached)) = self.cache.peek_lru() {
+            if now.duration_since(cached.received) < time_window {
+                break;
+            }
+            let (_, evicted) = self.cache.pop_lru().expect("cache is not empty");
+            self.cache_bytes -= evicted.size;
+            expired = true;
+        }
+        if expired {
+            self.report(now);
         }
     }
 }
@@ -272,7 +468,11 @@ impl TaskTransform<Event> for Dedupe {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::BTreeMap;
+    use std::{
+        collections::BTreeMap,
+        num::NonZeroUsize,
+        time::{Duration, Instant},
+    };
 
     use tokio::sync::mpsc;
     use tokio_stream::wrappers::ReceiverStream;
@@ -281,7 +481,7 @@ mod tests {
         event::{Event, LogEvent, Value},
         test_util::components::assert_transform_compliance,
         transforms::{
-            dedupe::{CacheConfig, DedupeConfig, FieldMatchConfig},
+            dedupe::{CacheConfig, Dedupe, DedupeConfig, FieldMatchConfig},
             test::create_topology,
         },
     };
@@ -295,6 +495,9 @@ mod tests {
         DedupeConfig {
             cache: CacheConfig {
                 num_events: std::num::NonZeroUsize::new(num_events).expect("non-zero num_events"),
+                time_window_secs: None,
+                max_bytes: None,
+                hash_fields: false,
             },
             fields: Some(FieldMatchConfig::MatchFields(fields)),
         }
@@ -308,6 +511,9 @@ mod tests {
         DedupeConfig {
             cache: CacheConfig {
                 num_events: std::num::NonZeroUsize::new(num_events).expect("non-zero num_events"),
+                time_window_secs: None,
+                max_bytes: None,
+                hash_fields: false,
             },
             fields: Some(FieldMatchConfig::IgnoreFields(fields)),
         }
@@ -642,4 +848,104 @@ mod tests {
         })
         .await;
     }
+
+    fn event(matched: &str) -> Event {
+        let mut event = Event::Log(LogEvent::from("message"));
+        event.as_mut_log().insert("matched", matched);
+        event
+    }
+
+    #[test]
+    fn time_window() {
+        let mut config = make_match_transform_config(100, vec!["matched".into()]);
+        config.cache.time_window_secs = Some(60);
+        let mut dedupe = Dedupe::new(config);
+        let start = Instant::now();
+
+        assert!(dedupe.transform_one_at(event("a"), start).is_some());
+        assert!(dedupe
+            .transform_one_at(event("a"), start + Duration::from_secs(30))
+            .is_none());
+        assert!(dedupe
+            .transform_one_at(event("b"), start + Duration::from_secs(45))
+            .is_some());
+
+        // Duplicates don't extend the window of the first event.
+        assert!(dedupe
+            .transform_one_at(event("a"), start + Duration::from_secs(60))
+            .is_some());
+        assert!(dedupe
+            .transform_one_at(event("b"), start + Duration::from_secs(60))
+            .is_none());
+        assert_eq!(dedupe.cache.len(), 2);
+    }
+
+    #[test]
+    fn max_bytes() {
+        let mut config = make_match_transform_config(100, vec!["matched".into()]);
+        let mut dedupe = Dedupe::new(config.clone());
+        let now = Instant::now();
+        dedupe.transform_one_at(event("a"), now);
+        let entry_bytes = dedupe.cache_bytes;
+
+        config.cache.max_bytes = NonZeroUsize::new(entry_bytes * 2);
+        let mut dedupe = Dedupe::new(config);
+        for matched in ["a", "b", "c"] {
+            assert!(dedupe.transform_one_at(event(matched), now).is_some());
+        }
+        assert_eq!(dedupe.cache.len(), 2);
+        assert_eq!(dedupe.cache_bytes, entry_bytes * 2);
+
+        // The oldest event was removed to make room for the others.
+        assert!(dedupe.transform_one_at(event("a"), now).is_some());
+        assert!(dedupe.transform_one_at(event("c"), now).is_none());
+    }
+
+    #[test]
+    fn reports_cache_size_periodically() {
+        let mut config = make_match_transform_config(100, vec!["matched".into()]);
+        config.cache.time_window_secs = Some(60);
+        let mut dedupe = Dedupe::new(config);
+        let start = Instant::now();
+
+        dedupe.transform_one_at(event("a"), start);
+        let entry_bytes = dedupe.cache_bytes;
+        assert_eq!(dedupe.last_report, Some((1, entry_bytes, start)));
+
+        // Changes within the report interval are not reported.
+        dedupe.transform_one_at(event("b"), start);
+        assert_eq!(dedupe.last_report, Some((1, entry_bytes, start)));
+
+        let later = start + REPORT_INTERVAL;
+        dedupe.transform_one_at(event("c"), later);
+        assert_eq!(dedupe.last_report, Some((3, entry_bytes * 3, later)));
+
+        // The cache becoming empty is reported right away.
+        let expired = start + Duration::from_secs(120);
+        dedupe.expire(expired);
+        assert_eq!(dedupe.last_report, Some((0, 0, expired)));
+    }
+
+    #[test]
+    fn hash_fields() {
+        let mut config = make_ignore_transform_config(100, vec![]);
+        config.cache.hash_fields = true;
+        let mut dedupe = Dedupe::new(config.clone());
+        let mut other = Dedupe::new(config);
+        let now = Instant::now();
+
+        assert!(dedupe.transform_one_at(event("a"), now).is_some());
+        assert!(dedupe.transform_one_at(event("a"), now).is_none());
+        assert!(dedupe.transform_one_at(event("b"), now).is_some());
+
+        // Hashes only depend on the fields of events.
+        other.transform_one_at(event("a"), now);
+        assert_eq!(
+            dedupe
+                .cache
+                .peek_lru()
+                .map(|(entry, _)| entry.stable_hash()),
+            other.cache.peek_lru().map(|(entry, _)| entry.stable_hash())
+        );
+    }
 }
@@ -53,6 +53,10 @@ impl TransformConfig for FilterConfig {
     fn enable_concurrency(&self) -> bool {
         true
     }
+
+    fn conditions(&self) -> Vec<(String, &AnyCondition)> {
+        vec![("condition".to_owned(), &self.condition)]
+    }
 }
 
 #[derive(Clone)]
@@ -0,0 +1,482 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+use vector_config::configurable_component;
+use vector_core::transform::{SyncTransform, TransformOutputsBuf};
+
+use crate::{
+    config::{DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext},
+    event::{discriminant::Discriminant, Event, LogEvent, Value},
+    internal_events::{JoinMissingKey, JoinTableSize},
+    schema,
+    transforms::Transform,
+};
+
+const UNMATCHED_OUTPUT: &str = "unmatched";
+
+/// Configuration for the `join` transform.
+#[configurable_component(transform("join"))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JoinConfig {
+    /// The inputs whose events are the left side of the join.
+    ///
+    /// Their events are enriched with the entry of the join table matching their key. Each of
+    /// them must also be listed in `inputs`.
+    #[configurable(metadata(docs::examples = "firewall", docs::examples = "my_route.my_output",))]
+    pub left_inputs: Vec<String>,
+
+    /// The inputs whose events are the right side of the join.
+    ///
+    /// Their events fill the join table, and are not forwarded. Each of them must also be listed
+    /// in `inputs`.
+    #[configurable(metadata(docs::examples = "dhcp", docs::examples = "my_route.my_output",))]
+    pub right_inputs: Vec<String>,
+
+    /// The fields forming the join key of the events of the left side.
+    #[configurable(metadata(docs::examples = "src_ip", docs::examples = "session_id",))]
+    pub left_key: Vec<String>,
+
+    /// The fields forming the join key of the events of the right side.
+    ///
+    /// They are compared, in order, with the fields of `left_key`. Defaults to `left_key`.
+    #[configurable(metadata(docs::examples = "ip", docs::examples = "session_id",))]
+    pub right_key: Option<Vec<String>>,
+
+    /// The fields of the events of the right side to store in the join table, and to insert into
+    /// the matching events of the left side.
+    ///
+    /// Fields of the left side with the same names are overwritten.
+    #[configurable(metadata(docs::examples = "hostname", docs::examples = "user.name",))]
+    pub fields: Vec<String>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub mode: JoinMode,
+
+    /// How long an entry of the join table is kept after it was last updated, in seconds.
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+
+    /// The maximum number of entries in the join table.
+    ///
+    /// When the table is full, the least recently used entry is removed to make room for new ones.
+    #[serde(default = "default_max_entries")]
+    pub max_entries: NonZeroUsize,
+}
+
+/// How events of the left side without a matching entry are handled.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum JoinMode {
+    /// Only the events with a matching entry are forwarded.
+    ///
+    /// The others are sent to the `unmatched` output.
+    Inner,
+
+    /// All the events are forwarded, whether they have a matching entry or not.
+    #[derivative(Default)]
+    Left,
+}
+
+/// The minimum interval between reports of the size of the join table.
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+const fn default_ttl_secs() -> u64 {
+    3600
+}
+
+fn default_max_entries() -> NonZeroUsize {
+    NonZeroUsize::new(100_000).expect("static non-zero number")
+}
+
+impl GenerateConfig for JoinConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"left_inputs = ["firewall"]
+right_inputs = ["dhcp"]
+left_key = ["src_ip"]
+right_key = ["ip"]
+fields = ["hostname"]"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+impl TransformConfig for JoinConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::synchronous(Join::new(self)))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn validate(&self, _: &schema::Definition) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.left_inputs.is_empty() {
+            errors.push("`left_inputs` must not be empty".to_owned());
+        }
+        if self.right_inputs.is_empty() {
+            errors.push("`right_inputs` must not be empty".to_owned());
+        }
+        for input in &self.right_inputs {
+            if self.left_inputs.contains(input) {
+                errors.push(format!(
+                    "`{}` is listed in both `left_inputs` and `right_inputs`",
+                    input
+                ));
+            }
+        }
+        if self.left_key.is_empty() {
+            errors.push("`left_key` must not be empty".to_owned());
+        }
+        if let Some(right_key) = &self.right_key {
+            if right_key.len() != self.left_key.len() {
+                errors.push(format!(
+                    "`right_key` has {} fields, but `left_key` has {}",
+                    right_key.len(),
+                    self.left_key.len()
+                ));
+            }
+        }
+        if self.fields.is_empty() {
+            errors.push("`fields` must not be empty".to_owned());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn outputs(&self, merged_definition: &schema::Definition) -> Vec<Output> {
+        let mut outputs = vec![Output::default(DataType::Log)];
+        if self.mode == JoinMode::Inner {
+            outputs.push(
+                Output::default(DataType::Log)
+                    .with_schema_definition(merged_definition.clone())
+                    .with_port(UNMATCHED_OUTPUT),
+            );
+        }
+        outputs
+    }
+}
+
+#[derive(Debug)]
+struct TableEntry {
+    fields: Vec<(String, Value)>,
+    expires_at: Instant,
+}
+
+#[derive(Clone)]
+pub struct Join {
+    right_inputs: Vec<String>,
+    left_key: Vec<String>,
+    right_key: Vec<String>,
+    fields: Vec<String>,
+    mode: JoinMode,
+    ttl: Duration,
+    /// The join table, shared by the clones of the transform.
+    table: Arc<Mutex<LruCache<Discriminant, TableEntry>>>,
+    /// The size of the join table last reported by this clone, and when.
+    last_report: Option<(usize, Instant)>,
+}
+
+impl Join {
+    pub fn new(config: &JoinConfig) -> Self {
+        Self {
+            right_inputs: config.right_inputs.clone(),
+            left_key: config.left_key.clone(),
+            right_key: config
+                .right_key
+                .clone()
+                .unwrap_or_else(|| config.left_key.clone()),
+            fields: config.fields.clone(),
+            mode: config.mode,
+            ttl: Duration::from_secs(config.ttl_secs),
+            table: Arc::new(Mutex::new(LruCache::new(config.max_entries))),
+            last_report: None,
+        }
+    }
+
+    fn process(&mut self, event: Event, now: Instant, output: &mut TransformOutputsBuf) {
+        let right = event.metadata().upstream_id().map_or(false, |upstream_id| {
+            self.right_inputs.iter().any(|input| input == upstream_id)
+        });
+        let mut log = event.into_log();
+
+        if right {
+            self.store(&log, now);
+        } else if self.enrich(&mut log, now) || self.mode == JoinMode::Left {
+            output.push(Event::Log(log));
+        } else {
+            output.push_named(UNMATCHED_OUTPUT, Event::Log(log));
+        }
+    }
+
+    /// Stores the fields of an event of the right side in the join table.
+    fn store(&mut self, log: &LogEvent, now: Instant) {
+        let key = match key(log, &self.right_key) {
+            Some(key) => key,
+            None => {
+                emit!(JoinMissingKey);
+                return;
+            }
+        };
+
+        let fields = self
+            .fields
+            .iter()
+            .filter_map(|field| {
+                log.get(field.as_str())
+                    .map(|value| (field.clone(), value.clone()))
+            })
+            .collect();
+        let entries = {
+            let mut table = self.table.lock().expect("join table lock poisoned");
+            table.put(
+                key,
+                TableEntry {
+                    fields,
+                    expires_at: now + self.ttl,
+                },
+            );
+            table.len()
+        };
+        self.report(entries, now);
+    }
+
+    /// Inserts the fields of the matching entry of the join table into an event of the left side,
+    /// and returns whether there was one.
+    fn enrich(&mut self, log: &mut LogEvent, now: Instant) -> bool {
+        let key = match key(log, &self.left_key) {
+            Some(key) => key,
+            None => return false,
+        };
+
+        let entries = {
+            let mut table = self.table.lock().expect("join table lock poisoned");
+            match table.get(&key) {
+                Some(entry) if entry.expires_at > now => {
+                    for (field, value) in &entry.fields {
+                        log.insert(field.as_str(), value.clone());
+                    }
+                    return true;
+                }
+                Some(_) => {}
+                None => return false,
+            }
+
+            // The entry expired.
+            table.pop(&key);
+            table.len()
+        };
+        self.report(entries, now);
+        false
+    }
+
+    /// Reports the size of the join table when it changed, at most once per report interval.
+    fn report(&mut self, entries: usize, now: Instant) {
+        let due = match self.last_report {
+            Some((reported, _)) if reported == entries => false,
+            Some((_, at)) => now.duration_since(at) >= REPORT_INTERVAL,
+            None => true,
+        };
+        if due {
+            self.last_report = Some((entries, now));
+            emit!(JoinTableSize { entries });
+        }
+    }
+}
+
+/// Returns the join key of an event, unless it is missing some of the key fields.
+fn key(log: &LogEvent, fields: &[String]) -> Option<Discriminant> {
+    fields
+        .iter()
+        .all(|field| log.contains(field.as_str()))
+        .then(|| Discriminant::from_log_event(log, fields))
+}
+
+impl SyncTransform for Join {
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        self.process(event, Instant::now(), output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<JoinConfig>();
+    }
+
+    fn join(config: &str) -> (Join, TransformOutputsBuf) {
+        let config = toml::from_str::<JoinConfig>(&format!(
+            r#"left_inputs = ["firewall"]
+right_inputs = ["dhcp"]
+left_key = ["src_ip"]
+right_key = ["ip"]
+fields = ["hostname"]
+{}"#,
+            config
+        ))
+        .unwrap();
+        let outputs =
+            TransformOutputsBuf::new_with_capacity(config.outputs(&schema::Definition::any()), 1);
+        (Join::new(&config), outputs)
+    }
+
+    fn from_upstream(upstream_id: &str, log: LogEvent) -> Event {
+        let mut event = Event::Log(log);
+        event.metadata_mut().set_upstream_id(Arc::from(upstream_id));
+        event
+    }
+
+    fn lease(ip: &str, hostname: &str) -> Event {
+        let mut log = LogEvent::default();
+        log.insert("ip", ip);
+        log.insert("hostname", hostname);
+        from_upstream("dhcp", log)
+    }
+
+    fn connection(src_ip: &str) -> Event {
+        let mut log = LogEvent::from("connection");
+        log.insert("src_ip", src_ip);
+        from_upstream("firewall", log)
+    }
+
+    fn hostnames(events: impl Iterator<Item = Event>) -> Vec<Option<String>> {
+        events
+            .map(|event| {
+                event
+                    .as_log()
+                    .get("hostname")
+                    .map(|hostname| hostname.to_string_lossy().into_owned())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn left_join() {
+        let (mut join, mut output) = join("");
+        let now = Instant::now();
+
+        join.process(lease("10.0.0.1", "laptop"), now, &mut output);
+        join.process(connection("10.0.0.1"), now, &mut output);
+        join.process(connection("10.0.0.2"), now, &mut output);
+        join.process(lease("10.0.0.1", "desktop"), now, &mut output);
+        join.process(connection("10.0.0.1"), now, &mut output);
+
+        assert_eq!(
+            hostnames(output.drain()),
+            [Some("laptop".into()), None, Some("desktop".into())]
+        );
+    }
+
+    #[test]
+    fn inner_join() {
+        let (mut join, mut output) = join("mode = \"inner\"");
+        let now = Instant::now();
+
+        join.process(lease("10.0.0.1", "laptop"), now, &mut output);
+        join.process(connection("10.0.0.1"), now, &mut output);
+        join.process(connection("10.0.0.2"), now, &mut output);
+
+        assert_eq!(hostnames(output.drain()), [Some("laptop".into())]);
+        let unmatched = output.drain_named(UNMATCHED_OUTPUT).collect::<Vec<_>>();
+        assert_eq!(unmatched, vec![connection("10.0.0.2")]);
+    }
+
+    #[test]
+    fn sides_are_selected_by_upstream() {
+        let (mut join, mut output) = join("");
+        let now = Instant::now();
+
+        // Events from other upstreams, or sent outside of the topology, are the left side.
+        let mut lease_from_firewall = LogEvent::default();
+        lease_from_firewall.insert("ip", "10.0.0.1");
+        lease_from_firewall.insert("hostname", "laptop");
+        join.process(
+            from_upstream("firewall", lease_from_firewall.clone()),
+            now,
+            &mut output,
+        );
+        join.process(Event::Log(lease_from_firewall), now, &mut output);
+        join.process(connection("10.0.0.1"), now, &mut output);
+
+        assert_eq!(
+            hostnames(output.drain()),
+            [Some("laptop".into()), Some("laptop".into()), None]
+        );
+        assert_eq!(join.table.lock().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn ttl_and_max_entries() {
+        let (mut join, mut output) = join("ttl_secs = 60\nmax_entries = 2");
+        let start = Instant::now();
+
+        join.process(lease("10.0.0.1", "a"), start, &mut output);
+        join.process(lease("10.0.0.2", "b"), start, &mut output);
+        join.process(lease("10.0.0.3", "c"), start, &mut output);
+        join.process(connection("10.0.0.1"), start, &mut output);
+        join.process(connection("10.0.0.3"), start, &mut output);
+        join.process(
+            connection("10.0.0.3"),
+            start + Duration::from_secs(61),
+            &mut output,
+        );
+
+        assert_eq!(hostnames(output.drain()), [None, Some("c".into()), None]);
+        assert_eq!(join.table.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn reports_table_size_periodically() {
+        let (mut join, mut output) = join("");
+        let start = Instant::now();
+
+        join.process(lease("10.0.0.1", "a"), start, &mut output);
+        assert_eq!(join.last_report, Some((1, start)));
+
+        // Changes within the report interval are not reported.
+        join.process(lease("10.0.0.2", "b"), start, &mut output);
+        assert_eq!(join.last_report, Some((1, start)));
+
+        let later = start + REPORT_INTERVAL;
+        join.process(lease("10.0.0.3", "c"), later, &mut output);
+        assert_eq!(join.last_report, Some((3, later)));
+    }
+
+    #[test]
+    fn validate() {
+        let config = toml::from_str::<JoinConfig>(
+            r#"left_inputs = ["firewall"]
+right_inputs = ["dhcp", "firewall"]
+left_key = ["src_ip", "vlan"]
+right_key = ["ip"]
+fields = []"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.validate(&schema::Definition::any()),
+            Err(vec![
+                "`firewall` is listed in both `left_inputs` and `right_inputs`".to_owned(),
+                "`right_key` has 1 fields, but `left_key` has 2".to_owned(),
+                "`fields` must not be empty".to_owned(),
+            ])
+        );
+    }
+}
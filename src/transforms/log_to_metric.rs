@@ -2,6 +2,7 @@ use std::num::ParseFloatError;
 
 use chrono::Utc;
 use indexmap::IndexMap;
+use snafu::Snafu;
 use vector_config::configurable_component;
 
 use crate::{
@@ -9,11 +10,12 @@ use crate::{
         log_schema, DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
     },
     event::{
-        metric::{Metric, MetricKind, MetricTags, MetricValue, StatisticKind},
+        metric::{samples_to_buckets, Metric, MetricKind, MetricTags, MetricValue, StatisticKind},
         Event, Value,
     },
     internal_events::{
-        LogToMetricFieldNullError, LogToMetricParseFloatError, ParserMissingFieldError, DROP_EVENT,
+        LogToMetricFieldNullError, LogToMetricParseError, LogToMetricParseFloatError,
+        ParserMissingFieldError, DROP_EVENT,
     },
     schema,
     template::{Template, TemplateRenderingError},
@@ -26,7 +28,20 @@ use crate::{
 #[serde(deny_unknown_fields)]
 pub struct LogToMetricConfig {
     /// A list of metrics to generate.
+    #[serde(default)]
     pub metrics: Vec<MetricConfig>,
+
+    /// Converts logs which are already shaped like metrics into metrics, instead of generating
+    /// the metrics listed in `metrics`.
+    ///
+    /// The logs must have the fields of the metrics output by the `metric_to_log` transform: a
+    /// `name`, and optionally a `namespace` and `tags`, a `kind` of `absolute` or `incremental`,
+    /// and a field named after the type of the metric holding its value, such as
+    /// `counter.value`, `gauge.value`, `set.values`, `distribution.samples`,
+    /// `aggregated_histogram.buckets`, or `aggregated_summary.quantiles`. The timestamp of the
+    /// metrics is read from the timestamp field of the logs.
+    #[serde(default = "crate::serde::default_false")]
+    pub all_metrics: bool,
 }
 
 /// Specification of a counter derived from a log event.
@@ -42,6 +57,19 @@ pub struct CounterConfig {
     pub kind: MetricKind,
 }
 
+/// Specification of a histogram derived from a log event.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+pub struct HistogramConfig {
+    /// The upper limits of the buckets of the histogram, in increasing order.
+    ///
+    /// When set, the value in `field` is counted in an aggregated histogram with these buckets.
+    /// Otherwise, it is added as a sample to a distribution.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = 0.1, docs::examples = 1.0))]
+    pub buckets: Vec<f64>,
+}
+
 /// Specification of a metric derived from a log event.
 // TODO: While we're resolving the schema for this enum somewhat reasonably (in
 // `generate-components-docs.rb`), we have a problem where an overlapping field (overlap between two
@@ -63,6 +91,10 @@ pub struct MetricConfig {
     pub namespace: Option<Template>,
 
     /// Tags to apply to the metric.
+    ///
+    /// A tag name ending with `*` expands into one tag for each key of the object field referenced
+    /// by its template, named after the key prefixed with the rest of the tag name. For example,
+    /// `"label_*" = "{{ labels }}"` adds a `label_<key>` tag for every key under `labels`.
     pub tags: Option<IndexMap<String, Template>>,
 
     #[configurable(derived)]
@@ -79,7 +111,7 @@ pub enum MetricTypeConfig {
     Counter(#[configurable(derived)] CounterConfig),
 
     /// A histogram.
-    Histogram,
+    Histogram(#[configurable(derived)] HistogramConfig),
 
     /// A gauge.
     Gauge,
@@ -105,6 +137,21 @@ const fn default_kind() -> MetricKind {
     MetricKind::Incremental
 }
 
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`metrics` can't be set together with `all_metrics`"))]
+    MetricsWithAllMetrics,
+
+    #[snafu(display(
+        "Tag {:?} must reference a single field to expand, as in \"{{{{ labels }}}}\"",
+        tag
+    ))]
+    InvalidTagExpansion { tag: String },
+
+    #[snafu(display("The buckets of histogram {:?} must be in increasing order", field))]
+    UnsortedBuckets { field: String },
+}
+
 #[derive(Debug, Clone)]
 pub struct LogToMetric {
     config: LogToMetricConfig,
@@ -123,6 +170,7 @@ impl GenerateConfig for LogToMetricConfig {
                     kind: MetricKind::Incremental,
                 }),
             }],
+            all_metrics: false,
         })
         .unwrap()
     }
@@ -131,6 +179,7 @@ impl GenerateConfig for LogToMetricConfig {
 #[async_trait::async_trait]
 impl TransformConfig for LogToMetricConfig {
     async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        self.validate()?;
         Ok(Transform::function(LogToMetric::new(self.clone())))
     }
 
@@ -147,6 +196,32 @@ impl TransformConfig for LogToMetricConfig {
     }
 }
 
+impl LogToMetricConfig {
+    fn validate(&self) -> Result<(), BuildError> {
+        if self.all_metrics && !self.metrics.is_empty() {
+            return Err(BuildError::MetricsWithAllMetrics);
+        }
+
+        for metric in &self.metrics {
+            for (tag, template) in metric.tags.iter().flatten() {
+                let fields = template.get_fields().unwrap_or_default();
+                if tag.ends_with('*') && fields.len() != 1 {
+                    return Err(BuildError::InvalidTagExpansion { tag: tag.clone() });
+                }
+            }
+
+            if let MetricTypeConfig::Histogram(histogram) = &metric.metric {
+                if histogram.buckets.windows(2).any(|pair| pair[0] >= pair[1]) {
+                    return Err(BuildError::UnsortedBuckets {
+                        field: metric.field().to_owned(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 impl LogToMetric {
     pub const fn new(config: LogToMetricConfig) -> Self {
         LogToMetric { config }
@@ -165,6 +240,9 @@ enum TransformError {
         field: String,
         error: ParseFloatError,
     },
+    ParseMetricError {
+        error: serde_json::Error,
+    },
 }
 
 fn render_template(template: &Template, event: &Event) -> Result<String, TransformError> {
@@ -182,6 +260,11 @@ fn render_tags(
         Some(tags) => {
             let mut map = MetricTags::default();
             for (name, template) in tags {
+                if name.ends_with('*') {
+                    expand_tags(name, template, event, &mut map);
+                    continue;
+                }
+
                 match render_template(template, event) {
                     Ok(tag) => {
                         map.insert(name.to_string(), tag);
@@ -201,6 +284,58 @@ fn render_tags(
     })
 }
 
+/// Adds a tag for each key of the object field referenced by `template`, named after the key
+/// prefixed with `name` without its trailing `*`. Fields which aren't objects are ignored.
+fn expand_tags(name: &str, template: &Template, event: &Event, tags: &mut MetricTags) {
+    let prefix = name.trim_end_matches('*');
+    // The template was validated to reference a single field.
+    let field = match template.get_fields() {
+        Some(fields) => fields.into_iter().next().unwrap_or_default(),
+        None => return,
+    };
+
+    match event.as_log().get(field.as_str()) {
+        Some(Value::Object(object)) => {
+            for (key, value) in object {
+                if !matches!(value, Value::Null) {
+                    tags.insert(
+                        format!("{}{}", prefix, key),
+                        value.to_string_lossy().into_owned(),
+                    );
+                }
+            }
+        }
+        Some(_) => {}
+        None => emit!(crate::internal_events::TemplateRenderingError {
+            error: TemplateRenderingError::MissingKeys {
+                missing_keys: vec![field],
+            },
+            drop_event: false,
+            field: Some(name),
+        }),
+    }
+}
+
+/// Converts a log shaped like a metric, as output by the `metric_to_log` transform, into a metric.
+fn log_to_metric(event: &Event) -> Result<Metric, TransformError> {
+    let log = event.as_log();
+
+    let mut value = serde_json::to_value(log.value())
+        .map_err(|error| TransformError::ParseMetricError { error })?;
+    let timestamp_key = log_schema().timestamp_key();
+    if let Some(object) = value.as_object_mut() {
+        if let Some(timestamp) = object.remove(timestamp_key) {
+            object.insert("timestamp".to_owned(), timestamp);
+        }
+    }
+
+    let metric = serde_json::from_value::<Metric>(value)
+        .map_err(|error| TransformError::ParseMetricError { error })?;
+    let timestamp = metric.timestamp().or_else(|| Some(Utc::now()));
+    let (series, data, _) = metric.into_parts();
+    Ok(Metric::from_parts(series, data, event.metadata().clone()).with_timestamp(timestamp))
+}
+
 fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformError> {
     let log = event.as_log();
 
@@ -248,21 +383,30 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
 
             (counter.kind, MetricValue::Counter { value })
         }
-        MetricTypeConfig::Histogram => {
+        MetricTypeConfig::Histogram(histogram) => {
             let value = value.to_string_lossy().parse().map_err(|error| {
                 TransformError::ParseFloatError {
                     field: field.to_string(),
                     error,
                 }
             })?;
+            let samples = vector_core::samples![value => 1];
 
-            (
-                MetricKind::Incremental,
+            let value = if histogram.buckets.is_empty() {
                 MetricValue::Distribution {
-                    samples: vector_core::samples![value => 1],
+                    samples,
                     statistic: StatisticKind::Histogram,
-                },
-            )
+                }
+            } else {
+                let (buckets, count, sum) = samples_to_buckets(&samples, &histogram.buckets);
+                MetricValue::AggregatedHistogram {
+                    buckets,
+                    count,
+                    sum,
+                }
+            };
+
+            (MetricKind::Incremental, value)
         }
         MetricTypeConfig::Summary => {
             let value = value.to_string_lossy().parse().map_err(|error| {
@@ -307,8 +451,43 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
         .with_timestamp(timestamp))
 }
 
+fn emit_error(err: TransformError) {
+    match err {
+        TransformError::FieldNull { field } => emit!(LogToMetricFieldNullError {
+            field: field.as_ref()
+        }),
+        TransformError::FieldNotFound { field } => {
+            emit!(ParserMissingFieldError::<DROP_EVENT> {
+                field: field.as_ref()
+            })
+        }
+        TransformError::ParseFloatError { field, error } => {
+            emit!(LogToMetricParseFloatError {
+                field: field.as_ref(),
+                error
+            })
+        }
+        TransformError::ParseMetricError { error } => emit!(LogToMetricParseError { error }),
+        TransformError::TemplateRenderingError(error) => {
+            emit!(crate::internal_events::TemplateRenderingError {
+                error,
+                drop_event: true,
+                field: None,
+            })
+        }
+    };
+}
+
 impl FunctionTransform for LogToMetric {
     fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
+        if self.config.all_metrics {
+            match log_to_metric(&event) {
+                Ok(metric) => output.push(Event::Metric(metric)),
+                Err(err) => emit_error(err),
+            }
+            return;
+        }
+
         // Metrics are "all or none" for a specific log. If a single fails, none are produced.
         let mut buffer = Vec::with_capacity(self.config.metrics.len());
 
@@ -318,29 +497,7 @@ impl FunctionTransform for LogToMetric {
                     buffer.push(Event::Metric(metric));
                 }
                 Err(err) => {
-                    match err {
-                        TransformError::FieldNull { field } => emit!(LogToMetricFieldNullError {
-                            field: field.as_ref()
-                        }),
-                        TransformError::FieldNotFound { field } => {
-                            emit!(ParserMissingFieldError::<DROP_EVENT> {
-                                field: field.as_ref()
-                            })
-                        }
-                        TransformError::ParseFloatError { field, error } => {
-                            emit!(LogToMetricParseFloatError {
-                                field: field.as_ref(),
-                                error
-                            })
-                        }
-                        TransformError::TemplateRenderingError(error) => {
-                            emit!(crate::internal_events::TemplateRenderingError {
-                                error,
-                                drop_event: true,
-                                field: None,
-                            })
-                        }
-                    };
+                    emit_error(err);
                     // early return to prevent the partial buffer from being sent
                     return;
                 }
@@ -862,4 +1019,138 @@ mod tests {
             .with_timestamp(Some(ts()))
         );
     }
+
+    #[tokio::test]
+    async fn response_time_histogram_buckets() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "histogram"
+            field = "response_time"
+            buckets = [1.0, 2.5, 5.0]
+            "#,
+        );
+
+        let event = create_event("response_time", "2.5");
+        let metadata = event.metadata().clone();
+        let metric = do_transform(config, event).await.unwrap();
+
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new_with_metadata(
+                "response_time",
+                MetricKind::Incremental,
+                MetricValue::AggregatedHistogram {
+                    buckets: vector_core::buckets![1.0 => 0, 2.5 => 1, 5.0 => 0],
+                    count: 1,
+                    sum: 2.5,
+                },
+                metadata
+            )
+            .with_timestamp(Some(ts()))
+        );
+    }
+
+    #[tokio::test]
+    async fn expand_object_tags() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "counter"
+            field = "message"
+            tags = {"label_*" = "{{ labels }}", "*" = "{{ missing }}", host = "localhost"}
+            "#,
+        );
+
+        let mut event = create_event("message", "i am log");
+        event.as_mut_log().insert("labels.app", "web");
+        event.as_mut_log().insert("labels.tier", "frontend");
+        let metadata = event.metadata().clone();
+
+        let metric = do_transform(config, event).await.unwrap();
+
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new_with_metadata(
+                "message",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+                metadata,
+            )
+            .with_tags(Some(metric_tags!(
+                "label_app" => "web",
+                "label_tier" => "frontend",
+                "host" => "localhost",
+            )))
+            .with_timestamp(Some(ts()))
+        );
+    }
+
+    #[tokio::test]
+    async fn all_metrics() {
+        let config = parse_config("all_metrics = true");
+
+        let mut event = create_event("name", "http_requests_total");
+        event.as_mut_log().insert("namespace", "app");
+        event.as_mut_log().insert("kind", "absolute");
+        event.as_mut_log().insert("tags.code", "200");
+        event.as_mut_log().insert("counter.value", 42.0);
+        let metadata = event.metadata().clone();
+
+        let metric = do_transform(config, event).await.unwrap();
+
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new_with_metadata(
+                "http_requests_total",
+                MetricKind::Absolute,
+                MetricValue::Counter { value: 42.0 },
+                metadata,
+            )
+            .with_namespace(Some("app"))
+            .with_tags(Some(metric_tags!("code" => "200")))
+            .with_timestamp(Some(ts()))
+        );
+    }
+
+    #[tokio::test]
+    async fn all_metrics_invalid_log() {
+        let config = parse_config("all_metrics = true");
+
+        let event = create_event("name", "http_requests_total");
+        assert_eq!(do_transform(config, event).await, None);
+    }
+
+    #[test]
+    fn invalid_config() {
+        let config = parse_config(
+            r#"
+            all_metrics = true
+            [[metrics]]
+            type = "counter"
+            field = "status"
+            "#,
+        );
+        assert!(config.validate().is_err());
+
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "counter"
+            field = "status"
+            tags = {"label_*" = "static"}
+            "#,
+        );
+        assert!(config.validate().is_err());
+
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "histogram"
+            field = "response_time"
+            buckets = [2.5, 1.0]
+            "#,
+        );
+        assert!(config.validate().is_err());
+    }
 }
@@ -8,7 +8,7 @@ use vector_core::transform::runtime_transform::{RuntimeTransform, Timer};
 
 use crate::schema::Definition;
 use crate::{
-    config::{self, DataType, Input, Output, CONFIG_PATHS},
+    config::{self, DataType, Input, Output},
     event::Event,
     internal_events::{LuaBuildError, LuaGcTriggered},
     schema,
@@ -76,20 +76,7 @@ pub struct LuaConfig {
 }
 
 fn default_config_paths() -> Vec<PathBuf> {
-    match CONFIG_PATHS.lock().ok() {
-        Some(config_paths) => config_paths
-            .clone()
-            .into_iter()
-            .map(|config_path| match config_path {
-                config::ConfigPath::File(mut path, _format) => {
-                    path.pop();
-                    path
-                }
-                config::ConfigPath::Dir(path) => path,
-            })
-            .collect(),
-        None => vec![],
-    }
+    config::config_dirs()
 }
 
 /// Lifecycle hooks.
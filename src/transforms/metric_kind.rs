@@ -0,0 +1,356 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use vector_config::configurable_component;
+
+use crate::{
+    config::{DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext},
+    event::{
+        metric::{MetricKind, MetricSeries, MetricValue},
+        Event, Metric,
+    },
+    internal_events::{MetricKindCounterReset, MetricKindSeriesExpired},
+    schema,
+    sinks::util::buffer::metrics::MetricSet,
+    transforms::{FunctionTransform, OutputBuffer, Transform},
+};
+
+/// Configuration for the `metric_kind` transform.
+#[configurable_component(transform("metric_kind"))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MetricKindConfig {
+    #[configurable(derived)]
+    pub mode: ConversionMode,
+
+    /// How long to keep the state of a series that is no longer received, in seconds.
+    ///
+    /// Once a series has not been received for this long, it starts over as if it was new: absolute
+    /// values are used as the new reference for increments, accumulated values restart from zero,
+    /// and rates are computed from the next value.
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+}
+
+/// The conversion applied to metrics.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConversionMode {
+    /// Converts absolute metrics to incremental ones, by subtracting the previous value of their series.
+    ///
+    /// The first value of a series is only used as a reference, and is not output. When an absolute
+    /// counter decreases, it is considered to have been reset, and its new value is output as the
+    /// increment.
+    Incremental,
+
+    /// Converts incremental metrics to absolute ones, by accumulating the values of their series.
+    Absolute,
+
+    /// Converts counters to absolute gauges of their rate of increase, per second.
+    ///
+    /// The rate is computed from the timestamps of the metrics, or from the time they are received
+    /// when they have no timestamp. The first value of a series is not output. Metrics which are not
+    /// counters are passed through unchanged.
+    Rate,
+}
+
+const fn default_ttl_secs() -> u64 {
+    300
+}
+
+impl GenerateConfig for MetricKindConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            mode: ConversionMode::Incremental,
+            ttl_secs: default_ttl_secs(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+impl TransformConfig for MetricKindConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::function(ConvertKind::new(self)))
+    }
+
+    fn input(&self) -> Input {
+        Input::metric()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Metric)]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct SeriesState {
+    last_seen: Instant,
+    timestamp: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct ConvertKind {
+    mode: ConversionMode,
+    ttl: Duration,
+    state: MetricSet,
+    series: HashMap<MetricSeries, SeriesState>,
+    last_sweep: Instant,
+}
+
+impl ConvertKind {
+    pub fn new(config: &MetricKindConfig) -> Self {
+        Self {
+            mode: config.mode,
+            ttl: Duration::from_secs(config.ttl_secs),
+            state: MetricSet::default(),
+            series: HashMap::new(),
+            last_sweep: Instant::now(),
+        }
+    }
+
+    fn transform_one(&mut self, metric: Metric, now: Instant) -> Option<Metric> {
+        self.sweep(now);
+
+        let timestamp = metric.timestamp().unwrap_or_else(Utc::now);
+        let previous = self.series.insert(
+            metric.series().clone(),
+            SeriesState {
+                last_seen: now,
+                timestamp,
+            },
+        );
+        // A stale series starts over, as if it was never seen.
+        let previous = match previous {
+            Some(previous) if now.duration_since(previous.last_seen) > self.ttl => {
+                self.state.remove(metric.series());
+                None
+            }
+            previous => previous,
+        };
+
+        match self.mode {
+            ConversionMode::Incremental => self.make_incremental(metric),
+            ConversionMode::Absolute => self.state.make_absolute(metric),
+            ConversionMode::Rate => self.make_rate(
+                metric,
+                previous.map(|previous| previous.timestamp),
+                timestamp,
+            ),
+        }
+    }
+
+    fn make_incremental(&mut self, metric: Metric) -> Option<Metric> {
+        let absolute_counter = match (metric.kind(), metric.value()) {
+            (MetricKind::Absolute, MetricValue::Counter { value }) => Some(*value),
+            _ => None,
+        };
+
+        let metric = self.state.make_incremental(metric)?;
+        match (absolute_counter, metric.value()) {
+            (Some(current), MetricValue::Counter { value }) if *value < 0.0 => {
+                emit!(MetricKindCounterReset {
+                    series: metric.series()
+                });
+                // The counter started over from zero since its previous value.
+                Some(metric.with_value(MetricValue::Counter { value: current }))
+            }
+            _ => Some(metric),
+        }
+    }
+
+    fn make_rate(
+        &mut self,
+        metric: Metric,
+        previous: Option<DateTime<Utc>>,
+        timestamp: DateTime<Utc>,
+    ) -> Option<Metric> {
+        if !matches!(metric.value(), MetricValue::Counter { .. }) {
+            return Some(metric);
+        }
+
+        let increment = self.make_incremental(metric)?;
+        let elapsed = (timestamp - previous?).num_milliseconds() as f64 / 1000.0;
+        if elapsed <= 0.0 {
+            return None;
+        }
+
+        let value = match increment.value() {
+            MetricValue::Counter { value } => value / elapsed,
+            _ => unreachable!("increments of counters are counters"),
+        };
+        Some(
+            increment
+                .with_value(MetricValue::Gauge { value })
+                .into_absolute(),
+        )
+    }
+
+    /// Forgets the series which have not been received for longer than the TTL, at most once per
+    /// TTL, to bound the state kept for series which are never received again.
+    fn sweep(&mut self, now: Instant) {
+        if now.duration_since(self.last_sweep) < self.ttl {
+            return;
+        }
+        self.last_sweep = now;
+
+        let (ttl, state) = (self.ttl, &mut self.state);
+        let before = self.series.len();
+        self.series.retain(|series, series_state| {
+            let fresh = now.duration_since(series_state.last_seen) <= ttl;
+            if !fresh {
+                state.remove(series);
+            }
+            fresh
+        });
+
+        let count = before - self.series.len();
+        if count > 0 {
+            emit!(MetricKindSeriesExpired { count });
+        }
+    }
+}
+
+impl FunctionTransform for ConvertKind {
+    fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
+        if let Some(metric) = self.transform_one(event.into_metric(), Instant::now()) {
+            output.push(Event::Metric(metric));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MetricKindConfig>();
+    }
+
+    fn convert_kind(mode: ConversionMode) -> ConvertKind {
+        ConvertKind::new(&MetricKindConfig { mode, ttl_secs: 60 })
+    }
+
+    fn counter(kind: MetricKind, value: f64, secs: i64) -> Metric {
+        Metric::new("requests", kind, MetricValue::Counter { value })
+            .with_timestamp(Some(Utc.timestamp(secs, 0)))
+    }
+
+    fn values(converter: &mut ConvertKind, metrics: Vec<Metric>) -> Vec<(MetricKind, MetricValue)> {
+        let now = Instant::now();
+        metrics
+            .into_iter()
+            .filter_map(|metric| converter.transform_one(metric, now))
+            .map(|metric| (metric.kind(), metric.value().clone()))
+            .collect()
+    }
+
+    #[test]
+    fn absolute_to_incremental_with_reset() {
+        let mut converter = convert_kind(ConversionMode::Incremental);
+        let inputs = [10.0, 15.0, 22.0, 4.0, 6.0]
+            .into_iter()
+            .map(|value| counter(MetricKind::Absolute, value, 0))
+            .collect();
+
+        assert_eq!(
+            values(&mut converter, inputs),
+            [5.0, 7.0, 4.0, 2.0]
+                .into_iter()
+                .map(|value| (MetricKind::Incremental, MetricValue::Counter { value }))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn incremental_to_absolute() {
+        let mut converter = convert_kind(ConversionMode::Absolute);
+        let inputs = [1.0, 2.0, 3.0]
+            .into_iter()
+            .map(|value| counter(MetricKind::Incremental, value, 0))
+            .collect();
+
+        assert_eq!(
+            values(&mut converter, inputs),
+            [1.0, 3.0, 6.0]
+                .into_iter()
+                .map(|value| (MetricKind::Absolute, MetricValue::Counter { value }))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn rate() {
+        let mut converter = convert_kind(ConversionMode::Rate);
+        let inputs = vec![
+            counter(MetricKind::Absolute, 100.0, 0),
+            counter(MetricKind::Absolute, 120.0, 10),
+            counter(MetricKind::Absolute, 180.0, 20),
+            Metric::new(
+                "temperature",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 21.0 },
+            ),
+        ];
+
+        assert_eq!(
+            values(&mut converter, inputs),
+            vec![
+                (MetricKind::Absolute, MetricValue::Gauge { value: 2.0 }),
+                (MetricKind::Absolute, MetricValue::Gauge { value: 6.0 }),
+                (MetricKind::Absolute, MetricValue::Gauge { value: 21.0 }),
+            ]
+        );
+
+        let inputs = vec![
+            counter(MetricKind::Incremental, 5.0, 0).with_name("errors"),
+            counter(MetricKind::Incremental, 5.0, 2).with_name("errors"),
+        ];
+        assert_eq!(
+            values(&mut converter, inputs),
+            vec![(MetricKind::Absolute, MetricValue::Gauge { value: 2.5 })]
+        );
+    }
+
+    #[test]
+    fn stale_series_start_over() {
+        let mut converter = convert_kind(ConversionMode::Incremental);
+        let start = Instant::now();
+
+        let output = converter.transform_one(counter(MetricKind::Absolute, 10.0, 0), start);
+        assert_eq!(output, None);
+        let output = converter.transform_one(
+            counter(MetricKind::Absolute, 12.0, 0),
+            start + Duration::from_secs(30),
+        );
+        assert_eq!(
+            output.map(|metric| metric.value().clone()),
+            Some(MetricValue::Counter { value: 2.0 })
+        );
+
+        // After the TTL, the next value is a new reference rather than an increment.
+        let output = converter.transform_one(
+            counter(MetricKind::Absolute, 50.0, 0),
+            start + Duration::from_secs(100),
+        );
+        assert_eq!(output, None);
+        assert_eq!(converter.series.len(), 1);
+
+        // Series which are not received anymore are forgotten.
+        let output = converter.transform_one(
+            counter(MetricKind::Absolute, 1.0, 0).with_name("other"),
+            start + Duration::from_secs(200),
+        );
+        assert_eq!(output, None);
+        assert_eq!(converter.series.len(), 1);
+        assert_eq!(converter.state.len(), 1);
+    }
+}
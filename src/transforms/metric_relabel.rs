@@ -0,0 +1,519 @@
+use md5::{Digest, Md5};
+use regex::Regex;
+use snafu::{ResultExt, Snafu};
+use vector_config::configurable_component;
+
+use crate::{
+    config::{DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext},
+    event::{Event, Metric},
+    internal_events::MetricRelabelEventDropped,
+    schema,
+    transforms::{FunctionTransform, OutputBuffer, Transform},
+};
+
+/// The label standing for the name of metrics in relabeling rules.
+const NAME_LABEL: &str = "__name__";
+
+/// Configuration for the `metric_relabel` transform.
+#[configurable_component(transform("metric_relabel"))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MetricRelabelConfig {
+    /// The relabeling rules, applied in order.
+    ///
+    /// The rules follow the semantics of the Prometheus `relabel_config`, where labels are the tags of
+    /// metrics, and the `__name__` label is their name.
+    pub rules: Vec<RelabelRuleConfig>,
+}
+
+/// A relabeling rule.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RelabelRuleConfig {
+    /// The labels whose values are concatenated, with `separator`, and matched against `regex`.
+    ///
+    /// Missing labels have an empty value.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "__name__", docs::examples = "service"))]
+    pub source_labels: Vec<String>,
+
+    /// The separator placed between the values of `source_labels`.
+    #[serde(default = "default_separator")]
+    pub separator: String,
+
+    /// The label to which the result of the `replace` and `hashmod` actions is written.
+    ///
+    /// For the `replace` action, the capture groups of `regex` can be referenced, as in `replacement`.
+    #[configurable(metadata(docs::examples = "instance"))]
+    pub target_label: Option<String>,
+
+    /// The regular expression matched against the concatenated values of `source_labels`, or
+    /// against label names for the `labelmap`, `labeldrop`, and `labelkeep` actions.
+    ///
+    /// The regular expression is anchored at both ends.
+    #[serde(default = "default_regex")]
+    pub regex: String,
+
+    /// The modulus taken of the hash of the concatenated values of `source_labels`, for the `hashmod` action.
+    pub modulus: Option<u64>,
+
+    /// The value written by the `replace` action, or the name of the labels written by the `labelmap`
+    /// action.
+    ///
+    /// The capture groups of `regex` can be referenced as `$1` or `${1}`, or by name.
+    #[serde(default = "default_replacement")]
+    pub replacement: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub action: RelabelAction,
+}
+
+/// The action of a relabeling rule.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RelabelAction {
+    /// Writes `replacement` to `target_label` if `regex` matches the values of `source_labels`.
+    ///
+    /// If the result is empty, `target_label` is removed.
+    #[derivative(Default)]
+    Replace,
+
+    /// Drops the metrics for which `regex` doesn't match the values of `source_labels`.
+    Keep,
+
+    /// Drops the metrics for which `regex` matches the values of `source_labels`.
+    Drop,
+
+    /// Writes the hash of the values of `source_labels`, modulo `modulus`, to `target_label`.
+    Hashmod,
+
+    /// Copies the labels whose name matches `regex` to the labels named by `replacement`.
+    Labelmap,
+
+    /// Removes the labels whose name matches `regex`.
+    Labeldrop,
+
+    /// Removes the labels whose name doesn't match `regex`.
+    Labelkeep,
+}
+
+fn default_separator() -> String {
+    ";".to_owned()
+}
+
+fn default_regex() -> String {
+    "(.*)".to_owned()
+}
+
+fn default_replacement() -> String {
+    "$1".to_owned()
+}
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid regular expression {:?} in rule {}: {}", regex, index, source))]
+    InvalidRegex {
+        index: usize,
+        regex: String,
+        source: regex::Error,
+    },
+
+    #[snafu(display("Rule {} with action {:?} requires a `target_label`", index, action))]
+    MissingTargetLabel { index: usize, action: RelabelAction },
+
+    #[snafu(display("Rule {} with action `hashmod` requires a non-zero `modulus`", index))]
+    MissingModulus { index: usize },
+}
+
+impl GenerateConfig for MetricRelabelConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"[[rules]]
+source_labels = ["__name__"]
+regex = "go_.*"
+action = "drop""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+impl TransformConfig for MetricRelabelConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::function(MetricRelabel::new(&self.rules)?))
+    }
+
+    fn input(&self) -> Input {
+        Input::metric()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Metric)]
+    }
+
+    fn enable_concurrency(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone, Debug)]
+struct RelabelRule {
+    source_labels: Vec<String>,
+    separator: String,
+    target_label: Option<String>,
+    regex: Regex,
+    modulus: u64,
+    replacement: String,
+    action: RelabelAction,
+}
+
+impl RelabelRule {
+    fn new(index: usize, config: &RelabelRuleConfig) -> Result<Self, BuildError> {
+        let regex = Regex::new(&format!("^(?:{})$", config.regex)).context(InvalidRegexSnafu {
+            index,
+            regex: config.regex.clone(),
+        })?;
+
+        let action = config.action;
+        if matches!(action, RelabelAction::Replace | RelabelAction::Hashmod)
+            && config.target_label.is_none()
+        {
+            return Err(BuildError::MissingTargetLabel { index, action });
+        }
+        let modulus = config.modulus.unwrap_or_default();
+        if action == RelabelAction::Hashmod && modulus == 0 {
+            return Err(BuildError::MissingModulus { index });
+        }
+
+        Ok(Self {
+            source_labels: config.source_labels.clone(),
+            separator: config.separator.clone(),
+            target_label: config.target_label.clone(),
+            regex,
+            modulus,
+            replacement: config.replacement.clone(),
+            action,
+        })
+    }
+
+    /// Applies the rule to `metric`, returning `None` if it is dropped.
+    fn apply(&self, mut metric: Metric) -> Option<Metric> {
+        match self.action {
+            RelabelAction::Replace => {
+                let value = self.source_value(&metric);
+                if let Some(captures) = self.regex.captures(&value) {
+                    let mut target = String::new();
+                    captures.expand(self.target_label(), &mut target);
+                    let mut replacement = String::new();
+                    captures.expand(&self.replacement, &mut replacement);
+
+                    if !target.is_empty() {
+                        metric = set_label(metric, target, replacement);
+                    }
+                }
+            }
+            RelabelAction::Keep => {
+                if !self.regex.is_match(&self.source_value(&metric)) {
+                    return None;
+                }
+            }
+            RelabelAction::Drop => {
+                if self.regex.is_match(&self.source_value(&metric)) {
+                    return None;
+                }
+            }
+            RelabelAction::Hashmod => {
+                let hash = Md5::digest(self.source_value(&metric).as_bytes());
+                let mut low = [0; 8];
+                low.copy_from_slice(&hash[8..]);
+                let value = u64::from_be_bytes(low) % self.modulus;
+                metric = set_label(metric, self.target_label().to_owned(), value.to_string());
+            }
+            RelabelAction::Labelmap => {
+                let labels = metric
+                    .tags()
+                    .map(|tags| {
+                        tags.iter_single()
+                            .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                let name = (NAME_LABEL.to_owned(), metric.name().to_owned());
+
+                for (name, value) in std::iter::once(name).chain(labels) {
+                    if self.regex.is_match(&name) {
+                        let target = self.regex.replace(&name, self.replacement.as_str());
+                        metric = set_label(metric, target.into_owned(), value);
+                    }
+                }
+            }
+            RelabelAction::Labeldrop => {
+                if let Some(tags) = metric.tags_mut() {
+                    tags.retain(|name, _| !self.regex.is_match(name));
+                }
+            }
+            RelabelAction::Labelkeep => {
+                if let Some(tags) = metric.tags_mut() {
+                    tags.retain(|name, _| self.regex.is_match(name));
+                }
+            }
+        }
+
+        Some(metric)
+    }
+
+    fn source_value(&self, metric: &Metric) -> String {
+        self.source_labels
+            .iter()
+            .map(|label| match label.as_str() {
+                NAME_LABEL => metric.name().to_owned(),
+                label => metric.tag_value(label).unwrap_or_default(),
+            })
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
+
+    fn target_label(&self) -> &str {
+        self.target_label
+            .as_deref()
+            .expect("validated when building the rule")
+    }
+}
+
+/// Sets the label `name` of `metric`, removing it if `value` is empty.
+fn set_label(mut metric: Metric, name: String, value: String) -> Metric {
+    match (name.as_str(), value.is_empty()) {
+        // Metrics can't be left without a name.
+        (NAME_LABEL, true) => metric,
+        (NAME_LABEL, false) => metric.with_name(value),
+        (_, true) => {
+            metric.remove_tag(&name);
+            metric
+        }
+        (_, false) => {
+            metric.insert_tag(name, value);
+            metric
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MetricRelabel {
+    rules: Vec<RelabelRule>,
+}
+
+impl MetricRelabel {
+    pub fn new(rules: &[RelabelRuleConfig]) -> crate::Result<Self> {
+        let rules = rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| RelabelRule::new(index, rule))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { rules })
+    }
+
+    fn transform_one(&self, metric: Metric) -> Option<Metric> {
+        self.rules
+            .iter()
+            .try_fold(metric, |metric, rule| rule.apply(metric))
+    }
+}
+
+impl FunctionTransform for MetricRelabel {
+    fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
+        match self.transform_one(event.into_metric()) {
+            Some(metric) => output.push(Event::Metric(metric)),
+            None => emit!(MetricRelabelEventDropped),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+    use vector_core::metric_tags;
+
+    use super::*;
+    use crate::event::metric::{MetricKind, MetricTags, MetricValue};
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MetricRelabelConfig>();
+    }
+
+    fn relabel(rules: &str) -> MetricRelabel {
+        let config = serde_yaml::from_str::<MetricRelabelConfig>(rules).unwrap();
+        MetricRelabel::new(&config.rules).unwrap()
+    }
+
+    fn metric(name: &str, tags: MetricTags) -> Metric {
+        Metric::new(
+            name,
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 1.0 },
+        )
+        .with_tags(Some(tags))
+    }
+
+    #[test]
+    fn replace_with_capture_groups() {
+        let relabel = relabel(
+            r#"
+rules:
+  - source_labels: [__address__, port]
+    separator: ":"
+    regex: "([^:]+):.*:(\\d+)"
+    target_label: instance
+    replacement: "${1}:${2}"
+  - source_labels: [missing]
+    target_label: region
+  - source_labels: [env]
+    regex: "prod-(.*)"
+    target_label: "env_${1}"
+    replacement: production
+"#,
+        );
+
+        let output = relabel.transform_one(metric(
+            "up",
+            metric_tags!(
+                "__address__" => "10.0.0.1:9100",
+                "port" => "8080",
+                "region" => "us",
+                "env" => "prod-eu",
+            ),
+        ));
+        assert_eq!(
+            output,
+            Some(metric(
+                "up",
+                metric_tags!(
+                    "__address__" => "10.0.0.1:9100",
+                    "port" => "8080",
+                    "env" => "prod-eu",
+                    "instance" => "10.0.0.1:8080",
+                    "env_eu" => "production",
+                ),
+            ))
+        );
+    }
+
+    #[test]
+    fn replace_name() {
+        let relabel = relabel(
+            r#"
+rules:
+  - source_labels: [__name__]
+    regex: "node_(.*)"
+    target_label: __name__
+    replacement: "host_$1"
+"#,
+        );
+
+        let output = relabel.transform_one(metric("node_load1", MetricTags::default()));
+        assert_eq!(output.unwrap().name(), "host_load1");
+    }
+
+    #[test]
+    fn keep_and_drop() {
+        let relabel = relabel(
+            r#"
+rules:
+  - source_labels: [job]
+    regex: "node|api"
+    action: keep
+  - source_labels: [__name__, job]
+    regex: "go_.*;.*"
+    action: drop
+"#,
+        );
+
+        let kept = metric("up", metric_tags!("job" => "node"));
+        assert_eq!(relabel.transform_one(kept.clone()), Some(kept));
+        assert_eq!(
+            relabel.transform_one(metric("up", metric_tags!("job" => "nodes"))),
+            None
+        );
+        assert_eq!(
+            relabel.transform_one(metric("go_goroutines", metric_tags!("job" => "api"))),
+            None
+        );
+    }
+
+    #[test]
+    fn hashmod() {
+        let relabel = relabel(
+            r#"
+rules:
+  - source_labels: [instance]
+    modulus: 8
+    target_label: shard
+    action: hashmod
+"#,
+        );
+
+        let output = relabel
+            .transform_one(metric("up", metric_tags!("instance" => "localhost:9100")))
+            .unwrap();
+        let shard = output.tag_value("shard").unwrap().parse::<u64>().unwrap();
+        assert!(shard < 8);
+
+        let again = relabel
+            .transform_one(metric("up", metric_tags!("instance" => "localhost:9100")))
+            .unwrap();
+        assert_eq!(output, again);
+    }
+
+    #[test]
+    fn labelmap_labeldrop_labelkeep() {
+        let relabel = relabel(
+            r#"
+rules:
+  - regex: "__meta_kubernetes_pod_label_(.+)"
+    action: labelmap
+  - regex: "__meta_.*"
+    action: labeldrop
+  - regex: "app|tier|instance"
+    action: labelkeep
+"#,
+        );
+
+        let output = relabel.transform_one(metric(
+            "up",
+            metric_tags!(
+                "__meta_kubernetes_pod_label_app" => "web",
+                "__meta_kubernetes_pod_label_tier" => "frontend",
+                "__meta_kubernetes_namespace" => "default",
+                "instance" => "a",
+                "job" => "kubernetes-pods",
+            ),
+        ));
+        assert_eq!(
+            output,
+            Some(metric(
+                "up",
+                metric_tags!("app" => "web", "tier" => "frontend", "instance" => "a"),
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let rules = |rules: &str| {
+            serde_yaml::from_str::<MetricRelabelConfig>(rules)
+                .unwrap()
+                .rules
+        };
+
+        assert!(MetricRelabel::new(&rules("rules: [{source_labels: [a]}]")).is_err());
+        assert!(MetricRelabel::new(&rules(
+            "rules: [{source_labels: [a], target_label: b, action: hashmod}]"
+        ))
+        .is_err());
+        assert!(MetricRelabel::new(&rules("rules: [{regex: '(', action: labeldrop}]")).is_err());
+    }
+}
@@ -12,9 +12,15 @@ pub mod aws_ec2_metadata;
 pub mod dedupe;
 #[cfg(feature = "transforms-filter")]
 pub mod filter;
+#[cfg(feature = "transforms-join")]
+pub mod join;
 pub mod log_to_metric;
 #[cfg(feature = "transforms-lua")]
 pub mod lua;
+#[cfg(feature = "transforms-metric_kind")]
+pub mod metric_kind;
+#[cfg(feature = "transforms-metric_relabel")]
+pub mod metric_relabel;
 #[cfg(feature = "transforms-metric_to_log")]
 pub mod metric_to_log;
 #[cfg(feature = "transforms-pipelines")]
@@ -29,8 +35,12 @@ pub mod route;
 pub mod sample;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub mod tag_cardinality_limit;
+#[cfg(feature = "transforms-tail_sampling")]
+pub mod tail_sampling;
 #[cfg(feature = "transforms-throttle")]
 pub mod throttle;
+#[cfg(feature = "transforms-window")]
+pub mod window;
 
 use vector_common::config::ComponentKey;
 use vector_config::{configurable_component, NamedComponent};
@@ -76,6 +86,10 @@ pub enum Transforms {
     #[cfg(feature = "transforms-filter")]
     Filter(#[configurable(derived)] filter::FilterConfig),
 
+    /// Join.
+    #[cfg(feature = "transforms-join")]
+    Join(#[configurable(derived)] join::JoinConfig),
+
     /// Log to metric.
     LogToMetric(#[configurable(derived)] log_to_metric::LogToMetricConfig),
 
@@ -83,6 +97,14 @@ pub enum Transforms {
     #[cfg(feature = "transforms-lua")]
     Lua(#[configurable(derived)] lua::LuaConfig),
 
+    /// Metric kind.
+    #[cfg(feature = "transforms-metric_kind")]
+    MetricKind(#[configurable(derived)] metric_kind::MetricKindConfig),
+
+    /// Metric relabel.
+    #[cfg(feature = "transforms-metric_relabel")]
+    MetricRelabel(#[configurable(derived)] metric_relabel::MetricRelabelConfig),
+
     /// Metric to log.
     #[cfg(feature = "transforms-metric_to_log")]
     MetricToLog(#[configurable(derived)] metric_to_log::MetricToLogConfig),
@@ -116,6 +138,10 @@ pub enum Transforms {
     #[cfg(feature = "transforms-tag_cardinality_limit")]
     TagCardinalityLimit(#[configurable(derived)] tag_cardinality_limit::TagCardinalityLimitConfig),
 
+    /// Tail sampling.
+    #[cfg(feature = "transforms-tail_sampling")]
+    TailSampling(#[configurable(derived)] tail_sampling::TailSamplingConfig),
+
     /// Test (basic).
     #[cfg(test)]
     TestBasic(#[configurable(derived)] crate::test_util::mock::transforms::BasicTransformConfig),
@@ -127,6 +153,10 @@ pub enum Transforms {
     /// Throttle.
     #[cfg(feature = "transforms-throttle")]
     Throttle(#[configurable(derived)] throttle::ThrottleConfig),
+
+    /// Window.
+    #[cfg(feature = "transforms-window")]
+    Window(#[configurable(derived)] window::WindowConfig),
 }
 
 // We can't use `enum_dispatch` here because it doesn't support associated constants.
@@ -143,9 +173,15 @@ impl NamedComponent for Transforms {
             Transforms::Dedupe(config) => config.get_component_name(),
             #[cfg(feature = "transforms-filter")]
             Transforms::Filter(config) => config.get_component_name(),
+            #[cfg(feature = "transforms-join")]
+            Transforms::Join(config) => config.get_component_name(),
             Transforms::LogToMetric(config) => config.get_component_name(),
             #[cfg(feature = "transforms-lua")]
             Transforms::Lua(config) => config.get_component_name(),
+            #[cfg(feature = "transforms-metric_kind")]
+            Transforms::MetricKind(config) => config.get_component_name(),
+            #[cfg(feature = "transforms-metric_relabel")]
+            Transforms::MetricRelabel(config) => config.get_component_name(),
             #[cfg(feature = "transforms-metric_to_log")]
             Transforms::MetricToLog(config) => config.get_component_name(),
             #[cfg(feature = "transforms-pipelines")]
@@ -162,12 +198,16 @@ impl NamedComponent for Transforms {
             Transforms::Sample(config) => config.get_component_name(),
             #[cfg(feature = "transforms-tag_cardinality_limit")]
             Transforms::TagCardinalityLimit(config) => config.get_component_name(),
+            #[cfg(feature = "transforms-tail_sampling")]
+            Transforms::TailSampling(config) => config.get_component_name(),
             #[cfg(test)]
             Transforms::TestBasic(config) => config.get_component_name(),
             #[cfg(test)]
             Transforms::TestNoop(config) => config.get_component_name(),
             #[cfg(feature = "transforms-throttle")]
             Transforms::Throttle(config) => config.get_component_name(),
+            #[cfg(feature = "transforms-window")]
+            Transforms::Window(config) => config.get_component_name(),
             #[allow(unreachable_patterns)]
             _ => unimplemented!(),
         }
@@ -132,6 +132,26 @@ impl TransformConfig for PipelineConfig {
     fn enable_concurrency(&self) -> bool {
         true
     }
+
+    fn conditions(&self) -> Vec<(String, &AnyCondition)> {
+        let nested = self
+            .transforms
+            .iter()
+            .enumerate()
+            .flat_map(|(index, transform)| {
+                transform
+                    .conditions()
+                    .into_iter()
+                    .map(move |(option, condition)| {
+                        (format!("transforms[{}].{}", index, option), condition)
+                    })
+            });
+        self.filter
+            .iter()
+            .map(|condition| ("filter".to_owned(), condition))
+            .chain(nested)
+            .collect()
+    }
 }
 
 impl PipelineConfig {
@@ -243,6 +263,21 @@ impl EventTypeConfig {
         }
         Ok(())
     }
+
+    /// Gets the conditions of the pipelines, prefixed with the name of their pipeline.
+    pub(super) fn conditions(&self) -> Vec<(String, &AnyCondition)> {
+        self.0
+            .iter()
+            .flat_map(|pipeline| {
+                pipeline
+                    .conditions()
+                    .into_iter()
+                    .map(move |(option, condition)| {
+                        (format!("{}.{}", pipeline.name, option), condition)
+                    })
+            })
+            .collect()
+    }
 }
 
 impl EventTypeConfig {
@@ -260,6 +260,22 @@ impl TransformConfig for PipelinesConfig {
         // The pipelines transform shouldn't be embedded in another pipelines transform.
         !parents.contains(self.get_component_name())
     }
+
+    fn conditions(&self) -> Vec<(String, &AnyCondition)> {
+        [
+            ("logs", &self.logs),
+            ("metrics", &self.metrics),
+            ("traces", &self.traces),
+        ]
+        .into_iter()
+        .flat_map(|(event_type, config)| {
+            config
+                .conditions()
+                .into_iter()
+                .map(move |(option, condition)| (format!("{}.{}", event_type, option), condition))
+        })
+        .collect()
+    }
 }
 
 impl GenerateConfig for PipelinesConfig {
@@ -198,6 +198,16 @@ impl TransformConfig for ReduceConfig {
 
         vec![Output::default(DataType::Log).with_schema_definition(schema_definition)]
     }
+
+    fn conditions(&self) -> Vec<(String, &AnyCondition)> {
+        [
+            ("starts_when", &self.starts_when),
+            ("ends_when", &self.ends_when),
+        ]
+        .into_iter()
+        .filter_map(|(option, condition)| Some((option.to_owned(), condition.as_ref()?)))
+        .collect()
+    }
 }
 
 #[derive(Debug)]
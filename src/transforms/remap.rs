@@ -3,7 +3,8 @@ use std::{
     collections::BTreeMap,
     fs::File,
     io::{self, Read},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    time::Duration,
 };
 
 use lookup::lookup_v2::{parse_value_path, ValuePath};
@@ -21,15 +22,17 @@ use vrl::prelude::state::TypeState;
 use vrl::{
     diagnostic::{Formatter, Note},
     prelude::{DiagnosticMessage, ExpressionError},
-    CompileConfig, Program, Runtime, Terminate, VrlRuntime,
+    CompileConfig, Profile, Program, Runtime, SourceMap, StateLimits, StateStore, Terminate,
+    Tracer, VrlRuntime, WriteTracer,
 };
 
 use crate::{
     config::{
-        log_schema, ComponentKey, DataType, Input, Output, TransformConfig, TransformContext,
+        config_dirs, log_schema, ComponentKey, DataType, Input, Output, TransformConfig,
+        TransformContext,
     },
     event::{Event, TargetEvents, VrlTarget},
-    internal_events::{RemapMappingAbort, RemapMappingError},
+    internal_events::{RemapMappingAbort, RemapMappingError, RemapProfile},
     schema,
     transforms::{SyncTransform, Transform, TransformOutputsBuf},
     Result,
@@ -58,6 +61,9 @@ pub struct RemapConfig {
     ///
     /// If a relative path is provided, its root is the current working directory.
     ///
+    /// Files imported by the program are resolved relative to the directory of this file first,
+    /// and then to the directories of the configuration files.
+    ///
     /// Required if `source` is missing.
     ///
     /// [vrl]: https://vector.dev/docs/reference/vrl
@@ -118,9 +124,84 @@ pub struct RemapConfig {
     #[configurable(derived)]
     #[serde(default)]
     pub runtime: VrlRuntime,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub state: StateConfig,
+
+    /// Measures the cost of each expression and function call of the program.
+    ///
+    /// The number of evaluations and the time spent evaluating them are reported as the
+    /// `vrl_evaluations_total` and `vrl_evaluation_duration_seconds` internal metrics, tagged with
+    /// the location in the program. Measuring adds overhead to every evaluation, so this should
+    /// only be enabled while looking for the expensive parts of a program.
+    #[serde(default = "crate::serde::default_false")]
+    pub profile: bool,
+}
+
+/// Limits of the key/value state the program keeps between events.
+///
+/// The state is read and written with the `get_state`, `set_state`, and `incr_state` functions. It
+/// is kept in memory, and shared by all the events processed by this transform. When a limit is
+/// reached, expired entries are evicted first, and then the least recently used ones.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct StateConfig {
+    /// The maximum number of entries in the state.
+    #[serde(default = "default_state_max_entries")]
+    #[derivative(Default(value = "default_state_max_entries()"))]
+    pub max_entries: usize,
+
+    /// The maximum size of the state, in bytes.
+    #[serde(default = "default_state_max_bytes")]
+    #[derivative(Default(value = "default_state_max_bytes()"))]
+    pub max_bytes: usize,
+
+    /// The time-to-live of the entries set without an explicit `ttl`, in seconds.
+    ///
+    /// If unset, these entries don't expire, and are only evicted to stay within the limits.
+    #[configurable(metadata(docs::examples = 3600))]
+    pub ttl_secs: Option<u64>,
+}
+
+const fn default_state_max_entries() -> usize {
+    10_000
+}
+
+const fn default_state_max_bytes() -> usize {
+    10 * 1024 * 1024
+}
+
+impl From<StateConfig> for StateLimits {
+    fn from(config: StateConfig) -> Self {
+        Self {
+            max_entries: config.max_entries,
+            max_bytes: config.max_bytes,
+            default_ttl: config.ttl_secs.map(Duration::from_secs),
+        }
+    }
 }
 
 impl RemapConfig {
+    fn read_source(&self) -> Result<String> {
+        match (&self.source, &self.file) {
+            (Some(source), None) => Ok(source.to_owned()),
+            (None, Some(path)) => {
+                let mut buffer = String::new();
+
+                File::open(path)
+                    .with_context(|_| FileOpenFailedSnafu { path })?
+                    .read_to_string(&mut buffer)
+                    .with_context(|_| FileReadFailedSnafu { path })?;
+
+                Ok(buffer)
+            }
+            _ => Err(Box::new(BuildError::SourceAndOrFile)),
+        }
+    }
+
     fn compile_vrl_program(
         &self,
         enrichment_tables: enrichment::TableRegistry,
@@ -131,20 +212,7 @@ impl RemapConfig {
         Vec<Box<dyn vrl::Function>>,
         CompileConfig,
     )> {
-        let source = match (&self.source, &self.file) {
-            (Some(source), None) => source.to_owned(),
-            (None, Some(path)) => {
-                let mut buffer = String::new();
-
-                File::open(path)
-                    .with_context(|_| FileOpenFailedSnafu { path })?
-                    .read_to_string(&mut buffer)
-                    .with_context(|_| FileReadFailedSnafu { path })?;
-
-                buffer
-            }
-            _ => return Err(Box::new(BuildError::SourceAndOrFile)),
-        };
+        let source = self.read_source()?;
 
         let mut functions = vrl_stdlib::all();
         functions.append(&mut enrichment::vrl_functions());
@@ -162,6 +230,18 @@ impl RemapConfig {
         config.set_custom(enrichment_tables);
         config.set_custom(MeaningList::default());
 
+        // Imports are resolved relative to the program file, if any, and then
+        // to the directories of the config files.
+        let mut import_paths = self
+            .file
+            .as_ref()
+            .and_then(|path| path.parent())
+            .map(Path::to_path_buf)
+            .into_iter()
+            .collect::<Vec<_>>();
+        import_paths.extend(config_dirs());
+        config.set_import_paths(import_paths);
+
         compile_vrl(&source, &functions, &state, config)
             .map_err(|diagnostics| {
                 Formatter::new(&source, diagnostics)
@@ -330,12 +410,46 @@ pub trait VrlRunner {
 #[derive(Debug)]
 pub struct AstRunner {
     pub runtime: Runtime,
+    trace: Option<ProgramTrace>,
+    profiler: Option<Profiler>,
+}
+
+/// What's needed to print the trace of a program.
+#[derive(Clone, Debug)]
+struct ProgramTrace {
+    component: String,
+    source: Arc<str>,
+}
+
+/// Collects the cost of the program for each event, to emit it as internal metrics.
+#[derive(Clone, Debug)]
+struct Profiler {
+    source_map: Arc<SourceMap>,
+    profile: Profile,
+}
+
+impl AstRunner {
+    pub const fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+            trace: None,
+            profiler: None,
+        }
+    }
 }
 
 impl Clone for AstRunner {
     fn clone(&self) -> Self {
+        // Variables are per runtime, but the state is shared by all the clones of the transform.
+        let mut runtime = Runtime::default();
+        if let Some(state_store) = self.runtime.state_store() {
+            runtime = runtime.with_state_store(state_store.clone());
+        }
+
         Self {
-            runtime: Runtime::default(),
+            runtime,
+            trace: self.trace.clone(),
+            profiler: self.profiler.clone(),
         }
     }
 }
@@ -347,7 +461,39 @@ impl VrlRunner for AstRunner {
         program: &Program,
         timezone: &TimeZone,
     ) -> std::result::Result<value::Value, Terminate> {
-        let result = self.runtime.resolve(target, program, timezone);
+        let mut tracer = self
+            .trace
+            .as_ref()
+            .map(|trace| WriteTracer::new(Vec::new(), &trace.source));
+        let result = self.runtime.resolve_with(
+            target,
+            program,
+            timezone,
+            tracer.as_mut().map(|tracer| tracer as &mut dyn Tracer),
+            self.profiler.as_mut().map(|profiler| &mut profiler.profile),
+        );
+
+        // The trace of an event is printed at once, so that the traces of events processed
+        // concurrently don't interleave.
+        if let (Some(trace), Some(tracer)) = (&self.trace, tracer) {
+            #[allow(clippy::print_stderr)]
+            {
+                eprint!(
+                    "Trace of transform '{}':\n{}",
+                    trace.component,
+                    String::from_utf8_lossy(&tracer.into_inner())
+                );
+            }
+        }
+
+        if let Some(profiler) = &mut self.profiler {
+            emit!(RemapProfile {
+                profile: &profiler.profile,
+                source_map: &profiler.source_map,
+            });
+            profiler.profile.clear();
+        }
+
         self.runtime.clear();
         result
     }
@@ -363,8 +509,30 @@ impl Remap<AstRunner> {
             context.merged_schema_definition.clone(),
         )?;
 
-        let runtime = Runtime::default();
-        let runner = AstRunner { runtime };
+        let state_store = StateStore::new(config.state.into());
+        let runtime = Runtime::default().with_state_store(state_store);
+        let mut runner = AstRunner::new(runtime);
+
+        let trace = context.trace_programs;
+        if trace || config.profile {
+            let source: Arc<str> = config.read_source()?.into();
+
+            if trace {
+                runner.trace = Some(ProgramTrace {
+                    component: context
+                        .key
+                        .as_ref()
+                        .map_or_else(|| "remap".to_owned(), ToString::to_string),
+                    source: Arc::clone(&source),
+                });
+            }
+            if config.profile {
+                runner.profiler = Some(Profiler {
+                    source_map: Arc::new(SourceMap::new(&source)),
+                    profile: Profile::default(),
+                });
+            }
+        }
 
         Self::new(config, context, program, runner).map(|remap| (remap, warnings))
     }
@@ -669,6 +837,40 @@ mod tests {
         )
     }
 
+    #[test]
+    fn trace_programs_from_context() {
+        let conf = RemapConfig {
+            source: Some(".foo = 1".to_string()),
+            ..Default::default()
+        };
+        assert!(remap(conf.clone()).unwrap().runner.trace.is_none());
+
+        let context = TransformContext {
+            key: Some(ComponentKey::from("remapper")),
+            trace_programs: true,
+            ..Default::default()
+        };
+        let (tform, _) = Remap::new_ast(conf, &context).unwrap();
+        let trace = tform.runner.trace.expect("programs are traced");
+        assert_eq!(trace.component, "remapper");
+    }
+
+    #[test]
+    fn profile_is_cleared_between_events() {
+        let conf = RemapConfig {
+            source: Some(".foo = upcase(string!(.message))".to_string()),
+            profile: true,
+            ..Default::default()
+        };
+        let mut tform = remap(conf).unwrap();
+
+        let result = transform_one(&mut tform, Event::from(LogEvent::from("event"))).unwrap();
+        assert_eq!(get_field_string(&result, "foo"), "EVENT");
+
+        let profiler = tform.runner().profiler.as_ref().unwrap();
+        assert!(profiler.profile.is_empty());
+    }
+
     fn get_field_string(event: &Event, field: &str) -> String {
         event
             .as_log()
@@ -719,6 +921,47 @@ mod tests {
         assert!(tform.runner().runtime.is_empty());
     }
 
+    #[test]
+    fn check_remap_keeps_state_between_events() {
+        let conf = RemapConfig {
+            source: Some(
+                indoc! {r#"
+                    user = string!(.user)
+                    .count = incr_state!(user)
+                    first_seen = get_state(user + "_first_seen")
+                    if first_seen == null {
+                        first_seen = .message
+                        set_state!(user + "_first_seen", first_seen)
+                    }
+                    .first_seen = first_seen
+                "#}
+                .to_owned(),
+            ),
+            ..Default::default()
+        };
+        let mut tform = remap(conf).unwrap();
+        let mut clone = tform.clone();
+
+        let event = |message: &str, user: &str| {
+            let mut event = LogEvent::from(message);
+            event.insert("user", user);
+            Event::from(event)
+        };
+
+        let result = transform_one(&mut tform, event("first", "alice")).unwrap();
+        assert_eq!(get_field_string(&result, "count"), "1");
+        assert_eq!(get_field_string(&result, "first_seen"), "first");
+
+        let result = transform_one(&mut tform, event("second", "bob")).unwrap();
+        assert_eq!(get_field_string(&result, "count"), "1");
+        assert_eq!(get_field_string(&result, "first_seen"), "second");
+
+        // Clones of the transform share the same state.
+        let result = transform_one(&mut clone, event("third", "alice")).unwrap();
+        assert_eq!(get_field_string(&result, "count"), "2");
+        assert_eq!(get_field_string(&result, "first_seen"), "first");
+    }
+
     #[test]
     fn check_remap_adds() {
         let event = {
@@ -123,6 +123,13 @@ impl TransformConfig for RouteConfig {
     fn enable_concurrency(&self) -> bool {
         true
     }
+
+    fn conditions(&self) -> Vec<(String, &AnyCondition)> {
+        self.route
+            .iter()
+            .map(|(name, condition)| (format!("route.{}", name), condition))
+            .collect()
+    }
 }
 
 #[cfg(test)]
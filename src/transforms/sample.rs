@@ -1,10 +1,16 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use snafu::Snafu;
 use vector_config::configurable_component;
 
 use crate::{
     conditions::{AnyCondition, Condition},
     config::{DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext},
     event::Event,
-    internal_events::SampleEventDiscarded,
+    internal_events::{SampleEventDiscarded, SampleRatesAdjusted},
     schema,
     transforms::{FunctionTransform, OutputBuffer, Transform},
 };
@@ -18,7 +24,12 @@ pub struct SampleConfig {
     ///
     /// For example, `rate = 10` means 1 out of every 10 events will be forwarded and the rest will
     /// be dropped.
-    pub rate: u64,
+    ///
+    /// Required unless `dynamic` is set.
+    pub rate: Option<u64>,
+
+    #[configurable(derived)]
+    pub dynamic: Option<DynamicSampleConfig>,
 
     /// The name of the log field whose value will be hashed to determine if the event should be
     /// passed.
@@ -33,10 +44,61 @@ pub struct SampleConfig {
     pub exclude: Option<AnyCondition>,
 }
 
+/// Dynamic sampling options.
+///
+/// Instead of a fixed `rate`, each value of `key_field` gets its own rate, adjusted periodically so
+/// that the events forwarded stay within an overall budget. Rare values are kept, while frequent
+/// values are sampled down. Each forwarded event has its effective rate in its `sample_rate` field.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DynamicSampleConfig {
+    /// The number of events to forward per second, across all values of `key_field`.
+    pub target_events_per_sec: f64,
+
+    /// The interval between adjustments of the rates, in seconds.
+    ///
+    /// The rates are computed from the number of events received for each value over the previous
+    /// interval. Until the first adjustment, and for values which were not received during the
+    /// previous interval, all events are forwarded.
+    #[serde(default = "default_adjustment_interval_secs")]
+    pub adjustment_interval_secs: u64,
+
+    /// The maximum number of values of `key_field` to track.
+    ///
+    /// Once reached, events with new values share a single rate, as do events without `key_field`.
+    #[serde(default = "default_max_keys")]
+    pub max_keys: usize,
+}
+
+const fn default_adjustment_interval_secs() -> u64 {
+    30
+}
+
+const fn default_max_keys() -> usize {
+    10_000
+}
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+enum BuildError {
+    #[snafu(display("one of `rate` or `dynamic` must be set"))]
+    MissingRate,
+
+    #[snafu(display("`rate` and `dynamic` can't both be set"))]
+    ConflictingRate,
+
+    #[snafu(display("`dynamic.target_events_per_sec` must be greater than zero"))]
+    InvalidTarget,
+
+    #[snafu(display("`dynamic.adjustment_interval_secs` must be greater than zero"))]
+    ZeroAdjustmentInterval,
+}
+
 impl GenerateConfig for SampleConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
-            rate: 10,
+            rate: Some(10),
+            dynamic: None,
             key_field: None,
             exclude: None::<AnyCondition>,
         })
@@ -47,14 +109,21 @@ impl GenerateConfig for SampleConfig {
 #[async_trait::async_trait]
 impl TransformConfig for SampleConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::function(Sample::new(
-            self.rate,
-            self.key_field.clone(),
-            self.exclude
-                .as_ref()
-                .map(|condition| condition.build(&context.enrichment_tables))
-                .transpose()?,
-        )))
+        let exclude = self
+            .exclude
+            .as_ref()
+            .map(|condition| condition.build(&context.enrichment_tables))
+            .transpose()?;
+
+        let sample = match (self.rate, &self.dynamic) {
+            (Some(rate), None) => Sample::new(rate, self.key_field.clone(), exclude),
+            (None, Some(dynamic)) => {
+                Sample::new(1, self.key_field.clone(), exclude).with_dynamic(dynamic)?
+            }
+            (None, None) => return Err(Box::new(BuildError::MissingRate)),
+            (Some(_), Some(_)) => return Err(Box::new(BuildError::ConflictingRate)),
+        };
+        Ok(Transform::function(sample))
     }
 
     fn input(&self) -> Input {
@@ -65,6 +134,13 @@ impl TransformConfig for SampleConfig {
         vec![Output::default(DataType::Log | DataType::Trace)
             .with_schema_definition(merged_definition.clone())]
     }
+
+    fn conditions(&self) -> Vec<(String, &AnyCondition)> {
+        self.exclude
+            .iter()
+            .map(|condition| ("exclude".to_owned(), condition))
+            .collect()
+    }
 }
 
 #[derive(Clone)]
@@ -73,6 +149,7 @@ pub struct Sample {
     key_field: Option<String>,
     exclude: Option<Condition>,
     count: u64,
+    dynamic: Option<DynamicSampler>,
 }
 
 impl Sample {
@@ -82,7 +159,124 @@ impl Sample {
             key_field,
             exclude,
             count: 0,
+            dynamic: None,
+        }
+    }
+
+    /// Samples events with rates adjusted per value of `key_field`, instead of with a fixed rate.
+    pub fn with_dynamic(mut self, config: &DynamicSampleConfig) -> crate::Result<Self> {
+        if config.target_events_per_sec.is_nan() || config.target_events_per_sec <= 0.0 {
+            return Err(Box::new(BuildError::InvalidTarget));
+        }
+        if config.adjustment_interval_secs == 0 {
+            return Err(Box::new(BuildError::ZeroAdjustmentInterval));
+        }
+
+        self.dynamic = Some(DynamicSampler {
+            target_events_per_sec: config.target_events_per_sec,
+            adjustment_interval: Duration::from_secs(config.adjustment_interval_secs),
+            max_keys: config.max_keys,
+            keys: HashMap::new(),
+            last_adjustment: Instant::now(),
+        });
+        Ok(self)
+    }
+
+    /// Returns the rate at which the event was sampled if it should be forwarded.
+    fn sample(&mut self, value: Option<&str>, now: Instant) -> Option<u64> {
+        if let Some(dynamic) = self.dynamic.as_mut() {
+            return dynamic.sample(value, now);
+        }
+
+        let num = if let Some(value) = value {
+            seahash::hash(value.as_bytes())
+        } else {
+            self.count
+        };
+
+        self.count = (self.count + 1) % self.rate;
+
+        (num % self.rate == 0).then_some(self.rate)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct KeyState {
+    /// The number of events received since the last adjustment.
+    received: u64,
+    /// The current rate, where zero means that the key was not received before the last adjustment.
+    rate: u64,
+    count: u64,
+}
+
+#[derive(Clone, Debug)]
+struct DynamicSampler {
+    target_events_per_sec: f64,
+    adjustment_interval: Duration,
+    max_keys: usize,
+    /// The state of each value of the key field, where `None` is shared by the events without it
+    /// and those with new values once `max_keys` is reached.
+    keys: HashMap<Option<String>, KeyState>,
+    last_adjustment: Instant,
+}
+
+impl DynamicSampler {
+    fn sample(&mut self, value: Option<&str>, now: Instant) -> Option<u64> {
+        let elapsed = now.duration_since(self.last_adjustment);
+        if elapsed >= self.adjustment_interval {
+            self.adjust(elapsed);
+            self.last_adjustment = now;
+        }
+
+        let key = value.map(str::to_owned);
+        let key =
+            if key.is_some() && !self.keys.contains_key(&key) && self.keys.len() >= self.max_keys {
+                None
+            } else {
+                key
+            };
+        let state = self.keys.entry(key).or_default();
+        state.received += 1;
+
+        let rate = state.rate.max(1);
+        let keep = state.count % rate == 0;
+        state.count = (state.count + 1) % rate;
+        keep.then_some(rate)
+    }
+
+    /// Computes the rates of the keys from the number of events received for each of them since the
+    /// last adjustment.
+    ///
+    /// The budget of events for the interval is shared evenly between the keys, from the least to
+    /// the most frequent one. Keys received less than their share are kept entirely, and the rest
+    /// of their share is left to the following keys, so that the most frequent keys are sampled
+    /// down the most.
+    fn adjust(&mut self, elapsed: Duration) {
+        self.keys.retain(|_, state| state.received > 0);
+
+        let mut states = self.keys.values_mut().collect::<Vec<_>>();
+        states.sort_by_key(|state| state.received);
+
+        let mut budget = self.target_events_per_sec * elapsed.as_secs_f64();
+        let total = states.len();
+        for (index, state) in states.into_iter().enumerate() {
+            let share = budget / (total - index) as f64;
+            let received = state.received as f64;
+            let rate = if received <= share {
+                1
+            } else {
+                (received / share).ceil() as u64
+            };
+
+            budget -= received / rate as f64;
+            if rate != state.rate {
+                state.count = 0;
+            }
+            state.rate = rate;
+            state.received = 0;
         }
+
+        emit!(SampleRatesAdjusted { keys: total });
     }
 }
 
@@ -112,18 +306,10 @@ impl FunctionTransform for Sample {
             })
             .map(|v| v.to_string_lossy());
 
-        let num = if let Some(value) = value {
-            seahash::hash(value.as_bytes())
-        } else {
-            self.count
-        };
-
-        self.count = (self.count + 1) % self.rate;
-
-        if num % self.rate == 0 {
+        if let Some(rate) = self.sample(value.as_deref(), Instant::now()) {
             match event {
-                Event::Log(ref mut event) => event.insert("sample_rate", self.rate.to_string()),
-                Event::Trace(ref mut event) => event.insert("sample_rate", self.rate.to_string()),
+                Event::Log(ref mut event) => event.insert("sample_rate", rate.to_string()),
+                Event::Trace(ref mut event) => event.insert("sample_rate", rate.to_string()),
                 Event::Metric(_) => panic!("component can never receive metric events"),
             };
             output.push(event);
@@ -338,11 +524,104 @@ mod tests {
         assert_eq!(total_passed, 1);
     }
 
+    fn dynamic_sampler(target_events_per_sec: f64, max_keys: usize) -> Sample {
+        Sample::new(1, Some("service".into()), None)
+            .with_dynamic(&DynamicSampleConfig {
+                target_events_per_sec,
+                adjustment_interval_secs: 1,
+                max_keys,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn dynamic_downsamples_frequent_keys() {
+        let mut sampler = dynamic_sampler(10.0, 100);
+        let start = sampler.dynamic.as_ref().unwrap().last_adjustment;
+
+        // Until the first adjustment, all events are kept.
+        for _ in 0..100 {
+            assert_eq!(sampler.sample(Some("noisy"), start), Some(1));
+        }
+        for _ in 0..5 {
+            assert_eq!(sampler.sample(Some("rare"), start), Some(1));
+        }
+
+        // The rare key fits in its share of the budget, and leaves the rest to the noisy one.
+        let next = start + Duration::from_secs(1);
+        let rare = (0..5)
+            .map(|_| sampler.sample(Some("rare"), next))
+            .collect::<Vec<_>>();
+        assert_eq!(rare, vec![Some(1); 5]);
+        let noisy = (0..100)
+            .filter_map(|_| sampler.sample(Some("noisy"), next))
+            .collect::<Vec<_>>();
+        assert_eq!(noisy, vec![20; 5]);
+
+        // Keys which are not received anymore are forgotten, and new keys are kept.
+        for _ in 0..10 {
+            sampler.sample(Some("noisy"), next);
+        }
+        let later = next + Duration::from_secs(1);
+        assert_eq!(sampler.sample(Some("new"), later), Some(1));
+        assert_eq!(sampler.dynamic.as_ref().unwrap().keys.len(), 3);
+        let last = later + Duration::from_secs(1);
+        assert_eq!(sampler.sample(Some("new"), last), Some(1));
+        assert_eq!(sampler.dynamic.as_ref().unwrap().keys.len(), 1);
+    }
+
+    #[test]
+    fn dynamic_bounds_keys() {
+        let mut sampler = dynamic_sampler(10.0, 2);
+        let start = sampler.dynamic.as_ref().unwrap().last_adjustment;
+
+        for key in ["a", "b", "c", "d"] {
+            sampler.sample(Some(key), start);
+        }
+        let keys = &sampler.dynamic.as_ref().unwrap().keys;
+        assert_eq!(keys.len(), 3);
+        assert_eq!(keys[&None].received, 2);
+    }
+
+    #[test]
+    fn dynamic_adds_sampling_rate_to_event() {
+        let mut sampler = dynamic_sampler(1.0, 100);
+        let mut event = Event::Log(LogEvent::from("hello"));
+        event.as_mut_log().insert("service", "api");
+
+        let passing = transform_one(&mut sampler, event).unwrap();
+        assert_eq!(passing.as_log()["sample_rate"], "1".into());
+    }
+
+    #[tokio::test]
+    async fn invalid_config() {
+        for (config, error) in [
+            ("", BuildError::MissingRate),
+            (
+                "rate = 10\ndynamic.target_events_per_sec = 10.0",
+                BuildError::ConflictingRate,
+            ),
+            (
+                "dynamic.target_events_per_sec = 0.0",
+                BuildError::InvalidTarget,
+            ),
+            (
+                "dynamic.target_events_per_sec = 1.0\ndynamic.adjustment_interval_secs = 0",
+                BuildError::ZeroAdjustmentInterval,
+            ),
+        ] {
+            let config = toml::from_str::<SampleConfig>(config).unwrap();
+            let result = config.build(&TransformContext::default()).await;
+            assert_eq!(result.err().unwrap().to_string(), error.to_string());
+        }
+    }
+
     #[tokio::test]
     async fn emits_internal_events() {
         assert_transform_compliance(async move {
             let config = SampleConfig {
-                rate: 1,
+                rate: Some(1),
+                dynamic: None,
                 key_field: None,
                 exclude: None,
             };
@@ -0,0 +1,664 @@
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use async_stream::stream;
+use futures::{Stream, StreamExt};
+use snafu::Snafu;
+use vector_config::configurable_component;
+use vector_core::event::Value;
+
+use crate::{
+    config::{DataType, Input, Output, TransformConfig, TransformContext},
+    event::{Event, TraceEvent},
+    internal_events::{TailSamplingDecision, TailSamplingEarlyDecision, TailSamplingEventsDropped},
+    schema,
+    transforms::{TaskTransform, Transform},
+};
+
+/// Configuration for the `tail_sampling` transform.
+#[configurable_component(transform("tail_sampling"))]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct TailSamplingConfig {
+    /// How long to wait for the spans of a trace, in milliseconds.
+    ///
+    /// The wait starts when the first event of a trace is received. Once it elapses, the policies
+    /// are applied to all the events received for the trace, and they are either all output or all
+    /// dropped. Events of a trace received after its decision follow the same decision.
+    #[serde(default = "default_decision_wait_ms")]
+    #[derivative(Default(value = "default_decision_wait_ms()"))]
+    pub decision_wait_ms: u64,
+
+    /// The maximum number of traces waiting for a decision.
+    ///
+    /// When this limit is reached, the oldest trace is decided before the end of its wait, to bound
+    /// the memory used by the transform.
+    #[serde(default = "default_max_traces")]
+    #[derivative(Default(value = "default_max_traces()"))]
+    pub max_traces: usize,
+
+    /// The maximum number of events buffered for a trace waiting for a decision.
+    ///
+    /// When a trace reaches this limit, it is decided before the end of its wait, so that a hot or
+    /// long-lived trace ID can't use unbounded memory. Its later events follow that decision.
+    #[serde(default = "default_max_events_per_trace")]
+    #[derivative(Default(value = "default_max_events_per_trace()"))]
+    pub max_events_per_trace: usize,
+
+    /// The sampling policies.
+    ///
+    /// They are applied in order, and a trace is kept as soon as one of them matches. Traces which
+    /// match none of them are dropped.
+    #[serde(default)]
+    pub policies: Vec<SamplingPolicy>,
+}
+
+/// A sampling policy.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SamplingPolicy {
+    /// Keeps traces with at least one span in error.
+    Errors,
+
+    /// Keeps traces with at least one span lasting at least `threshold_ms`.
+    Latency {
+        /// The minimum duration of a span, in milliseconds.
+        threshold_ms: u64,
+    },
+
+    /// Keeps traces with a span whose `meta`, or whose `tags`, set `key` to one of `values`.
+    Attribute {
+        /// The attribute to look up.
+        key: String,
+
+        /// The values of the attribute for which traces are kept.
+        values: Vec<String>,
+    },
+
+    /// Keeps a fraction of traces.
+    ///
+    /// Traces are chosen by hashing their ID, so that a given trace is either always kept or
+    /// always dropped, across instances of the transform.
+    Probabilistic {
+        /// The fraction of traces to keep, between 0.0 and 1.0.
+        rate: f64,
+    },
+}
+
+impl SamplingPolicy {
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::Errors => "errors",
+            Self::Latency { .. } => "latency",
+            Self::Attribute { .. } => "attribute",
+            Self::Probabilistic { .. } => "probabilistic",
+        }
+    }
+
+    fn matches(&self, trace_id: &str, events: &[Event]) -> bool {
+        match self {
+            Self::Errors => spans(events).any(|span| match span.get("error") {
+                Some(Value::Integer(error)) => *error != 0,
+                Some(Value::Boolean(error)) => *error,
+                _ => false,
+            }),
+            Self::Latency { threshold_ms } => {
+                let threshold_ns = threshold_ms.saturating_mul(1_000_000);
+                spans(events).any(|span| match span.get("duration") {
+                    Some(Value::Integer(duration)) => {
+                        *duration >= 0 && *duration as u64 >= threshold_ns
+                    }
+                    _ => false,
+                })
+            }
+            Self::Attribute { key, values } => {
+                let matches = |attributes: Option<&Value>| {
+                    attributes
+                        .and_then(|attributes| attributes.as_object())
+                        .and_then(|attributes| attributes.get(key.as_str()))
+                        .map_or(false, |value| {
+                            let value = value.to_string_lossy();
+                            values.iter().any(|expected| *expected == value)
+                        })
+                };
+                events
+                    .iter()
+                    .any(|event| matches(event.as_trace().get("tags")))
+                    || spans(events).any(|span| matches(span.get("meta")))
+            }
+            Self::Probabilistic { rate } => {
+                (seahash::hash(trace_id.as_bytes()) as f64) < rate * u64::MAX as f64
+            }
+        }
+    }
+}
+
+/// Returns the spans of the events of a trace.
+fn spans(events: &[Event]) -> impl Iterator<Item = &BTreeMap<String, Value>> {
+    events
+        .iter()
+        .filter_map(|event| match event.as_trace().get("spans") {
+            Some(Value::Array(spans)) => Some(spans),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(Value::as_object)
+}
+
+/// Returns the ID of the trace an event belongs to, from its `trace_id` field or from its first
+/// span.
+fn trace_id(trace: &TraceEvent) -> Option<String> {
+    trace
+        .get("trace_id")
+        .or_else(|| trace.get("spans[0].trace_id"))
+        .map(|trace_id| trace_id.to_string_lossy().into_owned())
+}
+
+const fn default_decision_wait_ms() -> u64 {
+    10 * 1000
+}
+
+const fn default_max_traces() -> usize {
+    50_000
+}
+
+const fn default_max_events_per_trace() -> usize {
+    1_000
+}
+
+/// The longest interval between checks for traces whose wait has elapsed.
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+enum BuildError {
+    #[snafu(display("`decision_wait_ms` must be greater than zero"))]
+    ZeroDecisionWait,
+
+    #[snafu(display("`max_traces` must be greater than zero"))]
+    ZeroMaxTraces,
+
+    #[snafu(display("`max_events_per_trace` must be greater than zero"))]
+    ZeroMaxEventsPerTrace,
+
+    #[snafu(display("sampling rate {} is not between 0.0 and 1.0", rate))]
+    InvalidRate { rate: f64 },
+}
+
+impl_generate_config_from_default!(TailSamplingConfig);
+
+#[async_trait::async_trait]
+impl TransformConfig for TailSamplingConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        TailSampling::new(self).map(Transform::event_task)
+    }
+
+    fn input(&self) -> Input {
+        Input::trace()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Trace)]
+    }
+}
+
+/// A trace waiting for a decision.
+#[derive(Debug)]
+struct PendingTrace {
+    /// Identifies the entry of the trace in `pending_order`, which is left there when the trace is
+    /// decided early.
+    sequence: u64,
+    events: Vec<Event>,
+}
+
+#[derive(Debug)]
+pub struct TailSampling {
+    decision_wait: Duration,
+    max_traces: usize,
+    max_events_per_trace: usize,
+    policies: Vec<SamplingPolicy>,
+
+    /// The traces waiting for a decision.
+    pending: HashMap<String, PendingTrace>,
+    /// The traces waiting for a decision, in the order they started waiting, with their sequence.
+    ///
+    /// Entries of the traces decided early are skipped once they reach the front.
+    pending_order: VecDeque<(String, Instant, u64)>,
+    next_sequence: u64,
+
+    /// Whether the traces decided recently were kept, for their late events.
+    decided: HashMap<String, bool>,
+    /// The traces decided recently, in the order they were decided.
+    decided_order: VecDeque<(String, Instant)>,
+}
+
+impl TailSampling {
+    pub fn new(config: &TailSamplingConfig) -> crate::Result<Self> {
+        if config.decision_wait_ms == 0 {
+            return Err(Box::new(BuildError::ZeroDecisionWait));
+        }
+        if config.max_traces == 0 {
+            return Err(Box::new(BuildError::ZeroMaxTraces));
+        }
+        if config.max_events_per_trace == 0 {
+            return Err(Box::new(BuildError::ZeroMaxEventsPerTrace));
+        }
+        for policy in &config.policies {
+            if let SamplingPolicy::Probabilistic { rate } = policy {
+                if !(0.0..=1.0).contains(rate) {
+                    return Err(Box::new(BuildError::InvalidRate { rate: *rate }));
+                }
+            }
+        }
+
+        Ok(Self {
+            decision_wait: Duration::from_millis(config.decision_wait_ms),
+            max_traces: config.max_traces,
+            max_events_per_trace: config.max_events_per_trace,
+            policies: config.policies.clone(),
+            pending: HashMap::new(),
+            pending_order: VecDeque::new(),
+            next_sequence: 0,
+            decided: HashMap::new(),
+            decided_order: VecDeque::new(),
+        })
+    }
+
+    fn record(&mut self, event: Event, now: Instant, output: &mut Vec<Event>) {
+        let trace_id = match trace_id(event.as_trace()) {
+            Some(trace_id) => trace_id,
+            // Events without a trace ID can't be grouped, so there is nothing to decide.
+            None => {
+                output.push(event);
+                return;
+            }
+        };
+
+        if let Some(&kept) = self.decided.get(&trace_id) {
+            if kept {
+                output.push(event);
+            } else {
+                emit!(TailSamplingEventsDropped { count: 1 });
+            }
+            return;
+        }
+
+        if !self.pending.contains_key(&trace_id) {
+            if self.pending.len() >= self.max_traces {
+                if let Some(oldest) = self.pop_pending() {
+                    emit!(TailSamplingEarlyDecision {
+                        reason: "max_traces"
+                    });
+                    self.decide(oldest, now, output);
+                }
+            }
+            let sequence = self.next_sequence;
+            self.next_sequence += 1;
+            self.pending_order
+                .push_back((trace_id.clone(), now, sequence));
+            self.pending.insert(
+                trace_id.clone(),
+                PendingTrace {
+                    sequence,
+                    events: Vec::new(),
+                },
+            );
+        }
+
+        let trace = self.pending.get_mut(&trace_id).expect("trace is pending");
+        trace.events.push(event);
+        if trace.events.len() >= self.max_events_per_trace {
+            emit!(TailSamplingEarlyDecision {
+                reason: "max_events_per_trace"
+            });
+            self.decide(trace_id, now, output);
+        }
+    }
+
+    /// Returns the oldest trace waiting for a decision, skipping the entries of the traces decided
+    /// early.
+    fn pop_pending(&mut self) -> Option<String> {
+        while let Some((trace_id, _, sequence)) = self.pending_order.pop_front() {
+            if self.is_pending(&trace_id, sequence) {
+                return Some(trace_id);
+            }
+        }
+        None
+    }
+
+    fn is_pending(&self, trace_id: &str, sequence: u64) -> bool {
+        self.pending
+            .get(trace_id)
+            .map_or(false, |trace| trace.sequence == sequence)
+    }
+
+    /// Decides the traces whose wait has elapsed, and forgets the decisions which are old enough
+    /// that no more events are expected for their traces.
+    fn flush_into(&mut self, now: Instant, output: &mut Vec<Event>) {
+        while let Some((_, since, _)) = self.pending_order.front() {
+            if now.duration_since(*since) < self.decision_wait {
+                break;
+            }
+            let (trace_id, _, sequence) = self.pending_order.pop_front().expect("front exists");
+            if self.is_pending(&trace_id, sequence) {
+                self.decide(trace_id, now, output);
+            }
+        }
+
+        while let Some((_, since)) = self.decided_order.front() {
+            if now.duration_since(*since) < self.decision_wait {
+                break;
+            }
+            let (trace_id, _) = self.decided_order.pop_front().expect("front exists");
+            self.decided.remove(&trace_id);
+        }
+    }
+
+    /// Decides all the traces waiting for a decision, regardless of their wait.
+    fn flush_all_into(&mut self, now: Instant, output: &mut Vec<Event>) {
+        while let Some(trace_id) = self.pop_pending() {
+            self.decide(trace_id, now, output);
+        }
+    }
+
+    fn decide(&mut self, trace_id: String, now: Instant, output: &mut Vec<Event>) {
+        let events = match self.pending.remove(&trace_id) {
+            Some(trace) => trace.events,
+            None => return,
+        };
+
+        let policy = self
+            .policies
+            .iter()
+            .find(|policy| policy.matches(&trace_id, &events));
+        let kept = policy.is_some();
+        emit!(TailSamplingDecision {
+            kept,
+            policy: policy.map(SamplingPolicy::name),
+        });
+
+        if kept {
+            output.extend(events);
+        } else {
+            emit!(TailSamplingEventsDropped {
+                count: events.len()
+            });
+        }
+
+        // The decisions are bounded like the pending traces, as they are kept for as long.
+        if self.decided.len() >= self.max_traces {
+            if let Some((oldest, _)) = self.decided_order.pop_front() {
+                self.decided.remove(&oldest);
+            }
+        }
+        self.decided_order.push_back((trace_id.clone(), now));
+        self.decided.insert(trace_id, kept);
+    }
+}
+
+impl TaskTransform<Event> for TailSampling {
+    fn transform(
+        mut self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut check_interval = tokio::time::interval(self.decision_wait.min(MAX_CHECK_INTERVAL));
+
+        Box::pin(stream! {
+            let mut output = Vec::new();
+            let mut done = false;
+            while !done {
+                tokio::select! {
+                    _ = check_interval.tick() => {
+                        self.flush_into(Instant::now(), &mut output);
+                    },
+                    maybe_event = input_rx.next() => {
+                        match maybe_event {
+                            None => {
+                                self.flush_all_into(Instant::now(), &mut output);
+                                done = true;
+                            }
+                            Some(event) => self.record(event, Instant::now(), &mut output),
+                        }
+                    }
+                };
+                for event in output.drain(..) {
+                    yield event;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+    use vector_core::event::LogEvent;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<TailSamplingConfig>();
+    }
+
+    fn tail_sampling(policies: &str) -> TailSampling {
+        let config = toml::from_str::<TailSamplingConfig>(&format!(
+            "decision_wait_ms = 1000\nmax_traces = 2\nmax_events_per_trace = 3\n{}",
+            policies
+        ))
+        .unwrap();
+        TailSampling::new(&config).unwrap()
+    }
+
+    fn span(trace_id: i64, duration_ms: i64, error: i64, meta: &[(&str, &str)]) -> Value {
+        let meta: BTreeMap<String, Value> = meta
+            .iter()
+            .map(|(key, value)| (key.to_string(), Value::from(*value)))
+            .collect();
+        Value::from(BTreeMap::from([
+            ("trace_id".to_string(), Value::from(trace_id)),
+            ("duration".to_string(), Value::from(duration_ms * 1_000_000)),
+            ("error".to_string(), Value::from(error)),
+            ("meta".to_string(), Value::from(meta)),
+        ]))
+    }
+
+    fn trace(spans: Vec<Value>) -> Event {
+        let mut trace = TraceEvent::from(LogEvent::default());
+        trace.insert("spans", spans);
+        Event::Trace(trace)
+    }
+
+    fn trace_ids(events: &[Event]) -> Vec<String> {
+        events
+            .iter()
+            .filter_map(|event| trace_id(event.as_trace()))
+            .collect()
+    }
+
+    #[test]
+    fn invalid_config() {
+        for (config, error) in [
+            ("decision_wait_ms = 0", BuildError::ZeroDecisionWait),
+            ("max_traces = 0", BuildError::ZeroMaxTraces),
+            (
+                "max_events_per_trace = 0",
+                BuildError::ZeroMaxEventsPerTrace,
+            ),
+            (
+                "policies = [{ type = \"probabilistic\", rate = 1.5 }]",
+                BuildError::InvalidRate { rate: 1.5 },
+            ),
+        ] {
+            let config = toml::from_str::<TailSamplingConfig>(config).unwrap();
+            assert_eq!(
+                TailSampling::new(&config).unwrap_err().to_string(),
+                error.to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn policies() {
+        let mut sampler = tail_sampling(
+            r#"
+            [[policies]]
+            type = "errors"
+
+            [[policies]]
+            type = "latency"
+            threshold_ms = 500
+
+            [[policies]]
+            type = "attribute"
+            key = "http.status_code"
+            values = ["429", "503"]
+            "#,
+        );
+        let start = Instant::now();
+        let mut output = vec![];
+
+        sampler.record(trace(vec![span(1, 10, 1, &[])]), start, &mut output);
+        sampler.record(trace(vec![span(2, 10, 0, &[])]), start, &mut output);
+        sampler.flush_into(start + Duration::from_secs(1), &mut output);
+        sampler.record(trace(vec![span(3, 800, 0, &[])]), start, &mut output);
+        sampler.record(
+            trace(vec![span(4, 10, 0, &[("http.status_code", "503")])]),
+            start,
+            &mut output,
+        );
+        sampler.flush_into(start + Duration::from_secs(1), &mut output);
+
+        assert_eq!(trace_ids(&output), ["1", "3", "4"]);
+    }
+
+    #[test]
+    fn whole_traces() {
+        let mut sampler = tail_sampling("policies = [{ type = \"errors\" }]");
+        let start = Instant::now();
+        let mut output = vec![];
+
+        sampler.record(trace(vec![span(1, 10, 0, &[])]), start, &mut output);
+        sampler.record(trace(vec![span(2, 10, 0, &[])]), start, &mut output);
+        sampler.record(trace(vec![span(1, 10, 1, &[])]), start, &mut output);
+        assert!(output.is_empty());
+
+        // Traces are decided once their wait elapses, with all their spans.
+        sampler.flush_into(start + Duration::from_millis(500), &mut output);
+        assert!(output.is_empty());
+        sampler.flush_into(start + Duration::from_secs(1), &mut output);
+        assert_eq!(trace_ids(&output), ["1", "1"]);
+
+        // Late events follow the decision of their trace.
+        output.clear();
+        let late = start + Duration::from_millis(1500);
+        sampler.record(trace(vec![span(1, 10, 0, &[])]), late, &mut output);
+        sampler.record(trace(vec![span(2, 10, 1, &[])]), late, &mut output);
+        assert_eq!(trace_ids(&output), ["1"]);
+
+        // Decisions are forgotten after another wait.
+        output.clear();
+        sampler.flush_into(start + Duration::from_secs(2), &mut output);
+        assert!(sampler.decided.is_empty());
+    }
+
+    #[test]
+    fn max_traces() {
+        let mut sampler = tail_sampling("policies = [{ type = \"errors\" }]");
+        let start = Instant::now();
+        let mut output = vec![];
+
+        sampler.record(trace(vec![span(1, 10, 1, &[])]), start, &mut output);
+        sampler.record(trace(vec![span(2, 10, 1, &[])]), start, &mut output);
+        sampler.record(trace(vec![span(3, 10, 1, &[])]), start, &mut output);
+
+        // The oldest trace is decided early to make room for the new one.
+        assert_eq!(trace_ids(&output), ["1"]);
+        assert_eq!(sampler.pending.len(), 2);
+    }
+
+    #[test]
+    fn max_events_per_trace() {
+        let mut sampler = tail_sampling("policies = [{ type = \"errors\" }]");
+        let start = Instant::now();
+        let mut output = vec![];
+
+        sampler.record(trace(vec![span(1, 10, 1, &[])]), start, &mut output);
+        sampler.record(trace(vec![span(2, 10, 1, &[])]), start, &mut output);
+        sampler.record(trace(vec![span(1, 10, 0, &[])]), start, &mut output);
+        assert!(output.is_empty());
+
+        // The trace is decided early once it buffers the maximum number of events.
+        sampler.record(trace(vec![span(1, 10, 0, &[])]), start, &mut output);
+        assert_eq!(trace_ids(&output), ["1", "1", "1"]);
+        assert_eq!(sampler.pending.len(), 1);
+
+        // Its later events follow the decision without being buffered.
+        output.clear();
+        sampler.record(trace(vec![span(1, 10, 0, &[])]), start, &mut output);
+        assert_eq!(trace_ids(&output), ["1"]);
+        assert!(!sampler.pending.contains_key("1"));
+
+        // Its entry is skipped when the wait elapses, and doesn't count towards `max_traces`.
+        output.clear();
+        sampler.record(trace(vec![span(3, 10, 1, &[])]), start, &mut output);
+        assert!(output.is_empty());
+        sampler.flush_into(start + Duration::from_secs(1), &mut output);
+        assert_eq!(trace_ids(&output), ["2", "3"]);
+        assert!(sampler.pending_order.is_empty());
+    }
+
+    #[test]
+    fn probabilistic() {
+        let mut sampler = tail_sampling("policies = [{ type = \"probabilistic\", rate = 0.25 }]");
+        let start = Instant::now();
+        let mut output = vec![];
+
+        for trace_id in 0..1000 {
+            sampler.record(trace(vec![span(trace_id, 10, 0, &[])]), start, &mut output);
+            sampler.flush_all_into(start, &mut output);
+        }
+        assert!((150..350).contains(&output.len()));
+
+        // Decisions only depend on the trace ID.
+        let mut again = tail_sampling("policies = [{ type = \"probabilistic\", rate = 0.25 }]");
+        let mut output_again = vec![];
+        for trace_id in 0..1000 {
+            again.record(
+                trace(vec![span(trace_id, 10, 0, &[])]),
+                start,
+                &mut output_again,
+            );
+            again.flush_all_into(start, &mut output_again);
+        }
+        assert_eq!(trace_ids(&output), trace_ids(&output_again));
+    }
+
+    #[tokio::test]
+    async fn flushes_on_shutdown() {
+        let sampler = toml::from_str::<TailSamplingConfig>("policies = [{ type = \"errors\" }]")
+            .unwrap()
+            .build(&TransformContext::default())
+            .await
+            .unwrap()
+            .into_task();
+
+        let inputs = vec![
+            trace(vec![span(1, 10, 1, &[])]),
+            trace(vec![span(2, 10, 0, &[])]),
+        ];
+        let output = sampler
+            .transform_events(Box::pin(stream::iter(inputs)))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(trace_ids(&output), ["1"]);
+    }
+}
@@ -1,32 +1,52 @@
-use std::{num::NonZeroU32, pin::Pin, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroU32,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
 
-use async_stream::stream;
-use futures::{Stream, StreamExt};
-use governor::{clock, Quota, RateLimiter};
+use governor::{
+    clock, middleware::NoOpMiddleware, state::keyed::DashMapStateStore, Quota, RateLimiter,
+};
 use serde_with::serde_as;
 use snafu::Snafu;
 use vector_config::configurable_component;
+use vector_core::transform::{SyncTransform, TransformOutputsBuf};
 
 use crate::{
     conditions::{AnyCondition, Condition},
     config::{DataType, Input, Output, TransformConfig, TransformContext},
     event::Event,
-    internal_events::{TemplateRenderingError, ThrottleEventDiscarded},
+    internal_events::{TemplateRenderingError, ThrottleEventDiscarded, ThrottleEventThrottled},
     schema,
     template::Template,
-    transforms::{TaskTransform, Transform},
+    transforms::Transform,
 };
 
+const DROPPED: &str = "dropped";
+
+/// The `key` tag of the metrics of the keys beyond `max_metric_keys`.
+const OTHER_KEYS: &str = "_other";
+
 /// Configuration for the `throttle` transform.
 #[serde_as]
 #[configurable_component(transform("throttle"))]
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
 #[serde(deny_unknown_fields)]
 pub struct ThrottleConfig {
     /// The number of events allowed for a given bucket per configured `window_secs`.
     ///
-    /// Each unique key will have its own `threshold`.
-    threshold: u32,
+    /// Each unique key will have its own `threshold`. Required unless `threshold_bytes` is set.
+    threshold: Option<u32>,
+
+    /// The number of bytes of events allowed for a given bucket per configured `window_secs`.
+    ///
+    /// Each unique key will have its own `threshold_bytes`, measured as the estimated size of its
+    /// events once encoded as JSON. Events larger than `threshold_bytes` are always throttled. When
+    /// both `threshold` and `threshold_bytes` are set, events are throttled as soon as either of
+    /// them is exceeded.
+    threshold_bytes: Option<u32>,
 
     /// The time window in which the configured `threshold` is applied, in seconds.
     #[serde_as(as = "serde_with::DurationSeconds<f64>")]
@@ -43,6 +63,26 @@ pub struct ThrottleConfig {
 
     /// A logical condition used to exclude events from sampling.
     exclude: Option<AnyCondition>,
+
+    /// Reroutes throttled events to a named output instead of dropping them.
+    ///
+    /// When set to `true`, throttled events are forwarded to a specially-named output, `dropped`,
+    /// so that they can be sent to another destination, such as cheaper storage.
+    #[serde(default = "crate::serde::default_false")]
+    reroute_dropped: bool,
+
+    /// The maximum number of keys for which throttled events are reported with their own `key`
+    /// tag.
+    ///
+    /// Events throttled for any other key are reported with the `key` tag set to `_other`, to bound
+    /// the cardinality of the metrics.
+    #[serde(default = "default_max_metric_keys")]
+    #[derivative(Default(value = "default_max_metric_keys()"))]
+    max_metric_keys: usize,
+}
+
+const fn default_max_metric_keys() -> usize {
+    100
 }
 
 impl_generate_config_from_default!(ThrottleConfig);
@@ -50,7 +90,7 @@ impl_generate_config_from_default!(ThrottleConfig);
 #[async_trait::async_trait]
 impl TransformConfig for ThrottleConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Throttle::new(self, context, clock::MonotonicClock).map(Transform::event_task)
+        Throttle::new(self, context, clock::MonotonicClock).map(Transform::synchronous)
     }
 
     fn input(&self) -> Input {
@@ -59,17 +99,141 @@ impl TransformConfig for ThrottleConfig {
 
     fn outputs(&self, merged_definition: &schema::Definition) -> Vec<Output> {
         // The event is not modified, so the definition is passed through as-is
-        vec![Output::default(DataType::Log).with_schema_definition(merged_definition.clone())]
+        let default_output =
+            Output::default(DataType::Log).with_schema_definition(merged_definition.clone());
+
+        if self.reroute_dropped {
+            vec![
+                default_output,
+                Output::default(DataType::Log)
+                    .with_schema_definition(merged_definition.clone())
+                    .with_port(DROPPED),
+            ]
+        } else {
+            vec![default_output]
+        }
+    }
+
+    fn conditions(&self) -> Vec<(String, &AnyCondition)> {
+        self.exclude
+            .iter()
+            .map(|condition| ("exclude".to_owned(), condition))
+            .collect()
+    }
+}
+
+type KeyedRateLimiter<C, I> =
+    RateLimiter<Option<String>, DashMapStateStore<Option<String>>, C, NoOpMiddleware<I>>;
+
+/// The limit which caused an event to be throttled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Limit {
+    Events,
+    Bytes,
+}
+
+/// A rate limiter of the bytes of each key, following the same cell rate algorithm as the limiter
+/// of events.
+///
+/// Unlike the latter, it can check whether an event fits in the budget of its key without using it
+/// up, so that events throttled by the limit of events don't use up the budget of bytes.
+struct BytesLimiter<I> {
+    threshold: u64,
+    /// The window, in nanoseconds.
+    window: u64,
+    /// The instant the times of the keys are relative to.
+    origin: I,
+    /// The time at which the budget of each key is fully replenished, in nanoseconds since
+    /// `origin`.
+    keys: Mutex<HashMap<Option<String>, u64>>,
+}
+
+/// The bytes of an event fitting in the budget of its key, used up by `consume`.
+struct BytesReservation<'a> {
+    keys: MutexGuard<'a, HashMap<Option<String>, u64>>,
+    key: &'a Option<String>,
+    replenished_at: u64,
+}
+
+impl<I: clock::Reference> BytesLimiter<I> {
+    fn new(window: Duration, threshold: u32, origin: I) -> crate::Result<Self> {
+        if threshold == 0 || window.is_zero() {
+            return Err(Box::new(ConfigError::NonZero));
+        }
+        Ok(Self {
+            threshold: u64::from(threshold),
+            window: u64::try_from(window.as_nanos()).unwrap_or(u64::MAX),
+            origin,
+            keys: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn nanos(&self, now: I) -> u64 {
+        u64::try_from(Duration::from(now.duration_since(self.origin)).as_nanos())
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Returns a reservation of `size` bytes for `key`, unless they don't fit in its budget.
+    ///
+    /// An event larger than the whole budget never fits.
+    fn check<'a>(
+        &'a self,
+        key: &'a Option<String>,
+        size: usize,
+        now: I,
+    ) -> Option<BytesReservation<'a>> {
+        let now = self.nanos(now);
+        let keys = self.keys.lock().expect("bytes limiter lock poisoned");
+        // The time it takes to replenish the budget of `size` bytes.
+        let cost =
+            u64::try_from(u128::from(self.window) * size as u128 / u128::from(self.threshold))
+                .unwrap_or(u64::MAX);
+        let replenished_at = keys
+            .get(key)
+            .map_or(now, |replenished_at| (*replenished_at).max(now))
+            .saturating_add(cost);
+        (replenished_at - now <= self.window).then_some(BytesReservation {
+            keys,
+            key,
+            replenished_at,
+        })
+    }
+
+    /// Forgets the keys whose budget is fully replenished.
+    fn retain_recent(&self, now: I) {
+        let now = self.nanos(now);
+        self.keys
+            .lock()
+            .expect("bytes limiter lock poisoned")
+            .retain(|_, replenished_at| *replenished_at > now);
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.keys.lock().expect("bytes limiter lock poisoned").len()
+    }
+}
+
+impl<'a> BytesReservation<'a> {
+    fn consume(mut self) {
+        self.keys.insert(self.key.clone(), self.replenished_at);
     }
 }
 
 #[derive(Clone)]
 pub struct Throttle<C: clock::Clock<Instant = I>, I: clock::Reference> {
-    quota: Quota,
+    /// The limiters of the number of events and of bytes, shared by the clones of the transform.
+    events_limiter: Option<Arc<KeyedRateLimiter<C, I>>>,
+    bytes_limiter: Option<Arc<BytesLimiter<I>>>,
+    clock: C,
     flush_keys_interval: Duration,
+    last_flush: I,
     key_field: Option<Template>,
     exclude: Option<Condition>,
-    clock: C,
+    reroute_dropped: bool,
+    max_metric_keys: usize,
+    /// The keys reported with their own `key` tag, shared by the clones of the transform.
+    metric_keys: Arc<Mutex<HashSet<String>>>,
 }
 
 impl<C, I> Throttle<C, I>
@@ -84,17 +248,23 @@ where
     ) -> crate::Result<Self> {
         let flush_keys_interval = config.window_secs;
 
-        let threshold = match NonZeroU32::new(config.threshold) {
-            Some(threshold) => threshold,
-            None => return Err(Box::new(ConfigError::NonZero)),
-        };
+        if config.threshold.is_none() && config.threshold_bytes.is_none() {
+            return Err(Box::new(ConfigError::MissingThreshold));
+        }
+        let events_limiter = config
+            .threshold
+            .map(|threshold| {
+                quota(flush_keys_interval, threshold)
+                    .map(|quota| Arc::new(RateLimiter::dashmap_with_clock(quota, &clock)))
+            })
+            .transpose()?;
+        let bytes_limiter = config
+            .threshold_bytes
+            .map(|threshold| {
+                BytesLimiter::new(flush_keys_interval, threshold, clock.now()).map(Arc::new)
+            })
+            .transpose()?;
 
-        let quota = match Quota::with_period(Duration::from_secs_f64(
-            flush_keys_interval.as_secs_f64() / f64::from(threshold.get()),
-        )) {
-            Some(quota) => quota.allow_burst(threshold),
-            None => return Err(Box::new(ConfigError::NonZero)),
-        };
         let exclude = config
             .exclude
             .as_ref()
@@ -102,106 +272,158 @@ where
             .transpose()?;
 
         Ok(Self {
-            quota,
+            events_limiter,
+            bytes_limiter,
+            last_flush: clock.now(),
             clock,
             flush_keys_interval,
             key_field: config.key_field.clone(),
             exclude,
+            reroute_dropped: config.reroute_dropped,
+            max_metric_keys: config.max_metric_keys,
+            metric_keys: Arc::new(Mutex::new(HashSet::new())),
         })
     }
+
+    /// Returns the limit exceeded by the event, if any.
+    ///
+    /// The budgets of the key are only used up when the event is within both limits: the bytes are
+    /// checked first, without using them up, and only consumed once the events limiter allowed the
+    /// event too.
+    fn check(&self, event: &Event, key: &Option<String>) -> Option<Limit> {
+        let bytes = match &self.bytes_limiter {
+            Some(limiter) => {
+                let size = event.as_log().estimated_json_encoded_size_of();
+                match limiter.check(key, size, self.clock.now()) {
+                    Some(reservation) => Some(reservation),
+                    None => return Some(Limit::Bytes),
+                }
+            }
+            None => None,
+        };
+
+        if let Some(limiter) = &self.events_limiter {
+            if limiter.check_key(key).is_err() {
+                return Some(Limit::Events);
+            }
+        }
+
+        if let Some(reservation) = bytes {
+            reservation.consume();
+        }
+        None
+    }
+
+    /// Returns the `key` tag for the metrics of a key, bounding the number of distinct tags.
+    fn metric_key(&self, key: Option<String>) -> String {
+        let key = key.unwrap_or_else(|| "None".to_string());
+        let mut metric_keys = self.metric_keys.lock().expect("metric keys lock poisoned");
+        if metric_keys.contains(&key) {
+            key
+        } else if metric_keys.len() < self.max_metric_keys {
+            metric_keys.insert(key.clone());
+            key
+        } else {
+            OTHER_KEYS.to_string()
+        }
+    }
+
+    /// Forgets the state of the keys which were not seen recently.
+    fn flush_keys(&mut self) {
+        let now = self.clock.now();
+        if Duration::from(now.duration_since(self.last_flush)) < self.flush_keys_interval * 2 {
+            return;
+        }
+        self.last_flush = now;
+
+        if let Some(limiter) = &self.events_limiter {
+            limiter.retain_recent();
+        }
+        if let Some(limiter) = &self.bytes_limiter {
+            limiter.retain_recent(now);
+        }
+    }
 }
 
-impl<C, I> TaskTransform<Event> for Throttle<C, I>
+fn quota(window: Duration, threshold: u32) -> crate::Result<Quota> {
+    let threshold = match NonZeroU32::new(threshold) {
+        Some(threshold) => threshold,
+        None => return Err(Box::new(ConfigError::NonZero)),
+    };
+
+    match Quota::with_period(Duration::from_secs_f64(
+        window.as_secs_f64() / f64::from(threshold.get()),
+    )) {
+        Some(quota) => Ok(quota.allow_burst(threshold)),
+        None => Err(Box::new(ConfigError::NonZero)),
+    }
+}
+
+impl<C, I> SyncTransform for Throttle<C, I>
 where
-    C: clock::Clock<Instant = I> + Send + 'static,
-    I: clock::Reference + Send + 'static,
+    C: clock::Clock<Instant = I> + Send + Sync + 'static,
+    I: clock::Reference + Send + Sync + 'static,
 {
-    fn transform(
-        self: Box<Self>,
-        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
-    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
-    where
-        Self: 'static,
-    {
-        let mut flush_keys = tokio::time::interval(self.flush_keys_interval * 2);
-
-        let limiter = RateLimiter::dashmap_with_clock(self.quota, &self.clock);
-
-        Box::pin(stream! {
-          loop {
-            let done = tokio::select! {
-                biased;
-
-                maybe_event = input_rx.next() => {
-                    match maybe_event {
-                        None => true,
-                        Some(event) => {
-                            let (throttle, event) = match self.exclude.as_ref() {
-                                Some(condition) => {
-                                    let (result, event) = condition.check(event);
-                                    (!result, event)
-                                },
-                                _ => (true, event)
-                            };
-                            let output = if throttle {
-                                let key = self.key_field.as_ref().and_then(|t| {
-                                    t.render_string(&event)
-                                        .map_err(|error| {
-                                            emit!(TemplateRenderingError {
-                                                error,
-                                                field: Some("key_field"),
-                                                drop_event: false,
-                                            })
-                                        })
-                                        .ok()
-                                });
-
-                                match limiter.check_key(&key) {
-                                    Ok(()) => {
-                                        Some(event)
-                                    }
-                                    _ => {
-                                        if let Some(key) = key {
-                                            emit!(ThrottleEventDiscarded{key})
-                                        } else {
-                                            emit!(ThrottleEventDiscarded{key: "None".to_string()})
-                                        }
-                                        None
-                                    }
-                                }
-                            } else {
-                                Some(event)
-                            };
-                            if let Some(event) = output {
-                                yield event;
-                            }
-                            false
-                        }
-                    }
-                }
-                _ = flush_keys.tick() => {
-                    limiter.retain_recent();
-                    false
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        self.flush_keys();
+
+        let (throttle, event) = match self.exclude.as_ref() {
+            Some(condition) => {
+                let (result, event) = condition.check(event);
+                (!result, event)
+            }
+            _ => (true, event),
+        };
+        if !throttle {
+            output.push(event);
+            return;
+        }
+
+        let key = self.key_field.as_ref().and_then(|t| {
+            t.render_string(&event)
+                .map_err(|error| {
+                    emit!(TemplateRenderingError {
+                        error,
+                        field: Some("key_field"),
+                        drop_event: false,
+                    })
+                })
+                .ok()
+        });
+
+        match self.check(&event, &key) {
+            None => output.push(event),
+            Some(limit) => {
+                emit!(ThrottleEventThrottled {
+                    key: &self.metric_key(key.clone()),
+                    limit: match limit {
+                        Limit::Events => "events",
+                        Limit::Bytes => "bytes",
+                    },
+                });
+                if self.reroute_dropped {
+                    output.push_named(DROPPED, event);
+                } else {
+                    emit!(ThrottleEventDiscarded {
+                        key: key.unwrap_or_else(|| "None".to_string()),
+                    });
                 }
-            };
-            if done { break }
-          }
-        })
+            }
+        }
     }
 }
 
 #[derive(Debug, Snafu)]
 pub enum ConfigError {
-    #[snafu(display("`threshold`, and `window_secs` must be non-zero"))]
+    #[snafu(display("`threshold`, `threshold_bytes` and `window_secs` must be non-zero"))]
     NonZero,
+
+    #[snafu(display("one of `threshold` or `threshold_bytes` must be set"))]
+    MissingThreshold,
 }
 
 #[cfg(test)]
 mod tests {
-    use std::task::Poll;
-
-    use futures::SinkExt;
-
     use super::*;
     use crate::{
         event::LogEvent, test_util::components::assert_transform_compliance,
@@ -215,8 +437,37 @@ mod tests {
         crate::test_util::test_generate_config::<ThrottleConfig>();
     }
 
-    #[tokio::test]
-    async fn throttle_events() {
+    type TestThrottle =
+        Throttle<clock::FakeRelativeClock, <clock::FakeRelativeClock as clock::Clock>::Instant>;
+
+    fn outputs(config: &ThrottleConfig) -> TransformOutputsBuf {
+        TransformOutputsBuf::new_with_capacity(config.outputs(&schema::Definition::any()), 1)
+    }
+
+    fn throttle(config: &str) -> (TestThrottle, clock::FakeRelativeClock, TransformOutputsBuf) {
+        let clock = clock::FakeRelativeClock::default();
+        let config = toml::from_str::<ThrottleConfig>(config).unwrap();
+        let throttle = Throttle::new(&config, &TransformContext::default(), clock.clone()).unwrap();
+        (throttle, clock, outputs(&config))
+    }
+
+    fn send(
+        throttle: &mut TestThrottle,
+        output: &mut TransformOutputsBuf,
+        event: impl Into<Event>,
+    ) -> usize {
+        throttle.transform(event.into(), output);
+        output.drain().count()
+    }
+
+    fn log(message: &str) -> LogEvent {
+        let mut log = LogEvent::default();
+        log.insert("message", message);
+        log
+    }
+
+    #[test]
+    fn throttle_events() {
         let clock = clock::FakeRelativeClock::default();
         let config = toml::from_str::<ThrottleConfig>(
             r#"
@@ -226,60 +477,37 @@ window_secs = 5
         )
         .unwrap();
 
-        let throttle = Throttle::new(&config, &TransformContext::default(), clock.clone())
-            .map(Transform::event_task)
-            .unwrap();
-
-        let throttle = throttle.into_task();
-
-        let (mut tx, rx) = futures::channel::mpsc::channel(10);
-        let mut out_stream = throttle.transform_events(Box::pin(rx));
+        let mut throttle =
+            Throttle::new(&config, &TransformContext::default(), clock.clone()).unwrap();
 
-        // tokio interval is always immediately ready, so we poll once to make sure
-        // we trip it/set the interval in the future
-        assert_eq!(Poll::Pending, futures::poll!(out_stream.next()));
+        let mut out = outputs(&config);
 
-        tx.send(LogEvent::default().into()).await.unwrap();
-        tx.send(LogEvent::default().into()).await.unwrap();
+        throttle.transform(LogEvent::default().into(), &mut out);
+        throttle.transform(LogEvent::default().into(), &mut out);
 
-        let mut count = 0_u8;
-        while count < 2 {
-            if let Some(_event) = out_stream.next().await {
-                count += 1;
-            } else {
-                panic!("Unexpectedly received None in output stream");
-            }
-        }
+        let count = out.drain().count();
         assert_eq!(2, count);
 
         clock.advance(Duration::from_secs(2));
 
-        tx.send(LogEvent::default().into()).await.unwrap();
+        throttle.transform(LogEvent::default().into(), &mut out);
 
-        // We should be back to pending, having the second event dropped
-        assert_eq!(Poll::Pending, futures::poll!(out_stream.next()));
+        // We should have nothing, having the third event dropped
+        assert_eq!(0, out.drain().count());
 
         clock.advance(Duration::from_secs(3));
 
-        tx.send(LogEvent::default().into()).await.unwrap();
+        throttle.transform(LogEvent::default().into(), &mut out);
 
         // The rate limiter should now be refreshed and allow an additional event through
-        if let Some(_event) = out_stream.next().await {
-        } else {
-            panic!("Unexpectedly received None in output stream");
-        }
-
-        // We should be back to pending, having nothing waiting for us
-        assert_eq!(Poll::Pending, futures::poll!(out_stream.next()));
-
-        tx.disconnect();
+        assert_eq!(1, out.drain().count());
 
-        // And still nothing there
-        assert_eq!(Poll::Ready(None), futures::poll!(out_stream.next()));
+        // We should have nothing waiting for us
+        assert_eq!(0, out.drain().count());
     }
 
-    #[tokio::test]
-    async fn throttle_exclude() {
+    #[test]
+    fn throttle_exclude() {
         let clock = clock::FakeRelativeClock::default();
         let config = toml::from_str::<ThrottleConfig>(
             r#"
@@ -292,69 +520,43 @@ exists(.special)
         )
         .unwrap();
 
-        let throttle = Throttle::new(&config, &TransformContext::default(), clock.clone())
-            .map(Transform::event_task)
-            .unwrap();
-
-        let throttle = throttle.into_task();
+        let mut throttle =
+            Throttle::new(&config, &TransformContext::default(), clock.clone()).unwrap();
 
-        let (mut tx, rx) = futures::channel::mpsc::channel(10);
-        let mut out_stream = throttle.transform_events(Box::pin(rx));
+        let mut out = outputs(&config);
 
-        // tokio interval is always immediately ready, so we poll once to make sure
-        // we trip it/set the interval in the future
-        assert_eq!(Poll::Pending, futures::poll!(out_stream.next()));
+        throttle.transform(LogEvent::default().into(), &mut out);
+        throttle.transform(LogEvent::default().into(), &mut out);
 
-        tx.send(LogEvent::default().into()).await.unwrap();
-        tx.send(LogEvent::default().into()).await.unwrap();
-
-        let mut count = 0_u8;
-        while count < 2 {
-            if let Some(_event) = out_stream.next().await {
-                count += 1;
-            } else {
-                panic!("Unexpectedly received None in output stream");
-            }
-        }
+        let count = out.drain().count();
         assert_eq!(2, count);
 
         clock.advance(Duration::from_secs(2));
 
-        tx.send(LogEvent::default().into()).await.unwrap();
+        throttle.transform(LogEvent::default().into(), &mut out);
 
-        // We should be back to pending, having the second event dropped
-        assert_eq!(Poll::Pending, futures::poll!(out_stream.next()));
+        // We should have nothing, having the third event dropped
+        assert_eq!(0, out.drain().count());
 
         let mut special_log = LogEvent::default();
         special_log.insert("special", "true");
-        tx.send(special_log.into()).await.unwrap();
+        throttle.transform(special_log.into(), &mut out);
         // The rate limiter should allow this log through regardless of current limit
-        if let Some(_event) = out_stream.next().await {
-        } else {
-            panic!("Unexpectedly received None in output stream");
-        }
+        assert_eq!(1, out.drain().count());
 
         clock.advance(Duration::from_secs(3));
 
-        tx.send(LogEvent::default().into()).await.unwrap();
+        throttle.transform(LogEvent::default().into(), &mut out);
 
         // The rate limiter should now be refreshed and allow an additional event through
-        if let Some(_event) = out_stream.next().await {
-        } else {
-            panic!("Unexpectedly received None in output stream");
-        }
+        assert_eq!(1, out.drain().count());
 
-        // We should be back to pending, having nothing waiting for us
-        assert_eq!(Poll::Pending, futures::poll!(out_stream.next()));
-
-        tx.disconnect();
-
-        // And still nothing there
-        assert_eq!(Poll::Ready(None), futures::poll!(out_stream.next()));
+        // We should have nothing waiting for us
+        assert_eq!(0, out.drain().count());
     }
 
-    #[tokio::test]
-    async fn throttle_buckets() {
+    #[test]
+    fn throttle_buckets() {
         let clock = clock::FakeRelativeClock::default();
         let config = toml::from_str::<ThrottleConfig>(
             r#"
@@ -365,53 +567,273 @@ key_field = "{{ bucket }}"
         )
         .unwrap();
 
-        let throttle = Throttle::new(&config, &TransformContext::default(), clock.clone())
-            .map(Transform::event_task)
-            .unwrap();
+        let mut throttle =
+            Throttle::new(&config, &TransformContext::default(), clock.clone()).unwrap();
 
-        let throttle = throttle.into_task();
-
-        let (mut tx, rx) = futures::channel::mpsc::channel(10);
-        let mut out_stream = throttle.transform_events(Box::pin(rx));
-
-        // tokio interval is always immediately ready, so we poll once to make sure
-        // we trip it/set the interval in the future
-        assert_eq!(Poll::Pending, futures::poll!(out_stream.next()));
+        let mut out = outputs(&config);
 
         let mut log_a = LogEvent::default();
         log_a.insert("bucket", "a");
         let mut log_b = LogEvent::default();
         log_b.insert("bucket", "b");
-        tx.send(log_a.into()).await.unwrap();
-        tx.send(log_b.into()).await.unwrap();
-
-        let mut count = 0_u8;
-        while count < 2 {
-            if let Some(_event) = out_stream.next().await {
-                count += 1;
-            } else {
-                panic!("Unexpectedly received None in output stream");
-            }
-        }
+        throttle.transform(log_a.into(), &mut out);
+        throttle.transform(log_b.into(), &mut out);
+
+        let count = out.drain().count();
         assert_eq!(2, count);
 
-        // We should be back to pending, having nothing waiting for us
-        assert_eq!(Poll::Pending, futures::poll!(out_stream.next()));
+        // We should have nothing waiting for us
+        assert_eq!(0, out.drain().count());
+    }
+
+    #[test]
+    fn throttle_bytes() {
+        let (mut throttle, clock, mut output) = throttle(
+            r#"
+threshold_bytes = 100
+window_secs = 5
+"#,
+        );
+
+        let small = log(&"a".repeat(20));
+        let large = log(&"a".repeat(200));
+        assert_eq!(small.estimated_json_encoded_size_of(), 34);
+
+        assert_eq!(send(&mut throttle, &mut output, small.clone()), 1);
+        assert_eq!(send(&mut throttle, &mut output, small.clone()), 1);
+        assert_eq!(send(&mut throttle, &mut output, small.clone()), 0);
+
+        clock.advance(Duration::from_secs(5));
+
+        // Events larger than the whole budget are always throttled
+        assert_eq!(send(&mut throttle, &mut output, large), 0);
+        assert_eq!(send(&mut throttle, &mut output, small), 1);
+    }
+
+    #[test]
+    fn throttled_bytes_keep_events_budget() {
+        let (mut throttle, _clock, mut output) = throttle(
+            r#"
+threshold = 2
+threshold_bytes = 100
+window_secs = 5
+"#,
+        );
+
+        let small = log(&"a".repeat(20));
+        let large = log(&"a".repeat(200));
+
+        // The large event is throttled for its size, without using up the budget of events
+        assert_eq!(send(&mut throttle, &mut output, large), 0);
+        assert_eq!(send(&mut throttle, &mut output, small.clone()), 1);
+        assert_eq!(send(&mut throttle, &mut output, small.clone()), 1);
+        assert_eq!(send(&mut throttle, &mut output, small), 0);
+    }
+
+    #[test]
+    fn throttled_events_keep_bytes_budget() {
+        let (mut throttle, clock, mut output) = throttle(
+            r#"
+threshold = 1
+threshold_bytes = 100
+window_secs = 5
+"#,
+        );
 
-        tx.disconnect();
+        let small = log(&"a".repeat(20));
+        let limiter = Arc::clone(throttle.bytes_limiter.as_ref().unwrap());
+
+        assert_eq!(send(&mut throttle, &mut output, small.clone()), 1);
+        // These events are throttled by the limit of events, without using up the budget of bytes
+        assert_eq!(send(&mut throttle, &mut output, small.clone()), 0);
+        assert_eq!(send(&mut throttle, &mut output, small), 0);
+
+        assert!(limiter.check(&None, 66, clock.now()).is_some());
+        assert!(limiter.check(&None, 67, clock.now()).is_none());
+    }
+
+    #[test]
+    fn flushes_keys_with_the_clock() {
+        let (mut throttle, clock, mut output) = throttle(
+            r#"
+threshold = 1
+threshold_bytes = 1000
+window_secs = 5
+key_field = "{{ message }}"
+"#,
+        );
+        let limiter = Arc::clone(throttle.events_limiter.as_ref().unwrap());
+        let bytes_limiter = Arc::clone(throttle.bytes_limiter.as_ref().unwrap());
+
+        assert_eq!(send(&mut throttle, &mut output, log("a")), 1);
+        assert_eq!(send(&mut throttle, &mut output, log("b")), 1);
+        assert_eq!(limiter.len(), 2);
+        assert_eq!(bytes_limiter.len(), 2);
+
+        // Keys are kept for two windows
+        clock.advance(Duration::from_secs(9));
+        assert_eq!(send(&mut throttle, &mut output, log("c")), 1);
+        assert_eq!(limiter.len(), 3);
+        assert_eq!(bytes_limiter.len(), 3);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(send(&mut throttle, &mut output, log("d")), 1);
+        assert_eq!(limiter.len(), 2);
+        assert_eq!(bytes_limiter.len(), 1);
+    }
+
+    #[test]
+    fn reroute_dropped() {
+        let (mut throttle, _clock, mut output) = throttle(
+            r#"
+threshold = 1
+window_secs = 5
+reroute_dropped = true
+"#,
+        );
+
+        throttle.transform(log("first").into(), &mut output);
+        throttle.transform(log("second").into(), &mut output);
+
+        assert_eq!(
+            output.drain().collect::<Vec<_>>(),
+            vec![Event::from(log("first"))]
+        );
+        assert_eq!(
+            output.drain_named(DROPPED).collect::<Vec<_>>(),
+            vec![Event::from(log("second"))]
+        );
+    }
+
+    #[test]
+    fn metric_keys_are_capped() {
+        let (throttle, _clock, _output) = throttle(
+            r#"
+threshold = 1
+window_secs = 5
+max_metric_keys = 2
+"#,
+        );
+
+        assert_eq!(throttle.metric_key(Some("a".into())), "a");
+        assert_eq!(throttle.metric_key(None), "None");
+        assert_eq!(throttle.metric_key(Some("b".into())), OTHER_KEYS);
+        assert_eq!(throttle.metric_key(Some("a".into())), "a");
+    }
+
+    #[test]
+    fn missing_threshold() {
+        let config = toml::from_str::<ThrottleConfig>("window_secs = 5").unwrap();
+        let error = Throttle::new(
+            &config,
+            &TransformContext::default(),
+            clock::FakeRelativeClock::default(),
+        )
+        .err()
+        .unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            "one of `threshold` or `threshold_bytes` must be set"
+        );
+    }
+
+    /// Runs the transform in a topology, and returns the `field` of the `passed` events expected to
+    /// go through.
+    async fn throttle_in_topology(
+        config: &str,
+        events: Vec<LogEvent>,
+        passed: usize,
+        field: &str,
+    ) -> Vec<String> {
+        let config = toml::from_str::<ThrottleConfig>(config).unwrap();
+        let (tx, rx) = mpsc::channel(events.len());
+        let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
+
+        for event in events {
+            tx.send(event.into()).await.unwrap();
+        }
+        let mut output = Vec::new();
+        while output.len() < passed {
+            let event = out.recv().await.unwrap();
+            output.push(event.as_log()[field].to_string_lossy().into_owned());
+        }
+
+        drop(tx);
+        topology.stop().await;
+        // The other events were throttled
+        assert_eq!(out.recv().await, None);
+        output
+    }
+
+    #[tokio::test]
+    async fn throttle_events_in_topology() {
+        let output = throttle_in_topology(
+            r#"
+threshold = 2
+window_secs = 3600
+"#,
+            vec![log("a"), log("b"), log("c")],
+            2,
+            "message",
+        )
+        .await;
+
+        assert_eq!(output, ["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn throttle_exclude_in_topology() {
+        let mut special_log = log("special");
+        special_log.insert("special", "true");
+
+        let output = throttle_in_topology(
+            r#"
+threshold = 2
+window_secs = 3600
+exclude = """
+exists(.special)
+"""
+"#,
+            vec![log("a"), log("b"), log("c"), special_log],
+            3,
+            "message",
+        )
+        .await;
+
+        // The rate limiter should allow the special log through regardless of current limit
+        assert_eq!(output, ["a", "b", "special"]);
+    }
+
+    #[tokio::test]
+    async fn throttle_buckets_in_topology() {
+        let mut log_a = LogEvent::default();
+        log_a.insert("bucket", "a");
+        let mut log_b = LogEvent::default();
+        log_b.insert("bucket", "b");
+
+        let output = throttle_in_topology(
+            r#"
+threshold = 1
+window_secs = 3600
+key_field = "{{ bucket }}"
+"#,
+            vec![log_a.clone(), log_b, log_a],
+            2,
+            "bucket",
+        )
+        .await;
 
-        // And still nothing there
-        assert_eq!(Poll::Ready(None), futures::poll!(out_stream.next()));
+        assert_eq!(output, ["a", "b"]);
     }
 
     #[tokio::test]
     async fn emits_internal_events() {
         assert_transform_compliance(async move {
             let config = ThrottleConfig {
-                threshold: 1,
+                threshold: Some(1),
                 window_secs: Duration::from_secs_f64(1.0),
-                key_field: None,
-                exclude: None,
+                ..Default::default()
             };
             let (tx, rx) = mpsc::channel(1);
             let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
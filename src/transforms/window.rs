@@ -0,0 +1,431 @@
+use std::{
+    collections::VecDeque,
+    num::NonZeroUsize,
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+use vector_config::configurable_component;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext},
+    event::{discriminant::Discriminant, Event},
+    internal_events::{WindowBufferSize, WindowEventsDropped},
+    schema,
+    transforms::{FunctionTransform, OutputBuffer, Transform},
+};
+
+/// Configuration for the `window` transform.
+#[configurable_component(transform("window"))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WindowConfig {
+    /// A condition used to trigger the forwarding of the buffered events.
+    ///
+    /// When an event matches this condition, it is forwarded along with the events buffered before
+    /// it in its group, and the `num_events_after` events following it in its group.
+    pub trigger: AnyCondition,
+
+    /// An ordered list of fields by which to group events.
+    ///
+    /// Each group has its own buffer, so that a trigger only forwards the events of its group.
+    /// When no fields are specified, all events are in a single group.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "service", docs::examples = "host",))]
+    pub group_by: Vec<String>,
+
+    /// The maximum number of groups with buffered events.
+    ///
+    /// When a new group would exceed this limit, the least recently used group is forgotten, along
+    /// with its buffered events.
+    #[serde(default = "default_max_groups")]
+    pub max_groups: NonZeroUsize,
+
+    /// The maximum number of events to buffer before a trigger, per group.
+    ///
+    /// When the buffer of a group is full, its oldest event is dropped.
+    #[serde(default = "default_num_events_before")]
+    pub num_events_before: usize,
+
+    /// The maximum age of the events buffered before a trigger, in seconds.
+    ///
+    /// Buffered events older than this are dropped. When unset, events are only dropped when the
+    /// buffer of their group is full.
+    pub max_age_secs: Option<u64>,
+
+    /// The number of events to forward after a trigger, per group.
+    #[serde(default)]
+    pub num_events_after: usize,
+}
+
+const fn default_num_events_before() -> usize {
+    100
+}
+
+fn default_max_groups() -> NonZeroUsize {
+    NonZeroUsize::new(10_000).expect("static non-zero number")
+}
+
+/// The minimum interval between reports of the size of the buffers.
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+impl GenerateConfig for WindowConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"trigger = ".level == \"error\""
+group_by = ["service"]
+num_events_before = 100"#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+impl TransformConfig for WindowConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::function(Window::new(
+            self,
+            self.trigger.build(&context.enrichment_tables)?,
+        )))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(&self, merged_definition: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Log).with_schema_definition(merged_definition.clone())]
+    }
+
+    fn conditions(&self) -> Vec<(String, &AnyCondition)> {
+        vec![("trigger".to_owned(), &self.trigger)]
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct GroupState {
+    /// The events received since the last trigger, from the oldest to the newest.
+    buffer: VecDeque<(Instant, Event)>,
+    /// The number of events still to forward after the last trigger.
+    remaining_after: usize,
+}
+
+pub struct Window {
+    trigger: Condition,
+    group_by: Vec<String>,
+    num_events_before: usize,
+    max_age: Option<Duration>,
+    num_events_after: usize,
+
+    groups: LruCache<Discriminant, GroupState>,
+    buffered_events: usize,
+    buffered_bytes: usize,
+    last_sweep: Instant,
+    /// The buffer size last reported, and when.
+    last_report: Option<(usize, usize, Instant)>,
+}
+
+impl Clone for Window {
+    /// Clones the configuration of the transform, with empty buffers.
+    fn clone(&self) -> Self {
+        Self {
+            trigger: self.trigger.clone(),
+            group_by: self.group_by.clone(),
+            num_events_before: self.num_events_before,
+            max_age: self.max_age,
+            num_events_after: self.num_events_after,
+            groups: LruCache::new(self.groups.cap()),
+            buffered_events: 0,
+            buffered_bytes: 0,
+            last_sweep: Instant::now(),
+            last_report: None,
+        }
+    }
+}
+
+impl Window {
+    pub fn new(config: &WindowConfig, trigger: Condition) -> Self {
+        Self {
+            trigger,
+            group_by: config.group_by.clone(),
+            num_events_before: config.num_events_before,
+            max_age: config.max_age_secs.map(Duration::from_secs),
+            num_events_after: config.num_events_after,
+            groups: LruCache::new(config.max_groups),
+            buffered_events: 0,
+            buffered_bytes: 0,
+            last_sweep: Instant::now(),
+            last_report: None,
+        }
+    }
+
+    fn process(&mut self, event: Event, now: Instant, output: &mut OutputBuffer) {
+        self.sweep(now);
+
+        let (triggered, event) = self.trigger.check(event);
+        let discriminant = Discriminant::from_log_event(event.as_log(), &self.group_by);
+        let mut group = self.groups.pop(&discriminant).unwrap_or_default();
+
+        if triggered {
+            let mut expired = 0;
+            for (received, buffered) in group.buffer.drain(..) {
+                self.buffered_events -= 1;
+                self.buffered_bytes -= buffered.size_of();
+                match self.max_age {
+                    Some(max_age) if now.duration_since(received) >= max_age => expired += 1,
+                    _ => output.push(buffered),
+                }
+            }
+            if expired > 0 {
+                emit!(WindowEventsDropped { count: expired });
+            }
+            group.remaining_after = self.num_events_after;
+            output.push(event);
+        } else if group.remaining_after > 0 {
+            group.remaining_after -= 1;
+            output.push(event);
+        } else if self.num_events_before > 0 {
+            self.buffered_events += 1;
+            self.buffered_bytes += event.size_of();
+            group.buffer.push_back((now, event));
+            if group.buffer.len() > self.num_events_before {
+                let (_, dropped) = group.buffer.pop_front().expect("buffer is not empty");
+                self.buffered_events -= 1;
+                self.buffered_bytes -= dropped.size_of();
+                emit!(WindowEventsDropped { count: 1 });
+            }
+        } else {
+            emit!(WindowEventsDropped { count: 1 });
+        }
+
+        // Groups left with nothing to do are forgotten right away, so that only the groups with
+        // buffered events count towards the maximum.
+        if !group.buffer.is_empty() || group.remaining_after > 0 {
+            if let Some((_, evicted)) = self.groups.push(discriminant, group) {
+                self.forget(evicted);
+            }
+        }
+
+        self.report(now);
+    }
+
+    /// Drops the buffered events of a group evicted to make room for a new one.
+    fn forget(&mut self, group: GroupState) {
+        let count = group.buffer.len();
+        if count > 0 {
+            self.buffered_events -= count;
+            self.buffered_bytes -= group
+                .buffer
+                .iter()
+                .map(|(_, event)| event.size_of())
+                .sum::<usize>();
+            emit!(WindowEventsDropped { count });
+        }
+    }
+
+    /// Reports the size of the buffers when it changed, at most once per report interval, and
+    /// right away when they become empty.
+    fn report(&mut self, now: Instant) {
+        let due = match self.last_report {
+            Some((events, bytes, _))
+                if events == self.buffered_events && bytes == self.buffered_bytes =>
+            {
+                false
+            }
+            Some((_, _, reported)) => {
+                self.buffered_events == 0 || now.duration_since(reported) >= REPORT_INTERVAL
+            }
+            None => true,
+        };
+        if due {
+            self.last_report = Some((self.buffered_events, self.buffered_bytes, now));
+            emit!(WindowBufferSize {
+                events: self.buffered_events,
+                bytes: self.buffered_bytes,
+            });
+        }
+    }
+
+    /// Drops the buffered events older than the maximum age, and forgets the groups left with
+    /// nothing to do, at most once per maximum age.
+    fn sweep(&mut self, now: Instant) {
+        let max_age = match self.max_age {
+            Some(max_age) if now.duration_since(self.last_sweep) >= max_age => max_age,
+            _ => return,
+        };
+        self.last_sweep = now;
+
+        let mut count = 0;
+        let mut bytes = 0;
+        let mut idle = Vec::new();
+        for (discriminant, group) in self.groups.iter_mut() {
+            while let Some((received, _)) = group.buffer.front() {
+                if now.duration_since(*received) < max_age {
+                    break;
+                }
+                let (_, dropped) = group.buffer.pop_front().expect("buffer is not empty");
+                count += 1;
+                bytes += dropped.size_of();
+            }
+            if group.buffer.is_empty() && group.remaining_after == 0 {
+                idle.push(discriminant.clone());
+            }
+        }
+        for discriminant in idle {
+            self.groups.pop(&discriminant);
+        }
+
+        if count > 0 {
+            self.buffered_events -= count;
+            self.buffered_bytes -= bytes;
+            emit!(WindowEventsDropped { count });
+        }
+    }
+}
+
+impl FunctionTransform for Window {
+    fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
+        self.process(event, Instant::now(), output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WindowConfig>();
+    }
+
+    fn window(config: &str) -> Window {
+        let config = toml::from_str::<WindowConfig>(&format!(
+            "trigger = \".level == \\\"error\\\"\"\n{}",
+            config
+        ))
+        .unwrap();
+        let trigger = config.trigger.build(&Default::default()).unwrap();
+        Window::new(&config, trigger)
+    }
+
+    fn log(service: &str, level: &str, message: &str) -> Event {
+        let mut log = LogEvent::from(message);
+        log.insert("service", service);
+        log.insert("level", level);
+        Event::Log(log)
+    }
+
+    fn messages(window: &mut Window, events: Vec<(Event, Instant)>) -> Vec<String> {
+        let mut output = OutputBuffer::default();
+        for (event, now) in events {
+            window.process(event, now, &mut output);
+        }
+        output
+            .into_events()
+            .map(|event| event.as_log()["message"].to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn forwards_events_around_triggers() {
+        let mut window = window("num_events_before = 2\nnum_events_after = 1");
+        let now = Instant::now();
+        let events = ["1", "2", "3", "error", "4", "5", "6", "error", "7"]
+            .into_iter()
+            .map(|message| {
+                let level = if message == "error" { "error" } else { "debug" };
+                (log("api", level, message), now)
+            })
+            .collect();
+
+        assert_eq!(
+            messages(&mut window, events),
+            ["2", "3", "error", "4", "5", "6", "error", "7"]
+        );
+        assert_eq!(window.buffered_events, 0);
+    }
+
+    #[test]
+    fn groups() {
+        let mut window = window("group_by = [\"service\"]\nnum_events_before = 10");
+        let now = Instant::now();
+        let events = vec![
+            (log("api", "debug", "api 1"), now),
+            (log("db", "debug", "db 1"), now),
+            (log("api", "debug", "api 2"), now),
+            (log("api", "error", "api error"), now),
+        ];
+
+        assert_eq!(
+            messages(&mut window, events),
+            ["api 1", "api 2", "api error"]
+        );
+        assert_eq!(window.buffered_events, 1);
+        assert_eq!(window.buffered_bytes, log("db", "debug", "db 1").size_of());
+    }
+
+    #[test]
+    fn forgets_idle_groups() {
+        let mut window = window("group_by = [\"service\"]\nnum_events_after = 1");
+        let now = Instant::now();
+        let events = vec![
+            (log("api", "error", "api error"), now),
+            (log("db", "error", "db error"), now),
+            (log("api", "debug", "api after"), now),
+        ];
+
+        assert_eq!(
+            messages(&mut window, events),
+            ["api error", "db error", "api after"]
+        );
+        assert_eq!(window.groups.len(), 1);
+    }
+
+    #[test]
+    fn max_groups() {
+        let mut window = window("group_by = [\"service\"]\nmax_groups = 2");
+        let now = Instant::now();
+        let events = vec![
+            (log("api", "debug", "api 1"), now),
+            (log("db", "debug", "db 1"), now),
+            (log("api", "debug", "api 2"), now),
+            (log("web", "debug", "web 1"), now),
+            (log("db", "error", "db error"), now),
+            (log("api", "error", "api error"), now),
+        ];
+
+        // The least recently used group is forgotten to make room for a new one.
+        assert_eq!(
+            messages(&mut window, events),
+            ["db error", "api 1", "api 2", "api error"]
+        );
+        assert_eq!(window.groups.len(), 1);
+        assert_eq!(window.buffered_events, 1);
+        assert_eq!(
+            window.buffered_bytes,
+            log("web", "debug", "web 1").size_of()
+        );
+    }
+
+    #[test]
+    fn max_age() {
+        let mut window = window("num_events_before = 10\nmax_age_secs = 60");
+        let start = Instant::now();
+        let events = vec![
+            (log("api", "debug", "old"), start),
+            (
+                log("api", "debug", "recent"),
+                start + Duration::from_secs(50),
+            ),
+            (
+                log("api", "error", "error"),
+                start + Duration::from_secs(70),
+            ),
+        ];
+
+        assert_eq!(messages(&mut window, events), ["recent", "error"]);
+    }
+}
@@ -38,6 +38,11 @@ pub struct Opts {
         value_delimiter(',')
     )]
     pub config_dirs: Vec<PathBuf>,
+
+    /// Print each expression evaluated by the `remap` transforms to stderr, with its location in
+    /// the program, the assignments it makes and its result.
+    #[arg(long)]
+    pub trace: bool,
 }
 
 impl Opts {
@@ -71,7 +76,7 @@ pub async fn cmd(opts: &Opts, signal_handler: &mut signal::SignalHandler) -> exi
     {
         println!("Running tests");
     }
-    match config::build_unit_tests_main(&paths, signal_handler).await {
+    match config::build_unit_tests_main(&paths, signal_handler, opts.trace).await {
         Ok(tests) => {
             if tests.is_empty() {
                 #[allow(clippy::print_stdout)]
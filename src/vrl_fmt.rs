@@ -0,0 +1,192 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::{
+    config::{self, TransformConfig},
+    transforms::Transforms,
+};
+
+#[derive(Parser, Debug)]
+#[command(rename_all = "kebab-case")]
+pub struct Opts {
+    /// Check that the VRL programs and conditions are formatted, without printing them. Exits
+    /// with an error listing the components whose VRL isn't formatted.
+    #[arg(long)]
+    check: bool,
+
+    /// Read configuration from one or more files. Wildcard paths are supported.
+    /// File format is detected from the file name.
+    /// If zero files are specified the default config path
+    /// `/etc/vector/vector.toml` will be targeted.
+    #[arg(
+        id = "config",
+        short,
+        long,
+        env = "VECTOR_CONFIG",
+        value_delimiter(',')
+    )]
+    paths: Vec<PathBuf>,
+
+    /// Vector config files in TOML format.
+    #[arg(id = "config-toml", long, value_delimiter(','))]
+    paths_toml: Vec<PathBuf>,
+
+    /// Vector config files in JSON format.
+    #[arg(id = "config-json", long, value_delimiter(','))]
+    paths_json: Vec<PathBuf>,
+
+    /// Vector config files in YAML format.
+    #[arg(id = "config-yaml", long, value_delimiter(','))]
+    paths_yaml: Vec<PathBuf>,
+
+    /// Read configuration from files in one or more directories.
+    /// File format is detected from the file name.
+    ///
+    /// Files not ending in .toml, .json, .yaml, or .yml will be ignored.
+    #[arg(
+        id = "config-dir",
+        short = 'C',
+        long,
+        env = "VECTOR_CONFIG_DIR",
+        value_delimiter(',')
+    )]
+    pub config_dirs: Vec<PathBuf>,
+}
+
+impl Opts {
+    fn paths_with_formats(&self) -> Vec<config::ConfigPath> {
+        config::merge_path_lists(vec![
+            (&self.paths, None),
+            (&self.paths_toml, Some(config::Format::Toml)),
+            (&self.paths_json, Some(config::Format::Json)),
+            (&self.paths_yaml, Some(config::Format::Yaml)),
+        ])
+        .map(|(path, hint)| config::ConfigPath::File(path, hint))
+        .chain(
+            self.config_dirs
+                .iter()
+                .map(|dir| config::ConfigPath::Dir(dir.to_path_buf())),
+        )
+        .collect()
+    }
+}
+
+/// Formats the VRL embedded in the configuration: the programs of the `remap`
+/// transforms, the VRL conditions of all transforms, and the conditions of the
+/// unit tests.
+///
+/// Programs are printed to stdout, preceded by the component and option they
+/// belong to. Programs loaded from a `file` are formatted too, but the file
+/// itself is never rewritten; use `vector vrl fmt` for that.
+#[allow(clippy::print_stdout, clippy::print_stderr)]
+pub(crate) fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    let paths = opts.paths_with_formats();
+    let paths = match config::process_paths(&paths) {
+        Some(paths) => paths,
+        None => return exitcode::CONFIG,
+    };
+
+    let builder = match config::load_builder_from_paths(&paths) {
+        Ok((builder, _)) => builder,
+        Err(errs) => {
+            for err in errs {
+                eprintln!("{}", err);
+            }
+            return exitcode::CONFIG;
+        }
+    };
+
+    let mut programs = Vec::new();
+    for (key, transform) in &builder.transforms {
+        if let Transforms::Remap(remap) = &transform.inner {
+            match (&remap.source, &remap.file) {
+                (Some(source), _) => programs.push((key.to_string(), source.clone())),
+                (None, Some(path)) => match std::fs::read_to_string(path) {
+                    Ok(source) => programs.push((key.to_string(), source)),
+                    Err(error) => {
+                        eprintln!(
+                            "Component \"{}\": unable to read {}: {}",
+                            key,
+                            path.display(),
+                            error
+                        );
+                        return exitcode::IOERR;
+                    }
+                },
+                (None, None) => {}
+            }
+        }
+
+        for (option, condition) in transform.inner.conditions() {
+            if let Some(source) = condition.vrl_source() {
+                programs.push((format!("{}.{}", key, option), source.to_owned()));
+            }
+        }
+    }
+    for test in &builder.tests {
+        for (output_index, output) in test.outputs.iter().enumerate() {
+            for (index, condition) in output.conditions.iter().flatten().enumerate() {
+                if let Some(source) = condition.vrl_source() {
+                    programs.push((
+                        format!(
+                            "tests.{}.outputs[{}].conditions[{}]",
+                            test.name, output_index, index
+                        ),
+                        source.to_owned(),
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut unformatted = Vec::new();
+    for (name, source) in programs {
+        let formatted = match vrl_cli::fmt::format_program(&source) {
+            Ok(formatted) => formatted,
+            Err(diagnostics) => {
+                eprintln!("Component \"{}\":\n{}", name, diagnostics);
+                return exitcode::DATAERR;
+            }
+        };
+
+        if opts.check {
+            if !is_formatted(&source, &formatted) {
+                unformatted.push(name);
+            }
+        } else {
+            println!("# {}\n{}", name, formatted);
+        }
+    }
+
+    if unformatted.is_empty() {
+        return exitcode::OK;
+    }
+
+    for name in unformatted {
+        eprintln!("Component \"{}\" has unformatted VRL.", name);
+    }
+    exitcode::DATAERR
+}
+
+/// Whether a program is already formatted.
+///
+/// A program written on a single line, as conditions usually are, doesn't need
+/// the final newline that the formatter adds.
+fn is_formatted(source: &str, formatted: &str) -> bool {
+    source == formatted || (!source.contains('\n') && formatted.strip_suffix('\n') == Some(source))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatted_programs() {
+        assert!(is_formatted(".foo = 1\n", ".foo = 1\n"));
+        assert!(is_formatted(".status == 500", ".status == 500\n"));
+        assert!(!is_formatted(".foo = 1\n.bar = 2", ".foo = 1\n.bar = 2\n"));
+        assert!(!is_formatted(".foo = 1\n\n", ".foo = 1\n"));
+        assert!(!is_formatted("  .foo = 1\n", ".foo = 1\n"));
+    }
+}
@@ -241,6 +241,16 @@ cli: {
 				out the [unit testing documentation](\(urls.vector_unit_tests)).
 				"""
 
+			flags: _default_flags & {
+				"trace": {
+					description: """
+						Print each expression evaluated by the `remap` transforms to stderr,
+						with its location in the program, the assignments it makes and its
+						result.
+						"""
+				}
+			}
+
 			options: {
 				"config-toml": {
 					description: env_vars.VECTOR_CONFIG_TOML.description
@@ -474,6 +484,30 @@ cli: {
 				}
 			}
 		}
+
+		"vrl-fmt": {
+			description: """
+				Format the VRL embedded in a Vector configuration in the canonical VRL style,
+				preserving comments: the programs of the `remap` transforms, the VRL conditions of
+				all transforms (such as the routes of `route` or the condition of `filter`), and the
+				conditions of unit tests. The formatted programs are printed to stdout. Use
+				`vector vrl fmt` to format standalone VRL files in place.
+				"""
+
+			example: "vector vrl-fmt --check --config /etc/vector/vector.toml"
+
+			flags: _default_flags & {
+				"check": {
+					description: """
+						Check that the programs and conditions are formatted, without printing them.
+						Exits with an error listing the components whose VRL isn't formatted. A program
+						written on a single line doesn't need a final newline.
+						"""
+				}
+			}
+
+			options: _core_options
+		}
 	}
 
 	env_vars: {
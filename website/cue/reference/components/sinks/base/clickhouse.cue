@@ -122,9 +122,13 @@ base: components: sinks: clickhouse: configuration: {
 		}
 	}
 	database: {
-		description: "The database that contains the table that data will be inserted into."
-		required:    false
-		type: string: syntax: "literal"
+		description: """
+			The database that contains the table that data will be inserted into.
+
+			Events are batched separately for each database this renders to. Defaults to `default`.
+			"""
+		required: false
+		type: string: syntax: "template"
 	}
 	encoding: {
 		description: "Transformations to prepare an event for serialization."
@@ -155,6 +159,38 @@ base: components: sinks: clickhouse: configuration: {
 		required:    true
 		type: string: syntax: "literal"
 	}
+	format: {
+		description: """
+			The format data is inserted in.
+
+			For the `row_binary` and `native` formats, the schema of each table is read with `DESCRIBE TABLE` (by the
+			healthcheck for a static table, or before its first insert otherwise), and event values are converted to the types
+			of their columns. It is read again after an insert fails because the table changed.
+			"""
+		required: false
+		type: string: {
+			default: "json_each_row"
+			enum: {
+				json_each_row: """
+					Insert rows as newline-delimited JSON objects.
+
+					Values of fields that do not match a column are sent as they are.
+					"""
+				native: """
+					Insert rows in the column-oriented `Native` format.
+
+					Values are written the same way as for `row_binary`. `LowCardinality` columns are not supported.
+					"""
+				row_binary: """
+					Insert rows in the `RowBinary` format.
+
+					Every column of the table is written, and fields that do not match a column are discarded. Missing values are
+					written as `NULL` for `Nullable` columns, and as the default value of the column type otherwise, rather than
+					as the column's `DEFAULT` expression.
+					"""
+			}
+		}
+	}
 	request: {
 		description: """
 			Middleware settings for outbound requests.
@@ -277,14 +313,23 @@ base: components: sinks: clickhouse: configuration: {
 		}
 	}
 	skip_unknown_fields: {
-		description: "Sets `input_format_skip_unknown_fields`, allowing Clickhouse to discard fields not present in the table schema."
-		required:    false
+		description: """
+			Sets `input_format_skip_unknown_fields`, allowing Clickhouse to discard fields not present in the table schema.
+
+			Only applies to the `json_each_row` format, as fields that are not present in the table schema are always
+			discarded by the other formats.
+			"""
+		required: false
 		type: bool: default: false
 	}
 	table: {
-		description: "The table that data will be inserted into."
-		required:    true
-		type: string: syntax: "literal"
+		description: """
+			The table that data will be inserted into.
+
+			Events are batched separately for each table this renders to.
+			"""
+		required: true
+		type: string: syntax: "template"
 	}
 	tls: {
 		description: "Standard TLS options."
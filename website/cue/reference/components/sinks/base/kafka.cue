@@ -183,6 +183,16 @@ base: components: sinks: kafka: configuration: {
 		required:    false
 		type: uint: default: 300000
 	}
+	partition_field: {
+		description: """
+			The log field name or tags key to use for the partition to write each event to.
+
+			The value must be an integer. If the field does not exist in the log or in tags, or its value is not an
+			integer, the partition is chosen from the key as usual.
+			"""
+		required: false
+		type: string: syntax: "literal"
+	}
 	sasl: {
 		description: "Configuration for SASL authentication when interacting with Kafka."
 		required:    false
@@ -320,8 +330,40 @@ base: components: sinks: kafka: configuration: {
 		}
 	}
 	topic: {
-		description: "The Kafka topic name to write events to."
-		required:    true
+		description: """
+			The Kafka topic name to write events to.
+
+			Templating can be used to route each event to a topic derived from its fields.
+			"""
+		required: true
 		type: string: syntax: "template"
 	}
+	transaction: {
+		description: """
+			Transactional producer configuration.
+
+			When enabled, events are written to Kafka in transactions, one per batch as configured by `batch`, and are only
+			acknowledged once their transaction is committed. If a transaction fails it is aborted, and consumers using the
+			`read_committed` isolation level never see its messages. If the producer fails with a fatal error, for example
+			because another producer with the same transactional ID fenced it, the sink stops.
+			"""
+		required: false
+		type: object: options: {
+			timeout_ms: {
+				description: "The maximum time, in milliseconds, a transaction may remain open before the broker aborts it."
+				required:    false
+				type: uint: default: 60000
+			}
+			transactional_id: {
+				description: """
+					The transactional ID of the producer.
+
+					This must be unique to each running instance of the sink, and stable across restarts, so that the transactions
+					left open by a previous instance are fenced off when a new one starts.
+					"""
+				required: true
+				type: string: syntax: "literal"
+			}
+		}
+	}
 }
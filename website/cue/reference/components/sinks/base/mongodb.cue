@@ -0,0 +1,333 @@
+package metadata
+
+base: components: sinks: mongodb: configuration: {
+	acknowledgements: {
+		description: """
+			Controls how acknowledgements are handled for this sink.
+
+			See [End-to-end Acknowledgements][e2e_acks] for more information on how Vector handles event acknowledgement.
+
+			[e2e_acks]: https://vector.dev/docs/about/under-the-hood/architecture/end-to-end-acknowledgements/
+			"""
+		required: false
+		type: object: options: enabled: {
+			description: """
+				Whether or not end-to-end acknowledgements are enabled.
+
+				When enabled for a sink, any source connected to that sink, where the source supports
+				end-to-end acknowledgements as well, will wait for events to be acknowledged by the sink
+				before acknowledging them at the source.
+
+				Enabling or disabling acknowledgements at the sink level takes precedence over any global
+				[`acknowledgements`][global_acks] configuration.
+
+				[global_acks]: https://vector.dev/docs/reference/configuration/global-options/#acknowledgements
+				"""
+			required: false
+			type: bool: {}
+		}
+	}
+	auth: {
+		description: """
+			Credentials used to authenticate to MongoDB.
+
+			These take precedence over any credentials in the endpoint.
+			"""
+		required: false
+		type: object: options: {
+			password: {
+				description: "The password of the user."
+				required:    true
+				type: string: syntax: "literal"
+			}
+			source: {
+				description: """
+					The database the user is defined in.
+
+					Defaults to the database of the endpoint, or `admin` if there is none.
+					"""
+				required: false
+				type: string: syntax: "literal"
+			}
+			user: {
+				description: "The user to authenticate as."
+				required:    true
+				type: string: syntax: "literal"
+			}
+		}
+	}
+	batch: {
+		description: "Event batching behavior."
+		required:    false
+		type: object: options: {
+			max_bytes: {
+				description: """
+					The maximum size of a batch that will be processed by a sink.
+
+					This is based on the uncompressed size of the batched events, before they are
+					serialized / compressed.
+					"""
+				required: false
+				type: uint: {}
+			}
+			max_events: {
+				description: "The maximum size of a batch, in events, before it is flushed."
+				required:    false
+				type: uint: {}
+			}
+			timeout_secs: {
+				description: "The maximum age of a batch, in seconds, before it is flushed."
+				required:    false
+				type: float: {}
+			}
+		}
+	}
+	collection: {
+		description: """
+			The collection to write events to.
+
+			Events are batched separately for each collection this renders to.
+			"""
+		required: true
+		type: string: syntax: "template"
+	}
+	database: {
+		description: """
+			The database to write events to.
+
+			Events are batched separately for each database this renders to.
+			"""
+		required: true
+		type: string: syntax: "template"
+	}
+	encoding: {
+		description: "Transformations to prepare an event for serialization."
+		required:    false
+		type: object: options: {
+			except_fields: {
+				description: "List of fields that will be excluded from the encoded event."
+				required:    false
+				type: array: items: type: string: syntax: "literal"
+			}
+			only_fields: {
+				description: "List of fields that will be included in the encoded event."
+				required:    false
+				type: array: items: type: string: syntax: "literal"
+			}
+			timestamp_format: {
+				description: "Format used for timestamp fields."
+				required:    false
+				type: string: enum: {
+					rfc3339: "Represent the timestamp as a RFC 3339 timestamp."
+					unix:    "Represent the timestamp as a Unix timestamp."
+				}
+			}
+		}
+	}
+	endpoint: {
+		description: """
+			The MongoDB deployment to connect to.
+
+			Must be in the [Connection String URI Format](https://www.mongodb.com/docs/manual/reference/connection-string/).
+			"""
+		required: true
+		type: string: syntax: "literal"
+	}
+	request: {
+		description: """
+			Middleware settings for outbound requests.
+
+			Various settings can be configured, such as concurrency and rate limits, timeouts, etc.
+			"""
+		required: false
+		type: object: options: {
+			adaptive_concurrency: {
+				description: """
+					Configuration of adaptive concurrency parameters.
+
+					These parameters typically do not require changes from the default, and incorrect values can lead to meta-stable or
+					unstable performance and sink behavior. Proceed with caution.
+					"""
+				required: false
+				type: object: {
+					default: {
+						decrease_ratio:      0.9
+						ewma_alpha:          0.4
+						rtt_deviation_scale: 2.5
+					}
+					options: {
+						decrease_ratio: {
+							description: """
+																The fraction of the current value to set the new concurrency limit when decreasing the limit.
+
+																Valid values are greater than `0` and less than `1`. Smaller values cause the algorithm to scale back rapidly
+																when latency increases.
+
+																Note that the new limit is rounded down after applying this ratio.
+																"""
+							required: false
+							type: float: default: 0.9
+						}
+						ewma_alpha: {
+							description: """
+																The weighting of new measurements compared to older measurements.
+
+																Valid values are greater than `0` and less than `1`.
+
+																ARC uses an exponentially weighted moving average (EWMA) of past RTT measurements as a reference to compare with
+																the current RTT. Smaller values cause this reference to adjust more slowly, which may be useful if a service has
+																unusually high response variability.
+																"""
+							required: false
+							type: float: default: 0.4
+						}
+						rtt_deviation_scale: {
+							description: """
+																Scale of RTT deviations which are not considered anomalous.
+
+																Valid values are greater than or equal to `0`, and we expect reasonable values to range from `1.0` to `3.0`.
+
+																When calculating the past RTT average, we also compute a secondary “deviation” value that indicates how variable
+																those values are. We use that deviation when comparing the past RTT average to the current measurements, so we
+																can ignore increases in RTT that are within an expected range. This factor is used to scale up the deviation to
+																an appropriate range.  Larger values cause the algorithm to ignore larger increases in the RTT.
+																"""
+							required: false
+							type: float: default: 2.5
+						}
+					}
+				}
+			}
+			concurrency: {
+				description: "Configuration for outbound request concurrency."
+				required:    false
+				type: {
+					number: {}
+					string: {
+						const:   "adaptive"
+						default: "none"
+					}
+				}
+			}
+			rate_limit_duration_secs: {
+				description: "The time window, in seconds, used for the `rate_limit_num` option."
+				required:    false
+				type: uint: default: 1
+			}
+			rate_limit_num: {
+				description: "The maximum number of requests allowed within the `rate_limit_duration_secs` time window."
+				required:    false
+				type: uint: default: 9223372036854775807
+			}
+			retry_attempts: {
+				description: """
+					The maximum number of retries to make for failed requests.
+
+					The default, for all intents and purposes, represents an infinite number of retries.
+					"""
+				required: false
+				type: uint: default: 9223372036854775807
+			}
+			retry_initial_backoff_secs: {
+				description: """
+					The amount of time to wait before attempting the first retry for a failed request.
+
+					After the first retry has failed, the fibonacci sequence will be used to select future backoffs.
+					"""
+				required: false
+				type: uint: default: 1
+			}
+			retry_max_duration_secs: {
+				description: "The maximum amount of time, in seconds, to wait between retries."
+				required:    false
+				type: uint: default: 3600
+			}
+			timeout_secs: {
+				description: """
+					The maximum time a request can take before being aborted.
+
+					It is highly recommended that you do not lower this value below the service’s internal timeout, as this could
+					create orphaned requests, pile on retries, and result in duplicate data downstream.
+					"""
+				required: false
+				type: uint: default: 60
+			}
+		}
+	}
+	tls: {
+		description: """
+			Configuration of TLS when connecting to MongoDB.
+
+			Enables TLS, even if it is not enabled by the endpoint.
+			"""
+		required: false
+		type: object: options: {
+			ca_file: {
+				description: "Absolute path to the CA certificate file used to verify the server certificate, in PEM format."
+				required:    false
+				type: string: syntax: "literal"
+			}
+			cert_key_file: {
+				description: "Absolute path to a file holding both the client certificate and its private key, in PEM format."
+				required:    false
+				type: string: syntax: "literal"
+			}
+			verify_certificate: {
+				description: """
+					Whether the server certificate is verified.
+
+					Do not disable this unless you understand the risks.
+					"""
+				required: false
+				type: bool: {}
+			}
+		}
+	}
+	upsert: {
+		description: """
+			Upsert documents by key, instead of inserting them.
+
+			Each event replaces the document with the same key, and is inserted if there is none.
+			"""
+		required: false
+		type: object: options: key: {
+			description: """
+				The fields whose values identify a document.
+
+				Missing fields match documents where the field is `null` or missing.
+				"""
+			required: true
+			type: array: items: type: string: syntax: "literal"
+		}
+	}
+	write_concern: {
+		description: """
+			The write concern requested for every write.
+
+			Events are only acknowledged once their write has been acknowledged with this write concern.
+			"""
+		required: false
+		type: object: options: {
+			journal: {
+				description: "Whether writes must be written to the on-disk journal before being acknowledged."
+				required:    false
+				type: bool: {}
+			}
+			timeout_secs: {
+				description: "How long to wait for the requested acknowledgement, in seconds, before failing the write."
+				required:    false
+				type: uint: {}
+			}
+			w: {
+				description: """
+					The acknowledgement requested for writes.
+
+					Either `majority`, a number of nodes, or the name of a custom write concern. Defaults to the write concern of
+					the deployment.
+					"""
+				required: false
+				type: string: syntax: "literal"
+			}
+		}
+	}
+}
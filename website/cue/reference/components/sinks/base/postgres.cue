@@ -0,0 +1,270 @@
+package metadata
+
+base: components: sinks: postgres: configuration: {
+	acknowledgements: {
+		description: """
+			Controls how acknowledgements are handled for this sink.
+
+			See [End-to-end Acknowledgements][e2e_acks] for more information on how Vector handles event acknowledgement.
+
+			[e2e_acks]: https://vector.dev/docs/about/under-the-hood/architecture/end-to-end-acknowledgements/
+			"""
+		required: false
+		type: object: options: enabled: {
+			description: """
+				Whether or not end-to-end acknowledgements are enabled.
+
+				When enabled for a sink, any source connected to that sink, where the source supports
+				end-to-end acknowledgements as well, will wait for events to be acknowledged by the sink
+				before acknowledging them at the source.
+
+				Enabling or disabling acknowledgements at the sink level takes precedence over any global
+				[`acknowledgements`][global_acks] configuration.
+
+				[global_acks]: https://vector.dev/docs/reference/configuration/global-options/#acknowledgements
+				"""
+			required: false
+			type: bool: {}
+		}
+	}
+	batch: {
+		description: "Event batching behavior."
+		required:    false
+		type: object: options: {
+			max_bytes: {
+				description: """
+					The maximum size of a batch that will be processed by a sink.
+
+					This is based on the uncompressed size of the batched events, before they are
+					serialized / compressed.
+					"""
+				required: false
+				type: uint: {}
+			}
+			max_events: {
+				description: "The maximum size of a batch, in events, before it is flushed."
+				required:    false
+				type: uint: {}
+			}
+			timeout_secs: {
+				description: "The maximum age of a batch, in seconds, before it is flushed."
+				required:    false
+				type: float: {}
+			}
+		}
+	}
+	columns: {
+		description: """
+			The columns to write to.
+
+			Each column is populated from the top-level event field of the same name, and is set to `NULL` if the event has
+			no such field. Columns that are not listed keep their default values.
+
+			If empty, all columns of the table are written. Must be set when `mode` is `copy`.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: syntax: "literal"
+		}
+	}
+	endpoint: {
+		description: """
+			The PostgreSQL server to connect to.
+
+			Must be in the [Connection URI
+			format](https://www.postgresql.org/docs/current/libpq-connect.html#id-1.7.3.8.3.6).
+			"""
+		required: true
+		type: string: syntax: "literal"
+	}
+	mode: {
+		description: """
+			The statement used to write each batch of events.
+
+			Either way, each batch is written within a single transaction, and its events are only acknowledged once that
+			transaction is committed.
+			"""
+		required: false
+		type: string: {
+			default: "insert"
+			enum: {
+				copy: """
+					Write with `COPY ... FROM STDIN`.
+
+					This is faster for large batches, but conflicts with existing rows cannot be handled.
+					"""
+				insert: "Write with a multi-row `INSERT` statement."
+			}
+		}
+	}
+	on_conflict: {
+		description: "How rows conflicting with existing ones are handled."
+		required:    false
+		type: object: options: {
+			action: {
+				description: "The action taken on conflicting rows."
+				required:    true
+				type: string: enum: {
+					do_nothing: "Skip the conflicting row, keeping the existing one."
+					do_update:  "Update the existing row with the values of the conflicting one."
+				}
+			}
+			target: {
+				description: """
+					The columns of the unique index or constraint on which conflicts are detected.
+
+					If empty, conflicts on any unique index or constraint are handled. Must be set when `action` is `do_update`.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: string: syntax: "literal"
+				}
+			}
+		}
+	}
+	request: {
+		description: """
+			Middleware settings for outbound requests.
+
+			Various settings can be configured, such as concurrency and rate limits, timeouts, etc.
+			"""
+		required: false
+		type: object: options: {
+			adaptive_concurrency: {
+				description: """
+					Configuration of adaptive concurrency parameters.
+
+					These parameters typically do not require changes from the default, and incorrect values can lead to meta-stable or
+					unstable performance and sink behavior. Proceed with caution.
+					"""
+				required: false
+				type: object: {
+					default: {
+						decrease_ratio:      0.9
+						ewma_alpha:          0.4
+						rtt_deviation_scale: 2.5
+					}
+					options: {
+						decrease_ratio: {
+							description: """
+																The fraction of the current value to set the new concurrency limit when decreasing the limit.
+
+																Valid values are greater than `0` and less than `1`. Smaller values cause the algorithm to scale back rapidly
+																when latency increases.
+
+																Note that the new limit is rounded down after applying this ratio.
+																"""
+							required: false
+							type: float: default: 0.9
+						}
+						ewma_alpha: {
+							description: """
+																The weighting of new measurements compared to older measurements.
+
+																Valid values are greater than `0` and less than `1`.
+
+																ARC uses an exponentially weighted moving average (EWMA) of past RTT measurements as a reference to compare with
+																the current RTT. Smaller values cause this reference to adjust more slowly, which may be useful if a service has
+																unusually high response variability.
+																"""
+							required: false
+							type: float: default: 0.4
+						}
+						rtt_deviation_scale: {
+							description: """
+																Scale of RTT deviations which are not considered anomalous.
+
+																Valid values are greater than or equal to `0`, and we expect reasonable values to range from `1.0` to `3.0`.
+
+																When calculating the past RTT average, we also compute a secondary “deviation” value that indicates how variable
+																those values are. We use that deviation when comparing the past RTT average to the current measurements, so we
+																can ignore increases in RTT that are within an expected range. This factor is used to scale up the deviation to
+																an appropriate range.  Larger values cause the algorithm to ignore larger increases in the RTT.
+																"""
+							required: false
+							type: float: default: 2.5
+						}
+					}
+				}
+			}
+			concurrency: {
+				description: "Configuration for outbound request concurrency."
+				required:    false
+				type: {
+					number: {}
+					string: {
+						const:   "adaptive"
+						default: "none"
+					}
+				}
+			}
+			rate_limit_duration_secs: {
+				description: "The time window, in seconds, used for the `rate_limit_num` option."
+				required:    false
+				type: uint: default: 1
+			}
+			rate_limit_num: {
+				description: "The maximum number of requests allowed within the `rate_limit_duration_secs` time window."
+				required:    false
+				type: uint: default: 9223372036854775807
+			}
+			retry_attempts: {
+				description: """
+					The maximum number of retries to make for failed requests.
+
+					The default, for all intents and purposes, represents an infinite number of retries.
+					"""
+				required: false
+				type: uint: default: 9223372036854775807
+			}
+			retry_initial_backoff_secs: {
+				description: """
+					The amount of time to wait before attempting the first retry for a failed request.
+
+					After the first retry has failed, the fibonacci sequence will be used to select future backoffs.
+					"""
+				required: false
+				type: uint: default: 1
+			}
+			retry_max_duration_secs: {
+				description: "The maximum amount of time, in seconds, to wait between retries."
+				required:    false
+				type: uint: default: 3600
+			}
+			timeout_secs: {
+				description: """
+					The maximum time a request can take before being aborted.
+
+					It is highly recommended that you do not lower this value below the service’s internal timeout, as this could
+					create orphaned requests, pile on retries, and result in duplicate data downstream.
+					"""
+				required: false
+				type: uint: default: 60
+			}
+		}
+	}
+	table: {
+		description: """
+			The table to write events to.
+
+			The name may be qualified with a schema, such as `audit.events`.
+			"""
+		required: true
+		type: string: syntax: "literal"
+	}
+	tls: {
+		description: "Configuration of TLS when connecting to PostgreSQL."
+		required:    false
+		type: object: options: ca_file: {
+			description: """
+				Absolute path to an additional CA certificate file.
+
+				The certficate must be in the DER or PEM (X.509) format.
+				"""
+			required: true
+			type: string: syntax: "literal"
+		}
+	}
+}
@@ -79,11 +79,12 @@ components: sinks: clickhouse: {
 		}}
 		database: {
 			common:      true
-			description: "The database that contains the table that data will be inserted into."
+			description: "The database that contains the table that data will be inserted into. Events are batched separately for each database this renders to."
 			required:    false
 			type: string: {
 				default: null
-				examples: ["mydatabase"]
+				examples: ["mydatabase", "{{ environment }}"]
+				syntax: "template"
 			}
 		}
 		endpoint: {
@@ -93,11 +94,25 @@ components: sinks: clickhouse: {
 				examples: ["http://localhost:8123"]
 			}
 		}
+		format: {
+			common:      false
+			description: "The format data is inserted in."
+			required:    false
+			type: string: {
+				default: "json_each_row"
+				enum: {
+					json_each_row: "Insert rows as newline-delimited JSON objects."
+					row_binary:    "Insert rows in the `RowBinary` format."
+					native:        "Insert rows in the column-oriented `Native` format."
+				}
+			}
+		}
 		table: {
-			description: "The table that data will be inserted into."
+			description: "The table that data will be inserted into. Events are batched separately for each table this renders to."
 			required:    true
 			type: string: {
-				examples: ["mytable"]
+				examples: ["mytable", "{{ application }}_logs"]
+				syntax: "template"
 			}
 		}
 		skip_unknown_fields: {
@@ -108,6 +123,29 @@ components: sinks: clickhouse: {
 		}
 	}
 
+	how_it_works: {
+		type_coercion: {
+			title: "Type coercion"
+			body: """
+				With the default `json_each_row` format, events are sent as JSON objects and ClickHouse converts
+				their values to the types of the columns.
+
+				With the `row_binary` and `native` formats, the schema of each table is read with `DESCRIBE TABLE`,
+				by the healthcheck for a static table, or before the first insert into a templated one. Event values
+				are then converted to the types of their columns: timestamps are written with the precision of
+				`DateTime` and `DateTime64` columns, numbers and booleans held in strings are parsed for numeric
+				columns, and objects are serialized for `String` columns. A batch containing a value that cannot be
+				converted is rejected. When an insert fails because the table changed, for example after an
+				`ALTER TABLE`, the schemas are read again before the next insert.
+
+				The `row_binary` and `native` formats write every column of the table, so they require all column
+				types to be supported by Vector: integers, floats, `Bool`, `String`, `FixedString`, `Date`,
+				`Date32`, `DateTime`, `DateTime64`, and `Nullable`, `Array` or (for `row_binary` only)
+				`LowCardinality` of these.
+				"""
+		}
+	}
+
 	input: {
 		logs:    true
 		metrics: null
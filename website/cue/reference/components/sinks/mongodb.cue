@@ -0,0 +1,105 @@
+package metadata
+
+components: sinks: mongodb: {
+	title: "MongoDB"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10_000_000
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			request: {
+				enabled: true
+				headers: false
+			}
+			tls: enabled: false
+			to: {
+				service: services.mongodb
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: [
+			"""
+				The configured user must be allowed to insert into, and when upserting update, the target collections.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	configuration: base.components.sinks.mongodb.configuration
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	how_it_works: {
+		document_mapping: {
+			title: "Document mapping"
+			body: """
+				Each event is written as one document, with the same structure as the event. Strings, integers, floats,
+				booleans, arrays and objects are written as their BSON equivalents, and timestamps are written as BSON
+				dates, which have a precision of a millisecond.
+				"""
+		}
+		upserts: {
+			title: "Upserts"
+			body: """
+				By default, each batch is written with a single unordered `insertMany` command. Vector sets the `_id`
+				of every document that does not have one, so that a batch retried after a partial failure inserts the
+				same documents again: those that were already inserted are rejected as duplicate keys, which is not
+				treated as an error. Other duplicate key errors, such as an event whose own `_id` already exists in the
+				collection or a violation of another unique index, fail the batch.
+
+				When `upsert.key` is set, each event instead replaces the document whose key fields have the same
+				values, and is inserted if there is none. This makes writes idempotent as well. Creating a unique index
+				on the key fields is recommended.
+				"""
+		}
+		write_concern: {
+			title: "Write concern"
+			body: """
+				Events are only acknowledged once MongoDB has acknowledged their write with the configured write
+				concern, so `write_concern.w` cannot be `0`. When no write concern is configured, the write concern of
+				the endpoint or deployment is used.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+	}
+}
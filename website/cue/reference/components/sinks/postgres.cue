@@ -0,0 +1,103 @@
+package metadata
+
+components: sinks: postgres: {
+	title: "PostgreSQL"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "batch"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			batch: {
+				enabled:      true
+				common:       false
+				max_bytes:    10_000_000
+				timeout_secs: 1.0
+			}
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: enabled: false
+			}
+			request: {
+				enabled:     true
+				concurrency: 1
+				headers:     false
+			}
+			tls: enabled: false
+			to: {
+				service: services.postgresql
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp", "unix"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: [
+			"""
+				The target table must already exist, and the configured user must be allowed to insert into it.
+				""",
+		]
+		warnings: []
+		notices: []
+	}
+
+	configuration: base.components.sinks.postgres.configuration
+
+	input: {
+		logs: true
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+		traces: false
+	}
+
+	how_it_works: {
+		column_mapping: {
+			title: "Column mapping"
+			body: """
+				Each event is written as one row. Every column listed in `columns` is populated from the top-level event
+				field of the same name, and values are converted to the column's type by the server, so nested objects
+				can be written to `json` or `jsonb` columns and timestamps to `timestamptz` columns. Event fields with no
+				matching column are ignored, and columns with no matching field are set to `NULL`.
+				"""
+		}
+		transactions: {
+			title: "Transactions"
+			body: """
+				Each batch is written within a single transaction. If any row of the batch cannot be written, for
+				example because it violates a constraint, the whole batch is rolled back and its events are rejected.
+				Events are only acknowledged once their transaction is committed.
+
+				Batches failing because of the connection, or with a serialization failure or deadlock (SQLSTATE classes
+				`08` and `40`), are retried as configured by `request`, over a new connection.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+	}
+}
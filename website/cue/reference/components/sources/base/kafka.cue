@@ -136,9 +136,17 @@ base: components: sources: kafka: configuration: {
 		}
 	}
 	group_id: {
-		description: "The consumer group name to be used to consume events from Kafka."
-		required:    true
-		type: string: syntax: "literal"
+		description: """
+			The consumer group name to be used to consume events from Kafka.
+
+			Required unless `partitions` is set. With explicitly assigned partitions, the group is only used to store and
+			commit offsets, and no offsets are committed if it is left empty.
+			"""
+		required: false
+		type: string: {
+			default: ""
+			syntax:  "literal"
+		}
 	}
 	headers_key: {
 		description: """
@@ -213,6 +221,33 @@ base: components: sources: kafka: configuration: {
 			syntax:  "literal"
 		}
 	}
+	partitions: {
+		description: """
+			Topic partitions to read events from, assigned explicitly instead of through consumer group subscription.
+
+			When set, `topics` is ignored and the source never takes part in a rebalance.
+			"""
+		required: false
+		type: array: items: type: object: options: {
+			partitions: {
+				description: """
+					The partitions of the topic to read events from.
+
+					If empty, all partitions of the topic, as reported by the cluster when the source starts, are read.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: int: {}
+				}
+			}
+			topic: {
+				description: "The topic to read events from."
+				required:    true
+				type: string: syntax: "literal"
+			}
+		}
+	}
 	sasl: {
 		description: "Configuration for SASL authentication when interacting with Kafka."
 		required:    false
@@ -259,6 +294,117 @@ base: components: sources: kafka: configuration: {
 		required:    false
 		type: uint: default: 60000
 	}
+	start_at: {
+		description: """
+			Where to start reading explicitly assigned `partitions`.
+
+			By default, reading starts from the offsets committed for `group_id`.
+			"""
+		required: false
+		type: object: options: {
+			offsets: {
+				description: """
+					The offset to start reading from, for each partition.
+
+					Partitions without an entry are read from the offsets committed for `group_id`.
+					"""
+				relevant_when: "type = \"offsets\""
+				required:      true
+				type: array: items: type: object: options: {
+					offset: {
+						description: "The offset within the partition."
+						required:    true
+						type: int: {}
+					}
+					partition: {
+						description: "The partition number."
+						required:    true
+						type: int: {}
+					}
+					topic: {
+						description: "The topic of the partition."
+						required:    true
+						type: string: syntax: "literal"
+					}
+				}
+			}
+			timestamp: {
+				description:   "The time to start reading from."
+				relevant_when: "type = \"timestamp\""
+				required:      true
+				type: string: syntax: "literal"
+			}
+			type: {
+				required: true
+				type: string: enum: {
+					beginning: "Read from the earliest offset still available in each partition."
+					end:       "Read only the messages produced after the source starts."
+					offsets:   "Read from explicit offsets."
+					stored:    "Read from the offsets committed for `group_id`, falling back to `auto_offset_reset`."
+					timestamp: "Read from the first message whose timestamp is at or after the given time."
+				}
+			}
+		}
+	}
+	stop_at: {
+		description: """
+			Where to stop reading explicitly assigned `partitions`.
+
+			Stop positions are exclusive: the message at the stop offset itself is not read. Once every assigned partition
+			has reached its stop position, and all events read have been acknowledged, the source finishes.
+
+			By default, partitions are read indefinitely.
+			"""
+		required: false
+		type: object: options: {
+			offsets: {
+				description: """
+					The offset to stop reading at, for each partition.
+
+					Partitions without an entry are read indefinitely, so the source only finishes if every assigned partition
+					has one.
+					"""
+				relevant_when: "type = \"offsets\""
+				required:      true
+				type: array: items: type: object: options: {
+					offset: {
+						description: "The offset within the partition."
+						required:    true
+						type: int: {}
+					}
+					partition: {
+						description: "The partition number."
+						required:    true
+						type: int: {}
+					}
+					topic: {
+						description: "The topic of the partition."
+						required:    true
+						type: string: syntax: "literal"
+					}
+				}
+			}
+			timestamp: {
+				description: """
+					The time to stop reading at.
+
+					Partitions without a message at or after this time when the source starts stop at their end, as observed
+					at that time.
+					"""
+				relevant_when: "type = \"timestamp\""
+				required:      true
+				type: string: syntax: "literal"
+			}
+			type: {
+				required: true
+				type: string: enum: {
+					end:       "Stop at the end of each partition, as observed when the source starts."
+					offsets:   "Stop at explicit offsets."
+					timestamp: "Stop at the first message whose timestamp is at or after the given time."
+				}
+			}
+		}
+	}
 	tls: {
 		description: "Configures the TLS options for incoming/outgoing connections."
 		required:    false
@@ -373,8 +519,13 @@ base: components: sources: kafka: configuration: {
 			The Kafka topics names to read events from.
 
 			Regular expression syntax is supported if the topic begins with `^`.
+
+			Ignored if `partitions` is set.
 			"""
-		required: true
-		type: array: items: type: string: syntax: "literal"
+		required: false
+		type: array: {
+			default: []
+			items: type: string: syntax: "literal"
+		}
 	}
 }
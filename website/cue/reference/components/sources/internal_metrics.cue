@@ -331,12 +331,36 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		counter_resets_total: {
+			description:       "The total number of absolute counters which decreased, and were considered to have been reset, in the metric_kind transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		dedupe_cache_bytes: {
+			description:       "The approximate number of bytes used by the events cached by the dedupe transform."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		dedupe_cache_events: {
+			description:       "The number of events cached by the dedupe transform."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		decode_errors_total: {
 			description:       "The total number of decode errors seen when decoding data in a source component."
 			type:              "counter"
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		join_table_entries: {
+			description:       "The number of entries in the join table of the join transform."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		k8s_format_picker_edge_cases_total: {
 			description:       "The total number of edge cases encountered while picking format of the Kubernetes log message."
 			type:              "counter"
@@ -478,6 +502,18 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		buffered_bytes: {
+			description:       "The number of bytes of the events held by the window transform before a trigger."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		buffered_events: {
+			description:       "The number of events held by the window transform before a trigger."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		buffer_discarded_events_total: {
 			description:       "The number of events dropped by this non-blocking buffer."
 			type:              "counter"
@@ -745,6 +781,18 @@ components: sources: internal_metrics: {
 				file: _file
 			}
 		}
+		early_trace_decisions_total: {
+			description:       "The total number of traces decided by the tail_sampling transform before the end of their wait, because `max_traces` traces were waiting for a decision or the trace buffered `max_events_per_trace` events."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		expired_series_total: {
+			description:       "The total number of series whose state was dropped by the metric_kind transform after not being received for its `ttl_secs`."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		files_added_total: {
 			description:       "The total number of files Vector has found to watch."
 			type:              "counter"
@@ -972,6 +1020,25 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		sampled_traces_total: {
+			description:       "The total number of traces decided by the tail_sampling transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				decision: {
+					description: "Whether the trace was kept or dropped."
+					required:    true
+					enum: {
+						dropped: "The trace matched none of the policies, and was dropped."
+						kept:    "The trace matched one of the policies, and was kept."
+					}
+				}
+				policy: {
+					description: "The type of the policy which kept the trace, or `none` when it was dropped."
+					required:    true
+				}
+			}
+		}
 		send_errors_total: {
 			description:       "The total number of errors sending messages."
 			type:              "counter"
@@ -1074,6 +1141,25 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		throttled_events_total: {
+			description:       "The total number of events throttled by the throttle transform."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				key: {
+					description: "The rendered `key_field` of the events, `None` when it is unset, or `_other` beyond the first `max_metric_keys` keys."
+					required:    true
+				}
+				limit: {
+					description: "The limit exceeded by the events."
+					required:    true
+					enum: {
+						bytes:  "The events exceeded `threshold_bytes`."
+						events: "The events exceeded `threshold`."
+					}
+				}
+			}
+		}
 		timestamp_parse_errors_total: {
 			description:       "The total number of errors encountered parsing [RFC 3339](\(urls.rfc_3339)) timestamps."
 			type:              "counter"
@@ -1142,6 +1228,25 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		vrl_evaluations_total: {
+			description: """
+				The number of evaluations of an expression or function call of a VRL program, reported by
+				the `remap` transform when its `profile` option is enabled.
+				"""
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _vrl_profile_tags
+		}
+		vrl_evaluation_duration_seconds: {
+			description: """
+				The time spent, for each event, evaluating an expression or function call of a VRL
+				program, reported by the `remap` transform when its `profile` option is enabled. The
+				time of a function call includes the evaluation of its arguments.
+				"""
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _vrl_profile_tags
+		}
 
 		// Windows metrics
 		windows_service_does_not_exist_total: {
@@ -1195,6 +1300,26 @@ components: sources: internal_metrics: {
 		}
 
 		// Helpful tag groupings
+		_vrl_profile_tags: _component_tags & {
+			kind: {
+				description: "What is evaluated."
+				required:    true
+				enum: {
+					expression:    "A top-level expression of the program or of one of its blocks."
+					function_call: "A function call."
+				}
+			}
+			function: {
+				description: "The name of the called function, for function calls."
+				required:    false
+				examples: ["parse_json"]
+			}
+			location: {
+				description: "The location in the program, as `line:column`."
+				required:    true
+				examples: ["3:9"]
+			}
+		}
 		_component_tags: _internal_metrics_tags & {
 			component_kind: _component_kind
 			component_id:   _component_id
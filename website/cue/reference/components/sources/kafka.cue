@@ -75,9 +75,13 @@ components: sources: kafka: {
 			}
 		}
 		group_id: {
-			description: "The consumer group name to be used to consume events from Kafka."
-			required:    true
+			description: """
+				The consumer group name to be used to consume events from Kafka. Required unless `partitions` is set, in
+				which case the group is only used to store and commit offsets.
+				"""
+			required: false
 			type: string: {
+				default: ""
 				examples: ["consumer-group-name"]
 			}
 		}
@@ -182,10 +186,16 @@ components: sources: kafka: {
 		}
 		socket_timeout_ms: components._kafka.configuration.socket_timeout_ms
 		topics: {
-			description: "The Kafka topics names to read events from. Regex is supported if the topic begins with `^`."
-			required:    true
-			type: array: items: type: string: {
-				examples: ["^(prefix1|prefix2)-.+", "topic-1", "topic-2"]
+			description: """
+				The Kafka topics names to read events from. Regex is supported if the topic begins with `^`. Ignored if
+				`partitions` is set.
+				"""
+			required: false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["^(prefix1|prefix2)-.+", "topic-1", "topic-2"]
+				}
 			}
 		}
 	}
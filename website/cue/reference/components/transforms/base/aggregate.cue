@@ -1,11 +1,131 @@
 package metadata
 
-base: components: transforms: aggregate: configuration: interval_ms: {
-	description: """
-		The interval between flushes, in milliseconds.
-
-		Over this period metrics with the same series data (name, namespace, tags, …) will be aggregated.
-		"""
-	required: false
-	type: uint: default: 10000
+base: components: transforms: aggregate: configuration: {
+	align_to_wall_clock: {
+		description: """
+			Whether to align flushes to wall-clock boundaries.
+
+			When enabled, flushes happen at multiples of `interval_ms` since the Unix epoch, for
+			example at the start of every minute for an interval of 60 seconds, instead of relative to
+			the start of Vector.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	group_by: {
+		description: """
+			Regroups metrics by keeping or dropping some of their tags before aggregating them.
+
+			The metrics which only differ by the removed tags are aggregated together, which reduces the
+			cardinality of the output, as in "sum by service".
+			"""
+		required: false
+		type: object: options: {
+			action: {
+				description: "What to do with the `tags` of a regrouping."
+				required:    false
+				type: string: {
+					default: "keep"
+					enum: {
+						drop: "Drops the listed tags, and keeps the others."
+						keep: "Keeps only the listed tags."
+					}
+				}
+			}
+			tags: {
+				description: "The tags to keep or drop."
+				required:    true
+				type: array: items: type: string: {
+					examples: ["service"]
+					syntax: "literal"
+				}
+			}
+		}
+	}
+	interval_ms: {
+		description: """
+			The interval between flushes, in milliseconds.
+
+			Over this period metrics with the same series data (name, namespace, tags, …) will be aggregated.
+			"""
+		required: false
+		type: uint: default: 10000
+	}
+	mode: {
+		description: "How the values of a metric are aggregated over a window."
+		required:    false
+		type: string: {
+			default: "auto"
+			enum: {
+				auto:   "Sums incremental metrics, and keeps the latest value of absolute ones."
+				count:  "Counts the values, or the samples of distributions, as an incremental counter."
+				latest: "Keeps the latest value."
+				max:    "Keeps the maximum value, as an absolute gauge."
+				mean:   "Averages the values, as an absolute gauge."
+				min:    "Keeps the minimum value, as an absolute gauge."
+				quantiles: """
+					Computes the `quantiles` of the values, and of the samples of distributions, as an
+					aggregated summary.
+					"""
+				sum: """
+					Sums the values.
+
+					Incremental values are added together, while only the latest value of each absolute series
+					is added, so that absolute counters can be summed across the series merged by `group_by`.
+					The result is a counter if all the values are counters, and a gauge otherwise.
+					"""
+			}
+		}
+	}
+	modes: {
+		description: "The aggregation modes of specific metrics, by metric name, overriding `mode`."
+		required:    false
+		type: object: options: "*": {
+			description: "The aggregation modes of specific metrics, by metric name, overriding `mode`."
+			required:    true
+			type: string: enum: {
+				auto:   "Sums incremental metrics, and keeps the latest value of absolute ones."
+				count:  "Counts the values, or the samples of distributions, as an incremental counter."
+				latest: "Keeps the latest value."
+				max:    "Keeps the maximum value, as an absolute gauge."
+				mean:   "Averages the values, as an absolute gauge."
+				min:    "Keeps the minimum value, as an absolute gauge."
+				quantiles: """
+					Computes the `quantiles` of the values, and of the samples of distributions, as an
+					aggregated summary.
+					"""
+				sum: """
+					Sums the values.
+
+					Incremental values are added together, while only the latest value of each absolute series
+					is added, so that absolute counters can be summed across the series merged by `group_by`.
+					The result is a counter if all the values are counters, and a gauge otherwise.
+					"""
+			}
+		}
+	}
+	quantiles: {
+		description: """
+			The quantiles computed by the `quantiles` aggregation mode.
+
+			Each quantile must be between 0.0 and 1.0, inclusive.
+			"""
+		required: false
+		type: array: {
+			default: [0.5, 0.75, 0.9, 0.95, 0.99]
+			items: type: float: {}
+		}
+	}
+	window_ms: {
+		description: """
+			The length of sliding windows, in milliseconds.
+
+			When set, each flush outputs the aggregation of the metrics received over this period,
+			instead of over `interval_ms` only, so that consecutive windows overlap. It must be a
+			multiple of `interval_ms`. Since they cover overlapping periods, the metrics output for
+			sliding windows are always absolute.
+			"""
+		required: false
+		type: uint: examples: [60000]
+	}
 }
@@ -4,10 +4,44 @@ base: components: transforms: dedupe: configuration: {
 	cache: {
 		description: "Caching configuration for deduplication."
 		required:    false
-		type: object: options: num_events: {
-			description: "Number of events to cache and use for comparing incoming events to previously seen events."
-			required:    false
-			type: uint: default: 5000
+		type: object: options: {
+			hash_fields: {
+				description: """
+					Whether to cache a hash of the fields of events, instead of their values.
+
+					This bounds the memory used by each cached event, regardless of the size of its fields, and
+					provides a key which is stable across Vector instances. Distinct events may be considered
+					duplicates if their hashes collide, which is very unlikely.
+					"""
+				required: false
+				type: bool: default: false
+			}
+			max_bytes: {
+				description: """
+					The maximum size of the cache, in bytes.
+
+					When set, the least recently used events are removed from the cache once the size of the
+					cached fields exceeds this limit, even if it holds fewer than `num_events` events.
+					"""
+				required: false
+				type: uint: {}
+			}
+			num_events: {
+				description: "Number of events to cache and use for comparing incoming events to previously seen events."
+				required:    false
+				type: uint: default: 5000
+			}
+			time_window_secs: {
+				description: """
+					How long an event is cached, in seconds.
+
+					When set, an event is only considered a duplicate of the events received within this
+					duration before it, regardless of the number of events received in between, as long as they
+					fit in the cache.
+					"""
+				required: false
+				type: uint: {}
+			}
 		}
 	}
 	fields: {
@@ -0,0 +1,92 @@
+package metadata
+
+base: components: transforms: join: configuration: {
+	fields: {
+		description: """
+			The fields of the events of the right side to store in the join table, and to insert into
+			the matching events of the left side.
+
+			Fields of the left side with the same names are overwritten.
+			"""
+		required: true
+		type: array: items: type: string: {
+			examples: ["hostname", "user.name"]
+			syntax: "literal"
+		}
+	}
+	left_inputs: {
+		description: """
+			The inputs whose events are the left side of the join.
+
+			Their events are enriched with the entry of the join table matching their key. Each of
+			them must also be listed in `inputs`.
+			"""
+		required: true
+		type: array: items: type: string: {
+			examples: ["firewall", "my_route.my_output"]
+			syntax: "literal"
+		}
+	}
+	left_key: {
+		description: "The fields forming the join key of the events of the left side."
+		required:    true
+		type: array: items: type: string: {
+			examples: ["src_ip", "session_id"]
+			syntax: "literal"
+		}
+	}
+	max_entries: {
+		description: """
+			The maximum number of entries in the join table.
+
+			When the table is full, the least recently used entry is removed to make room for new ones.
+			"""
+		required: false
+		type: uint: default: 100000
+	}
+	mode: {
+		description: "How events of the left side without a matching entry are handled."
+		required:    false
+		type: string: {
+			default: "left"
+			enum: {
+				inner: """
+					Only the events with a matching entry are forwarded.
+
+					The others are sent to the `unmatched` output.
+					"""
+				left: "All the events are forwarded, whether they have a matching entry or not."
+			}
+		}
+	}
+	right_inputs: {
+		description: """
+			The inputs whose events are the right side of the join.
+
+			Their events fill the join table, and are not forwarded. Each of them must also be listed
+			in `inputs`.
+			"""
+		required: true
+		type: array: items: type: string: {
+			examples: ["dhcp", "my_route.my_output"]
+			syntax: "literal"
+		}
+	}
+	right_key: {
+		description: """
+			The fields forming the join key of the events of the right side.
+
+			They are compared, in order, with the fields of `left_key`. Defaults to `left_key`.
+			"""
+		required: false
+		type: array: items: type: string: {
+			examples: ["ip", "session_id"]
+			syntax: "literal"
+		}
+	}
+	ttl_secs: {
+		description: "How long an entry of the join table is kept after it was last updated, in seconds."
+		required:    false
+		type: uint: default: 3600
+	}
+}
@@ -1,73 +1,119 @@
 package metadata
 
-base: components: transforms: log_to_metric: configuration: metrics: {
-	description: "A list of metrics to generate."
-	required:    true
-	type: array: items: type: object: options: {
-		field: {
-			description: "Name of the field in the event to generate the metric."
-			required:    true
-			type: string: syntax: "template"
-		}
-		increment_by_value: {
-			description:   "Increments the counter by the value in `field`, instead of only by `1`."
-			relevant_when: "type = \"counter\""
-			required:      false
-			type: bool: default: false
-		}
-		kind: {
-			description: """
-				Metric kind.
+base: components: transforms: log_to_metric: configuration: {
+	all_metrics: {
+		description: """
+			Converts logs which are already shaped like metrics into metrics, instead of generating
+			the metrics listed in `metrics`.
 
-				Metrics can be either absolute of incremental. Absolute metrics represent a sort of "last write wins" scenario,
-				where the latest absolute value seen is meant to be the actual metric value.  In constrast, and perhaps intuitively,
-				incremental metrics are meant to be additive, such that we don't know what total value of the metric is, but we know
-				that we'll be adding or subtracting the given value from it.
+			The logs must have the fields of the metrics output by the `metric_to_log` transform: a
+			`name`, and optionally a `namespace` and `tags`, a `kind` of `absolute` or `incremental`,
+			and a field named after the type of the metric holding its value, such as
+			`counter.value`, `gauge.value`, `set.values`, `distribution.samples`,
+			`aggregated_histogram.buckets`, or `aggregated_summary.quantiles`. The timestamp of the
+			metrics is read from the timestamp field of the logs.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	metrics: {
+		description: "A list of metrics to generate."
+		required:    false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				buckets: {
+					description: """
+						The upper limits of the buckets of the histogram, in increasing order.
 
-				Generally speaking, most metrics storage systems deal with incremental updates. A notable exception is Prometheus,
-				which deals with, and expects, absolute values from clients.
-				"""
-			relevant_when: "type = \"counter\""
-			required:      false
-			type: string: {
-				default: "incremental"
-				enum: {
-					absolute:    "Absolute metric."
-					incremental: "Incremental metric."
+						When set, the value in `field` is counted in an aggregated histogram with these buckets.
+						Otherwise, it is added as a sample to a distribution.
+						"""
+					relevant_when: "type = \"histogram\""
+					required:      false
+					type: array: {
+						default: []
+						items: type: float: examples: [0.1, 1.0]
+					}
 				}
-			}
-		}
-		name: {
-			description: """
-				Overrides the name of the counter.
+				field: {
+					description: "Name of the field in the event to generate the metric."
+					required:    true
+					type: string: syntax: "template"
+				}
+				increment_by_value: {
+					description:   "Increments the counter by the value in `field`, instead of only by `1`."
+					relevant_when: "type = \"counter\""
+					required:      false
+					type: bool: default: false
+				}
+				kind: {
+					description: """
+						Metric kind.
 
-				If not specified, `field` is used as the name of the metric.
-				"""
-			required: false
-			type: string: syntax: "template"
-		}
-		namespace: {
-			description: "Sets the namespace for the metric."
-			required:    false
-			type: string: syntax: "template"
-		}
-		tags: {
-			description: "Tags to apply to the metric."
-			required:    false
-			type: object: options: "*": {
-				description: "Tags to apply to the metric."
-				required:    true
-				type: string: syntax: "template"
-			}
-		}
-		type: {
-			required: true
-			type: string: enum: {
-				counter:   "A counter."
-				gauge:     "A gauge."
-				histogram: "A histogram."
-				set:       "A set."
-				summary:   "A summary."
+						Metrics can be either absolute of incremental. Absolute metrics represent a sort of "last write wins" scenario,
+						where the latest absolute value seen is meant to be the actual metric value.  In constrast, and perhaps intuitively,
+						incremental metrics are meant to be additive, such that we don't know what total value of the metric is, but we know
+						that we'll be adding or subtracting the given value from it.
+
+						Generally speaking, most metrics storage systems deal with incremental updates. A notable exception is Prometheus,
+						which deals with, and expects, absolute values from clients.
+						"""
+					relevant_when: "type = \"counter\""
+					required:      false
+					type: string: {
+						default: "incremental"
+						enum: {
+							absolute:    "Absolute metric."
+							incremental: "Incremental metric."
+						}
+					}
+				}
+				name: {
+					description: """
+						Overrides the name of the counter.
+
+						If not specified, `field` is used as the name of the metric.
+						"""
+					required: false
+					type: string: syntax: "template"
+				}
+				namespace: {
+					description: "Sets the namespace for the metric."
+					required:    false
+					type: string: syntax: "template"
+				}
+				tags: {
+					description: """
+						Tags to apply to the metric.
+
+						A tag name ending with `*` expands into one tag for each key of the object field referenced
+						by its template, named after the key prefixed with the rest of the tag name. For example,
+						`"label_*" = "{{ labels }}"` adds a `label_<key>` tag for every key under `labels`.
+						"""
+					required: false
+					type: object: options: "*": {
+						description: """
+							Tags to apply to the metric.
+
+							A tag name ending with `*` expands into one tag for each key of the object field referenced
+							by its template, named after the key prefixed with the rest of the tag name. For example,
+							`"label_*" = "{{ labels }}"` adds a `label_<key>` tag for every key under `labels`.
+							"""
+						required: true
+						type: string: syntax: "template"
+					}
+				}
+				type: {
+					required: true
+					type: string: enum: {
+						counter:   "A counter."
+						gauge:     "A gauge."
+						histogram: "A histogram."
+						set:       "A set."
+						summary:   "A summary."
+					}
+				}
 			}
 		}
 	}
@@ -0,0 +1,36 @@
+package metadata
+
+base: components: transforms: metric_kind: configuration: {
+	mode: {
+		description: "The conversion applied to metrics."
+		required:    true
+		type: string: enum: {
+			absolute: "Converts incremental metrics to absolute ones, by accumulating the values of their series."
+			incremental: """
+				Converts absolute metrics to incremental ones, by subtracting the previous value of their series.
+
+				The first value of a series is only used as a reference, and is not output. When an absolute
+				counter decreases, it is considered to have been reset, and its new value is output as the
+				increment.
+				"""
+			rate: """
+				Converts counters to absolute gauges of their rate of increase, per second.
+
+				The rate is computed from the timestamps of the metrics, or from the time they are received
+				when they have no timestamp. The first value of a series is not output. Metrics which are not
+				counters are passed through unchanged.
+				"""
+		}
+	}
+	ttl_secs: {
+		description: """
+			How long to keep the state of a series that is no longer received, in seconds.
+
+			Once a series has not been received for this long, it starts over as if it was new: absolute
+			values are used as the new reference for increments, accumulated values restart from zero,
+			and rates are computed from the next value.
+			"""
+		required: false
+		type: uint: default: 300
+	}
+}
@@ -0,0 +1,99 @@
+package metadata
+
+base: components: transforms: metric_relabel: configuration: rules: {
+	description: """
+		The relabeling rules, applied in order.
+
+		The rules follow the semantics of the Prometheus `relabel_config`, where labels are the tags of
+		metrics, and the `__name__` label is their name.
+		"""
+	required: true
+	type: array: items: type: object: options: {
+		action: {
+			description: "The action of a relabeling rule."
+			required:    false
+			type: string: {
+				default: "replace"
+				enum: {
+					drop:      "Drops the metrics for which `regex` matches the values of `source_labels`."
+					hashmod:   "Writes the hash of the values of `source_labels`, modulo `modulus`, to `target_label`."
+					keep:      "Drops the metrics for which `regex` doesn't match the values of `source_labels`."
+					labeldrop: "Removes the labels whose name matches `regex`."
+					labelkeep: "Removes the labels whose name doesn't match `regex`."
+					labelmap:  "Copies the labels whose name matches `regex` to the labels named by `replacement`."
+					replace: """
+						Writes `replacement` to `target_label` if `regex` matches the values of `source_labels`.
+
+						If the result is empty, `target_label` is removed.
+						"""
+				}
+			}
+		}
+		modulus: {
+			description: "The modulus taken of the hash of the concatenated values of `source_labels`, for the `hashmod` action."
+			required:    false
+			type: uint: {}
+		}
+		regex: {
+			description: """
+				The regular expression matched against the concatenated values of `source_labels`, or
+				against label names for the `labelmap`, `labeldrop`, and `labelkeep` actions.
+
+				The regular expression is anchored at both ends.
+				"""
+			required: false
+			type: string: {
+				default: "(.*)"
+				syntax:  "literal"
+			}
+		}
+		replacement: {
+			description: """
+				The value written by the `replace` action, or the name of the labels written by the `labelmap`
+				action.
+
+				The capture groups of `regex` can be referenced as `$1` or `${1}`, or by name.
+				"""
+			required: false
+			type: string: {
+				default: "$1"
+				syntax:  "literal"
+			}
+		}
+		separator: {
+			description: "The separator placed between the values of `source_labels`."
+			required:    false
+			type: string: {
+				default: ";"
+				syntax:  "literal"
+			}
+		}
+		source_labels: {
+			description: """
+				The labels whose values are concatenated, with `separator`, and matched against `regex`.
+
+				Missing labels have an empty value.
+				"""
+			required: false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["__name__", "service"]
+					syntax: "literal"
+				}
+			}
+		}
+		target_label: {
+			description: """
+				The label to which the result of the `replace` and `hashmod` actions is written.
+
+				For the `replace` action, the capture groups of `regex` can be referenced, as in `replacement`.
+				"""
+			required: false
+			type: string: {
+				examples: ["instance"]
+				syntax: "literal"
+			}
+		}
+	}
+}
@@ -41,6 +41,9 @@ base: components: transforms: remap: configuration: {
 
 			If a relative path is provided, its root is the current working directory.
 
+			Files imported by the program are resolved relative to the directory of this file first,
+			and then to the directories of the configuration files.
+
 			Required if `source` is missing.
 
 			[vrl]: https://vector.dev/docs/reference/vrl
@@ -51,6 +54,18 @@ base: components: transforms: remap: configuration: {
 			syntax: "literal"
 		}
 	}
+	profile: {
+		description: """
+			Measures the cost of each expression and function call of the program.
+
+			The number of evaluations and the time spent evaluating them are reported as the
+			`vrl_evaluations_total` and `vrl_evaluation_duration_seconds` internal metrics, tagged with
+			the location in the program. Measuring adds overhead to every evaluation, so this should
+			only be enabled while looking for the expensive parts of a program.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	reroute_dropped: {
 		description: """
 			Reroutes dropped events to a named output instead of halting processing on them.
@@ -98,6 +113,37 @@ base: components: transforms: remap: configuration: {
 			syntax: "remap_program"
 		}
 	}
+	state: {
+		description: """
+			Limits of the key/value state the program keeps between events.
+
+			The state is read and written with the `get_state`, `set_state`, and `incr_state` functions. It
+			is kept in memory, and shared by all the events processed by this transform. When a limit is
+			reached, expired entries are evicted first, and then the least recently used ones.
+			"""
+		required: false
+		type: object: options: {
+			max_bytes: {
+				description: "The maximum size of the state, in bytes."
+				required:    false
+				type: uint: default: 10485760
+			}
+			max_entries: {
+				description: "The maximum number of entries in the state."
+				required:    false
+				type: uint: default: 10000
+			}
+			ttl_secs: {
+				description: """
+					The time-to-live of the entries set without an explicit `ttl`, in seconds.
+
+					If unset, these entries don't expire, and are only evicted to stay within the limits.
+					"""
+				required: false
+				type: uint: examples: [3600]
+			}
+		}
+	}
 	timezone: {
 		description: """
 			The name of the timezone to apply to timestamp conversions that do not contain an explicit
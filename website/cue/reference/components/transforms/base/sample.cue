@@ -1,6 +1,43 @@
 package metadata
 
 base: components: transforms: sample: configuration: {
+	dynamic: {
+		description: """
+			Dynamic sampling options.
+
+			Instead of a fixed `rate`, each value of `key_field` gets its own rate, adjusted periodically so
+			that the events forwarded stay within an overall budget. Rare values are kept, while frequent
+			values are sampled down. Each forwarded event has its effective rate in its `sample_rate` field.
+			"""
+		required: false
+		type: object: options: {
+			adjustment_interval_secs: {
+				description: """
+					The interval between adjustments of the rates, in seconds.
+
+					The rates are computed from the number of events received for each value over the previous
+					interval. Until the first adjustment, and for values which were not received during the
+					previous interval, all events are forwarded.
+					"""
+				required: false
+				type: uint: default: 30
+			}
+			max_keys: {
+				description: """
+					The maximum number of values of `key_field` to track.
+
+					Once reached, events with new values share a single rate, as do events without `key_field`.
+					"""
+				required: false
+				type: uint: default: 10000
+			}
+			target_events_per_sec: {
+				description: "The number of events to forward per second, across all values of `key_field`."
+				required:    true
+				type: float: {}
+			}
+		}
+	}
 	exclude: {
 		description: "A logical condition used to exclude events from sampling."
 		required:    false
@@ -27,8 +64,10 @@ base: components: transforms: sample: configuration: {
 
 			For example, `rate = 10` means 1 out of every 10 events will be forwarded and the rest will
 			be dropped.
+
+			Required unless `dynamic` is set.
 			"""
-		required: true
+		required: false
 		type: uint: {}
 	}
 }
@@ -0,0 +1,87 @@
+package metadata
+
+base: components: transforms: tail_sampling: configuration: {
+	decision_wait_ms: {
+		description: """
+			How long to wait for the spans of a trace, in milliseconds.
+
+			The wait starts when the first event of a trace is received. Once it elapses, the policies
+			are applied to all the events received for the trace, and they are either all output or all
+			dropped. Events of a trace received after its decision follow the same decision.
+			"""
+		required: false
+		type: uint: default: 10000
+	}
+	max_events_per_trace: {
+		description: """
+			The maximum number of events buffered for a trace waiting for a decision.
+
+			When a trace reaches this limit, it is decided before the end of its wait, so that a hot or
+			long-lived trace ID can't use unbounded memory. Its later events follow that decision.
+			"""
+		required: false
+		type: uint: default: 1000
+	}
+	max_traces: {
+		description: """
+			The maximum number of traces waiting for a decision.
+
+			When this limit is reached, the oldest trace is decided before the end of its wait, to bound
+			the memory used by the transform.
+			"""
+		required: false
+		type: uint: default: 50000
+	}
+	policies: {
+		description: """
+			The sampling policies.
+
+			They are applied in order, and a trace is kept as soon as one of them matches. Traces which
+			match none of them are dropped.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				key: {
+					description:   "The attribute to look up."
+					relevant_when: "type = \"attribute\""
+					required:      true
+					type: string: {}
+				}
+				rate: {
+					description:   "The fraction of traces to keep, between 0.0 and 1.0."
+					relevant_when: "type = \"probabilistic\""
+					required:      true
+					type: float: {}
+				}
+				threshold_ms: {
+					description:   "The minimum duration of a span, in milliseconds."
+					relevant_when: "type = \"latency\""
+					required:      true
+					type: uint: {}
+				}
+				type: {
+					required: true
+					type: string: enum: {
+						attribute: "Keeps traces with a span whose `meta`, or whose `tags`, set `key` to one of `values`."
+						errors:    "Keeps traces with at least one span in error."
+						latency:   "Keeps traces with at least one span lasting at least `threshold_ms`."
+						probabilistic: """
+							Keeps a fraction of traces.
+
+							Traces are chosen by hashing their ID, so that a given trace is either always kept or
+							always dropped, across instances of the transform.
+							"""
+					}
+				}
+				values: {
+					description:   "The values of the attribute for which traces are kept."
+					relevant_when: "type = \"attribute\""
+					required:      true
+					type: array: items: type: string: {}
+				}
+			}
+		}
+	}
+}
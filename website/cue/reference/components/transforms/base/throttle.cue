@@ -21,13 +21,46 @@ base: components: transforms: throttle: configuration: {
 			syntax: "template"
 		}
 	}
+	max_metric_keys: {
+		description: """
+			The maximum number of keys for which throttled events are reported with their own `key`
+			tag.
+
+			Events throttled for any other key are reported with the `key` tag set to `_other`, to bound
+			the cardinality of the metrics.
+			"""
+		required: false
+		type: uint: default: 100
+	}
+	reroute_dropped: {
+		description: """
+			Reroutes throttled events to a named output instead of dropping them.
+
+			When set to `true`, throttled events are forwarded to a specially-named output, `dropped`,
+			so that they can be sent to another destination, such as cheaper storage.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	threshold: {
 		description: """
 			The number of events allowed for a given bucket per configured `window_secs`.
 
-			Each unique key will have its own `threshold`.
+			Each unique key will have its own `threshold`. Required unless `threshold_bytes` is set.
+			"""
+		required: false
+		type: uint: {}
+	}
+	threshold_bytes: {
+		description: """
+			The number of bytes of events allowed for a given bucket per configured `window_secs`.
+
+			Each unique key will have its own `threshold_bytes`, measured as the estimated size of its
+			events once encoded as JSON. Events larger than `threshold_bytes` are always throttled. When
+			both `threshold` and `threshold_bytes` are set, events are throttled as soon as either of
+			them is exceeded.
 			"""
-		required: true
+		required: false
 		type: uint: {}
 	}
 	window_secs: {
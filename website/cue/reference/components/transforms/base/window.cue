@@ -0,0 +1,64 @@
+package metadata
+
+base: components: transforms: window: configuration: {
+	group_by: {
+		description: """
+			An ordered list of fields by which to group events.
+
+			Each group has its own buffer, so that a trigger only forwards the events of its group.
+			When no fields are specified, all events are in a single group.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: {
+				examples: ["service", "host"]
+				syntax: "literal"
+			}
+		}
+	}
+	max_age_secs: {
+		description: """
+			The maximum age of the events buffered before a trigger, in seconds.
+
+			Buffered events older than this are dropped. When unset, events are only dropped when the
+			buffer of their group is full.
+			"""
+		required: false
+		type: uint: {}
+	}
+	max_groups: {
+		description: """
+			The maximum number of groups with buffered events.
+
+			When a new group would exceed this limit, the least recently used group is forgotten, along
+			with its buffered events.
+			"""
+		required: false
+		type: uint: default: 10000
+	}
+	num_events_after: {
+		description: "The number of events to forward after a trigger, per group."
+		required:    false
+		type: uint: default: 0
+	}
+	num_events_before: {
+		description: """
+			The maximum number of events to buffer before a trigger, per group.
+
+			When the buffer of a group is full, its oldest event is dropped.
+			"""
+		required: false
+		type: uint: default: 100
+	}
+	trigger: {
+		description: """
+			A condition used to trigger the forwarding of the buffered events.
+
+			When an event matches this condition, it is forwarded along with the events buffered before
+			it in its group, and the `num_events_after` events following it in its group.
+			"""
+		required: true
+		type: condition: {}
+	}
+}
@@ -83,6 +83,22 @@ components: transforms: dedupe: {
 				"""
 		}
 
+		time_window: {
+			title: "Time Window"
+			body: """
+				When `cache.time_window_secs` is set, cached Events are also removed
+				once they are older than the window, and duplicates no longer reset
+				their place in line. An Event is then only considered a duplicate
+				of the Events received within the window before it, no matter how
+				many Events were received in between, as long as they fit in the
+				cache. The `cache.max_bytes` option additionally bounds the memory
+				used by the cache, and `cache.hash_fields` replaces the cached
+				values by a hash of 8 bytes. The `dedupe_cache_events` and
+				`dedupe_cache_bytes` metrics report the size of the cache when it
+				changes, at most once per second.
+				"""
+		}
+
 		missing_fields: {
 			title: "Missing Fields"
 			body: """
@@ -95,6 +111,8 @@ components: transforms: dedupe: {
 	}
 
 	telemetry: metrics: {
+		dedupe_cache_bytes:     components.sources.internal_metrics.output.metrics.dedupe_cache_bytes
+		dedupe_cache_events:    components.sources.internal_metrics.output.metrics.dedupe_cache_events
 		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
 	}
 }
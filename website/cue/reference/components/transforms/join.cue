@@ -0,0 +1,96 @@
+package metadata
+
+components: transforms: join: {
+	title: "Join"
+
+	description: """
+		Correlates two streams of logs, storing fields of the events of one of them in a table
+		keyed by a set of fields, and inserting them into the matching events of the other one.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: base.components.transforms.join.configuration
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	outputs: [
+		components._default_output,
+		{
+			name: "unmatched"
+			description: """
+				When `mode` is `inner`, the events of the left side without a matching entry in the
+				join table are sent to the `unmatched` output instead of the default one. For a
+				transform component named `foo`, this output can be accessed by specifying
+				`foo.unmatched` as the input to another component.
+				"""
+		},
+	]
+
+	examples: [
+		{
+			title: "Enrich firewall logs with DHCP leases"
+			configuration: {
+				left_inputs: ["firewall"]
+				right_inputs: ["dhcp"]
+				left_key: ["src_ip"]
+				right_key: ["ip"]
+				fields: ["hostname"]
+			}
+			input: [
+				{log: {source_type: "dhcp", ip: "10.0.0.1", hostname: "laptop"}},
+				{log: {source_type: "firewall", src_ip: "10.0.0.1", action: "deny"}},
+			]
+			output: [
+				{log: {source_type: "firewall", src_ip: "10.0.0.1", action: "deny", hostname: "laptop"}},
+			]
+		},
+	]
+
+	how_it_works: {
+		sides: {
+			title: "Sides of the join"
+			body: """
+				The sides of the join are selected by the component each event comes from. Events
+				sent by one of the `right_inputs` update the entry of the join table for their key,
+				and are not forwarded. All other events, including those of the `left_inputs`, are the
+				left side: they are looked up in the table, and have the stored fields inserted when
+				an entry matches. Events missing any of their key fields never match. Inputs are
+				matched by their exact ID, such as `dhcp` or `my_route.my_output`.
+				"""
+		}
+
+		state: {
+			title: "State"
+			body: """
+				The join table is kept in memory, and is lost when Vector restarts. Entries expire
+				`ttl_secs` after they were last updated, and the table holds at most `max_entries`
+				entries, removing the least recently used ones first. The `join_table_entries`
+				metric reports the number of entries, and is updated at most once per second.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		join_table_entries: components.sources.internal_metrics.output.metrics.join_table_entries
+	}
+}
@@ -329,6 +329,15 @@ components: transforms: log_to_metric: {
 				will not be emitted.
 				"""
 		}
+		all_metrics: {
+			title: "Logs Shaped Like Metrics"
+			body: """
+				With `all_metrics` enabled, each log is converted into a single metric read from its
+				fields, in the shape output by the `metric_to_log` transform, so that metrics can
+				round trip through logs without configuring each metric. Logs which can't be read as
+				metrics are dropped.
+				"""
+		}
 	}
 
 	telemetry: metrics: {
@@ -0,0 +1,110 @@
+package metadata
+
+components: transforms: metric_kind: {
+	title: "Metric Kind"
+
+	description: """
+		Converts metrics between the incremental and absolute kinds, or converts counters to
+		gauges of their per-second rate, so that downstream components receive metrics of the
+		kind they expect.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		convert: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: base.components.transforms.metric_kind.configuration
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+		traces: false
+	}
+
+	examples: [
+		{
+			title: "Absolute counter to incremental"
+			configuration: {
+				mode: "incremental"
+			}
+			input: [
+				{
+					metric: {
+						kind: "absolute"
+						name: "requests_total"
+						counter: {
+							value: 10.0
+						}
+					}
+				},
+				{
+					metric: {
+						kind: "absolute"
+						name: "requests_total"
+						counter: {
+							value: 15.0
+						}
+					}
+				},
+			]
+			output: [
+				{
+					metric: {
+						kind: "incremental"
+						name: "requests_total"
+						counter: {
+							value: 5.0
+						}
+					}
+				},
+			]
+		},
+	]
+
+	how_it_works: {
+		state: {
+			title: "State"
+			body: """
+				The transform keeps the latest value of each series, as identified by its name,
+				namespace, and tags, to compute increments, accumulated values, and rates. This
+				state is kept in memory, and is lost when Vector restarts, so that the first value
+				of each series after a restart is only used as a reference. The state of series
+				which are not received for `ttl_secs` is dropped.
+				"""
+		}
+
+		counter_resets: {
+			title: "Counter resets"
+			body: """
+				When an absolute counter decreases, as happens when the process exposing it
+				restarts, it is considered to have been reset to zero, and its new value is used as
+				the increment instead of a negative value.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		counter_resets_total: components.sources.internal_metrics.output.metrics.counter_resets_total
+		expired_series_total: components.sources.internal_metrics.output.metrics.expired_series_total
+	}
+}
@@ -0,0 +1,104 @@
+package metadata
+
+components: transforms: metric_relabel: {
+	title: "Metric Relabel"
+
+	description: """
+		Rewrites the names and tags of metrics, or drops metrics, with rules following the
+		semantics of Prometheus relabeling, so that existing `metric_relabel_configs` can be
+		reused as they are.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		shape: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: base.components.transforms.metric_relabel.configuration
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+		traces: false
+	}
+
+	examples: [
+		{
+			title: "Rename a metric and map Kubernetes labels"
+			configuration: {
+				rules: [
+					{
+						source_labels: ["__name__"]
+						regex:        "node_(.*)"
+						target_label: "__name__"
+						replacement:  "host_$1"
+					},
+					{
+						regex:  "__meta_kubernetes_pod_label_(.+)"
+						action: "labelmap"
+					},
+					{
+						regex:  "__meta_.*"
+						action: "labeldrop"
+					},
+				]
+			}
+			input: metric: {
+				kind: "absolute"
+				name: "node_load1"
+				tags: {
+					"__meta_kubernetes_pod_label_app": "web"
+					"__meta_kubernetes_namespace":     "default"
+				}
+				gauge: {
+					value: 0.5
+				}
+			}
+			output: metric: {
+				kind: "absolute"
+				name: "host_load1"
+				tags: {
+					app: "web"
+				}
+				gauge: {
+					value: 0.5
+				}
+			}
+		},
+	]
+
+	how_it_works: {
+		labels: {
+			title: "Labels"
+			body: """
+				The labels of relabeling rules are the tags of metrics, and the special `__name__`
+				label is their name. A rule setting a label to an empty value removes the tag, as in
+				Prometheus, while the name of metrics is never removed. The `labeldrop` and
+				`labelkeep` actions only apply to tags.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+	}
+}
@@ -156,6 +156,8 @@ components: transforms: "remap": {
 	]
 
 	telemetry: metrics: {
-		processing_errors_total: components.sources.internal_metrics.output.metrics.processing_errors_total
+		processing_errors_total:         components.sources.internal_metrics.output.metrics.processing_errors_total
+		vrl_evaluations_total:           components.sources.internal_metrics.output.metrics.vrl_evaluations_total
+		vrl_evaluation_duration_seconds: components.sources.internal_metrics.output.metrics.vrl_evaluation_duration_seconds
 	}
 }
@@ -32,6 +32,23 @@ components: transforms: sample: {
 		traces:  true
 	}
 
+	how_it_works: {
+		dynamic_sampling: {
+			title: "Dynamic sampling"
+			body: """
+				With the `dynamic` option, the transform targets an overall number of events per
+				second rather than a fixed rate. Every `dynamic.adjustment_interval_secs`, it counts
+				the events received for each value of `key_field` over the interval, and shares the
+				budget for the interval between them, from the least to the most frequent value.
+				Values received less than their share are kept entirely, and the rest of their share
+				is left to the more frequent values, which are sampled down accordingly.
+
+				Every forwarded event has the rate it was sampled at in its `sample_rate` field, so
+				that downstream systems can multiply counts by it to estimate the original volume.
+				"""
+		}
+	}
+
 	telemetry: metrics: {
 		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
 	}
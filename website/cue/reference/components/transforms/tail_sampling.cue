@@ -0,0 +1,109 @@
+package metadata
+
+components: transforms: tail_sampling: {
+	title: "Tail Sampling"
+
+	description: """
+		Samples whole traces, deciding whether to keep each of them once all its spans are
+		expected to have been received, based on errors, latency, attributes, or a fraction of
+		traces.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: base.components.transforms.tail_sampling.configuration
+
+	input: {
+		logs:    false
+		metrics: null
+		traces:  true
+	}
+
+	examples: [
+		{
+			title: "Keep slow or failed traces, and a sample of the others"
+			configuration: {
+				decision_wait_ms: 30000
+				policies: [
+					{type: "errors"},
+					{type: "latency", threshold_ms: 1000},
+					{type: "probabilistic", rate: 0.1},
+				]
+			}
+			input: [
+				{
+					trace: {
+						spans: [
+							{trace_id: 123, span_id: 1, duration: 1500000000, error: 0},
+							{trace_id: 123, span_id: 2, parent_id: 1, duration: 200000000, error: 0},
+						]
+					}
+				},
+			]
+			output: [
+				{
+					trace: {
+						spans: [
+							{trace_id: 123, span_id: 1, duration: 1500000000, error: 0},
+							{trace_id: 123, span_id: 2, parent_id: 1, duration: 200000000, error: 0},
+						]
+					}
+				},
+			]
+		},
+	]
+
+	how_it_works: {
+		trace_grouping: {
+			title: "Trace grouping"
+			body: """
+				Events are grouped by the `trace_id` field of the trace, or of its first span when it
+				has none, as set by the `datadog_agent` source. The events of a trace are held until
+				`decision_wait_ms` has elapsed since the first of them was received, and are then
+				either all output or all dropped. Events without a trace ID are passed through.
+				"""
+		}
+
+		policies: {
+			title: "Policies"
+			body: """
+				Policies look at the `error`, `duration` (in nanoseconds), and `meta` fields of the
+				spans, and at the `tags` field of the traces. They are applied in order, and the first
+				one to match keeps the trace. The `sampled_traces_total` metric counts the decisions,
+				along with the policy which kept each trace.
+				"""
+		}
+
+		memory: {
+			title: "Memory"
+			body: """
+				At most `max_traces` traces wait for a decision. When a new trace is received while
+				this limit is reached, the oldest trace is decided early, which may happen before all
+				its spans are received. Likewise, a trace is decided early once `max_events_per_trace`
+				of its events are buffered. Decisions are remembered for another `decision_wait_ms`, so
+				that late events of a trace follow its decision. This state is kept in memory, and the
+				traces waiting for a decision are decided when Vector shuts down.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		early_trace_decisions_total: components.sources.internal_metrics.output.metrics.early_trace_decisions_total
+		sampled_traces_total:        components.sources.internal_metrics.output.metrics.sampled_traces_total
+	}
+}
@@ -32,8 +32,21 @@ components: transforms: throttle: {
 		traces:  false
 	}
 
+	outputs: [
+		components._default_output,
+		{
+			name: "dropped"
+			description: """
+				When `reroute_dropped` is `true`, throttled events are sent to the `dropped` output
+				instead of being discarded. For a transform component named `foo`, this output can be
+				accessed by specifying `foo.dropped` as the input to another component.
+				"""
+		},
+	]
+
 	telemetry: metrics: {
 		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+		throttled_events_total: components.sources.internal_metrics.output.metrics.throttled_events_total
 	}
 
 	examples: [
@@ -106,8 +119,21 @@ components: transforms: throttle: {
 					body: """
 						The rate limiter will allow up to `threshold` number of events through and drop any further events
 						for that particular bucket when the rate limiter is at capacity. Any event passed when the rate
-						limiter is at capacity will be discarded and tracked by an `events_discarded_total` metric tagged
-						by the bucket's `key`.
+						limiter is at capacity will be discarded, or sent to the `dropped` output when `reroute_dropped`
+						is set, and tracked by a `throttled_events_total` metric tagged by the bucket's `key`. To bound
+						the cardinality of this metric, only the first `max_metric_keys` keys get their own tag, and all
+						others are reported as `_other`.
+						"""
+				},
+				{
+					title: "Byte Budgets"
+					body: """
+						With `threshold_bytes`, each bucket also gets a budget of bytes per `window_secs`, so that a few
+						large events cannot use up the capacity of a bucket. Each event consumes as many cells as its
+						estimated size once encoded as JSON, so an event larger than `threshold_bytes` is always
+						throttled. When both `threshold` and `threshold_bytes` are set, an event is throttled as soon as
+						either limit is reached. An event only counts towards either limit when it is within both, so
+						that an event throttled by one of them doesn't use up the budget of the other one.
 						"""
 				},
 			]
@@ -0,0 +1,87 @@
+package metadata
+
+components: transforms: window: {
+	title: "Window"
+
+	description: """
+		Buffers recent events and only forwards them around the events matching a trigger
+		condition, to keep the context of errors without forwarding every event.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: base.components.transforms.window.configuration
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  false
+	}
+
+	examples: [
+		{
+			title: "Debug logs preceding errors"
+			configuration: {
+				trigger:           ".level == \"error\""
+				group_by: ["service"]
+				num_events_before: 2
+			}
+			input: [
+				{log: {service: "api", level: "debug", message: "Connecting to the database."}},
+				{log: {service: "api", level: "debug", message: "Sending a query."}},
+				{log: {service: "api", level: "error", message: "Query timed out."}},
+			]
+			output: [
+				{log: {service: "api", level: "debug", message: "Connecting to the database."}},
+				{log: {service: "api", level: "debug", message: "Sending a query."}},
+				{log: {service: "api", level: "error", message: "Query timed out."}},
+			]
+		},
+	]
+
+	how_it_works: {
+		windows: {
+			title: "Windows"
+			body: """
+				Events which don't match `trigger` are held in the buffer of their group, up to
+				`num_events_before` events and, when set, for up to `max_age_secs`. When an event
+				matches `trigger`, the events buffered in its group are forwarded, in the order they
+				were received, followed by the event itself and the next `num_events_after` events of
+				its group. Events which leave the buffer without a trigger are dropped.
+				"""
+		}
+
+		memory: {
+			title: "Memory"
+			body: """
+				Buffered events are kept in memory, and are lost when Vector restarts. The
+				`buffered_events` and `buffered_bytes` metrics report the number and size of the
+				events currently buffered, across all groups, and are updated at most once per second.
+				Since each group has its own buffer, grouping by fields with many values increases the
+				memory used accordingly: at most `max_groups` groups with buffered events are kept,
+				and the least recently used one is forgotten, with its events, to make room for a new
+				one. Groups with no buffered events are forgotten right away.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		buffered_bytes:  components.sources.internal_metrics.output.metrics.buffered_bytes
+		buffered_events: components.sources.internal_metrics.output.metrics.buffered_events
+	}
+}
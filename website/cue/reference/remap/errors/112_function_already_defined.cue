@@ -0,0 +1,27 @@
+package metadata
+
+remap: errors: "112": {
+	title:       "Function already defined"
+	description: """
+		A [function definition](\(urls.vrl_expressions)#function-definition) uses the name of a
+		built-in function, or of another function defined in the program or in one of its imports.
+		"""
+	resolution: """
+		Rename the function so that its name is unique.
+		"""
+
+	examples: [
+		{
+			"title": "\(title) (built-in function)"
+			source: #"""
+				fn upcase(value) {
+				  value + "!"
+				}
+				"""#
+			diff: #"""
+				-fn upcase(value) {
+				+fn shout(value) {
+				"""#
+		},
+	]
+}
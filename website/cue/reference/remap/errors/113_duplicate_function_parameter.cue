@@ -0,0 +1,29 @@
+package metadata
+
+remap: errors: "113": {
+	title:       "Duplicate function parameter"
+	description: """
+		A [function definition](\(urls.vrl_expressions)#function-definition) declares the same
+		parameter more than once.
+		"""
+	resolution: """
+		Give each parameter of the function a distinct name.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				fn join_names(name, name) {
+				  name + " " + name
+				}
+				"""#
+			diff: #"""
+				-fn join_names(name, name) {
+				-  name + " " + name
+				+fn join_names(first, last) {
+				+  first + " " + last
+				"""#
+		},
+	]
+}
@@ -0,0 +1,31 @@
+package metadata
+
+remap: errors: "114": {
+	title:       "Recursive function call"
+	description: """
+		A [function definition](\(urls.vrl_expressions)#function-definition) calls itself, either
+		directly or through other functions of the program.
+		"""
+	rationale: """
+		VRL programs are guaranteed to terminate, which recursion would make impossible to ensure.
+		"""
+	resolution: """
+		Rewrite the function without calling itself, for example with one of the iteration
+		functions such as `map_values` or `for_each`.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				fn trim_all(values) {
+				  trim_all(values)
+				}
+				"""#
+			diff: #"""
+				-  trim_all(values)
+				+  map_values(values) -> |value| { strip_whitespace!(value) }
+				"""#
+		},
+	]
+}
@@ -0,0 +1,26 @@
+package metadata
+
+remap: errors: "115": {
+	title:       "Unreadable imported file"
+	description: """
+		The file of an `import` statement doesn't exist or can't be read.
+		"""
+	resolution: """
+		Check the path of the imported file, and that Vector is allowed to read it. Relative paths are
+		resolved against the directory of the importing file, and then against the directory of the
+		program file and of the Vector configuration files.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				import "lib/string.vrl"
+				"""#
+			diff: #"""
+				-import "lib/string.vrl"
+				+import "lib/strings.vrl"
+				"""#
+		},
+	]
+}
@@ -0,0 +1,26 @@
+package metadata
+
+remap: errors: "116": {
+	title:       "Error in imported file"
+	description: """
+		A file imported with an `import` statement doesn't compile. The diagnostics of the imported
+		file are reported as a note of this error, against the source of that file.
+		"""
+	resolution: """
+		Fix the errors reported in the note, in the imported file.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				# lib/strings.vrl contains: fn shout(value) { upcase(value }
+				import "lib/strings.vrl"
+				"""#
+			diff: #"""
+				-fn shout(value) { upcase(value }
+				+fn shout(value) { upcase(value) }
+				"""#
+		},
+	]
+}
@@ -0,0 +1,34 @@
+package metadata
+
+remap: errors: "117": {
+	title:       "Unexpected expression in imported file"
+	description: """
+		A file imported with an `import` statement contains an expression other than a
+		[function definition](\(urls.vrl_expressions)#function-definition) or an `import`
+		statement.
+		"""
+	rationale: """
+		Imported files provide functions to the programs that import them, and aren't run on their
+		own, so any other expression would never be evaluated.
+		"""
+	resolution: """
+		Move the expression into a function of the imported file, or into the importing program.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				# lib/strings.vrl
+				.message = upcase(.message)
+
+				fn shout(value) {
+				  upcase(value) + "!"
+				}
+				"""#
+			diff: #"""
+				-.message = upcase(.message)
+				"""#
+		},
+	]
+}
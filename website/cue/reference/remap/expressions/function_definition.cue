@@ -0,0 +1,75 @@
+package metadata
+
+remap: expressions: function_definition: {
+	title: "Function definition"
+	description: """
+		A _function definition_ declares a function that can be called from anywhere in the program,
+		including before its definition.
+
+		The body of a function only has access to its parameters: variables defined outside of the
+		function aren't visible, and variables assigned in the body are discarded once the function
+		returns. Changes to the event are kept.
+
+		Functions can't be recursive, and can't share their name with a built-in function or with
+		another function of the program.
+
+		Functions defined in other files are made available with an `import` statement, as in
+		`import "lib/strings.vrl"`. Imported files can only contain function definitions and other
+		imports. Relative paths are resolved against the directory of the importing file, and then
+		against the directory of the program file and of the Vector configuration files.
+		"""
+	return: """
+		Calling the function returns the result of the last evaluated expression in its body. The call
+		is fallible if the body is fallible.
+		"""
+
+	grammar: {
+		source: """
+			"fn" ~ function_name ~ "(" ~ parameters? ~ ")" ~ block
+			"""
+		definitions: {
+			function_name: {
+				description: """
+					`function_name` is the name of the function, following the same rules as a
+					variable name.
+					"""
+			}
+			parameters: {
+				description: """
+					A comma-separated list of parameter names. Arguments can be passed by position, or
+					by keyword using the parameter name.
+					"""
+			}
+			block: {
+				description: """
+					The body of the function, evaluated with the arguments of each call.
+					"""
+			}
+		}
+	}
+
+	examples: [
+		{
+			title: "Function definition"
+			source: #"""
+				fn greet(greeting, name) {
+					greeting + ", " + name + "!"
+				}
+
+				greet("Hello", name: "World")
+				"""#
+			return: "Hello, World!"
+		},
+		{
+			title: "Fallible function"
+			source: #"""
+				fn parse_port(value) {
+					to_int(value)
+				}
+
+				parse_port("eighty") ?? 80
+				"""#
+			return: 80
+		},
+	]
+}
@@ -0,0 +1,79 @@
+package metadata
+
+remap: functions: community_id: {
+	category: "IP"
+	description: """
+		Generates a [Community ID](\(urls.community_id)) flow hash for a network flow, as used by Zeek and
+		Suricata to correlate the records of the same flow.
+		"""
+	notices: [
+		"""
+			Both directions of a flow hash to the same ID. For ICMP and ICMPv6, `source_port` and
+			`destination_port` are the message type and code.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "source_ip"
+			description: "The source IP address (v4 or v6)."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "destination_ip"
+			description: "The destination IP address (v4 or v6)."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "protocol"
+			description: "The IANA protocol number, such as `6` for TCP or `17` for UDP."
+			required:    true
+			type: ["integer"]
+		},
+		{
+			name:        "source_port"
+			description: "The source port. Must be set together with `destination_port`."
+			required:    false
+			type: ["integer"]
+		},
+		{
+			name:        "destination_port"
+			description: "The destination port. Must be set together with `source_port`."
+			required:    false
+			type: ["integer"]
+		},
+		{
+			name:        "seed"
+			description: "The seed to hash the flow with."
+			required:    false
+			default:     0
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`source_ip` or `destination_ip` isn't a valid IP address.",
+		"`source_ip` and `destination_ip` aren't of the same IP version.",
+		"`protocol`, a port or `seed` is out of range.",
+		"Only one of `source_port` and `destination_port` is set.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "TCP flow"
+			source: #"""
+				community_id!(source_ip: "1.2.3.4", destination_ip: "5.6.7.8", source_port: 1122, destination_port: 3344, protocol: 6)
+				"""#
+			return: "1:wCb3OG7yAFWelaUydu0D+125CLM="
+		},
+		{
+			title: "ICMP echo reply"
+			source: #"""
+				community_id!(source_ip: "5.6.7.8", destination_ip: "1.2.3.4", source_port: 0, destination_port: 0, protocol: 1)
+				"""#
+			return: "1:crodRHL2FEsHjbv3UkRrfbs4bZ0="
+		},
+	]
+}
@@ -0,0 +1,34 @@
+package metadata
+
+remap: functions: crc32: {
+	category:    "Cryptography"
+	description: """
+		Calculates a [CRC-32](\(urls.crc)) checksum of the `value`.
+		"""
+	notices: [
+		"""
+			This isn't a cryptographic hash, use it for sharding or detecting accidental changes only.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to calculate the checksum for."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["integer"]
+
+	examples: [
+		{
+			title: "Calculate a CRC-32 checksum"
+			source: #"""
+				crc32("foo")
+				"""#
+			return: 2356372769
+		},
+	]
+}
@@ -0,0 +1,44 @@
+package metadata
+
+remap: functions: decode_gzip: {
+	category:    "Codec"
+	description: """
+		Decodes the `value` (a [Gzip](\(urls.gzip)) compressed string) into its original string.
+		"""
+	notices: [
+		"""
+			The decompressed value is limited to `max_size` bytes, to guard against decompression bombs.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The [Gzip](\(urls.gzip)) data to decode."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "max_size"
+			description: "The maximum size of the decompressed value, in bytes."
+			required:    false
+			default:     10485760
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid encoded Gzip string.",
+		"The decompressed value is larger than `max_size`.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decode Gzip data"
+			source: """
+				decode_gzip!(decode_base64!("H4sIAAAAAAACA6vML1XISCxLVSguTU5OLS5OK83JqVRISU3OT0lNUchNBQD7BGDaIAAAAA=="))
+				"""
+			return: "you have successfully decoded me"
+		},
+	]
+}
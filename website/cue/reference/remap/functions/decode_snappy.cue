@@ -0,0 +1,44 @@
+package metadata
+
+remap: functions: decode_snappy: {
+	category:    "Codec"
+	description: """
+		Decodes the `value` (a [Snappy](\(urls.snappy)) compressed string) into its original string.
+		"""
+	notices: [
+		"""
+			The decompressed value is limited to `max_size` bytes, to guard against decompression bombs.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The [Snappy](\(urls.snappy)) data to decode."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "max_size"
+			description: "The maximum size of the decompressed value, in bytes."
+			required:    false
+			default:     10485760
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid encoded Snappy string.",
+		"The decompressed value is larger than `max_size`.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decode Snappy data"
+			source: """
+				decode_snappy!(decode_base64!("IHx5b3UgaGF2ZSBzdWNjZXNzZnVsbHkgZGVjb2RlZCBtZQ=="))
+				"""
+			return: "you have successfully decoded me"
+		},
+	]
+}
@@ -0,0 +1,44 @@
+package metadata
+
+remap: functions: decode_zlib: {
+	category:    "Codec"
+	description: """
+		Decodes the `value` (a [Zlib](\(urls.zlib)) compressed string) into its original string.
+		"""
+	notices: [
+		"""
+			The decompressed value is limited to `max_size` bytes, to guard against decompression bombs.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The [Zlib](\(urls.zlib)) data to decode."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "max_size"
+			description: "The maximum size of the decompressed value, in bytes."
+			required:    false
+			default:     10485760
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid encoded Zlib string.",
+		"The decompressed value is larger than `max_size`.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decode Zlib data"
+			source: """
+				decode_zlib!(decode_base64!("eJyrzC9VyEgsS1UoLk1OTi0uTivNyalUSElNzk9JTVHITQUAzLIMQQ=="))
+				"""
+			return: "you have successfully decoded me"
+		},
+	]
+}
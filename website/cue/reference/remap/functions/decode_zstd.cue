@@ -0,0 +1,44 @@
+package metadata
+
+remap: functions: decode_zstd: {
+	category:    "Codec"
+	description: """
+		Decodes the `value` (a [Zstandard](\(urls.zstd)) compressed string) into its original string.
+		"""
+	notices: [
+		"""
+			The decompressed value is limited to `max_size` bytes, to guard against decompression bombs.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The [Zstandard](\(urls.zstd)) data to decode."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "max_size"
+			description: "The maximum size of the decompressed value, in bytes."
+			required:    false
+			default:     10485760
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid encoded Zstandard string.",
+		"The decompressed value is larger than `max_size`.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decode Zstandard data"
+			source: """
+				decode_zstd!(decode_base64!("KLUv/SAgAQEAeW91IGhhdmUgc3VjY2Vzc2Z1bGx5IGRlY29kZWQgbWU="))
+				"""
+			return: "you have successfully decoded me"
+		},
+	]
+}
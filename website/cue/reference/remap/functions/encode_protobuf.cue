@@ -0,0 +1,56 @@
+package metadata
+
+remap: functions: encode_protobuf: {
+	category:    "Codec"
+	description: """
+		Encodes the `value` as a protobuf message of type `message_type`, described by the
+		descriptor set in `desc_file`.
+
+		The descriptor set is loaded once, when the program is compiled. It can be generated from
+		`.proto` files with `protoc --include_imports --descriptor_set_out=<desc_file>`.
+
+		The keys of `value` are matched against the field names of the message, or their JSON
+		names. Fields set to `null` are left unset, and enum values can be given either by name or
+		by number.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object to encode."
+			required:    true
+			type: ["object"]
+		},
+		{
+			name:        "desc_file"
+			description: "The path to the descriptor set file. This must be a string literal."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "message_type"
+			description: "The fully qualified name of the message type, such as `package.Message`. This must be a string literal."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` has a key which isn't a field of `message_type`.",
+		"A value doesn't match the type of its field.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encode a protobuf message"
+			source: #"""
+				encode_base64(encode_protobuf!(
+					{ "name": "someone", "phones": [{ "number": "123456" }] },
+					desc_file: "/etc/vector/protobuf/person.desc",
+					message_type: "example.Person"
+				))
+				"""#
+			return: "Cgdzb21lb25lIggKBjEyMzQ1Ng=="
+		},
+	]
+}
@@ -0,0 +1,38 @@
+package metadata
+
+remap: functions: encode_zstd: {
+	category:    "Codec"
+	description: """
+		Encodes the `value` to [Zstandard](\(urls.zstd)).
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to encode."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "compression_level"
+			description: "The compression level, from 1 to 21."
+			required:    false
+			default:     3
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`compression_level` is out of range.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encode to Zstandard"
+			source: """
+				decode_zstd!(encode_zstd("please encode me"))
+				"""
+			return: "please encode me"
+		},
+	]
+}
@@ -0,0 +1,41 @@
+package metadata
+
+remap: functions: get_state: {
+	category: "State"
+	description: """
+		Returns the value stored under `key` in the state of the program, or `null` if
+		`key` isn't set or has expired.
+
+		The state is kept between events, and shared by all the events processed by the same
+		`remap` transform. It is set with the `set_state` and `incr_state` functions.
+		"""
+
+	arguments: [
+		{
+			name:        "key"
+			description: "The key of the value."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["any"]
+
+	examples: [
+		{
+			title: "Get a missing key"
+			source: #"""
+				get_state("last_seen")
+				"""#
+			return: null
+		},
+		{
+			title: "Get a key"
+			source: #"""
+				set_state!("last_seen", "2021-03-12T00:00:00Z")
+				get_state("last_seen")
+				"""#
+			return: "2021-03-12T00:00:00Z"
+		},
+	]
+}
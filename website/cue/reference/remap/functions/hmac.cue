@@ -0,0 +1,61 @@
+package metadata
+
+remap: functions: hmac: {
+	category:    "Cryptography"
+	description: """
+		Calculates an [HMAC](\(urls.hmac)) of the `value` with the `key`, and returns it as raw bytes.
+		"""
+	notices: [
+		"""
+			The result isn't printable, use `encode_base64` or `encode_base16` to encode it.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to calculate the HMAC for."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The secret key."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The hash algorithm to use."
+			enum: {
+				"SHA1":    "SHA-1 algorithm"
+				"SHA-224": "SHA-224 algorithm"
+				"SHA-256": "SHA-256 algorithm"
+				"SHA-384": "SHA-384 algorithm"
+				"SHA-512": "SHA-512 algorithm"
+			}
+			required: false
+			default:  "SHA-256"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Calculate an HMAC"
+			source: #"""
+				encode_base64(hmac("Hello there", "super-secret-key"))
+				"""#
+			return: "eLGE8YMviv85NPXgISRUZxstBNSU47JQdcXkUWcClmI="
+		},
+		{
+			title: "Calculate an HMAC with SHA-1"
+			source: #"""
+				encode_base64(hmac("Hello there", "super-secret-key", algorithm: "SHA1"))
+				"""#
+			return: "MiyBIHO8Set9+6crALiwkS0yFPE="
+		},
+	]
+}
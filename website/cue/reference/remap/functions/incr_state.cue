@@ -0,0 +1,57 @@
+package metadata
+
+remap: functions: incr_state: {
+	category: "State"
+	description: """
+		Adds `by` to the integer stored under `key` in the state of the program, and returns the
+		new value. If `key` isn't set, it is set to `by`.
+
+		The expiry of an existing key is only changed if `ttl` is given.
+		"""
+
+	arguments: [
+		{
+			name:        "key"
+			description: "The key of the counter."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "by"
+			description: "The amount to add to the counter."
+			required:    false
+			default:     1
+			type: ["integer"]
+		},
+		{
+			name: "ttl"
+			description: """
+				The number of seconds after which the counter expires. New counters default to the
+				`state.ttl_secs` option of the `remap` transform.
+				"""
+			required: false
+			type: ["integer", "float"]
+		},
+	]
+	internal_failure_reasons: [
+		"The value stored under `key` isn't an integer.",
+		"The counter overflows.",
+		"`ttl` isn't a positive number of seconds.",
+		"The program doesn't keep state, such as when it isn't run by a `remap` transform.",
+	]
+	return: types: ["integer"]
+
+	examples: [
+		{
+			title: "Count the events of each user"
+			input: log: user: "alice"
+			source: #"""
+				.count = incr_state!(.user)
+				"""#
+			output: log: {
+				user:  "alice"
+				count: 1
+			}
+		},
+	]
+}
@@ -0,0 +1,52 @@
+package metadata
+
+remap: functions: ip_anonymize: {
+	category: "IP"
+	description: """
+		Anonymizes the `value` IP address with [Crypto-PAn](\(urls.crypto_pan)).
+		"""
+	notices: [
+		"""
+			The anonymization is prefix-preserving: addresses sharing a prefix of a number of bits are anonymized
+			to addresses sharing a prefix of the same number of bits, so subnets can still be analyzed. The same
+			key always anonymizes an address the same way.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address (v4 or v6)."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The 32-byte secret key."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid IP address.",
+		"`key` isn't 32 bytes long.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Anonymize an IPv4 address"
+			source: #"""
+				ip_anonymize!("192.168.1.1", key: "boojahyoo3vaeToong0Eijee7Ahz3yee")
+				"""#
+			return: "206.171.6.128"
+		},
+		{
+			title: "Anonymize an IPv6 address"
+			source: #"""
+				ip_anonymize!("2001:db8::1", key: "boojahyoo3vaeToong0Eijee7Ahz3yee")
+				"""#
+			return: "3041:e87:cfc0:1800:187f:e4d0:7f1c:701"
+		},
+	]
+}
@@ -0,0 +1,53 @@
+package metadata
+
+remap: functions: ip_truncate: {
+	category: "IP"
+	description: """
+		Truncates the `value` IP address to its network prefix, zeroing out the remaining bits.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address (v4 or v6)."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "ipv4_prefix"
+			description: "The number of leading bits to keep of IPv4 addresses."
+			required:    false
+			default:     24
+			type: ["integer"]
+		},
+		{
+			name:        "ipv6_prefix"
+			description: "The number of leading bits to keep of IPv6 addresses."
+			required:    false
+			default:     48
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid IP address.",
+		"The prefix is larger than the address.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Truncate an IPv4 address"
+			source: #"""
+				ip_truncate!("192.168.10.23")
+				"""#
+			return: "192.168.10.0"
+		},
+		{
+			title: "Truncate an IPv6 address"
+			source: #"""
+				ip_truncate!("2404:6800:4003:c02::64", ipv6_prefix: 32)
+				"""#
+			return: "2404:6800::"
+		},
+	]
+}
@@ -0,0 +1,58 @@
+package metadata
+
+remap: functions: parse_protobuf: {
+	category:    "Parse"
+	description: """
+		Parses the `value` as a protobuf message of type `message_type`, described by the
+		descriptor set in `desc_file`.
+
+		The descriptor set is loaded once, when the program is compiled. It can be generated from
+		`.proto` files with `protoc --include_imports --descriptor_set_out=<desc_file>`.
+
+		The fields of the message are keyed by their name in the resulting object. Fields that
+		aren't set are omitted, and enum values are named after their variant when it is known.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The protobuf encoded message to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "desc_file"
+			description: "The path to the descriptor set file. This must be a string literal."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "message_type"
+			description: "The fully qualified name of the message type, such as `package.Message`. This must be a string literal."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid protobuf message of type `message_type`.",
+		"`value` contains an unsigned integer larger than the largest VRL integer.",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse a protobuf message"
+			source: #"""
+				parse_protobuf!(
+					decode_base64!("Cgdzb21lb25lIggKBjEyMzQ1Ng=="),
+					desc_file: "/etc/vector/protobuf/person.desc",
+					message_type: "example.Person"
+				)
+				"""#
+			return: {
+				name: "someone"
+				phones: [{number: "123456"}]
+			}
+		},
+	]
+}
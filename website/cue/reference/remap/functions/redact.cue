@@ -6,8 +6,8 @@ remap: functions: redact: {
 		Redact sensitive data in `value` such as:
 
 		- [US social security card numbers](\(urls.us_social_security_number))
+		- credit card numbers, email addresses, IP addresses, phone numbers, IBANs and tokens
 		- and other forms of personally identifiable information via custom patterns
-		- (more to come!)
 
 		This can help achieve compliance by ensuring sensitive data never leaves your network.
 		"""
@@ -27,7 +27,7 @@ remap: functions: redact: {
 				For arrays and objects it will recurse into any nested arrays or objects. Any non-string elements will
 				be skipped.
 
-				Any redacted text will be replaced with `[REDACTED]`.
+				Any redacted text will be replaced according to the `redactor`, with `[REDACTED]` by default.
 				"""#
 			required: true
 			type: ["string", "object", "array"]
@@ -48,6 +48,18 @@ remap: functions: redact: {
 				- `pattern`: Redact text matching any regular expressions specified in the, required, `patterns`
 					key. This is the expanded form of just passing a regular expression as a filter.
 				- `us_social_security_number`: Redact US social security card numbers.
+				- `credit_card_number`: Redact credit card numbers of 13 to 19 digits, optionally grouped with
+					spaces or dashes, that pass the Luhn check.
+				- `email_address`: Redact email addresses.
+				- `ipv4_address`: Redact IPv4 addresses.
+				- `ipv6_address`: Redact IPv6 addresses with at least two groups, one of which has a decimal
+					digit, that are not part of a longer word. Paths such as `std::fs::read` or `a::b` are kept.
+				- `phone_number`: Redact phone numbers, either in the E.164 format or with an area code.
+				- `iban`: Redact international bank account numbers that pass the ISO 13616 checksum.
+				- `jwt`: Redact JSON web tokens.
+				- `bearer_token`: Redact the token of `Bearer` authorization headers, keeping the scheme.
+
+				Filters are applied in order, so a filter can redact text that a later filter would match.
 
 				See examples for more details.
 
@@ -57,6 +69,32 @@ remap: functions: redact: {
 			required: true
 			type: ["array"]
 		},
+		{
+			name: "redactor"
+			description: #"""
+				How to replace the redacted text.
+
+				The redactor can be specified as an object with a `type` key that corresponds to a named redactor
+				and additional keys for customizing it, or as a named redactor if it has no required parameters.
+
+				Named redactors are:
+
+				- `full`: Replace the text with `[REDACTED]`.
+				- `text`: Replace the text with the, required, `replacement` key.
+				- `sha2`: Replace the text with its SHA-2 hash, so that redacted values can still be correlated.
+					The optional `key` key is a secret to compute an HMAC with, which prevents guessing the
+					redacted text from its hash. The `variant` key is one of `SHA-224`, `SHA-256` (the default),
+					`SHA-384` or `SHA-512`, and the `encoding` key is either `base16` (the default) or `base64`.
+				- `mask`: Replace the letters and digits of the text with the `character` key, `*` by default,
+					except for the first `keep_first` and the last `keep_last` ones. Other characters, such as
+					separators, are kept.
+
+				This parameter must be a static expression.
+				"""#
+			required: false
+			default:  "full"
+			type: ["string", "object"]
+		},
 	]
 	internal_failure_reasons: []
 	return: types: ["string", "object", "array"]
@@ -79,5 +117,19 @@ remap: functions: redact: {
 				ssn:  "[REDACTED]"
 			}
 		},
+		{
+			title: "Mask credit card numbers except for the last 4 digits"
+			source: #"""
+				redact("paid with 4111 1111 1111 1111", filters: ["credit_card_number"], redactor: { "type": "mask", "keep_last": 4 })
+				"""#
+			return: "paid with **** **** **** 1111"
+		},
+		{
+			title: "Replace email addresses with a keyed hash"
+			source: #"""
+				redact("sent by jane@example.com", filters: ["email_address"], redactor: { "type": "sha2", "key": "secret" })
+				"""#
+			return: "sent by fb817989d942e7ffb3d4b8b204f7abca29f4c25c3fa46574da84c50f30d07513"
+		},
 	]
 }
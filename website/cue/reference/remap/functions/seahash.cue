@@ -0,0 +1,41 @@
+package metadata
+
+remap: functions: seahash: {
+	category:    "Cryptography"
+	description: """
+		Calculates a [SeaHash](\(urls.seahash)) hash of the `value`.
+		"""
+	notices: [
+		"""
+			This isn't a cryptographic hash. Hashes above the maximum integer wrap around to negative integers.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to calculate the hash for."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["integer"]
+
+	examples: [
+		{
+			title: "Calculate a SeaHash hash"
+			source: #"""
+				seahash("foobar")
+				"""#
+			return: 5348458858952426560
+		},
+		{
+			title: "Calculate a negative SeaHash hash"
+			source: #"""
+				seahash("bar")
+				"""#
+			return: -2796170501982571315
+		},
+	]
+}
@@ -0,0 +1,60 @@
+package metadata
+
+remap: functions: set_state: {
+	category: "State"
+	description: """
+		Stores `value` under `key` in the state of the program, so that it can be read by the
+		following events with `get_state`.
+
+		The state is bounded by the `state` options of the `remap` transform. When a limit is
+		reached, expired entries are evicted first, and then the least recently used ones.
+		"""
+
+	arguments: [
+		{
+			name:        "key"
+			description: "The key of the value."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "value"
+			description: "The value to store."
+			required:    true
+			type: ["any"]
+		},
+		{
+			name: "ttl"
+			description: """
+				The number of seconds after which the value expires. Defaults to the `state.ttl_secs`
+				option of the `remap` transform.
+				"""
+			required: false
+			type: ["integer", "float"]
+		},
+	]
+	internal_failure_reasons: [
+		"`ttl` isn't a positive number of seconds.",
+		"`value` is larger than the `state.max_bytes` option of the `remap` transform.",
+		"The program doesn't keep state, such as when it isn't run by a `remap` transform.",
+	]
+	return: types: ["null"]
+
+	examples: [
+		{
+			title: "Remember the first time a user is seen"
+			input: log: user: "alice"
+			source: #"""
+				user = string!(.user)
+				if get_state(user) == null {
+					set_state!(user, now(), ttl: 86400)
+					.first_seen = true
+				}
+				"""#
+			output: log: {
+				user:       "alice"
+				first_seen: true
+			}
+		},
+	]
+}
@@ -0,0 +1,53 @@
+package metadata
+
+remap: functions: xxhash: {
+	category:    "Cryptography"
+	description: """
+		Calculates an [xxHash](\(urls.xxhash)) hash of the `value`.
+		"""
+	notices: [
+		"""
+			This isn't a cryptographic hash. `XXH64` hashes above the maximum integer wrap around to negative
+			integers.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to calculate the hash for."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "variant"
+			description: "The variant of the algorithm to use."
+			enum: {
+				"XXH32": "32-bit xxHash algorithm"
+				"XXH64": "64-bit xxHash algorithm"
+			}
+			required: false
+			default:  "XXH32"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["integer"]
+
+	examples: [
+		{
+			title: "Calculate an xxHash hash"
+			source: #"""
+				xxhash("foo")
+				"""#
+			return: 3792637401
+		},
+		{
+			title: "Calculate a 64-bit xxHash hash"
+			source: #"""
+				xxhash("foo", variant: "XXH64")
+				"""#
+			return: 3728699739546630719
+		},
+	]
+}
@@ -111,10 +111,12 @@ urls: {
 	cloudsmith:                                 "https://cloudsmith.io/~timber/repos/vector/packages/"
 	cloudsmith_apt:                             "https://cloudsmith.io/~timber/repos/vector/setup/#formats-deb"
 	cloudsmith_yum:                             "https://cloudsmith.io/~timber/repos/vector/setup/#formats-rpm"
+	community_id:                               "\(github)/corelight/community-id-spec"
 	console:                                    "\(wikipedia)/wiki/System_console"
 	conventional_commits:                       "https://www.conventionalcommits.org"
 	contributing:                               "\(vector_repo)/blob/master/CONTRIBUTING.md#setup"
 	crc:                                        "\(wikipedia)/wiki/Cyclic_redundancy_check"
+	crypto_pan:                                 "\(wikipedia)/wiki/Crypto-PAn"
 	ctime:                                      "https://www.cplusplus.com/reference/ctime"
 	cue:                                        "https://cuelang.org/"
 	csv:                                        "\(wikipedia)/wiki/Comma-separated_values"
@@ -240,6 +242,7 @@ urls: {
 	heroku:                                     "https://www.heroku.com"
 	heroku_http_log_drain:                      "https://devcenter.heroku.com/articles/log-drains#https-drains"
 	heroku_start:                               "https://devcenter.heroku.com/start"
+	hmac:                                       "\(wikipedia)/wiki/HMAC"
 	homebrew:                                   "https://brew.sh/"
 	homebrew_services:                          "\(github)/Homebrew/homebrew-services"
 	honeycomb:                                  "https://honeycomb.io"
@@ -443,6 +446,7 @@ urls: {
 	rustup:                                     "https://rustup.rs"
 	redis:                                      "https://redis.io"
 	redis_rs:                                   "https://github.com/mitsuhiko/redis-rs"
+	seahash:                                    "https://docs.rs/seahash"
 	sematext:                                   "https://sematext.com"
 	sematext_create_logs_app:                   "https://apps.sematext.com/ui/integrations"
 	sematext_es:                                "https://sematext.com/docs/logs/index-events-via-elasticsearch-api/"
@@ -640,6 +644,7 @@ urls: {
 	windows_installer:                          "\(wikipedia)/wiki/Windows_Installer"
 	windows_service:                            "https://docs.microsoft.com/en-us/powershell/module/microsoft.powershell.management/new-service"
 	woothee:                                    "https://github.com/woothee/woothee"
+	xxhash:                                     "https://cyan4973.github.io/xxHash/"
 	yaml:                                       "https://yaml.org/"
 	ytt:                                        "https://carvel.dev/ytt/"
 	yum:                                        "\(wikipedia)/wiki/Yum_(software)"